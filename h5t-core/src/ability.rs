@@ -1,3 +1,4 @@
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// An ability score (1-30).
@@ -18,7 +19,8 @@ pub fn score_to_modifier(score: Score) -> Modifier {
 ///
 /// It can represent the ability scores themselves, the ability score modifiers, or any other
 /// numerical values related to abilities, depending on the parameter chosen for the type `T`.
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Ability<T> {
     pub strength: T,
     pub dexterity: T,
@@ -42,8 +44,92 @@ impl Ability<Score> {
     }
 }
 
+impl<T: Copy> Ability<T> {
+    /// Returns the value of the given ability.
+    pub fn get(&self, kind: AbilityKind) -> T {
+        match kind {
+            AbilityKind::Strength => self.strength,
+            AbilityKind::Dexterity => self.dexterity,
+            AbilityKind::Constitution => self.constitution,
+            AbilityKind::Intelligence => self.intelligence,
+            AbilityKind::Wisdom => self.wisdom,
+            AbilityKind::Charisma => self.charisma,
+        }
+    }
+}
+
+/// One of the six abilities, such as the ability a saving throw is made with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum AbilityKind {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl AbilityKind {
+    /// Every ability, in the order stat blocks list them.
+    pub const ALL: [AbilityKind; 6] = [
+        AbilityKind::Strength,
+        AbilityKind::Dexterity,
+        AbilityKind::Constitution,
+        AbilityKind::Intelligence,
+        AbilityKind::Wisdom,
+        AbilityKind::Charisma,
+    ];
+
+    /// Returns the ability's lowercase name.
+    pub fn name(self) -> &'static str {
+        match self {
+            AbilityKind::Strength => "strength",
+            AbilityKind::Dexterity => "dexterity",
+            AbilityKind::Constitution => "constitution",
+            AbilityKind::Intelligence => "intelligence",
+            AbilityKind::Wisdom => "wisdom",
+            AbilityKind::Charisma => "charisma",
+        }
+    }
+
+    /// Returns the ability's three-letter abbreviation, such as `Wis`.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            AbilityKind::Strength => "Str",
+            AbilityKind::Dexterity => "Dex",
+            AbilityKind::Constitution => "Con",
+            AbilityKind::Intelligence => "Int",
+            AbilityKind::Wisdom => "Wis",
+            AbilityKind::Charisma => "Cha",
+        }
+    }
+}
+
+impl std::fmt::Display for AbilityKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+/// Abilities are parsed from their name or abbreviation, ignoring case.
+impl std::str::FromStr for AbilityKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AbilityKind::ALL
+            .into_iter()
+            .find(|kind| {
+                kind.name().eq_ignore_ascii_case(s) || kind.abbreviation().eq_ignore_ascii_case(s)
+            })
+            .ok_or_else(|| format!("unknown ability `{}`", s))
+    }
+}
+
 /// A type that packs together all skills.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Skill<T> {
     /// Acrobatics (Dexterity).
     pub acrobatics: T,
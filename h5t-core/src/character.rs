@@ -0,0 +1,166 @@
+use crate::ability::{Ability, Modifier, Score, Skill};
+use crate::dice::DiceExpression;
+use crate::monster::{Senses, Speed};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A player character.
+///
+/// Unlike monsters, characters aren't loaded from the API, so only the details that matter in
+/// combat are tracked. Saving throw and skill modifiers are computed from the ability scores and
+/// proficiencies.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Character {
+    /// The character's name.
+    pub name: String,
+
+    /// The name of the player controlling the character.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub player: String,
+
+    /// The character's class (or classes, e.g. `Fighter 3 / Wizard 2`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub class: String,
+
+    /// The character's total level.
+    #[cfg_attr(feature = "serde", serde(default = "Character::default_level"))]
+    pub level: u32,
+
+    /// The character's ability scores.
+    pub scores: Ability<Score>,
+
+    /// The character's armor class.
+    pub armor_class: u32,
+
+    /// The character's maximum hit points.
+    pub hit_points: i32,
+
+    /// The number of sides of the character's hit dice, such as `10` for a fighter. Characters
+    /// have one hit die per level; multiclass characters use the size of their first class.
+    #[cfg_attr(feature = "serde", serde(default = "Character::default_hit_die"))]
+    pub hit_die: u32,
+
+    /// The character's speeds.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub speed: Speed,
+
+    /// The character's special senses, such as darkvision from its species. Its passive
+    /// Perception is computed from its skills instead.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub senses: Senses,
+
+    /// The saving throws the character is proficient in.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub saving_throws: Ability<bool>,
+
+    /// The skills the character is proficient in.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub skills: Skill<bool>,
+}
+
+impl Character {
+    #[cfg(feature = "serde")]
+    const fn default_level() -> u32 {
+        1
+    }
+
+    #[cfg(feature = "serde")]
+    const fn default_hit_die() -> u32 {
+        8
+    }
+
+    /// Returns the roll for spending the given number of hit dice: the dice, plus the
+    /// character's Constitution modifier for each.
+    pub fn hit_dice_roll(&self, count: u32) -> DiceExpression {
+        let constitution = self.scores.modifiers().constitution * count as i32;
+        DiceExpression::new(count, self.hit_die.max(1), constitution)
+    }
+
+    /// Returns the character's proficiency bonus, based on their level.
+    pub fn proficiency_bonus(&self) -> Modifier {
+        2 + (self.level.max(1) as Modifier - 1) / 4
+    }
+
+    /// Returns the character's saving throw modifiers.
+    pub fn saving_throw_modifiers(&self) -> Ability<Modifier> {
+        let modifiers = self.scores.modifiers();
+        let bonus = |proficient: bool| if proficient { self.proficiency_bonus() } else { 0 };
+        Ability {
+            strength: modifiers.strength + bonus(self.saving_throws.strength),
+            dexterity: modifiers.dexterity + bonus(self.saving_throws.dexterity),
+            constitution: modifiers.constitution + bonus(self.saving_throws.constitution),
+            intelligence: modifiers.intelligence + bonus(self.saving_throws.intelligence),
+            wisdom: modifiers.wisdom + bonus(self.saving_throws.wisdom),
+            charisma: modifiers.charisma + bonus(self.saving_throws.charisma),
+        }
+    }
+
+    /// Returns the character's skill modifiers.
+    pub fn skill_modifiers(&self) -> Skill<Modifier> {
+        let Ability { strength, dexterity, intelligence, wisdom, charisma, .. } =
+            self.scores.modifiers();
+        let skills = &self.skills;
+        let bonus = |proficient: bool| if proficient { self.proficiency_bonus() } else { 0 };
+        Skill {
+            acrobatics: dexterity + bonus(skills.acrobatics),
+            animal_handling: wisdom + bonus(skills.animal_handling),
+            arcana: intelligence + bonus(skills.arcana),
+            athletics: strength + bonus(skills.athletics),
+            deception: charisma + bonus(skills.deception),
+            history: intelligence + bonus(skills.history),
+            insight: wisdom + bonus(skills.insight),
+            intimidation: charisma + bonus(skills.intimidation),
+            investigation: intelligence + bonus(skills.investigation),
+            medicine: wisdom + bonus(skills.medicine),
+            nature: intelligence + bonus(skills.nature),
+            perception: wisdom + bonus(skills.perception),
+            performance: charisma + bonus(skills.performance),
+            persuasion: charisma + bonus(skills.persuasion),
+            religion: intelligence + bonus(skills.religion),
+            sleight_of_hand: dexterity + bonus(skills.sleight_of_hand),
+            stealth: dexterity + bonus(skills.stealth),
+            survival: wisdom + bonus(skills.survival),
+        }
+    }
+
+    /// Returns the character's passive Perception, Insight, and Investigation scores.
+    pub fn passive_scores(&self) -> (Score, Score, Score) {
+        let skills = self.skill_modifiers();
+        (10 + skills.perception, 10 + skills.insight, 10 + skills.investigation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensure that proficiencies add the proficiency bonus for the character's level.
+    #[test]
+    fn modifiers() {
+        let character = Character {
+            name: "Tordek".to_string(),
+            level: 5,
+            scores: Ability {
+                strength: 16,
+                dexterity: 12,
+                constitution: 14,
+                intelligence: 8,
+                wisdom: 13,
+                charisma: 10,
+            },
+            saving_throws: Ability { strength: true, ..Default::default() },
+            skills: Skill { perception: true, ..Default::default() },
+            ..Default::default()
+        };
+
+        assert_eq!(character.proficiency_bonus(), 3);
+        assert_eq!(character.saving_throw_modifiers().strength, 6);
+        assert_eq!(character.saving_throw_modifiers().dexterity, 1);
+        assert_eq!(character.skill_modifiers().perception, 4);
+        assert_eq!(character.skill_modifiers().investigation, -1);
+        assert_eq!(character.passive_scores(), (14, 11, 9));
+        let character = Character { hit_die: 10, ..character };
+        assert_eq!(character.hit_dice_roll(2).to_string(), "2d10 + 4");
+    }
+}
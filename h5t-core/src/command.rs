@@ -0,0 +1,235 @@
+use crate::{
+    Combatant, CombatantEdit, ConditionDuration, ConditionKind, ConditionSource, DamageType, Effect,
+    Grapple, InitiativeModifier, Position, Side, SpeedFactor, TurnOrder,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A change to the encounter, made with [`Tracker::execute`](crate::Tracker::execute).
+///
+/// Every change the tracker can make has a command, so anything that needs to follow the
+/// encounter (the combat log, undo, syncing to other screens, scripts) only has to watch one
+/// path. Combatants are referred to by their index in the initiative order.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TrackerCommand {
+    /// Damage a combatant, applying its resistances, vulnerabilities, and immunities.
+    Damage { index: usize, amount: i32, kind: Option<DamageType> },
+
+    /// Heal a combatant, from 0 if it is below 0 hit points, up to its maximum hit points.
+    Heal { index: usize, amount: i32 },
+
+    /// Give a combatant temporary hit points, unless it already has as many or more.
+    GrantTemporaryHitPoints { index: usize, amount: i32 },
+
+    /// Stabilize a combatant at 0 hit points, dropping it there if it is still standing.
+    Stabilize { index: usize },
+
+    /// Mark a combatant dead.
+    Kill { index: usize },
+
+    /// Set a combatant's hit points, logging the change as damage or healing.
+    SetHitPoints { index: usize, value: i32 },
+
+    /// Apply a condition to a combatant, or extend it if the combatant already has it.
+    AddCondition { index: usize, kind: ConditionKind, duration: ConditionDuration },
+
+    /// Apply several conditions to several combatants at once, in a single change, or extend them
+    /// where they already have them. The source is recorded with the conditions.
+    AddConditions {
+        indexes: Vec<usize>,
+        kinds: Vec<ConditionKind>,
+        duration: ConditionDuration,
+        source: ConditionSource,
+    },
+
+    /// End a condition on a combatant early.
+    RemoveCondition { index: usize, kind: ConditionKind },
+
+    /// Attach an effect to a combatant, applying the condition it causes.
+    AddEffect { index: usize, effect: Effect },
+
+    /// End an effect on a combatant, by its position among the combatant's effects, along with
+    /// the condition it caused.
+    EndEffect { index: usize, effect: usize },
+
+    /// Stop (or restart) a combatant's regeneration on its next turn.
+    SuppressRegeneration { index: usize, suppressed: bool },
+
+    /// Have a combatant grapple (or restrain) another, applying the condition to the target.
+    Grapple(Grapple),
+
+    /// End every grapple a combatant is part of, as the grappler or the target.
+    Release { index: usize },
+
+    /// Advance to the next combatant's turn.
+    NextTurn,
+
+    /// Pass the turn to a combatant who hasn't acted this round, as in popcorn initiative.
+    PassTurn { next: usize },
+
+    /// Use an action of the current combatant.
+    UseAction,
+
+    /// Use a bonus action of the current combatant.
+    UseBonusAction,
+
+    /// Use a reaction of the current combatant.
+    UseReaction,
+
+    /// Add a combatant to the end of the initiative order.
+    Add(Box<Combatant>),
+
+    /// Remove a combatant from the initiative order.
+    Remove { index: usize },
+
+    /// Save combatants as a named group, or delete the group if there are none.
+    SaveGroup { name: String, members: Vec<usize> },
+
+    /// Move a combatant to a side of the fight.
+    SetSide { index: usize, side: Side },
+
+    /// Move a combatant to a zone, or out of every zone.
+    SetZone { index: usize, zone: Option<String> },
+
+    /// Move a combatant's token to a square of the battle grid, or take it off the grid.
+    SetPosition { index: usize, position: Option<Position> },
+
+    /// Change how turns pass from one combatant to the next.
+    SetTurnOrder(TurnOrder),
+
+    /// Have copies of the same monster next to each other take their turns together, or not.
+    SetCombinedTurns(bool),
+
+    /// Set or clear a combatant's initiative roll.
+    SetInitiative { index: usize, value: Option<i32> },
+
+    /// Replace what changes a combatant's initiative rolls, besides its Dexterity modifier.
+    SetInitiativeModifiers { index: usize, modifiers: Vec<InitiativeModifier> },
+
+    /// Replace what a combatant declared it will do this round, in speed factor initiative.
+    DeclareSpeedFactors { index: usize, speed_factors: Vec<SpeedFactor> },
+
+    /// Set a combatant's exhaustion level, applying or ending the exhaustion condition with it.
+    SetExhaustion { index: usize, level: u32 },
+
+    /// Surprise a combatant, or stop surprising it.
+    SetSurprised { index: usize, surprised: bool },
+
+    /// Flag or unflag a combatant as a minion, for [`Rules::minions`](crate::Rules::minions).
+    SetMinion { index: usize, minion: bool },
+
+    /// Flag or unflag a combatant as the leader of its side, for
+    /// [`Rules::morale`](crate::Rules::morale).
+    SetLeader { index: usize, leader: bool },
+
+    /// Have a combatant flee the fight, such as after failing a morale check, or stop fleeing.
+    SetFleeing { index: usize, fleeing: bool },
+
+    /// Start or stop concentrating on a spell. Stopping offers to remove the combatant's summons.
+    SetConcentration { index: usize, concentrating: bool },
+
+    /// Link a combatant to the combatant that summoned it, or unlink it.
+    SetSummoner { index: usize, summoner: Option<usize> },
+
+    /// Give Inspiration to a player character, or have them spend it.
+    SetInspiration { index: usize, inspiration: bool },
+
+    /// Have a combatant finish a short rest, spending hit dice to regain the hit points rolled
+    /// for them.
+    ShortRest { index: usize, hit_dice: u32, healing: i32 },
+
+    /// Have a combatant finish a long rest, regaining its hit points and hit dice.
+    LongRest { index: usize },
+
+    /// Change a combatant's name, maximum hit points, armor class, or the stats only some kinds
+    /// of combatants have, by hand.
+    Edit { index: usize, edit: CombatantEdit },
+
+    /// Replace a combatant's notes.
+    SetNotes { index: usize, notes: String },
+
+    /// Replace the encounter's notes.
+    SetEncounterNotes { notes: String },
+
+    /// Add in-game time to the clock by hand, or take it back if the number of seconds is
+    /// negative.
+    PassTime { seconds: i64 },
+
+    /// Record the XP earned for the enemy monsters defeated so far in the combat log, split
+    /// between the player characters.
+    AwardXp,
+}
+
+/// The combatants a [`TrackerCommand`] can change, for anything that keeps state per combatant
+/// (such as formatted rows) and only wants to update what changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Changed {
+    /// No combatant, only the encounter itself (its notes or groups).
+    Encounter,
+
+    /// The combatant with the given index.
+    Combatant(usize),
+
+    /// The combatant whose turn it is.
+    Turn,
+
+    /// Any combatant, or the initiative order itself, since indexes shift when a combatant is
+    /// removed.
+    All,
+}
+
+impl TrackerCommand {
+    /// Returns the combatants the command can change. Damage can carry over to other combatants
+    /// too, which only the log it records shows, so subscribers should use
+    /// [`TrackerEvent::changed`](crate::TrackerEvent::changed) instead.
+    pub fn changed(&self) -> Changed {
+        match self {
+            Self::Damage { index, .. }
+            | Self::Heal { index, .. }
+            | Self::GrantTemporaryHitPoints { index, .. }
+            | Self::Stabilize { index }
+            | Self::Kill { index }
+            | Self::SetHitPoints { index, .. }
+            | Self::AddCondition { index, .. }
+            | Self::RemoveCondition { index, .. }
+            | Self::AddEffect { index, .. }
+            | Self::EndEffect { index, .. }
+            | Self::SuppressRegeneration { index, .. }
+            | Self::SetSide { index, .. }
+            | Self::SetZone { index, .. }
+            | Self::SetPosition { index, .. }
+            | Self::SetInitiative { index, .. }
+            | Self::SetInitiativeModifiers { index, .. }
+            | Self::DeclareSpeedFactors { index, .. }
+            | Self::SetExhaustion { index, .. }
+            | Self::SetSurprised { index, .. }
+            | Self::SetMinion { index, .. }
+            | Self::SetLeader { index, .. }
+            | Self::SetFleeing { index, .. }
+            | Self::Edit { index, .. }
+            | Self::SetConcentration { index, .. }
+            | Self::SetSummoner { index, .. }
+            | Self::SetInspiration { index, .. }
+            | Self::ShortRest { index, .. }
+            | Self::LongRest { index }
+            | Self::SetNotes { index, .. } => Changed::Combatant(*index),
+            Self::UseAction | Self::UseBonusAction | Self::UseReaction => Changed::Turn,
+            // the turn ends for one combatant and starts for the next, and grapples have two sides
+            Self::NextTurn
+            | Self::PassTurn { .. }
+            | Self::SetCombinedTurns(_)
+            | Self::Add(_)
+            | Self::Remove { .. }
+            | Self::Grapple(_)
+            | Self::Release { .. } => Changed::All,
+            Self::AddConditions { .. } => Changed::All,
+            Self::SaveGroup { .. }
+            | Self::SetTurnOrder(_)
+            | Self::SetEncounterNotes { .. }
+            | Self::PassTime { .. }
+            | Self::AwardXp => Changed::Encounter,
+        }
+    }
+}
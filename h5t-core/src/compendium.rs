@@ -0,0 +1,118 @@
+use crate::Monster;
+use crate::monster::Type;
+#[cfg(feature = "serde")]
+use std::io::Read;
+
+/// A collection of monsters that can be added to an encounter.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Compendium {
+    /// The monsters, in the order they were loaded.
+    monsters: Vec<Monster>,
+}
+
+impl Compendium {
+    /// Create a compendium with the given monsters.
+    pub fn new(monsters: Vec<Monster>) -> Self {
+        Self { monsters }
+    }
+
+    /// Load a compendium from a JSON array of monsters, in the format provided by
+    /// <https://www.dnd5eapi.co/>.
+    #[cfg(feature = "serde")]
+    pub fn from_reader(reader: impl Read) -> serde_json::Result<Self> {
+        serde_json::from_reader(reader).map(Self::new)
+    }
+
+    /// Adds the monsters of another compendium, such as one loaded from another file.
+    pub fn extend(&mut self, other: Compendium) {
+        self.monsters.extend(other.monsters);
+    }
+
+    /// Returns every monster in the compendium.
+    pub fn monsters(&self) -> &[Monster] {
+        &self.monsters
+    }
+
+    /// Find a monster by its name or its index, ignoring case.
+    pub fn get(&self, name: &str) -> Option<&Monster> {
+        self.monsters
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(name) || m.index.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the monsters whose name starts with the given prefix, ignoring case.
+    pub fn search<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a Monster> {
+        let query = MonsterQuery::name(prefix);
+        self.monsters.iter().filter(move |m| query.matches(m))
+    }
+}
+
+/// The monsters to look for in a [`MonsterSource`].
+#[derive(Clone, Debug, Default)]
+pub struct MonsterQuery {
+    /// The start of the monster's name, ignoring case. Empty to match every name.
+    pub name: String,
+
+    /// The lowest challenge rating to match, if any.
+    pub min_challenge: Option<f32>,
+
+    /// The highest challenge rating to match, if any.
+    pub max_challenge: Option<f32>,
+
+    /// The creature type to match, if any.
+    pub kind: Option<Type>,
+}
+
+impl MonsterQuery {
+    /// Looks for the monsters whose name starts with the given prefix, ignoring case.
+    pub fn name(prefix: impl Into<String>) -> Self {
+        Self { name: prefix.into(), ..Self::default() }
+    }
+
+    /// Returns whether the monster matches the query.
+    pub fn matches(&self, monster: &Monster) -> bool {
+        let name = monster.name
+            .get(..self.name.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(&self.name));
+        name
+            && self.min_challenge.is_none_or(|min| monster.challenge_rating >= min)
+            && self.max_challenge.is_none_or(|max| monster.challenge_rating <= max)
+            && self.kind.is_none_or(|kind| monster.r#type == kind)
+    }
+}
+
+/// Where monsters are looked up when they are added to an encounter: a [`Compendium`] held in
+/// memory, or a database that only loads the monsters asked for.
+pub trait MonsterSource: std::fmt::Debug {
+    /// Finds a monster by its name or its index, ignoring case.
+    fn find(&self, name: &str) -> Option<Monster>;
+
+    /// Returns the monsters matching the query, in the order they were loaded.
+    fn query(&self, query: &MonsterQuery) -> Vec<Monster>;
+
+    /// Returns the names of every monster, in the order they were loaded.
+    fn names(&self) -> Vec<String>;
+
+    /// Returns the number of monsters.
+    fn count(&self) -> usize;
+}
+
+impl MonsterSource for Compendium {
+    fn find(&self, name: &str) -> Option<Monster> {
+        self.get(name).cloned()
+    }
+
+    fn query(&self, query: &MonsterQuery) -> Vec<Monster> {
+        self.monsters.iter().filter(|m| query.matches(m)).cloned().collect()
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.monsters.iter().map(|m| m.name.clone()).collect()
+    }
+
+    fn count(&self) -> usize {
+        self.monsters.len()
+    }
+}
@@ -1,18 +1,47 @@
+use crate::Edition;
 use enumset::EnumSetType;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::num::NonZeroU32;
 
 /// A condition and how long it lasts.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Condition {
     /// The condition to apply.
     pub kind: ConditionKind,
 
     /// The duration of the condition.
     pub duration: ConditionDuration,
+
+    /// What applied the condition, if it was recorded.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source: ConditionSource,
+}
+
+/// What applied a condition: the combatant that did, what it used, or both, such as an adult red
+/// dragon's Frightful Presence.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ConditionSource {
+    /// The index of the combatant that applied the condition.
+    pub combatant: Option<usize>,
+
+    /// What caused the condition, such as a spell or an action.
+    pub cause: Option<String>,
+}
+
+impl ConditionSource {
+    /// Returns `true` if nothing about the source was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.combatant.is_none() && self.cause.is_none()
+    }
 }
 
 /// All possible conditions that can be applied to a combatant.
 #[derive(EnumSetType, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum ConditionKind {
     Blinded,
     Charmed,
@@ -37,6 +66,116 @@ impl std::fmt::Display for ConditionKind {
     }
 }
 
+/// Conditions are parsed from their name, ignoring case.
+impl std::str::FromStr for ConditionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        enumset::EnumSet::<ConditionKind>::all()
+            .into_iter()
+            .find(|kind| kind.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown condition `{}`", s))
+    }
+}
+
+/// The conditions that come with other conditions, as their rules say: an unconscious creature
+/// is also incapacitated and prone, for example.
+pub const IMPLIED_CONDITIONS: [(ConditionKind, &[ConditionKind]); 4] = [
+    (ConditionKind::Paralyzed, &[ConditionKind::Incapacitated]),
+    (ConditionKind::Petrified, &[ConditionKind::Incapacitated]),
+    (ConditionKind::Stunned, &[ConditionKind::Incapacitated]),
+    (ConditionKind::Unconscious, &[ConditionKind::Incapacitated, ConditionKind::Prone]),
+];
+
+impl ConditionKind {
+    /// Returns the conditions that come with this one, from [`IMPLIED_CONDITIONS`].
+    pub fn implies(self) -> &'static [ConditionKind] {
+        IMPLIED_CONDITIONS
+            .iter()
+            .find(|(kind, _)| *kind == self)
+            .map_or(&[], |(_, implied)| implied)
+    }
+
+    /// Summarizes what the condition does, as worded in the given edition of the rules.
+    pub fn summary(self, edition: Edition) -> &'static str {
+        use ConditionKind::*;
+        match (edition, self) {
+            (Edition::Rules2024, Exhaustion) => {
+                "-2 to d20 Tests and -5 ft. speed per level, dies at level 6"
+            },
+            (Edition::Rules2024, Grappled) => {
+                "Speed 0, disadvantage on attacks against anyone but the grappler"
+            },
+            (Edition::Rules2024, Incapacitated) => {
+                "No actions, bonus actions, or reactions, loses concentration, can't speak"
+            },
+            (Edition::Rules2024, Invisible) => {
+                "Advantage on initiative and attacks, attacks against it have disadvantage"
+            },
+            (Edition::Rules2024, Paralyzed) => {
+                "Incapacitated, speed 0, fails Str/Dex saves, hits within 5 ft. are crits"
+            },
+            (Edition::Rules2024, Stunned) => {
+                "Incapacitated, fails Str/Dex saves, attacks against it have advantage"
+            },
+            (_, Blinded) => "Can't see, attacked with advantage, disadvantage on attacks",
+            (_, Charmed) => "Can't attack the charmer, who has advantage on social checks",
+            (_, Deafened) => "Can't hear, fails checks that need hearing",
+            (_, Exhaustion) => "Levels 1-6, each adding a worse effect, up to death",
+            (_, Frightened) => "Disadvantage while the source is in sight, can't move closer",
+            (_, Grappled) => "Speed 0, ends if the grappler is incapacitated or moved away",
+            (_, Incapacitated) => "Can't take actions or reactions",
+            (_, Invisible) => "Can't be seen, advantage on attacks, attacked with disadvantage",
+            (_, Paralyzed) => {
+                "Incapacitated, can't move, fails Str/Dex saves, hits within 5 ft. are crits"
+            },
+            (_, Petrified) => "Incapacitated stone, resists all damage, fails Str/Dex saves",
+            (_, Poisoned) => "Disadvantage on attack rolls and ability checks",
+            (_, Prone) => "Crawls, melee attacks against it have advantage, ranged disadvantage",
+            (_, Restrained) => {
+                "Speed 0, attacked with advantage, disadvantage on attacks and Dex saves"
+            },
+            (_, Stunned) => {
+                "Incapacitated, can't move, fails Str/Dex saves, attacked with advantage"
+            },
+            (_, Unconscious) => {
+                "Incapacitated, prone, fails Str/Dex saves, hits within 5 ft. are crits"
+            },
+        }
+    }
+}
+
+/// Conditions that came with a condition that ended, and that the combatant still has.
+///
+/// They aren't cleared along with it, since they may have been applied on their own as well (a
+/// creature knocked prone before being knocked out stays prone). The tracker collects these in
+/// [`Tracker::lingering`](crate::Tracker::lingering), for the UI to offer to clear them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lingering {
+    /// The index of the combatant with the conditions.
+    pub index: usize,
+
+    /// The condition that ended.
+    pub ended: ConditionKind,
+
+    /// The conditions that came with it.
+    pub conditions: Vec<ConditionKind>,
+}
+
+/// Conditions a combatant applied to others, which they still have once it dropped. They may end
+/// with it, such as a spell it concentrated on, or not, such as being knocked prone.
+///
+/// The tracker collects these in [`Tracker::orphaned`](crate::Tracker::orphaned), for the UI to
+/// offer to clear them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Orphaned {
+    /// The index of the combatant that applied the conditions.
+    pub source: usize,
+
+    /// The index of each combatant with one of the conditions, and the condition.
+    pub conditions: Vec<(usize, ConditionKind)>,
+}
+
 macro_rules! make_abbreviations {
     ($($kind:ident => $abbreviation:expr),* $(,)?) => {
         impl ConditionKind {
@@ -84,6 +223,7 @@ make_abbreviations! {
 
 /// Duration of a condition.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum ConditionDuration {
     /// The condition lasts until the end of the combatant's next turn.
     #[default]
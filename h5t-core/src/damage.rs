@@ -0,0 +1,109 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A type of damage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum DamageType {
+    Acid,
+    Bludgeoning,
+    Cold,
+    Fire,
+    Force,
+    Lightning,
+    Necrotic,
+    Piercing,
+    Poison,
+    Psychic,
+    Radiant,
+    Slashing,
+    Thunder,
+}
+
+impl DamageType {
+    /// Every damage type, in alphabetical order.
+    pub const ALL: [DamageType; 13] = [
+        DamageType::Acid,
+        DamageType::Bludgeoning,
+        DamageType::Cold,
+        DamageType::Fire,
+        DamageType::Force,
+        DamageType::Lightning,
+        DamageType::Necrotic,
+        DamageType::Piercing,
+        DamageType::Poison,
+        DamageType::Psychic,
+        DamageType::Radiant,
+        DamageType::Slashing,
+        DamageType::Thunder,
+    ];
+
+    /// Returns the lowercase name of the damage type, as it is written in stat blocks.
+    pub fn name(self) -> &'static str {
+        match self {
+            DamageType::Acid => "acid",
+            DamageType::Bludgeoning => "bludgeoning",
+            DamageType::Cold => "cold",
+            DamageType::Fire => "fire",
+            DamageType::Force => "force",
+            DamageType::Lightning => "lightning",
+            DamageType::Necrotic => "necrotic",
+            DamageType::Piercing => "piercing",
+            DamageType::Poison => "poison",
+            DamageType::Psychic => "psychic",
+            DamageType::Radiant => "radiant",
+            DamageType::Slashing => "slashing",
+            DamageType::Thunder => "thunder",
+        }
+    }
+}
+
+impl std::fmt::Display for DamageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for DamageType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DamageType::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown damage type `{}`", s))
+    }
+}
+
+/// How a creature is affected by a type of damage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum DamageModifier {
+    /// The creature takes the full damage.
+    #[default]
+    Normal,
+
+    /// The creature takes half damage, rounded down.
+    Resistant,
+
+    /// The creature takes double damage.
+    Vulnerable,
+
+    /// The creature takes no damage.
+    Immune,
+}
+
+impl DamageModifier {
+    /// Returns the damage actually taken from an attack that deals `amount` damage.
+    pub fn apply(self, amount: i32) -> i32 {
+        match self {
+            DamageModifier::Normal => amount,
+            DamageModifier::Resistant => amount / 2,
+            DamageModifier::Vulnerable => amount * 2,
+            DamageModifier::Immune => 0,
+        }
+    }
+}
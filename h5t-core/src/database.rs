@@ -0,0 +1,326 @@
+use crate::compendium::{MonsterQuery, MonsterSource};
+use crate::Monster;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// The tables of the database, and the indexes searches go through.
+///
+/// Monsters are kept as the JSON they were imported from, next to the columns they are searched
+/// by. Names are compared without case, so prefix searches (`LIKE 'gob%'`) use the name index.
+const SCHEMA: &str = "
+    PRAGMA foreign_keys = ON;
+
+    CREATE TABLE IF NOT EXISTS sources (
+        path TEXT PRIMARY KEY,
+        modified INTEGER NOT NULL,
+        size INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS monsters (
+        id INTEGER PRIMARY KEY,
+        source TEXT NOT NULL REFERENCES sources (path) ON DELETE CASCADE,
+        monster_index TEXT NOT NULL COLLATE NOCASE,
+        name TEXT NOT NULL COLLATE NOCASE,
+        challenge_rating REAL NOT NULL,
+        type TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS monsters_source ON monsters (source);
+    CREATE INDEX IF NOT EXISTS monsters_index ON monsters (monster_index);
+    CREATE INDEX IF NOT EXISTS monsters_name ON monsters (name);
+    CREATE INDEX IF NOT EXISTS monsters_challenge_rating ON monsters (challenge_rating);
+    CREATE INDEX IF NOT EXISTS monsters_type ON monsters (type);
+";
+
+/// An error reading or writing a [`CompendiumDb`], or reading a file imported into it.
+#[derive(Debug)]
+pub enum DatabaseError {
+    /// The database couldn't be opened, read, or written.
+    Sqlite(rusqlite::Error),
+
+    /// A file to import couldn't be read.
+    Io { path: PathBuf, source: std::io::Error },
+
+    /// A file to import isn't a JSON array of monsters.
+    Json { path: PathBuf, source: serde_json::Error },
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sqlite(source) => write!(f, "database error: {}", source),
+            Self::Io { path, source } => {
+                write!(f, "could not access {}: {}", path.display(), source)
+            },
+            Self::Json { path, source } => {
+                write!(f, "could not parse {}: {}", path.display(), source)
+            },
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Sqlite(source) => Some(source),
+            Self::Io { source, .. } => Some(source),
+            Self::Json { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for DatabaseError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Sqlite(error)
+    }
+}
+
+/// A compendium kept in a SQLite database, indexed by name, challenge rating, and type.
+///
+/// Monster files are imported once, and only imported again when they change, so large
+/// compendiums aren't parsed every time h5t starts. Monsters are only loaded from the database
+/// when they are looked up.
+#[derive(Debug)]
+pub struct CompendiumDb {
+    /// The connection to the database.
+    connection: Connection,
+}
+
+impl CompendiumDb {
+    /// Opens the database in the given file, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Opens a database held in memory, which is lost when it's dropped.
+    pub fn open_in_memory() -> Result<Self, DatabaseError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    /// Creates the tables and indexes of a newly opened database, unless they exist.
+    fn with_connection(connection: Connection) -> Result<Self, DatabaseError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Makes the database hold the monsters of the given files, in the format provided by
+    /// <https://www.dnd5eapi.co/>, and no others.
+    ///
+    /// Files that didn't change since they were last imported are skipped, and the monsters of
+    /// files that aren't given anymore are removed. Returns the number of files imported.
+    pub fn sync(&mut self, paths: &[impl AsRef<Path>]) -> Result<usize, DatabaseError> {
+        let mut imported = 0;
+        for path in paths {
+            if self.is_stale(path.as_ref())? {
+                self.import(path)?;
+                imported += 1;
+            }
+        }
+
+        let keep = paths
+            .iter()
+            .map(|path| source_key(path.as_ref()))
+            .collect::<Vec<_>>();
+        let sources = self.connection
+            .prepare("SELECT path FROM sources")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        for source in sources.into_iter().filter(|source| !keep.contains(source)) {
+            self.connection.execute("DELETE FROM sources WHERE path = ?1", [source])?;
+        }
+
+        Ok(imported)
+    }
+
+    /// Imports the monsters of a file, replacing the ones imported from it before.
+    pub fn import(&mut self, path: impl AsRef<Path>) -> Result<(), DatabaseError> {
+        let path = path.as_ref();
+        let io = |source| DatabaseError::Io { path: path.to_path_buf(), source };
+        let json = |source| DatabaseError::Json { path: path.to_path_buf(), source };
+
+        let (modified, size) = file_stamp(path).map_err(io)?;
+        let file = std::fs::File::open(path).map_err(io)?;
+        let values: Vec<serde_json::Value> =
+            serde_json::from_reader(std::io::BufReader::new(file)).map_err(json)?;
+
+        let key = source_key(path);
+        let transaction = self.connection.transaction()?;
+        transaction.execute("DELETE FROM sources WHERE path = ?1", [&key])?;
+        transaction.execute(
+            "INSERT INTO sources (path, modified, size) VALUES (?1, ?2, ?3)",
+            params![key, modified, size],
+        )?;
+        {
+            let mut insert = transaction.prepare(
+                "INSERT INTO monsters (source, monster_index, name, challenge_rating, type, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for value in values {
+                let monster = Monster::deserialize(&value).map_err(json)?;
+                insert.execute(params![
+                    key,
+                    monster.index,
+                    monster.name,
+                    monster.challenge_rating,
+                    monster.r#type.name(),
+                    value.to_string(),
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Returns whether the file changed since it was last imported, or was never imported.
+    fn is_stale(&self, path: &Path) -> Result<bool, DatabaseError> {
+        let stamp = file_stamp(path)
+            .map_err(|source| DatabaseError::Io { path: path.to_path_buf(), source })?;
+        let imported = self.connection
+            .query_row(
+                "SELECT modified, size FROM sources WHERE path = ?1",
+                [source_key(path)],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .optional()?;
+        Ok(imported != Some(stamp))
+    }
+
+    /// Runs a query that selects the `data` column of monsters, and loads them. Monsters that
+    /// can't be loaded anymore are skipped, though they were checked when they were imported.
+    fn load(&self, sql: &str, params: impl rusqlite::Params) -> rusqlite::Result<Vec<Monster>> {
+        let mut statement = self.connection.prepare_cached(sql)?;
+        let rows = statement.query_map(params, |row| row.get::<_, String>(0))?;
+        let mut monsters = Vec::new();
+        for data in rows {
+            monsters.extend(serde_json::from_str(&data?).ok());
+        }
+        Ok(monsters)
+    }
+}
+
+/// Reads from the database can only fail if its file is damaged or removed while h5t runs, in
+/// which case lookups find nothing.
+impl MonsterSource for CompendiumDb {
+    fn find(&self, name: &str) -> Option<Monster> {
+        self.load(
+            "SELECT data FROM monsters WHERE name = ?1 OR monster_index = ?1 ORDER BY id LIMIT 1",
+            [name],
+        )
+            .ok()?
+            .pop()
+    }
+
+    fn query(&self, query: &MonsterQuery) -> Vec<Monster> {
+        self.load(
+            "SELECT data FROM monsters
+             WHERE name LIKE ?1 ESCAPE '\\'
+                 AND (?2 IS NULL OR challenge_rating >= ?2)
+                 AND (?3 IS NULL OR challenge_rating <= ?3)
+                 AND (?4 IS NULL OR type = ?4)
+             ORDER BY id",
+            params![
+                format!("{}%", escape_like(&query.name)),
+                query.min_challenge,
+                query.max_challenge,
+                query.kind.map(|kind| kind.name()),
+            ],
+        )
+            .unwrap_or_default()
+    }
+
+    fn names(&self) -> Vec<String> {
+        let names = || -> Result<Vec<String>, rusqlite::Error> {
+            self.connection
+                .prepare_cached("SELECT name FROM monsters ORDER BY id")?
+                .query_map([], |row| row.get(0))?
+                .collect()
+        };
+        names().unwrap_or_default()
+    }
+
+    fn count(&self) -> usize {
+        self.connection
+            .query_row("SELECT COUNT(*) FROM monsters", [], |row| row.get::<_, i64>(0))
+            .map_or(0, |count| count as usize)
+    }
+}
+
+/// Returns the key a file is recorded under, its absolute path if it can be found.
+fn source_key(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Returns when a file was last modified (in nanoseconds since the Unix epoch) and its size, to
+/// tell whether it changed since it was imported.
+fn file_stamp(path: &Path) -> std::io::Result<(i64, i64)> {
+    let metadata = std::fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as i64);
+    Ok((modified, metadata.len() as i64))
+}
+
+/// Escapes the wildcards of a `LIKE` pattern, so they match themselves.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Imports the bundled monsters into a database held in memory.
+    fn bundled() -> CompendiumDb {
+        let mut db = CompendiumDb::open_in_memory().unwrap();
+        db.sync(&[concat!(env!("CARGO_MANIFEST_DIR"), "/../data/monsters.json")]).unwrap();
+        db
+    }
+
+    /// Monsters are found by name or index, ignoring case.
+    #[test]
+    fn test_find() {
+        let db = bundled();
+        assert_eq!(db.find("goblin").unwrap().name, "Goblin");
+        assert_eq!(db.find("ADULT-RED-DRAGON").unwrap().name, "Adult Red Dragon");
+        assert!(db.find("Tarrasque Jr.").is_none());
+    }
+
+    /// Queries match the same monsters as they do in memory.
+    #[test]
+    fn test_query() {
+        use crate::monster::Type;
+
+        let db = bundled();
+        let query = MonsterQuery {
+            name: "ad".to_string(),
+            min_challenge: Some(10.0),
+            max_challenge: None,
+            kind: Some(Type::Dragon),
+        };
+        let found = db.query(&query);
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|m| query.matches(m)));
+        assert!(db.query(&MonsterQuery::name("100%")).is_empty());
+    }
+
+    /// Files that didn't change aren't imported again.
+    #[test]
+    fn test_sync_skips_unchanged_files() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/monsters.json");
+        let mut db = bundled();
+        let count = db.count();
+        assert_eq!(db.sync(&[path]).unwrap(), 0);
+        assert_eq!(db.count(), count);
+
+        db.sync(&[] as &[&str]).unwrap();
+        assert_eq!(db.count(), 0);
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// One term of a [`DiceExpression`], added or subtracted from the total.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Term {
+    /// `count` dice with `sides` sides each, e.g. `2d6`.
+    Dice { count: u32, sides: u32 },
+
+    /// A constant, e.g. `3`.
+    Constant(i32),
+}
+
+/// A sum of dice rolls and constants, such as `2d6 + 3` or `-5`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiceExpression {
+    /// The terms of the expression and whether each one is subtracted.
+    terms: Vec<(bool, Term)>,
+}
+
+impl DiceExpression {
+    /// Creates an expression rolling `count` dice with `sides` sides each, plus a constant (left
+    /// out if it is zero).
+    pub fn new(count: u32, sides: u32, constant: i32) -> Self {
+        let mut terms = vec![(false, Term::Dice { count, sides })];
+        if constant != 0 {
+            terms.push((constant < 0, Term::Constant(constant.abs())));
+        }
+        Self { terms }
+    }
+
+    /// Returns the expression for rolling this one `times` times and adding up the results, such
+    /// as `4d6 + 8` for 4 rolls of `1d6 + 2`.
+    pub fn times(&self, times: u32) -> Self {
+        let terms = self.terms
+            .iter()
+            .map(|&(negative, term)| (negative, match term {
+                Term::Dice { count, sides } => Term::Dice { count: count * times, sides },
+                Term::Constant(n) => Term::Constant(n * times as i32),
+            }))
+            .collect();
+        Self { terms }
+    }
+
+    /// Returns `true` if the expression has no dice, meaning it always has the same value.
+    pub fn is_constant(&self) -> bool {
+        self.terms.iter().all(|(_, term)| matches!(term, Term::Constant(_)))
+    }
+
+    /// Returns the smallest and largest values the expression can have.
+    pub fn range(&self) -> (i32, i32) {
+        self.terms.iter().fold((0, 0), |(min, max), (negative, term)| {
+            let (low, high) = match *term {
+                Term::Dice { count, sides } => (count as i32, count.saturating_mul(sides) as i32),
+                Term::Constant(n) => (n, n),
+            };
+            if *negative { (min - high, max - low) } else { (min + low, max + high) }
+        })
+    }
+
+    /// Evaluates the expression, rolling each die with `roll`, which is given the number of sides
+    /// of the die and returns a value between 1 and that number.
+    pub fn roll(&self, mut roll: impl FnMut(u32) -> u32) -> i32 {
+        self.terms
+            .iter()
+            .map(|(negative, term)| {
+                let value = match *term {
+                    Term::Dice { count, sides } => (0..count).map(|_| roll(sides) as i32).sum(),
+                    Term::Constant(n) => n,
+                };
+                if *negative { -value } else { value }
+            })
+            .sum()
+    }
+}
+
+impl FromStr for DiceExpression {
+    type Err = String;
+
+    /// Parses an expression such as `2d6+3`, `d20 - 1`, or `-5`. Spaces are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+        if input.is_empty() {
+            return Err("empty expression".to_string());
+        }
+
+        let mut terms = Vec::new();
+        let mut rest = input.as_str();
+        while !rest.is_empty() {
+            let negative = rest.starts_with('-');
+            rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+
+            let end = rest.find(['+', '-']).unwrap_or(rest.len());
+            let (term, tail) = rest.split_at(end);
+            rest = tail;
+
+            let invalid = || format!("invalid term `{}`", term);
+            let term = match term.split_once('d') {
+                Some((count, sides)) => Term::Dice {
+                    count: if count.is_empty() { 1 } else { count.parse().map_err(|_| invalid())? },
+                    sides: sides.parse().ok().filter(|&sides| sides > 0).ok_or_else(invalid)?,
+                },
+                None => Term::Constant(term.parse().map_err(|_| invalid())?),
+            };
+            terms.push((negative, term));
+        }
+
+        Ok(Self { terms })
+    }
+}
+
+impl fmt::Display for DiceExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (negative, term)) in self.terms.iter().enumerate() {
+            match (i, negative) {
+                (0, true) => write!(f, "-")?,
+                (0, false) => (),
+                (_, true) => write!(f, " - ")?,
+                (_, false) => write!(f, " + ")?,
+            }
+            match term {
+                Term::Dice { count, sides } => write!(f, "{}d{}", count, sides)?,
+                Term::Constant(n) => write!(f, "{}", n)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Expressions are stored as text, such as `"2d6 + 3"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for DiceExpression {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DiceExpression {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensure that expressions are parsed and rolled correctly, rolling the maximum every time.
+    #[test]
+    fn parse_and_roll() {
+        let expression = "2d6 + d4 - 3".parse::<DiceExpression>().unwrap();
+        assert_eq!(expression.to_string(), "2d6 + 1d4 - 3");
+        assert_eq!(expression.range(), (0, 13));
+        assert_eq!(expression.roll(|sides| sides), 13);
+        assert!(!expression.is_constant());
+
+        let expression = "-5".parse::<DiceExpression>().unwrap();
+        assert!(expression.is_constant());
+        assert_eq!(expression.roll(|_| unreachable!()), -5);
+
+        assert!("2d".parse::<DiceExpression>().is_err());
+        assert!("d0".parse::<DiceExpression>().is_err());
+        assert!("3+".parse::<DiceExpression>().is_err());
+        assert!("".parse::<DiceExpression>().is_err());
+    }
+}
@@ -0,0 +1,101 @@
+/// How hard an encounter is for the party, from the encounter building rules of the Dungeon
+/// Master's Guide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    /// Below the easy threshold.
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+    Deadly,
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The XP thresholds of a character of each level from 1 to 20, by level minus 1: easy, medium,
+/// hard, and deadly.
+const THRESHOLDS: [[i32; 4]; 20] = [
+    [25, 50, 75, 100],
+    [50, 100, 150, 200],
+    [75, 150, 225, 400],
+    [125, 250, 375, 500],
+    [250, 500, 750, 1100],
+    [300, 600, 900, 1400],
+    [350, 750, 1100, 1700],
+    [450, 900, 1400, 2100],
+    [550, 1100, 1600, 2400],
+    [600, 1200, 1900, 2800],
+    [800, 1600, 2400, 3600],
+    [1000, 2000, 3000, 4500],
+    [1100, 2200, 3400, 5100],
+    [1250, 2500, 3800, 5700],
+    [1400, 2800, 4300, 6400],
+    [1600, 3200, 4800, 7200],
+    [2000, 3900, 5900, 8800],
+    [2100, 4200, 6300, 9500],
+    [2400, 4900, 7300, 10900],
+    [2800, 5700, 8500, 12700],
+];
+
+/// The XP multipliers for the number of monsters, from the smallest to the largest.
+const MULTIPLIERS: [f32; 8] = [0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 4.0, 5.0];
+
+/// The threat the monsters still standing pose to the party.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Threat {
+    /// The XP of the monsters, multiplied for their number.
+    pub adjusted_xp: i32,
+
+    /// The party's easy, medium, hard, and deadly thresholds.
+    pub thresholds: [i32; 4],
+}
+
+impl Threat {
+    /// Rates monsters worth the given XP against a party of characters of the given levels.
+    /// Levels are clamped between 1 and 20.
+    ///
+    /// The XP is multiplied for the number of monsters, more for a small party (fewer than three
+    /// characters) and less for a large one (six or more).
+    pub fn new(monster_xp: &[i32], levels: &[u32]) -> Self {
+        let mut thresholds = [0; 4];
+        for level in levels {
+            let row = THRESHOLDS[(*level).clamp(1, 20) as usize - 1];
+            for (threshold, xp) in thresholds.iter_mut().zip(row) {
+                *threshold += xp;
+            }
+        }
+
+        let step = match monster_xp.len() {
+            0 | 1 => 1,
+            2 => 2,
+            3..=6 => 3,
+            7..=10 => 4,
+            11..=14 => 5,
+            _ => 6,
+        };
+        let step = match levels.len() {
+            0..3 => step + 1,
+            3..6 => step,
+            _ => step - 1,
+        };
+        let total = monster_xp.iter().sum::<i32>();
+        let adjusted_xp = (total as f32 * MULTIPLIERS[step]) as i32;
+        Self { adjusted_xp, thresholds }
+    }
+
+    /// Returns the difficulty of the highest threshold the adjusted XP reaches.
+    pub fn difficulty(&self) -> Difficulty {
+        let reached = self.thresholds.iter().filter(|threshold| self.adjusted_xp >= **threshold);
+        match reached.count() {
+            0 => Difficulty::Trivial,
+            1 => Difficulty::Easy,
+            2 => Difficulty::Medium,
+            3 => Difficulty::Hard,
+            _ => Difficulty::Deadly,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{Combatant, CombatantKind};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Changes made by hand to a combatant's stats, such as fixing a typo in its name or adjusting a
+/// monster mid-fight. Fields left as [`None`] keep their values.
+///
+/// The fields that only some kinds of combatants have are ignored for the others.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CombatantEdit {
+    /// The combatant's new name.
+    pub name: Option<String>,
+
+    /// The combatant's new maximum hit points.
+    pub max_hit_points: Option<i32>,
+
+    /// The combatant's new armor class.
+    pub armor_class: Option<u32>,
+
+    /// The monster's new challenge rating, which also changes the XP it is worth if its stat
+    /// block doesn't give any.
+    pub challenge_rating: Option<f32>,
+
+    /// The player character's new level, which also changes their proficiency bonus.
+    pub level: Option<u32>,
+}
+
+impl CombatantEdit {
+    /// Make the changes to the given combatant. Returns `false` if nothing changed.
+    pub fn apply(&self, combatant: &mut Combatant) -> bool {
+        /// Replace a value with the new one, if any, returning `true` if it changed.
+        fn set<T: Clone + PartialEq>(value: &mut T, new: &Option<T>) -> bool {
+            match new {
+                Some(new) if value != new => {
+                    *value = new.clone();
+                    true
+                },
+                _ => false,
+            }
+        }
+
+        match &mut combatant.kind {
+            CombatantKind::Monster(monster) => {
+                set(&mut monster.name, &self.name)
+                    | set(&mut monster.hit_points, &self.max_hit_points)
+                    | set(&mut monster.armor_class.value, &self.armor_class)
+                    | set(&mut monster.challenge_rating, &self.challenge_rating)
+            },
+            CombatantKind::Character(character) => {
+                set(&mut character.name, &self.name)
+                    | set(&mut character.hit_points, &self.max_hit_points)
+                    | set(&mut character.armor_class, &self.armor_class)
+                    | set(&mut character.level, &self.level)
+            },
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{AbilityKind, ConditionKind, ConditionSource, DamageType, DiceExpression};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Something that happens to a combatant on its own turn, such as repeating a saving throw to end
+/// a spell, or taking ongoing damage from an Immolation spell.
+///
+/// Effects are attached to a combatant when they are applied, along with the condition they
+/// cause (if any), which ends with the effect.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Effect {
+    /// When the effect triggers.
+    pub trigger: Trigger,
+
+    /// What happens when the effect triggers.
+    pub outcome: Outcome,
+
+    /// The condition caused by the effect, which ends with it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub condition: Option<ConditionKind>,
+
+    /// What applied the effect, recorded as the source of its condition.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source: ConditionSource,
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.outcome, self.trigger)?;
+        match (&self.outcome, self.condition) {
+            (Outcome::Save { .. }, Some(condition)) => write!(f, ", ends {}", condition),
+            (_, Some(condition)) => write!(f, " ({})", condition),
+            (_, None) => Ok(()),
+        }
+    }
+}
+
+/// When an [`Effect`] triggers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Trigger {
+    /// At the start of the combatant's turn, once its actions are restored.
+    StartOfTurn,
+
+    /// At the end of the combatant's turn.
+    EndOfTurn,
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trigger::StartOfTurn => write!(f, "at start of turn"),
+            Trigger::EndOfTurn => write!(f, "at end of turn"),
+        }
+    }
+}
+
+/// What happens when an [`Effect`] triggers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Outcome {
+    /// The combatant repeats a saving throw, and the effect ends if it succeeds. The table rolls
+    /// the save, so the tracker only asks for the result.
+    Save { ability: AbilityKind, dc: u32 },
+
+    /// The combatant takes damage, applying its resistances, vulnerabilities, and immunities.
+    /// Damage with dice is rolled by the table (or the UI), constant damage is taken right away.
+    Damage { amount: DiceExpression, kind: Option<DamageType> },
+
+    /// The effect ends.
+    End,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Save { ability, dc } => write!(f, "DC {} {} save", dc, ability),
+            Outcome::Damage { amount, kind: Some(kind) } => {
+                write!(f, "{} {} damage", amount, kind.name())
+            },
+            Outcome::Damage { amount, kind: None } => write!(f, "{} damage", amount),
+            Outcome::End => write!(f, "ends"),
+        }
+    }
+}
+
+/// Hit points a combatant regains at the start of each of its turns, such as a troll's.
+///
+/// Some damage types stop the regeneration on the combatant's next turn, which can also be
+/// stopped by hand for anything else that stops it, such as a vampire in sunlight.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Regeneration {
+    /// The hit points regained.
+    pub amount: i32,
+
+    /// Whether the combatant needs at least 1 hit point to regenerate.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub needs_hit_points: bool,
+
+    /// The damage types that stop the regeneration on the combatant's next turn.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub suppressed_by: Vec<DamageType>,
+
+    /// Whether the regeneration doesn't work at the start of the combatant's next turn.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub suppressed: bool,
+}
+
+impl Regeneration {
+    /// Reads the regeneration described by a monster's Regeneration trait, written as in the SRD:
+    ///
+    /// > The troll regains 10 hit points at the start of its turn. If the troll takes acid or fire
+    /// > damage, this trait doesn't function at the start of the troll's next turn.
+    ///
+    /// Returns [`None`] if the description doesn't say how many hit points are regained.
+    pub fn parse(description: &str) -> Option<Self> {
+        let description = description.to_ascii_lowercase();
+        let (_, regained) = description.split_once("regains ")?;
+        let amount = regained.split_whitespace().next()?.parse().ok()?;
+
+        // the damage types named in the sentence about taking damage
+        let suppressed_by = description
+            .split_once(" takes ")
+            .and_then(|(_, rest)| rest.split(['.', ',']).next())
+            .map(|sentence| {
+                DamageType::ALL.into_iter().filter(|kind| sentence.contains(kind.name())).collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            amount,
+            needs_hit_points: description.contains("at least 1 hit"),
+            suppressed_by,
+            suppressed: false,
+        })
+    }
+}
+
+/// An effect that triggered, but needs the table to resolve it, such as a saving throw to roll.
+///
+/// The tracker collects these in [`Tracker::triggered`](crate::Tracker::triggered), for the UI
+/// to ask about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Triggered {
+    /// The index of the combatant the effect is on.
+    pub index: usize,
+
+    /// The index of the effect among the combatant's effects.
+    pub effect: usize,
+
+    /// The effect itself, as it was when it triggered.
+    pub details: Effect,
+}
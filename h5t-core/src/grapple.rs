@@ -0,0 +1,42 @@
+use crate::ConditionKind;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// One combatant holding another, such as an ogre grappling a fighter, or a giant constrictor
+/// snake restraining a creature in its coils.
+///
+/// The target has the condition for as long as the grapple lasts. Either side breaking it ends
+/// it on both: the grappler lets go, or the target escapes (with the escape DC, if any).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Grapple {
+    /// The index of the combatant holding the target.
+    pub grappler: usize,
+
+    /// The index of the combatant being held.
+    pub target: usize,
+
+    /// Whether the target is restrained, rather than only grappled.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub restrains: bool,
+
+    /// The DC of the check the target makes to escape, if the grappler's stat block gives one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub escape_dc: Option<u32>,
+}
+
+impl Grapple {
+    /// Returns the condition the target has while held.
+    pub fn condition(&self) -> ConditionKind {
+        if self.restrains {
+            ConditionKind::Restrained
+        } else {
+            ConditionKind::Grappled
+        }
+    }
+
+    /// Returns `true` if the combatant with the given index is either side of the grapple.
+    pub fn involves(&self, index: usize) -> bool {
+        self.grappler == index || self.target == index
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A square of the battle grid, counted in squares from the grid's origin, with `x` going right
+/// and `y` going down.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Position {
+    /// The column of the square.
+    pub x: i32,
+
+    /// The row of the square.
+    pub y: i32,
+}
+
+impl Position {
+    /// The width of a square, in feet.
+    pub const SQUARE_FEET: u32 = 5;
+
+    /// Create a new [`Position`] at the given column and row.
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the position moved by the given number of squares.
+    pub fn offset(self, dx: i32, dy: i32) -> Self {
+        Self { x: self.x + dx, y: self.y + dy }
+    }
+
+    /// Returns the distance to another position, in feet.
+    ///
+    /// Moving diagonally costs as much as moving straight, as in the standard rules, so the
+    /// distance is the larger of the horizontal and vertical distances, in squares.
+    pub fn distance(self, other: Position) -> u32 {
+        let squares = self.x.abs_diff(other.x).max(self.y.abs_diff(other.y));
+        squares * Self::SQUARE_FEET
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::monster::Size;
+use crate::{Combatant, Side};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Something that changes a combatant's initiative rolls, besides its Dexterity modifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum InitiativeModifier {
+    /// A flat bonus (or penalty), such as the +5 from the Alert feat.
+    Bonus(i32),
+
+    /// Half the proficiency bonus, rounded down, such as from a bard's Jack of All Trades.
+    HalfProficiency,
+
+    /// Advantage on the roll, such as from a barbarian's Feral Instinct.
+    Advantage,
+}
+
+impl fmt::Display for InitiativeModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitiativeModifier::Bonus(bonus) => write!(f, "{:+}", bonus),
+            InitiativeModifier::HalfProficiency => write!(f, "half proficiency"),
+            InitiativeModifier::Advantage => write!(f, "advantage"),
+        }
+    }
+}
+
+/// Modifiers are parsed from a signed or unsigned number for a bonus, `half` (or `jack`) for
+/// half the proficiency bonus, and `adv` (or `advantage`) for advantage.
+impl std::str::FromStr for InitiativeModifier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "half" | "jack" => Ok(InitiativeModifier::HalfProficiency),
+            "adv" | "advantage" => Ok(InitiativeModifier::Advantage),
+            bonus => bonus
+                .parse()
+                .map(InitiativeModifier::Bonus)
+                .map_err(|_| format!("unknown initiative modifier `{}`", s)),
+        }
+    }
+}
+
+/// How turns pass from one combatant to the next.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TurnOrder {
+    /// Every combatant takes its turn in initiative order.
+    #[default]
+    Individual,
+
+    /// The side initiative variant from the Dungeon Master's Guide: every combatant on a side
+    /// takes its turn, in initiative order, before the next side goes.
+    ///
+    /// The sides go in the given order, with any side left out going last, in [`Side`] order.
+    Sides(Vec<Side>),
+
+    /// Popcorn (or elective) initiative: after each turn, the combatant who took it picks who
+    /// goes next, among those who haven't acted this round. Once everyone has, the last one picks
+    /// who starts the next round, themselves included.
+    Popcorn,
+
+    /// The speed factor variant from the Dungeon Master's Guide: at the start of each round,
+    /// every combatant declares what it will do, then rolls initiative again, adjusted by its
+    /// [speed factors](SpeedFactor) and size. Turns go from the highest roll to the lowest.
+    SpeedFactor,
+}
+
+impl TurnOrder {
+    /// Returns the indexes of the combatants in the order they take their turns each round, given
+    /// the combatant whose turn it is and the ones who took their turns earlier this round, in
+    /// the order they took them.
+    ///
+    /// In popcorn initiative, the order isn't known ahead of time, so the combatants who haven't
+    /// acted yet follow the current one in initiative order.
+    pub fn sequence(&self, combatants: &[Combatant], turn: usize, acted: &[usize]) -> Vec<usize> {
+        let mut sequence = (0..combatants.len()).collect::<Vec<_>>();
+        match self {
+            TurnOrder::Individual => (),
+            TurnOrder::Sides(sides) => sequence.sort_by_key(|&index| {
+                let side = combatants[index].side;
+                (sides.iter().position(|s| *s == side).unwrap_or(sides.len()), side)
+            }),
+            TurnOrder::SpeedFactor => {
+                sequence.sort_by_key(|&index| std::cmp::Reverse(combatants[index].initiative));
+            },
+            TurnOrder::Popcorn => sequence.sort_by_key(|&index| {
+                match acted.iter().position(|i| *i == index) {
+                    Some(position) => (0, position),
+                    None if index == turn => (1, 0),
+                    None => (2, 0),
+                }
+            }),
+        }
+        sequence
+    }
+}
+
+impl fmt::Display for TurnOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TurnOrder::Individual => write!(f, "individual initiative"),
+            TurnOrder::Sides(sides) if sides.is_empty() => write!(f, "side initiative"),
+            TurnOrder::Sides(sides) => {
+                let sides = sides.iter().map(Side::to_string).collect::<Vec<_>>();
+                write!(f, "side initiative ({})", sides.join(", "))
+            },
+            TurnOrder::Popcorn => write!(f, "popcorn initiative"),
+            TurnOrder::SpeedFactor => write!(f, "speed factor initiative"),
+        }
+    }
+}
+
+/// An action declared at the start of a round in [speed factor initiative](TurnOrder::SpeedFactor),
+/// which makes the combatant act sooner or later that round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SpeedFactor {
+    /// Attacking with a heavy melee weapon.
+    HeavyWeapon,
+
+    /// Attacking with a light or finesse melee weapon.
+    LightWeapon,
+
+    /// Attacking with a two-handed melee weapon.
+    TwoHandedWeapon,
+
+    /// Attacking with a ranged weapon with the loading property.
+    LoadingWeapon,
+
+    /// Casting a spell of the given level.
+    CastSpell(u32),
+}
+
+impl SpeedFactor {
+    /// Every speed factor, with spells of levels 1 to 9. Cantrips don't change initiative.
+    pub const ALL: [SpeedFactor; 13] = [
+        SpeedFactor::HeavyWeapon,
+        SpeedFactor::LightWeapon,
+        SpeedFactor::TwoHandedWeapon,
+        SpeedFactor::LoadingWeapon,
+        SpeedFactor::CastSpell(1),
+        SpeedFactor::CastSpell(2),
+        SpeedFactor::CastSpell(3),
+        SpeedFactor::CastSpell(4),
+        SpeedFactor::CastSpell(5),
+        SpeedFactor::CastSpell(6),
+        SpeedFactor::CastSpell(7),
+        SpeedFactor::CastSpell(8),
+        SpeedFactor::CastSpell(9),
+    ];
+
+    /// Returns the modifier to the initiative roll, from the speed factor table.
+    pub fn modifier(self) -> i32 {
+        match self {
+            SpeedFactor::HeavyWeapon | SpeedFactor::TwoHandedWeapon => -2,
+            SpeedFactor::LightWeapon => 2,
+            SpeedFactor::LoadingWeapon => -5,
+            SpeedFactor::CastSpell(level) => -(level as i32),
+        }
+    }
+
+    /// Returns the modifier to the initiative roll of a creature of the given size, from the
+    /// speed factor table.
+    pub fn size_modifier(size: &Size) -> i32 {
+        match size {
+            Size::Tiny => 5,
+            Size::Small => 2,
+            Size::Medium => 0,
+            Size::Large => -2,
+            Size::Huge => -5,
+            Size::Gargantuan => -8,
+        }
+    }
+}
+
+impl fmt::Display for SpeedFactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpeedFactor::HeavyWeapon => write!(f, "Heavy melee weapon")?,
+            SpeedFactor::LightWeapon => write!(f, "Light or finesse melee weapon")?,
+            SpeedFactor::TwoHandedWeapon => write!(f, "Two-handed melee weapon")?,
+            SpeedFactor::LoadingWeapon => write!(f, "Ranged weapon with loading")?,
+            SpeedFactor::CastSpell(level) => write!(f, "Level {} spell", level)?,
+        }
+        write!(f, " ({:+})", self.modifier())
+    }
+}
@@ -1,15 +1,68 @@
 pub mod ability;
+pub mod character;
+pub mod command;
+pub mod compendium;
 pub mod condition;
+pub mod damage;
+#[cfg(feature = "sqlite")]
+pub mod database;
+pub mod difficulty;
+pub mod dice;
+pub mod edit;
+pub mod effect;
+pub mod grapple;
+pub mod grid;
+pub mod initiative;
+pub mod log;
+pub mod loot;
+pub mod mob;
 pub mod monster;
+pub mod morale;
+pub mod rules;
+pub mod session;
+pub mod stats;
+pub mod subscriber;
+pub mod summon;
+pub mod xp;
 
-use ability::Modifier;
-pub use ability::{Ability, score_to_modifier};
-pub use condition::{Condition, ConditionKind, ConditionDuration};
+use ability::{Modifier, Score};
+pub use ability::{Ability, AbilityKind, score_to_modifier};
+pub use character::Character;
+pub use command::{Changed, TrackerCommand};
+pub use compendium::{Compendium, MonsterQuery, MonsterSource};
+pub use condition::{
+    Condition, ConditionKind, ConditionDuration, ConditionSource, Lingering, Orphaned,
+};
+pub use damage::{DamageModifier, DamageType};
+#[cfg(feature = "sqlite")]
+pub use database::{CompendiumDb, DatabaseError};
+pub use dice::DiceExpression;
+pub use edit::CombatantEdit;
+pub use difficulty::{Difficulty, Threat};
+pub use effect::{Effect, Outcome, Regeneration, Trigger, Triggered};
+pub use grapple::Grapple;
+pub use grid::Position;
+pub use initiative::{InitiativeModifier, SpeedFactor, TurnOrder};
+pub use log::{LogEntry, LogEvent};
+pub use loot::{Coin, Coins};
+pub use mob::MobAttack;
 pub use monster::Monster;
-use monster::Speed;
+pub use morale::{MoraleCheck, MoraleReason};
+pub use rules::{Edition, Rules};
+pub use session::Session;
+pub use stats::CombatStats;
+pub use subscriber::{SubscriptionId, TrackerEvent};
+pub use summon::{Dismissal, DismissalReason};
+pub use xp::XpAward;
+use subscriber::Subscribers;
+use monster::{Senses, Speed};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// The number of actions, bonus actions, and reactions a combatant has.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Action {
     pub actions: u32,
     pub bonus_actions: u32,
@@ -27,10 +80,46 @@ impl Default for Action {
     }
 }
 
+/// The side of the fight a combatant is on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Side {
+    /// On the players' side.
+    Ally,
+
+    /// Not taking either side.
+    Neutral,
+
+    /// Fighting against the players.
+    #[default]
+    Enemy,
+}
+
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::str::FromStr for Side {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ally" => Ok(Side::Ally),
+            "neutral" => Ok(Side::Neutral),
+            "enemy" => Ok(Side::Enemy),
+            _ => Err(format!("unknown side `{}`", s)),
+        }
+    }
+}
+
 /// A combatant in the initiative tracker.
 ///
 /// Combatants can include player characters, monsters, NPCs, etc.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Combatant {
     /// The kind of combatant.
     pub kind: CombatantKind,
@@ -41,14 +130,106 @@ pub struct Combatant {
     /// The combatant's current hit points.
     pub hit_points: i32,
 
+    /// The combatant's temporary hit points, which are lost before its hit points.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub temporary_hit_points: i32,
+
     /// The actions available to the combatant.
     pub actions: Action,
+
+    /// The side of the fight the combatant is on.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub side: Side,
+
+    /// Free-form notes about the combatant, written during play.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub notes: String,
+
+    /// The combatant's initiative roll, if one was entered.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub initiative: Option<i32>,
+
+    /// The effects that trigger on the combatant's turns, in the order they were applied.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub effects: Vec<Effect>,
+
+    /// The hit points the combatant regains at the start of its turns, if it regenerates.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub regeneration: Option<Regeneration>,
+
+    /// Whether the combatant has Inspiration. Only player characters can have it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inspiration: bool,
+
+    /// The number of hit dice the combatant spent since its last long rest. Only player
+    /// characters have hit dice.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hit_dice_spent: u32,
+
+    /// What changes the combatant's initiative rolls, besides its Dexterity modifier.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub initiative_modifiers: Vec<InitiativeModifier>,
+
+    /// What the combatant declared it will do this round, in speed factor initiative.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub speed_factors: Vec<SpeedFactor>,
+
+    /// The combatant's level of exhaustion, from 0 to [`Edition::MAX_EXHAUSTION`]. It has the
+    /// exhaustion condition at any level above 0.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub exhaustion: u32,
+
+    /// Whether the combatant is surprised, until the end of its first turn.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub surprised: bool,
+
+    /// Whether the combatant is a minion, which drops to any damage with [`Rules::minions`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub minion: bool,
+
+    /// Whether the combatant is concentrating on a spell.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub concentrating: bool,
+
+    /// The index of the combatant that summoned this one, if any, such as the cleric casting a
+    /// spiritual weapon.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub summoner: Option<usize>,
+
+    /// The zone (or range band) the combatant is in, if any, such as "Bridge" or "Far", to place
+    /// combatants without a map.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub zone: Option<String>,
+
+    /// The square of the battle grid the combatant stands on, if it was placed on the grid.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub position: Option<Position>,
+
+    /// Whether the combatant leads its side, whose followers check their morale when it drops
+    /// with [`Rules::morale`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub leader: bool,
+
+    /// Whether the combatant is fleeing the fight, having failed a morale check.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fleeing: bool,
+
+    /// Whether the combatant is stable at 0 hit points, and stops making death saving throws.
+    /// Taking damage or regaining hit points ends it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stable: bool,
+
+    /// Whether the combatant was marked dead, rather than dropping to 0 hit points. Dead
+    /// combatants aren't healed, until their hit points are set above 0 again.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dead: bool,
 }
 
 impl From<CombatantKind> for Combatant {
     fn from(kind: CombatantKind) -> Self {
         match kind {
             CombatantKind::Monster(monster) => monster.into(),
+            CombatantKind::Character(character) => character.into(),
         }
     }
 }
@@ -58,6 +239,7 @@ impl Combatant {
     pub fn name(&self) -> &str {
         match &self.kind {
             CombatantKind::Monster(monster) => &monster.name,
+            CombatantKind::Character(character) => &character.name,
         }
     }
 
@@ -65,6 +247,23 @@ impl Combatant {
     pub fn armor_class(&self) -> u32 {
         match &self.kind {
             CombatantKind::Monster(monster) => monster.armor_class.value,
+            CombatantKind::Character(character) => character.armor_class,
+        }
+    }
+
+    /// Returns the combatant's passive Perception score.
+    pub fn passive_perception(&self) -> Score {
+        match &self.kind {
+            CombatantKind::Monster(monster) => monster.passive_perception(),
+            CombatantKind::Character(character) => character.passive_scores().0,
+        }
+    }
+
+    /// Returns the combatant's special senses.
+    pub fn senses(&self) -> &Senses {
+        match &self.kind {
+            CombatantKind::Monster(monster) => &monster.senses,
+            CombatantKind::Character(character) => &character.senses,
         }
     }
 
@@ -72,6 +271,7 @@ impl Combatant {
     pub fn speed(&self) -> &Speed {
         match &self.kind {
             CombatantKind::Monster(monster) => &monster.speed,
+            CombatantKind::Character(character) => &character.speed,
         }
     }
 
@@ -79,6 +279,24 @@ impl Combatant {
     pub fn max_hit_points(&self) -> i32 {
         match &self.kind {
             CombatantKind::Monster(monster) => monster.hit_points,
+            CombatantKind::Character(character) => character.hit_points,
+        }
+    }
+
+    /// Returns `true` if the combatant is bloodied: still standing, but at or below half its
+    /// maximum hit points.
+    pub fn is_bloodied(&self) -> bool {
+        self.hit_points > 0 && self.hit_points * 2 <= self.max_hit_points()
+    }
+
+    /// Returns the number of hit dice the combatant has left to spend, one per level for player
+    /// characters, and none for monsters.
+    pub fn hit_dice_left(&self) -> u32 {
+        match &self.kind {
+            CombatantKind::Monster(_) => 0,
+            CombatantKind::Character(character) => {
+                character.level.max(1).saturating_sub(self.hit_dice_spent)
+            },
         }
     }
 
@@ -86,22 +304,112 @@ impl Combatant {
     pub fn proficiency_bonus(&self) -> Modifier {
         match &self.kind {
             CombatantKind::Monster(monster) => monster.proficiency_bonus,
+            CombatantKind::Character(character) => character.proficiency_bonus(),
         }
     }
 
+    /// Returns the bonus added to the combatant's initiative rolls: its Dexterity modifier, plus
+    /// its bonuses from [`Combatant::initiative_modifiers`].
+    pub fn initiative_bonus(&self) -> Modifier {
+        let dexterity = match &self.kind {
+            CombatantKind::Monster(monster) => monster.scores.dexterity,
+            CombatantKind::Character(character) => character.scores.dexterity,
+        };
+        let bonuses = self.initiative_modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                InitiativeModifier::Bonus(bonus) => *bonus,
+                _ => 0,
+            })
+            .sum::<Modifier>();
+        let jack_of_all_trades = InitiativeModifier::HalfProficiency;
+        let half_proficiency = if self.initiative_modifiers.contains(&jack_of_all_trades) {
+            self.proficiency_bonus() / 2
+        } else {
+            0
+        };
+        score_to_modifier(dexterity) + bonuses + half_proficiency
+    }
+
+    /// Rolls the combatant's initiative, rolling the d20 with `roll` and adding its
+    /// [initiative bonus](Combatant::initiative_bonus).
+    ///
+    /// With advantage, the d20 is rolled twice, keeping the higher roll. In the 2024 rules,
+    /// surprised combatants roll with disadvantage instead, keeping the lower roll; both cancel
+    /// out.
+    pub fn roll_initiative(&self, edition: Edition, mut roll: impl FnMut(u32) -> u32) -> i32 {
+        let advantage = self.initiative_modifiers.contains(&InitiativeModifier::Advantage);
+        let disadvantage = self.surprised && edition == Edition::Rules2024;
+        let d20 = match (advantage, disadvantage) {
+            (true, false) => roll(20).max(roll(20)),
+            (false, true) => roll(20).min(roll(20)),
+            _ => roll(20),
+        };
+        d20 as i32 + self.initiative_bonus()
+    }
+
+    /// Rolls the combatant's initiative for a round of
+    /// [speed factor initiative](TurnOrder::SpeedFactor), like [`Combatant::roll_initiative`],
+    /// adding the modifiers of its size and the speed factors it declared.
+    pub fn roll_speed_factor(&self, edition: Edition, roll: impl FnMut(u32) -> u32) -> i32 {
+        let size = match &self.kind {
+            CombatantKind::Monster(monster) => SpeedFactor::size_modifier(&monster.size),
+            CombatantKind::Character(_) => 0,
+        };
+        let declared = self.speed_factors.iter().map(|factor| factor.modifier()).sum::<i32>();
+        self.roll_initiative(edition, roll) + size + declared
+    }
+
     /// Damage the combatant by the given amount.
     ///
     /// The amount will not saturate to 0, meaning the combatant can have negative hit points.
     pub fn damage(&mut self, amount: i32) {
         self.hit_points -= amount;
     }
+
+    /// Returns how the combatant is affected by the given damage type.
+    pub fn damage_modifier(&self, kind: DamageType) -> DamageModifier {
+        match &self.kind {
+            CombatantKind::Monster(monster) => monster.damage_modifier(kind),
+            CombatantKind::Character(_) => DamageModifier::Normal,
+        }
+    }
+
+    /// Returns `true` if the given condition can't be applied to the combatant.
+    pub fn is_immune_to(&self, kind: ConditionKind) -> bool {
+        match &self.kind {
+            CombatantKind::Monster(monster) => monster.condition_immunities.contains(&kind),
+            CombatantKind::Character(_) => false,
+        }
+    }
+
+    /// Damage the combatant by the given amount of damage of the given type, applying its
+    /// resistances, vulnerabilities, and immunities. Returns the damage actually taken.
+    ///
+    /// Damage without a type is always taken in full. The combatant's temporary hit points are
+    /// lost first, and the rest of the damage comes off its hit points.
+    pub fn take_damage(&mut self, amount: i32, kind: Option<DamageType>) -> i32 {
+        let taken = kind
+            .map(|kind| self.damage_modifier(kind).apply(amount))
+            .unwrap_or(amount);
+        let absorbed = taken.clamp(0, self.temporary_hit_points.max(0));
+        self.temporary_hit_points -= absorbed;
+        self.damage(taken - absorbed);
+        taken
+    }
 }
 
 /// A kind of combatant.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[allow(clippy::large_enum_variant)] // most combatants are monsters, boxing them gains nothing
 pub enum CombatantKind {
     /// Pre-made monster.
     Monster(Monster),
+
+    /// Player character.
+    Character(Character),
 }
 
 impl From<Monster> for CombatantKind {
@@ -110,13 +418,72 @@ impl From<Monster> for CombatantKind {
     }
 }
 
+impl From<Character> for CombatantKind {
+    fn from(character: Character) -> Self {
+        Self::Character(character)
+    }
+}
+
+impl From<Character> for Combatant {
+    fn from(character: Character) -> Self {
+        Self {
+            hit_points: character.hit_points,
+            temporary_hit_points: 0,
+            conditions: Vec::new(),
+            kind: character.into(),
+            actions: Action::default(),
+            side: Side::Ally,
+            notes: String::new(),
+            initiative: None,
+            effects: Vec::new(),
+            regeneration: None,
+            inspiration: false,
+            hit_dice_spent: 0,
+            initiative_modifiers: Vec::new(),
+            speed_factors: Vec::new(),
+            exhaustion: 0,
+            surprised: false,
+            minion: false,
+            concentrating: false,
+            summoner: None,
+            zone: None,
+            position: None,
+            leader: false,
+            fleeing: false,
+            stable: false,
+            dead: false,
+        }
+    }
+}
+
 impl From<Monster> for Combatant {
     fn from(monster: Monster) -> Self {
         Self {
             hit_points: monster.hit_points,
+            temporary_hit_points: 0,
             conditions: Vec::new(),
+            regeneration: monster.regeneration(),
             kind: monster.into(),
             actions: Action::default(),
+            side: Side::default(),
+            notes: String::new(),
+            initiative: None,
+            effects: Vec::new(),
+            inspiration: false,
+            hit_dice_spent: 0,
+            initiative_modifiers: Vec::new(),
+            speed_factors: Vec::new(),
+            exhaustion: 0,
+            surprised: false,
+            minion: false,
+            concentrating: false,
+            summoner: None,
+            zone: None,
+            position: None,
+            leader: false,
+            fleeing: false,
+            stable: false,
+            dead: false,
         }
     }
 }
@@ -126,6 +493,7 @@ impl From<Monster> for Combatant {
 /// It handles the order of play and tracks every important detail, such as the current turn,
 /// conditions on each combatant, actions taken, etc.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Tracker {
     /// The index of the combatant that is taking their turn.
     pub turn: usize,
@@ -135,22 +503,542 @@ pub struct Tracker {
 
     /// The list of combatants.
     pub combatants: Vec<Combatant>,
+
+    /// The combat log, from oldest to newest event.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub log: Vec<LogEntry>,
+
+    /// The name of the combatant who took the first turn, noted when the turn passes for the
+    /// first time, since the log doesn't say whose turn started the combat.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub opening: Option<String>,
+
+    /// Free-form notes about the encounter, written during play.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub notes: String,
+
+    /// Named groups of combatants (such as the party), by the indexes of their members, so the
+    /// same combatants can be targeted again quickly.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub groups: BTreeMap<String, Vec<usize>>,
+
+    /// The combatants holding other combatants, from oldest to newest.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub grapples: Vec<Grapple>,
+
+    /// How turns pass from one combatant to the next, chosen when setting up the encounter.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub turn_order: TurnOrder,
+
+    /// Whether copies of the same monster next to each other in the turn sequence take their
+    /// turns together, as one turn.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub combined_turns: bool,
+
+    /// The indexes of the combatants who took their turns earlier this round, in the order they
+    /// took them. The combatant whose turn it is isn't counted until its turn ends.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub acted: Vec<usize>,
+
+    /// The in-game time that passed, in seconds: [`Tracker::ROUND_SECONDS`] for each round, plus
+    /// the time added by hand, such as rests between encounters.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub elapsed: u64,
+
+    /// The optional rules in play. These are chosen by the table rather than the encounter, so
+    /// they aren't saved with it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub rules: Rules,
+
+    /// Effects that triggered but need the table to resolve them, such as saving throws to
+    /// roll, from oldest to newest. They are left for the UI to take and ask about.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub triggered: Vec<Triggered>,
+
+    /// Conditions left behind by conditions that ended, from oldest to newest. They are left for
+    /// the UI to take and offer to clear.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub lingering: Vec<Lingering>,
+
+    /// Conditions whose source dropped, from oldest to newest. They are left for the UI to take
+    /// and offer to clear.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub orphaned: Vec<Orphaned>,
+
+    /// Summons whose summoner stopped concentrating or dropped, from oldest to newest. They are
+    /// left for the UI to take and offer to remove.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub dismissals: Vec<Dismissal>,
+
+    /// Monsters that have to check their morale with [`Rules::morale`], from oldest to newest.
+    /// They are left for the UI to take and offer to roll.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub morale_checks: Vec<MoraleCheck>,
+
+    /// The callbacks told about every change made with [`Tracker::execute`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    subscribers: Subscribers,
 }
 
 impl Tracker {
+    /// The in-game time a round of combat takes, in seconds.
+    pub const ROUND_SECONDS: u64 = 6;
+
     /// Create a new initiative tracker with the given combatants.
     pub fn new(combatants: impl Into<Vec<Combatant>>) -> Self {
         Self {
             turn: 0,
             round: 0,
             combatants: combatants.into(),
+            log: Vec::new(),
+            opening: None,
+            notes: String::new(),
+            groups: BTreeMap::new(),
+            grapples: Vec::new(),
+            turn_order: TurnOrder::default(),
+            combined_turns: false,
+            acted: Vec::new(),
+            elapsed: 0,
+            rules: Rules::default(),
+            triggered: Vec::new(),
+            lingering: Vec::new(),
+            orphaned: Vec::new(),
+            dismissals: Vec::new(),
+            morale_checks: Vec::new(),
+            subscribers: Subscribers::default(),
+        }
+    }
+
+    /// Make a change to the encounter. This is the path every change made by the UI goes through.
+    ///
+    /// Returns `false` if the command couldn't be carried out or wouldn't change anything, such as
+    /// removing the last combatant, using an action with none left, applying a condition the
+    /// combatant already has, or referring to a combatant that isn't in the initiative order.
+    /// Otherwise, the change is passed to every subscriber.
+    pub fn execute(&mut self, command: TrackerCommand) -> bool {
+        if !self.in_range(&command) {
+            return false;
+        }
+
+        let start = self.log.len();
+        let changed = match &command {
+            TrackerCommand::Damage { amount: 0, .. } => false,
+            TrackerCommand::Damage { index, amount, kind } => {
+                self.damage(*index, *amount, *kind);
+                true
+            },
+            TrackerCommand::Heal { index, amount } => self.heal(*index, *amount) > 0,
+            TrackerCommand::GrantTemporaryHitPoints { index, amount } => {
+                self.grant_temporary_hit_points(*index, *amount)
+            },
+            TrackerCommand::SetHitPoints { index, value } => self.set_hit_points(*index, *value),
+            TrackerCommand::AddCondition { index, kind, duration } => {
+                self.apply_condition(*index, *kind, *duration)
+            },
+            TrackerCommand::AddConditions { indexes, kinds, duration, source } => {
+                self.apply_conditions(indexes, kinds, *duration, source)
+            },
+            TrackerCommand::RemoveCondition { index, kind } => {
+                self.remove_condition(*index, *kind)
+            },
+            TrackerCommand::AddEffect { index, effect } => {
+                self.add_effect(*index, effect.clone());
+                true
+            },
+            TrackerCommand::EndEffect { index, effect } => self.end_effect(*index, *effect),
+            TrackerCommand::SuppressRegeneration { index, suppressed } => {
+                self.suppress_regeneration(*index, *suppressed)
+            },
+            TrackerCommand::Grapple(grapple) => self.grapple(grapple.clone()),
+            TrackerCommand::Release { index } => self.release(*index),
+            TrackerCommand::NextTurn => {
+                self.next_turn();
+                true
+            },
+            TrackerCommand::PassTurn { next } => self.pass_turn(*next),
+            TrackerCommand::UseAction => self.use_action(),
+            TrackerCommand::UseBonusAction => self.use_bonus_action(),
+            TrackerCommand::UseReaction => self.use_reaction(),
+            TrackerCommand::Add(combatant) => {
+                self.add(combatant.as_ref().clone());
+                true
+            },
+            TrackerCommand::Remove { index } => self.remove(*index).is_some(),
+            TrackerCommand::SaveGroup { name, members } => {
+                self.save_group(name.clone(), members.clone());
+                true
+            },
+            TrackerCommand::SetSide { index, side } => {
+                let combatant = &mut self.combatants[*index];
+                let moved = combatant.side != *side;
+                combatant.side = *side;
+                moved
+            },
+            TrackerCommand::SetZone { index, zone } => self.set_zone(*index, zone.clone()),
+            TrackerCommand::SetPosition { index, position } => {
+                let combatant = &mut self.combatants[*index];
+                let moved = combatant.position != *position;
+                combatant.position = *position;
+                moved
+            },
+            TrackerCommand::SetTurnOrder(turn_order) => self.set_turn_order(turn_order.clone()),
+            TrackerCommand::SetCombinedTurns(combined) => self.set_combined_turns(*combined),
+            TrackerCommand::PassTime { seconds } => self.pass_time(*seconds),
+            TrackerCommand::AwardXp => {
+                let award = self.xp_award();
+                if award.total() == 0 {
+                    return false;
+                }
+                self.record(LogEvent::XpAwarded {
+                    total: award.total(),
+                    share: award.share(),
+                    characters: award.characters.len(),
+                });
+                true
+            },
+            TrackerCommand::SetInitiative { index, value } => {
+                let combatant = &mut self.combatants[*index];
+                let rolled = combatant.initiative != *value;
+                combatant.initiative = *value;
+                rolled
+            },
+            TrackerCommand::SetInspiration { index, inspiration } => {
+                self.set_inspiration(*index, *inspiration)
+            },
+            TrackerCommand::ShortRest { index, hit_dice, healing } => {
+                self.short_rest(*index, *hit_dice, *healing)
+            },
+            TrackerCommand::LongRest { index } => self.long_rest(*index),
+            TrackerCommand::DeclareSpeedFactors { index, speed_factors } => {
+                let combatant = &mut self.combatants[*index];
+                let declared = combatant.speed_factors != *speed_factors;
+                combatant.speed_factors.clone_from(speed_factors);
+                declared
+            },
+            TrackerCommand::SetInitiativeModifiers { index, modifiers } => {
+                let combatant = &mut self.combatants[*index];
+                let modified = combatant.initiative_modifiers != *modifiers;
+                combatant.initiative_modifiers.clone_from(modifiers);
+                modified
+            },
+            TrackerCommand::SetExhaustion { index, level } => self.set_exhaustion(*index, *level),
+            TrackerCommand::SetSurprised { index, surprised } => {
+                self.set_surprised(*index, *surprised)
+            },
+            TrackerCommand::SetMinion { index, minion } => {
+                let combatant = &mut self.combatants[*index];
+                let flagged = combatant.minion != *minion;
+                combatant.minion = *minion;
+                flagged
+            },
+            TrackerCommand::SetLeader { index, leader } => {
+                let combatant = &mut self.combatants[*index];
+                let flagged = combatant.leader != *leader;
+                combatant.leader = *leader;
+                flagged
+            },
+            TrackerCommand::SetFleeing { index, fleeing } => self.set_fleeing(*index, *fleeing),
+            TrackerCommand::Stabilize { index } => self.stabilize(*index),
+            TrackerCommand::Kill { index } => self.kill(*index),
+            TrackerCommand::Edit { index, edit } => self.edit(*index, edit),
+            TrackerCommand::SetConcentration { index, concentrating } => {
+                self.set_concentration(*index, *concentrating)
+            },
+            TrackerCommand::SetSummoner { index, summoner } => {
+                self.set_summoner(*index, *summoner)
+            },
+            TrackerCommand::SetNotes { index, notes } => {
+                let combatant = &mut self.combatants[*index];
+                let edited = combatant.notes != *notes;
+                combatant.notes.clone_from(notes);
+                edited
+            },
+            TrackerCommand::SetEncounterNotes { notes } => {
+                let edited = self.notes != *notes;
+                self.notes.clone_from(notes);
+                edited
+            },
+        };
+
+        if changed {
+            let event = TrackerEvent { command: &command, log: &self.log[start..] };
+            self.subscribers.notify(&event);
+        }
+        changed
+    }
+
+    /// Returns `true` if every combatant the command refers to is in the initiative order,
+    /// including the current combatant, for the commands acting on the turn.
+    fn in_range(&self, command: &TrackerCommand) -> bool {
+        let len = self.combatants.len();
+        match command {
+            TrackerCommand::AddConditions { indexes, .. } => {
+                indexes.iter().all(|index| *index < len)
+            },
+            TrackerCommand::Grapple(grapple) => grapple.grappler < len && grapple.target < len,
+            TrackerCommand::SetSummoner { index, summoner } => {
+                *index < len && summoner.is_none_or(|summoner| summoner < len)
+            },
+            TrackerCommand::Release { index }
+            | TrackerCommand::Remove { index }
+            | TrackerCommand::PassTurn { next: index } => *index < len,
+            TrackerCommand::NextTurn
+            | TrackerCommand::UseAction
+            | TrackerCommand::UseBonusAction
+            | TrackerCommand::UseReaction => self.turn < len,
+            _ => match command.changed() {
+                Changed::Combatant(index) => index < len,
+                _ => true,
+            },
+        }
+    }
+
+    /// Calls the callback with every change made with [`Tracker::execute`] from now on, after
+    /// it is made. Returns an ID to remove the callback with [`Tracker::unsubscribe`].
+    ///
+    /// Subscribers aren't saved with the encounter, so a loaded encounter starts without any.
+    pub fn subscribe(
+        &mut self,
+        callback: impl FnMut(&TrackerEvent) + Send + 'static,
+    ) -> SubscriptionId {
+        self.subscribers.add(Box::new(callback))
+    }
+
+    /// Removes a callback added with [`Tracker::subscribe`]. Returns `false` if it was already
+    /// removed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.subscribers.remove(id)
+    }
+
+    /// Returns the indexes of the combatants in the order they take their turns each round,
+    /// following the [turn order](Tracker::turn_order).
+    pub fn turn_sequence(&self) -> Vec<usize> {
+        self.turn_order.sequence(&self.combatants, self.turn, &self.acted)
+    }
+
+    /// Returns the combatants who take their turns together, in turn order. Each combatant takes
+    /// its turn on its own, unless turns are [combined](Tracker::combined_turns): then copies of
+    /// the same monster next to each other in the turn sequence take one turn together.
+    pub fn turn_groups(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for index in self.turn_sequence() {
+            match groups.last_mut() {
+                Some(group) if self.combined_turns && self.identical(group[0], index) => {
+                    group.push(index);
+                },
+                _ => groups.push(vec![index]),
+            }
+        }
+        groups
+    }
+
+    /// Returns `true` if the combatants with the given indexes are copies of the same monster.
+    fn identical(&self, a: usize, b: usize) -> bool {
+        match (&self.combatants[a].kind, &self.combatants[b].kind) {
+            (CombatantKind::Monster(a), CombatantKind::Monster(b)) => a.name == b.name,
+            _ => false,
+        }
+    }
+
+    /// Returns the indexes of the combatants taking the current turn, in turn order: the
+    /// combatant whose turn it is, along with the rest of its [group](Tracker::turn_groups).
+    pub fn turn_members(&self) -> Vec<usize> {
+        self.turn_groups()
+            .into_iter()
+            .find(|group| group.contains(&self.turn))
+            .unwrap_or_else(|| vec![self.turn])
+    }
+
+    /// Returns the indexes of the combatants who haven't taken their turns this round, besides
+    /// the ones taking it now, in initiative order.
+    pub fn waiting(&self) -> Vec<usize> {
+        let members = self.turn_members();
+        (0..self.combatants.len())
+            .filter(|index| !members.contains(index) && !self.acted.contains(index))
+            .collect()
+    }
+
+    /// Returns `true` if the turn of the combatant with the given index is passed over, as
+    /// [`Rules::skip_dead`] and [`Rules::skip_unconscious`] say.
+    pub fn skips_turn(&self, index: usize) -> bool {
+        let combatant = &self.combatants[index];
+        if combatant.dead {
+            return self.rules.skip_dead;
+        }
+        let CombatantKind::Monster(_) = combatant.kind else { return false };
+        let regenerates = combatant.regeneration
+            .as_ref()
+            .is_some_and(|regeneration| !regeneration.needs_hit_points);
+        let dead = combatant.hit_points <= 0 && !regenerates;
+        let unconscious = combatant.conditions
+            .iter()
+            .any(|condition| condition.kind == ConditionKind::Unconscious);
+        self.rules.skip_dead && dead || self.rules.skip_unconscious && unconscious
+    }
+
+    /// Returns the index of the combatant whose turn comes after the given combatant's (and its
+    /// group's), and whether a new round starts with it.
+    ///
+    /// Groups whose turns are all [skipped](Tracker::skips_turn) are passed over, unless every
+    /// group's are.
+    pub fn next_in_sequence(&self, index: usize) -> (usize, bool) {
+        let groups = self.turn_groups();
+        let skipped = |group: &Vec<usize>| group.iter().all(|member| self.skips_turn(*member));
+        let everyone_skipped = groups.iter().all(skipped);
+        let takes_turn = |group: &&Vec<usize>| everyone_skipped || !skipped(group);
+
+        let position = groups.iter().position(|group| group.contains(&index)).unwrap_or(0) + 1;
+        let next = match self.turn_order {
+            // initiative is rolled again each round, so the order can change between rounds
+            TurnOrder::SpeedFactor => {
+                let waiting = self.waiting();
+                groups
+                    .iter()
+                    .filter(takes_turn)
+                    .map(|group| group[0])
+                    .find(|i| *i != index && waiting.contains(i))
+            },
+            _ => groups[position.min(groups.len())..]
+                .iter()
+                .find(takes_turn)
+                .map(|group| group[0]),
+        };
+        match next {
+            Some(next) => (next, false),
+            None => (groups.iter().find(takes_turn).unwrap_or(&groups[0])[0], true),
         }
     }
 
+    /// Returns the indexes of the combatants whose turns are [skipped](Tracker::skips_turn)
+    /// between the current turn and the given combatant's, in turn order, when a new round
+    /// starts in between or not.
+    fn skipped_before(&self, next: usize, new_round: bool) -> Vec<usize> {
+        let groups = self.turn_groups();
+        let position = |index| groups.iter().position(|group| group.contains(&index));
+        let current = position(self.turn).map_or(0, |position| position + 1);
+        let next = position(next).unwrap_or(0);
+        let between = if new_round {
+            [&groups[current.min(groups.len())..], &groups[..next]].concat()
+        } else {
+            groups.get(current..next).unwrap_or_default().to_vec()
+        };
+        between
+            .into_iter()
+            .flatten()
+            .filter(|index| self.skips_turn(*index))
+            .collect()
+    }
+
     /// Advance the tracker to the next combatant's turn.
+    ///
+    /// The current combatant's turn ends first, then the next combatant's turn starts, with
+    /// effects triggering at each step. With [combined turns](Tracker::combined_turns), every
+    /// combatant in the group ends or starts its turn. The turns of combatants passed over in
+    /// between are logged as [skipped](Tracker::skips_turn).
     pub fn next_turn(&mut self) {
+        self.note_opening();
+        for index in self.turn_members() {
+            self.end_turn(index);
+        }
+
+        let (next, new_round) = self.next_in_sequence(self.turn);
+        for index in self.skipped_before(next, new_round) {
+            let combatant = self.combatants[index].name().to_string();
+            self.record(LogEvent::TurnSkipped { combatant });
+        }
+        self.advance(next, new_round);
+
+        for index in self.turn_members() {
+            self.start_turn(index);
+        }
+    }
+
+    /// Pass the turn to the combatant with the given index, as the current combatant picks in
+    /// [popcorn initiative](TurnOrder::Popcorn).
+    ///
+    /// Returns `false` if the combatant already took its turn this round, or is taking it now,
+    /// unless everyone has. Then a new round starts with the picked combatant, whoever it is.
+    pub fn pass_turn(&mut self, next: usize) -> bool {
+        let waiting = self.waiting();
+        if next >= self.combatants.len() || !waiting.is_empty() && !waiting.contains(&next) {
+            return false;
+        }
+
+        self.note_opening();
+        for index in self.turn_members() {
+            self.end_turn(index);
+        }
+        self.advance(next, waiting.is_empty());
+        for index in self.turn_members() {
+            self.start_turn(index);
+        }
+        true
+    }
+
+    /// Note who took the opening turn in [`Tracker::opening`], if no turn passed yet.
+    fn note_opening(&mut self) {
+        let started = |entry: &LogEntry| matches!(entry.event, LogEvent::TurnStarted { .. });
+        if self.opening.is_none() && !self.log.iter().any(started) {
+            self.opening = Some(self.combatants[self.turn].name().to_string());
+        }
+    }
+
+    /// Hand the turn to the combatant with the given index, counting the combatants taking the
+    /// current turn as having acted, or starting over if a new round starts.
+    fn advance(&mut self, next: usize, new_round: bool) {
+        let members = self.turn_members();
+        self.acted.extend(members);
+        if new_round {
+            self.round += 1;
+            self.elapsed += Self::ROUND_SECONDS;
+            self.acted.clear();
+        }
+        self.turn = next;
+    }
+
+    /// Change how turns pass from one combatant to the next. The current combatant keeps the
+    /// turn.
+    pub fn set_turn_order(&mut self, turn_order: TurnOrder) -> bool {
+        if self.turn_order == turn_order {
+            return false;
+        }
+        self.record(LogEvent::TurnOrder { turn_order: turn_order.to_string() });
+        self.turn_order = turn_order;
+        true
+    }
+
+    /// Have copies of the same monster next to each other in the turn sequence take their turns
+    /// together, or each on its own again.
+    pub fn set_combined_turns(&mut self, combined: bool) -> bool {
+        if self.combined_turns == combined {
+            return false;
+        }
+        self.record(LogEvent::CombinedTurns { combined });
+        self.combined_turns = combined;
+        true
+    }
+
+    /// Add in-game time to the clock, or take it back if the number of seconds is negative. The
+    /// clock doesn't go below zero.
+    pub fn pass_time(&mut self, seconds: i64) -> bool {
+        let elapsed = self.elapsed.saturating_add_signed(seconds);
+        if elapsed == self.elapsed {
+            return false;
+        }
+        let seconds = elapsed as i64 - self.elapsed as i64;
+        self.record(LogEvent::TimePassed { seconds });
+        self.elapsed = elapsed;
+        true
+    }
+
+    /// End the turn of the combatant with the given index: trigger its end-of-turn effects, then
+    /// advance its condition durations.
+    fn end_turn(&mut self, index: usize) {
+        self.trigger_effects(index, Trigger::EndOfTurn);
+
         // advance condition durations
-        self.current_combatant_mut()
+        let mut expired = Vec::new();
+        self.combatants[index]
             .conditions
             .retain_mut(|c| {
                 let new_duration = c.duration.decrement();
@@ -159,110 +1047,2535 @@ impl Tracker {
                     true
                 } else {
                     // condition expired
+                    expired.push(c.kind);
                     false
                 }
             });
 
-        self.turn = (self.turn + 1) % self.combatants.len();
-        if self.turn == 0 {
-            self.round += 1;
+        if expired.contains(&ConditionKind::Exhaustion) {
+            self.combatants[index].exhaustion = 0;
+        }
+
+        // surprise lasts until the end of the combatant's first turn, and in the 2014 rules, it
+        // can't react until then either
+        let edition = self.rules.edition;
+        let combatant = &mut self.combatants[index];
+        if std::mem::take(&mut combatant.surprised) && edition == Edition::Rules2014 {
+            combatant.actions.reactions = 1;
+        }
+
+        let combatant = self.combatants[index].name().to_string();
+        for &condition in &expired {
+            self.record(LogEvent::ConditionEnded { combatant: combatant.clone(), condition });
         }
 
-        // restore current combatant's actions at the start of their turn
-        self.current_combatant_mut().actions = Action::default();
+        // conditions expiring together with the ones they came with don't linger
+        for condition in expired {
+            self.find_lingering(index, condition);
+        }
     }
 
-    /// Get the combatant that is currently taking their turn.
-    pub fn current_combatant(&self) -> &Combatant {
-        &self.combatants[self.turn]
+    /// Start the turn of the combatant with the given index: restore its actions, regenerate,
+    /// then trigger its start-of-turn effects.
+    ///
+    /// In the 2014 rules, a surprised combatant gets no actions on its turn instead.
+    fn start_turn(&mut self, index: usize) {
+        let surprised = self.combatants[index].surprised
+            && self.rules.edition == Edition::Rules2014;
+        self.combatants[index].actions = if surprised {
+            Action { actions: 0, bonus_actions: 0, reactions: 0 }
+        } else {
+            Action::default()
+        };
+
+        let combatant = self.combatants[index].name().to_string();
+        self.record(LogEvent::TurnStarted { combatant: combatant.clone() });
+        if surprised {
+            self.record(LogEvent::Surprised { combatant });
+        }
+
+        self.regenerate(index);
+        self.trigger_effects(index, Trigger::StartOfTurn);
     }
 
-    /// Get mutable access to the combatant that is currently taking their turn.
-    pub fn current_combatant_mut(&mut self) -> &mut Combatant {
-        &mut self.combatants[self.turn]
+    /// Heal the combatant with the given index by its regeneration, up to its maximum hit points,
+    /// unless the regeneration is suppressed this turn. The suppression wears off afterwards.
+    fn regenerate(&mut self, index: usize) {
+        let combatant = &mut self.combatants[index];
+        let Some(regeneration) = &mut combatant.regeneration else { return };
+        let suppressed = std::mem::take(&mut regeneration.suppressed);
+        let down = combatant.dead || regeneration.needs_hit_points && combatant.hit_points < 1;
+        let amount = regeneration.amount
+            .min(combatant.max_hit_points() - combatant.hit_points)
+            .max(0);
+
+        if suppressed {
+            let combatant = combatant.name().to_string();
+            self.record(LogEvent::RegenerationSuppressed { combatant });
+        } else if !down && amount > 0 {
+            self.damage(index, -amount, None);
+        }
     }
 
-    /// Use an action for the current combatant. Returns `true` if the action was used, or `false`
-    /// if the combatant had no actions left to use.
+    /// Trigger the effects of the combatant with the given index that trigger at the given time.
     ///
-    /// This function only decrements the number of actions available to the combatant, meaning the
-    /// combat log will not display any information about the action taken.
-    pub fn use_action(&mut self) -> bool {
-        let count = &mut self.combatants[self.turn].actions.actions;
-        if *count == 0 {
-            return false;
+    /// Effects that end, and constant damage, are resolved right away. Saving throws and damage
+    /// with dice are added to [`Tracker::triggered`] for the table to resolve.
+    fn trigger_effects(&mut self, index: usize, trigger: Trigger) {
+        let effects = self.combatants[index].effects
+            .iter()
+            .enumerate()
+            .filter(|(_, effect)| effect.trigger == trigger)
+            .map(|(position, effect)| (position, effect.clone()))
+            .collect::<Vec<_>>();
+
+        // from the last effect to the first, so ending one doesn't move the ones left to trigger
+        for (position, effect) in effects.into_iter().rev() {
+            let combatant = self.combatants[index].name().to_string();
+            self.record(LogEvent::EffectTriggered { combatant, effect: effect.to_string() });
+
+            match &effect.outcome {
+                Outcome::End => { self.end_effect(index, position); },
+                Outcome::Damage { amount, kind } if amount.is_constant() => {
+                    self.damage(index, amount.range().0, *kind);
+                },
+                Outcome::Save { .. } | Outcome::Damage { .. } => {
+                    self.triggered.push(Triggered { index, effect: position, details: effect });
+                },
+            }
         }
-        *count = count.saturating_sub(1);
-        true
     }
 
-    /// Use a bonus action for the current combatant. Returns `true` if the bonus action was used,
-    /// or `false` if the combatant had no bonus actions left to use.
+    /// Add a combatant to the end of the initiative order.
+    pub fn add(&mut self, combatant: impl Into<Combatant>) {
+        let combatant = combatant.into();
+        self.record(LogEvent::Joined { combatant: combatant.name().to_string() });
+        self.combatants.push(combatant);
+    }
+
+    /// Remove the combatant with the given index from the initiative order, keeping the turn on
+    /// the same combatant (or passing it to the next one, if the removed combatant had the turn).
     ///
-    /// This function only decrements the number of bonus actions available to the combatant,
-    /// meaning the combat log will not display any information about the bonus action taken.
-    pub fn use_bonus_action(&mut self) -> bool {
-        let count = &mut self.combatants[self.turn].actions.bonus_actions;
-        if *count == 0 {
-            return false;
+    /// The last combatant can't be removed, so this returns [`None`] if there is only one
+    /// combatant, or if the index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Option<Combatant> {
+        if self.combatants.len() <= 1 || index >= self.combatants.len() {
+            return None;
         }
-        *count = count.saturating_sub(1);
-        true
+
+        // if the removed combatant has the turn, it passes to the rest of its group, if any, or
+        // to the next one
+        let members = self.turn_members();
+        let next = match members.iter().find(|member| **member != index) {
+            Some(member) => *member,
+            None => self.next_in_sequence(self.turn).0,
+        };
+
+        self.end_grapples(|grapple| grapple.involves(index));
+        let combatant = self.combatants.remove(index);
+        for grapple in &mut self.grapples {
+            if grapple.grappler > index { grapple.grappler -= 1 }
+            if grapple.target > index { grapple.target -= 1 }
+        }
+        for members in self.groups.values_mut() {
+            members.retain(|member| *member != index);
+            members.iter_mut().filter(|member| **member > index).for_each(|member| *member -= 1);
+        }
+        self.groups.retain(|_, members| !members.is_empty());
+        self.triggered.retain(|triggered| triggered.index != index);
+        self.triggered
+            .iter_mut()
+            .filter(|triggered| triggered.index > index)
+            .for_each(|triggered| triggered.index -= 1);
+        self.lingering.retain(|lingering| lingering.index != index);
+        self.lingering
+            .iter_mut()
+            .filter(|lingering| lingering.index > index)
+            .for_each(|lingering| lingering.index -= 1);
+        /// Forget the removed combatant, and shift the indexes after it.
+        fn shift(other: Option<usize>, index: usize) -> Option<usize> {
+            match other {
+                Some(other) if other == index => None,
+                Some(other) if other > index => Some(other - 1),
+                other => other,
+            }
+        }
+        for combatant in &mut self.combatants {
+            combatant.summoner = shift(combatant.summoner, index);
+            let conditions = combatant.conditions.iter_mut().map(|c| &mut c.source);
+            let effects = combatant.effects.iter_mut().map(|e| &mut e.source);
+            for source in conditions.chain(effects) {
+                source.combatant = shift(source.combatant, index);
+            }
+        }
+        self.acted.retain(|acted| *acted != index);
+        self.acted.iter_mut().filter(|acted| **acted > index).for_each(|acted| *acted -= 1);
+        self.dismissals.retain_mut(|dismissal| {
+            dismissal.summons.retain(|summon| *summon != index);
+            dismissal.summons.iter_mut().filter(|summon| **summon > index).for_each(|s| *s -= 1);
+            if dismissal.summoner > index { dismissal.summoner -= 1 }
+            dismissal.summoner != index && !dismissal.summons.is_empty()
+        });
+        self.orphaned.retain_mut(|orphaned| {
+            orphaned.conditions.retain(|(target, _)| *target != index);
+            orphaned.conditions.iter_mut().filter(|(t, _)| *t > index).for_each(|(t, _)| *t -= 1);
+            if orphaned.source > index { orphaned.source -= 1 }
+            orphaned.source != index && !orphaned.conditions.is_empty()
+        });
+        self.morale_checks.retain(|check| check.index != index);
+        self.morale_checks
+            .iter_mut()
+            .filter(|check| check.index > index)
+            .for_each(|check| check.index -= 1);
+
+        if index == self.turn {
+            self.turn = next;
+        }
+        if index < self.turn {
+            self.turn -= 1;
+        }
+
+        self.record(LogEvent::Left { combatant: combatant.name().to_string() });
+        Some(combatant)
     }
 
-    /// Use a reaction for the current combatant. Returns `true` if the reaction was used, or
-    /// `false` if the combatant had no reactions left to use.
-    ///
-    /// This function only decrements the number of reactions available to the combatant, meaning
-    /// the combat log will not display any information about the reaction taken.
-    pub fn use_reaction(&mut self) -> bool {
-        let count = &mut self.combatants[self.turn].actions.reactions;
-        if *count == 0 {
+    /// Save the combatants with the given indexes as a named group, replacing any group with the
+    /// same name. Saving an empty group deletes it.
+    pub fn save_group(&mut self, name: impl Into<String>, mut members: Vec<usize>) {
+        let name = name.into();
+        members.retain(|member| *member < self.combatants.len());
+        members.sort_unstable();
+        members.dedup();
+        if members.is_empty() {
+            self.groups.remove(&name);
+        } else {
+            self.groups.insert(name, members);
+        }
+    }
+
+    /// Returns the zones the combatants are in, in alphabetical order.
+    pub fn zones(&self) -> Vec<&str> {
+        self.combatants
+            .iter()
+            .filter_map(|combatant| combatant.zone.as_deref())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns the indexes of the combatants in the given zone.
+    pub fn in_zone(&self, zone: &str) -> Vec<usize> {
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.zone.as_deref() == Some(zone))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Move the combatant with the given index to a zone, or out of every zone, and log it.
+    /// Returns `false` if it is already there.
+    pub fn set_zone(&mut self, index: usize, zone: Option<String>) -> bool {
+        let combatant = &mut self.combatants[index];
+        if combatant.zone == zone {
             return false;
         }
-        *count = count.saturating_sub(1);
+        combatant.zone = zone.clone();
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::Moved { combatant, zone });
         true
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Returns `true` once every enemy is at 0 hit points, if there are any enemies.
+    pub fn is_over(&self) -> bool {
+        let mut enemies = self.combatants.iter().filter(|c| c.side == Side::Enemy).peekable();
+        enemies.peek().is_some() && enemies.all(|combatant| combatant.hit_points <= 0)
+    }
 
-    /// Ensure that the tracker advances turns correctly.
-    #[test]
-    fn test_tracker_next_turn() {
-        let mut tracker = Tracker::new(vec![
-            Monster {
-                index: "goblin".to_string(),
-                name: "Goblin".to_string(),
-                ..Default::default()
-            }.into(),
+    /// Returns the threat the enemy monsters still standing pose to the player characters, or
+    /// [`None`] if there are no player characters to measure it against.
+    pub fn threat(&self) -> Option<Threat> {
+        let mut levels = Vec::new();
+        let mut monster_xp = Vec::new();
+        for combatant in &self.combatants {
+            match &combatant.kind {
+                CombatantKind::Character(character) => levels.push(character.level),
+                CombatantKind::Monster(monster)
+                    if combatant.side == Side::Enemy && combatant.hit_points > 0 =>
+                {
+                    monster_xp.push(monster.xp_value());
+                },
+                CombatantKind::Monster(_) => (),
+            }
+        }
+        (!levels.is_empty()).then(|| Threat::new(&monster_xp, &levels))
+    }
+
+    /// Returns the enemy monsters at 0 hit points, with their indexes, in turn order.
+    pub fn defeated(&self) -> Vec<(usize, &Monster)> {
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.side == Side::Enemy && combatant.hit_points <= 0)
+            .filter_map(|(index, combatant)| match &combatant.kind {
+                CombatantKind::Monster(monster) => Some((index, monster)),
+                CombatantKind::Character(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the XP earned for the [defeated](Tracker::defeated) monsters, split between the
+    /// player characters.
+    pub fn xp_award(&self) -> XpAward {
+        let mut award = XpAward::default();
+        for (_, monster) in self.defeated() {
+            let xp = monster.xp_value();
+            match award.defeated.iter_mut().find(|(name, ..)| *name == monster.name) {
+                Some((_, count, total)) => {
+                    *count += 1;
+                    *total += xp;
+                },
+                None => award.defeated.push((monster.name.clone(), 1, xp)),
+            }
+        }
+        award.characters = self.combatants
+            .iter()
+            .filter(|combatant| matches!(combatant.kind, CombatantKind::Character(_)))
+            .map(|combatant| combatant.name().to_string())
+            .collect();
+        award
+    }
+
+    /// Returns the damage dealt and taken, healing done, and kills of every combatant named in the
+    /// [log](Tracker::log), removed combatants included, tallied with [`stats::tally`].
+    ///
+    /// Until the turn passes for the first time, the opening turn is the current one.
+    pub fn combat_stats(&self) -> Vec<CombatStats> {
+        let opening = self.opening.as_deref().or_else(|| {
+            let started = |entry: &LogEntry| matches!(entry.event, LogEvent::TurnStarted { .. });
+            (!self.log.iter().any(started)).then(|| self.combatants[self.turn].name())
+        });
+        stats::tally(&self.log, opening)
+    }
+
+    /// Returns the combined hit points of the combatants with the given indices before the first
+    /// round and at the end of every round since, replayed from the [log](Tracker::log) with
+    /// [`stats::hit_points_by_round`]. The last value is their hit points now.
+    pub fn hit_points_by_round(&self, indices: impl IntoIterator<Item = usize>) -> Vec<i32> {
+        let combatants = indices
+            .into_iter()
+            .map(|index| (self.combatants[index].name(), self.combatants[index].hit_points))
+            .collect::<Vec<_>>();
+        stats::hit_points_by_round(&self.log, &combatants, self.round)
+    }
+
+    /// Returns the first square of the battle grid no combatant stands on, going right along each
+    /// row from the origin, then down. New tokens are placed there.
+    pub fn free_square(&self) -> Position {
+        let taken = self.combatants
+            .iter()
+            .filter_map(|combatant| combatant.position)
+            .collect::<Vec<_>>();
+        // a row as wide as the number of combatants always has a free square
+        let width = self.combatants.len() as i32 + 1;
+        (0..)
+            .map(|square| Position::new(square % width, square / width))
+            .find(|square| !taken.contains(square))
+            .unwrap_or_default()
+    }
+
+    /// Returns the distance in feet between the combatants with the given indexes on the battle
+    /// grid, or [`None`] if either isn't on it.
+    pub fn distance(&self, a: usize, b: usize) -> Option<u32> {
+        Some(self.combatants[a].position?.distance(self.combatants[b].position?))
+    }
+
+    /// Returns the indexes of the combatants summoned by the combatant with the given index.
+    pub fn summons(&self, index: usize) -> Vec<usize> {
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.summoner == Some(index))
+            .map(|(summon, _)| summon)
+            .collect()
+    }
+
+    /// Link the combatant with the given index to the combatant that summoned it, or unlink it.
+    /// Returns `false` if the link doesn't change, or the combatant would summon itself.
+    pub fn set_summoner(&mut self, index: usize, summoner: Option<usize>) -> bool {
+        if summoner == Some(index) || self.combatants[index].summoner == summoner {
+            return false;
+        }
+
+        self.combatants[index].summoner = summoner;
+        if let Some(summoner) = summoner {
+            self.record(LogEvent::Summoned {
+                summoner: self.combatants[summoner].name().to_string(),
+                combatant: self.combatants[index].name().to_string(),
+            });
+        }
+        true
+    }
+
+    /// Start or stop concentrating for the combatant with the given index, and log it. Returns
+    /// `false` if it already was (or wasn't) concentrating.
+    ///
+    /// When a combatant stops concentrating, its summons are added to [`Tracker::dismissals`].
+    pub fn set_concentration(&mut self, index: usize, concentrating: bool) -> bool {
+        let combatant = &mut self.combatants[index];
+        if combatant.concentrating == concentrating {
+            return false;
+        }
+
+        combatant.concentrating = concentrating;
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::Concentration { combatant, concentrating });
+        if !concentrating {
+            self.dismiss_summons(index, DismissalReason::Concentration);
+        }
+        true
+    }
+
+    /// Add the summons of the combatant with the given index to [`Tracker::dismissals`], if it
+    /// has any.
+    fn dismiss_summons(&mut self, summoner: usize, reason: DismissalReason) {
+        let summons = self.summons(summoner);
+        if !summons.is_empty() {
+            self.dismissals.push(Dismissal { summoner, reason, summons });
+        }
+    }
+
+    /// Add an event to the combat log, in the current round.
+    pub fn record(&mut self, event: LogEvent) {
+        self.log.push(LogEntry { round: self.round, event });
+    }
+
+    /// Damage the combatant with the given index, applying its resistances, vulnerabilities, and
+    /// immunities, and log the damage actually taken. Returns the damage actually taken.
+    ///
+    /// Damage of a type that stops the combatant's regeneration suppresses it on its next turn.
+    /// If the damage drops the combatant to 0 hit points, that is logged too, its concentration
+    /// ends, and its summons are added to [`Tracker::dismissals`]. With
+    /// [`Rules::floor_hit_points`], hit points stop at 0.
+    ///
+    /// With [`Rules::minions`], any damage drops a [minion](Combatant::minion), and the damage past
+    /// its hit points carries over to the next minions in its groups, dropping each one it is
+    /// enough for.
+    pub fn damage(&mut self, index: usize, amount: i32, kind: Option<DamageType>) -> i32 {
+        let combatant = &self.combatants[index];
+        let minion = self.rules.minions && combatant.minion && combatant.hit_points > 0;
+        let hit_points = combatant.hit_points;
+        let taken = self.deal_damage(index, amount, kind, minion);
+        if minion && taken > hit_points {
+            self.carry_over(index, taken - hit_points);
+        }
+        taken
+    }
+
+    /// Damage the combatant with the given index, as [`Tracker::damage`] does without the minion
+    /// rules. Any damage taken drops the combatant if `fatal` is set.
+    fn deal_damage(
+        &mut self,
+        index: usize,
+        amount: i32,
+        kind: Option<DamageType>,
+        fatal: bool,
+    ) -> i32 {
+        let combatant = &mut self.combatants[index];
+        let before = combatant.hit_points;
+        let was_up = before > 0;
+        let taken = combatant.take_damage(amount, kind);
+        if fatal && taken > 0 {
+            combatant.hit_points = combatant.hit_points.min(0);
+        }
+        if self.rules.floor_hit_points {
+            combatant.hit_points = combatant.hit_points.max(0);
+        }
+        if taken > 0
+            && let Some(kind) = kind
+            && let Some(regeneration) = &mut combatant.regeneration
+            && regeneration.suppressed_by.contains(&kind)
+        {
+            regeneration.suppressed = true;
+        }
+        if taken != 0 {
+            combatant.stable = false;
+        }
+        if combatant.hit_points > 0 {
+            combatant.dead = false;
+        }
+        let downed = was_up && combatant.hit_points <= 0;
+
+        let name = combatant.name().to_string();
+        let hit_points = Some((before, combatant.hit_points));
+        self.record(LogEvent::Damaged {
+            combatant: name,
+            amount: taken,
+            kind,
+            hit_points,
+            index: Some(index),
+        });
+        if downed {
+            self.knock_down(index);
+        }
+        taken
+    }
+
+    /// Log that the combatant with the given index dropped to 0 hit points, end its
+    /// concentration, and add its summons to [`Tracker::dismissals`]. With [`Rules::morale`], the
+    /// monsters its drop shakes check their morale.
+    fn knock_down(&mut self, index: usize) {
+        let combatant = self.combatants[index].name().to_string();
+        self.record(LogEvent::Downed { combatant: combatant.clone() });
+        // dropping is why the summons are dismissed, so concentration ends without asking
+        if std::mem::take(&mut self.combatants[index].concentrating) {
+            self.record(LogEvent::Concentration { combatant, concentrating: false });
+        }
+        self.dismiss_summons(index, DismissalReason::Downed);
+        self.orphan_conditions(index);
+        if self.rules.morale {
+            self.shake_morale(index);
+        }
+    }
+
+    /// Add the conditions the combatant with the given index applied to others, which they still
+    /// have, to [`Tracker::orphaned`].
+    fn orphan_conditions(&mut self, source: usize) {
+        if self.orphaned.iter().any(|orphaned| orphaned.source == source) {
+            return;
+        }
+        let conditions = self.combatants
+            .iter()
+            .enumerate()
+            .flat_map(|(index, combatant)| {
+                combatant.conditions
+                    .iter()
+                    .filter(|condition| condition.source.combatant == Some(source))
+                    .map(move |condition| (index, condition.kind))
+            })
+            .collect::<Vec<_>>();
+        if !conditions.is_empty() {
+            self.orphaned.push(Orphaned { source, conditions });
+        }
+    }
+
+    /// Stabilize the combatant with the given index at 0 hit points, as Spare the Dying or a
+    /// successful Wisdom (Medicine) check does, and log it. Returns `false` if it is dead or
+    /// already stable.
+    ///
+    /// A combatant still standing drops to 0 hit points first, as when an attacker knocks it out
+    /// instead of killing it.
+    pub fn stabilize(&mut self, index: usize) -> bool {
+        let combatant = &mut self.combatants[index];
+        if combatant.dead || combatant.stable {
+            return false;
+        }
+
+        let before = combatant.hit_points;
+        combatant.hit_points = 0;
+        combatant.stable = true;
+        if before > 0 {
+            self.knock_down(index);
+        }
+        let combatant = self.combatants[index].name().to_string();
+        self.record(LogEvent::Stabilized { combatant, hit_points: Some((before, 0)) });
+        true
+    }
+
+    /// Mark the combatant with the given index dead, such as a character failing its third death
+    /// saving throw, and log it. Returns `false` if it already is.
+    ///
+    /// A combatant still standing drops to 0 hit points, and loses its temporary hit points.
+    pub fn kill(&mut self, index: usize) -> bool {
+        let combatant = &mut self.combatants[index];
+        if combatant.dead {
+            return false;
+        }
+
+        let before = combatant.hit_points;
+        combatant.hit_points = before.min(0);
+        combatant.temporary_hit_points = 0;
+        combatant.stable = false;
+        combatant.dead = true;
+        if before > 0 {
+            self.knock_down(index);
+        } else {
+            self.orphan_conditions(index);
+        }
+        let combatant = &self.combatants[index];
+        let hit_points = Some((before, combatant.hit_points));
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::Died { combatant, hit_points });
+        true
+    }
+
+    /// Add the monsters whose morale the monster with the given index dropping shakes to
+    /// [`Tracker::morale_checks`]: its side if it was a [leader](Combatant::leader), and its
+    /// groups if half their members are down now.
+    ///
+    /// Only monsters still up and not already fleeing check their morale, once each.
+    fn shake_morale(&mut self, index: usize) {
+        if !matches!(self.combatants[index].kind, CombatantKind::Monster(_)) {
+            return;
+        }
+
+        let mut checks = Vec::new();
+        if self.combatants[index].leader {
+            let side = self.combatants[index].side;
+            let followers = self.combatants
+                .iter()
+                .enumerate()
+                .filter(|(_, combatant)| combatant.side == side)
+                .map(|(member, _)| member);
+            checks.extend(followers.map(|member| (member, MoraleReason::LeaderDropped)));
+        }
+
+        let copies = (0..self.combatants.len()).filter(|member| self.identical(index, *member));
+        let groups = self.groups
+            .values()
+            .filter(|members| members.contains(&index))
+            .cloned()
+            .chain(std::iter::once(copies.collect()));
+        for members in groups {
+            let down = members
+                .iter()
+                .filter(|member| self.combatants[**member].hit_points <= 0)
+                .count();
+            // only the member whose drop brought the group to half shakes it
+            if members.len() >= 2 && down * 2 >= members.len() && (down - 1) * 2 < members.len() {
+                checks.extend(members.into_iter().map(|member| (member, MoraleReason::GroupHalved)));
+            }
+        }
+
+        for (member, reason) in checks {
+            let combatant = &self.combatants[member];
+            if matches!(combatant.kind, CombatantKind::Monster(_))
+                && combatant.hit_points > 0
+                && !combatant.fleeing
+                && !self.morale_checks.iter().any(|check| check.index == member)
+            {
+                self.morale_checks.push(MoraleCheck { index: member, reason });
+            }
+        }
+    }
+
+    /// Change the stats of the combatant with the given index by hand, and log it. Returns
+    /// `false` if nothing changed.
+    ///
+    /// Hit points above the new maximum are lowered to it.
+    pub fn edit(&mut self, index: usize, edit: &CombatantEdit) -> bool {
+        let combatant = &mut self.combatants[index];
+        let old_name = combatant.name().to_string();
+        if !edit.apply(combatant) {
+            return false;
+        }
+
+        let before = combatant.hit_points;
+        combatant.hit_points = before.min(combatant.max_hit_points());
+        let hit_points = Some((before, combatant.hit_points));
+        let name = combatant.name().to_string();
+        self.record(LogEvent::Edited { combatant: old_name, name, hit_points });
+        true
+    }
+
+    /// Have the combatant with the given index flee the fight, or stop fleeing, and log it.
+    /// Returns `false` if it already was (or wasn't) fleeing.
+    pub fn set_fleeing(&mut self, index: usize, fleeing: bool) -> bool {
+        let combatant = &mut self.combatants[index];
+        if combatant.fleeing == fleeing {
+            return false;
+        }
+
+        combatant.fleeing = fleeing;
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::Fleeing { combatant, fleeing });
+        true
+    }
+
+    /// Carry the damage past a minion's hit points over to the next minions still up in its
+    /// groups, in group order, as if the minions shared a pool of hit points. Each minion the
+    /// damage left is enough for drops, until one has more hit points than that.
+    ///
+    /// The damage already went through the first minion's resistances, so it is dealt without a
+    /// type.
+    fn carry_over(&mut self, index: usize, mut damage: i32) {
+        // the members after the minion, then the ones before it, as if the group were a circle
+        let mut next = Vec::new();
+        for members in self.groups.values().filter(|members| members.contains(&index)) {
+            let position = members.iter().position(|member| *member == index).unwrap_or(0);
+            let (before, after) = members.split_at(position);
+            for &member in after.iter().chain(before) {
+                let combatant = &self.combatants[member];
+                if member != index
+                    && combatant.minion
+                    && combatant.hit_points > 0
+                    && !next.contains(&member)
+                {
+                    next.push(member);
+                }
+            }
+        }
+
+        for member in next {
+            let hit_points = self.combatants[member].hit_points;
+            if damage < hit_points {
+                break;
+            }
+            damage -= hit_points;
+            self.deal_damage(member, hit_points, None, true);
+        }
+    }
+
+    /// Heal the combatant with the given index, and log the healing. Returns the hit points
+    /// actually regained.
+    ///
+    /// A combatant below 0 hit points heals from 0, and healing stops at its maximum hit points.
+    /// [Dead](Combatant::dead) combatants aren't healed.
+    pub fn heal(&mut self, index: usize, amount: i32) -> i32 {
+        let combatant = &mut self.combatants[index];
+        if combatant.dead {
+            return 0;
+        }
+        let hit_points = combatant.hit_points.max(0);
+        let healed = (hit_points + amount.max(0)).min(combatant.max_hit_points()).max(hit_points);
+        if healed == hit_points {
+            return 0;
+        }
+        combatant.hit_points = hit_points;
+        self.deal_damage(index, hit_points - healed, None, false);
+        healed - hit_points
+    }
+
+    /// Set the hit points of the combatant with the given index, logging the change as damage or
+    /// healing. Minions are left at the given hit points, without the minion rules. Returns
+    /// `false` if the combatant already has that many.
+    pub fn set_hit_points(&mut self, index: usize, value: i32) -> bool {
+        let difference = self.combatants[index].hit_points - value;
+        if difference == 0 {
+            return false;
+        }
+
+        // the value is for the hit points themselves, so the temporary ones are left alone
+        let temporary = std::mem::take(&mut self.combatants[index].temporary_hit_points);
+        self.deal_damage(index, difference, None, false);
+        self.combatants[index].temporary_hit_points = temporary;
+        true
+    }
+
+    /// Give the combatant with the given index temporary hit points, and log it. Returns `false`
+    /// if it already has as many or more.
+    ///
+    /// Temporary hit points don't stack: the combatant keeps whichever amount is higher.
+    pub fn grant_temporary_hit_points(&mut self, index: usize, amount: i32) -> bool {
+        let combatant = &mut self.combatants[index];
+        if amount <= combatant.temporary_hit_points {
+            return false;
+        }
+
+        combatant.temporary_hit_points = amount;
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::TemporaryHitPoints { combatant, amount });
+        true
+    }
+
+    /// Apply a condition to the combatant with the given index, and log it, along with the
+    /// conditions it implies (see [`ConditionKind::implies`]). Returns `false` if the combatant
+    /// already had the condition, or is immune to it.
+    ///
+    /// If the combatant already has the condition, its duration is extended when the new one is
+    /// longer, and nothing is logged. If the combatant is immune to it, the immunity is logged
+    /// instead.
+    pub fn apply_condition(
+        &mut self,
+        index: usize,
+        kind: ConditionKind,
+        duration: ConditionDuration,
+    ) -> bool {
+        self.apply_condition_from(index, kind, duration, &ConditionSource::default())
+    }
+
+    /// Apply a condition to the combatant with the given index, as [`Tracker::apply_condition`]
+    /// does, recording what applied it. A combatant isn't recorded as the source of its own
+    /// conditions.
+    ///
+    /// If the combatant already has the condition without a source, it takes this one.
+    pub fn apply_condition_from(
+        &mut self,
+        index: usize,
+        kind: ConditionKind,
+        duration: ConditionDuration,
+        source: &ConditionSource,
+    ) -> bool {
+        let mut source = source.clone();
+        if source.combatant == Some(index) {
+            source.combatant = None;
+        }
+        let combatant = &mut self.combatants[index];
+        if combatant.is_immune_to(kind) {
+            let combatant = combatant.name().to_string();
+            self.record(LogEvent::ConditionImmune { combatant, condition: kind });
+            return false;
+        }
+
+        let existing = combatant.conditions.iter_mut().find(|c| c.kind == kind);
+        let applied = if let Some(existing) = existing {
+            if let ConditionDuration::Rounds(existing_duration) = existing.duration
+                && let ConditionDuration::Rounds(new_duration) = duration
+                && new_duration > existing_duration
+            {
+                existing.duration = duration;
+            }
+            if existing.source.is_empty() {
+                existing.source = source.clone();
+            }
+            false
+        } else {
+            combatant.conditions.push(Condition { kind, duration, source: source.clone() });
+            if kind == ConditionKind::Exhaustion {
+                combatant.exhaustion = combatant.exhaustion.max(1);
+            }
+            let combatant = combatant.name().to_string();
+            self.record(LogEvent::ConditionApplied { combatant, condition: kind });
+            true
+        };
+
+        for &implied in kind.implies() {
+            self.apply_condition_from(index, implied, duration, &source);
+        }
+
+        // an incapacitated grappler can't hold on
+        if kind == ConditionKind::Incapacitated {
+            self.end_grapples(|grapple| grapple.grappler == index);
+        }
+        applied
+    }
+
+    /// Apply each of the given conditions to each of the combatants with the given indexes, as
+    /// [`Tracker::apply_condition_from`] does, such as Hypnotic Pattern charming and
+    /// incapacitating everyone it catches. Returns `false` if none of them was applied.
+    pub fn apply_conditions(
+        &mut self,
+        indexes: &[usize],
+        kinds: &[ConditionKind],
+        duration: ConditionDuration,
+        source: &ConditionSource,
+    ) -> bool {
+        let mut applied = false;
+        for &index in indexes {
+            for &kind in kinds {
+                applied |= self.apply_condition_from(index, kind, duration, source);
+            }
+        }
+        applied
+    }
+
+    /// End a condition on the combatant with the given index early, and log it. Returns `false` if
+    /// the combatant didn't have the condition.
+    ///
+    /// The conditions it implies are left on the combatant, and added to [`Tracker::lingering`].
+    pub fn remove_condition(&mut self, index: usize, kind: ConditionKind) -> bool {
+        let combatant = &mut self.combatants[index];
+        let Some(position) = combatant.conditions.iter().position(|c| c.kind == kind) else {
+            return false;
+        };
+
+        combatant.conditions.remove(position);
+        if kind == ConditionKind::Exhaustion {
+            combatant.exhaustion = 0;
+        }
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::ConditionEnded { combatant, condition: kind });
+        self.find_lingering(index, kind);
+        self.end_grapples(|grapple| grapple.target == index && grapple.condition() == kind);
+        true
+    }
+
+    /// Add the conditions implied by the given condition, which ended, to [`Tracker::lingering`],
+    /// if the combatant with the given index still has them, and none of its other conditions
+    /// implies them.
+    fn find_lingering(&mut self, index: usize, ended: ConditionKind) {
+        let conditions = &self.combatants[index].conditions;
+        let has = |kind: ConditionKind| conditions.iter().any(|c| c.kind == kind);
+        let lingering = ended
+            .implies()
+            .iter()
+            .copied()
+            .filter(|&kind| has(kind))
+            .filter(|&kind| !conditions.iter().any(|c| c.kind.implies().contains(&kind)))
+            .collect::<Vec<_>>();
+
+        if !lingering.is_empty() {
+            self.lingering.push(Lingering { index, ended, conditions: lingering });
+        }
+    }
+
+    /// Attach an effect to the combatant with the given index, applying the condition it causes
+    /// until it ends.
+    pub fn add_effect(&mut self, index: usize, effect: Effect) {
+        if let Some(condition) = effect.condition {
+            let duration = ConditionDuration::Forever;
+            self.apply_condition_from(index, condition, duration, &effect.source);
+        }
+        self.combatants[index].effects.push(effect);
+    }
+
+    /// End the effect at the given position among the effects of the combatant with the given
+    /// index, along with the condition it caused, and log it. Returns `false` if there is no such
+    /// effect.
+    pub fn end_effect(&mut self, index: usize, effect: usize) -> bool {
+        let effects = &mut self.combatants[index].effects;
+        if effect >= effects.len() {
+            return false;
+        }
+
+        let effect = effects.remove(effect);
+        let combatant = self.combatants[index].name().to_string();
+        self.record(LogEvent::EffectEnded { combatant, effect: effect.to_string() });
+        if let Some(condition) = effect.condition {
+            self.remove_condition(index, condition);
+        }
+        true
+    }
+
+    /// Have a combatant grapple (or restrain) another, applying the condition to the target until
+    /// the grapple ends, and log it. Returns `false` if the combatant would grapple itself, already
+    /// holds the target the same way, or the target is immune to the condition.
+    pub fn grapple(&mut self, grapple: Grapple) -> bool {
+        if grapple.grappler == grapple.target || self.grapples.contains(&grapple) {
+            return false;
+        }
+
+        let kind = grapple.condition();
+        let target = &self.combatants[grapple.target];
+        if target.is_immune_to(kind) {
+            let combatant = target.name().to_string();
+            self.record(LogEvent::ConditionImmune { combatant, condition: kind });
+            return false;
+        }
+
+        self.record(LogEvent::Grappled {
+            grappler: self.combatants[grapple.grappler].name().to_string(),
+            target: target.name().to_string(),
+            condition: kind,
+        });
+        self.apply_condition(grapple.target, kind, ConditionDuration::Forever);
+        self.grapples.push(grapple);
+        true
+    }
+
+    /// End every grapple the combatant with the given index is part of, on either side, clearing
+    /// the condition from each target, and log it. Returns `false` if it wasn't part of any.
+    pub fn release(&mut self, index: usize) -> bool {
+        self.end_grapples(|grapple| grapple.involves(index))
+    }
+
+    /// End the grapples matching the given predicate, and log it. Their targets lose the condition,
+    /// unless another grapple still holds them the same way. Returns `false` if none matched.
+    fn end_grapples(&mut self, ends: impl Fn(&Grapple) -> bool) -> bool {
+        let (ended, kept) = std::mem::take(&mut self.grapples).into_iter().partition(ends);
+        self.grapples = kept;
+
+        for grapple in &ended {
+            self.record(LogEvent::Released {
+                grappler: self.combatants[grapple.grappler].name().to_string(),
+                target: self.combatants[grapple.target].name().to_string(),
+            });
+            let kind = grapple.condition();
+            let held = self.grapples
+                .iter()
+                .any(|other| other.target == grapple.target && other.condition() == kind);
+            if !held {
+                self.remove_condition(grapple.target, kind);
+            }
+        }
+        !ended.is_empty()
+    }
+
+    /// Give Inspiration to the combatant with the given index, or have it spend it, and log it.
+    /// Returns `false` if the combatant isn't a player character, or already is in that state.
+    pub fn set_inspiration(&mut self, index: usize, inspiration: bool) -> bool {
+        let combatant = &mut self.combatants[index];
+        if !matches!(combatant.kind, CombatantKind::Character(_))
+            || combatant.inspiration == inspiration
+        {
+            return false;
+        }
+
+        combatant.inspiration = inspiration;
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::Inspiration { combatant, gained: inspiration });
+        true
+    }
+
+    /// Have the combatant with the given index finish a short rest, spending the given number of
+    /// hit dice to regain the given hit points (rolled by the table), up to its maximum, and log
+    /// it. Returns `false` if it doesn't have that many hit dice left.
+    ///
+    /// A short rest lasts an hour, so the combatant's actions are restored, and every condition
+    /// that doesn't last until removed ends.
+    pub fn short_rest(&mut self, index: usize, hit_dice: u32, healing: i32) -> bool {
+        let combatant = &mut self.combatants[index];
+        if hit_dice > combatant.hit_dice_left() {
+            return false;
+        }
+
+        combatant.hit_dice_spent += hit_dice;
+        combatant.actions = Action::default();
+        let missing = (combatant.max_hit_points() - combatant.hit_points).max(0);
+        let healed = healing.clamp(0, missing);
+        combatant.hit_points += healed;
+        let ended = combatant.conditions
+            .iter()
+            .filter(|condition| condition.duration != ConditionDuration::Forever)
+            .map(|condition| condition.kind)
+            .collect::<Vec<_>>();
+
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::ShortRest { combatant, hit_dice, healed });
+        for kind in ended {
+            self.remove_condition(index, kind);
+        }
+        true
+    }
+
+    /// Set the exhaustion level of the combatant with the given index, up to
+    /// [`Edition::MAX_EXHAUSTION`], and log it. The exhaustion condition is applied along with
+    /// it, or ended at level 0. Returns `false` if the level doesn't change, or the combatant is
+    /// immune to exhaustion.
+    pub fn set_exhaustion(&mut self, index: usize, level: u32) -> bool {
+        let level = level.min(Edition::MAX_EXHAUSTION);
+        let combatant = &mut self.combatants[index];
+        if combatant.exhaustion == level {
+            return false;
+        }
+        if level > 0 && combatant.is_immune_to(ConditionKind::Exhaustion) {
+            // logs the immunity
+            self.apply_condition(index, ConditionKind::Exhaustion, ConditionDuration::Forever);
+            return false;
+        }
+
+        combatant.exhaustion = level;
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::Exhaustion { combatant, level });
+        if level == 0 {
+            self.remove_condition(index, ConditionKind::Exhaustion);
+        } else {
+            self.apply_condition(index, ConditionKind::Exhaustion, ConditionDuration::Forever);
+        }
+        true
+    }
+
+    /// Surprise the combatant with the given index, or stop surprising it. Returns `false` if it
+    /// already was (or wasn't) surprised.
+    ///
+    /// Surprise lasts until the end of the combatant's next turn. In the 2014 rules, it can't
+    /// act on that turn, nor react until it ends. In the 2024 rules, it rolls initiative with
+    /// disadvantage instead.
+    pub fn set_surprised(&mut self, index: usize, surprised: bool) -> bool {
+        let combatant = &mut self.combatants[index];
+        if combatant.surprised == surprised {
+            return false;
+        }
+
+        combatant.surprised = surprised;
+        if self.rules.edition == Edition::Rules2014 {
+            combatant.actions.reactions = u32::from(!surprised);
+        }
+        true
+    }
+
+    /// Have the combatant with the given index finish a long rest, and log it. Returns `false` if
+    /// it has no hit points, since it needs at least 1 to benefit from the rest.
+    ///
+    /// The combatant regains all its hit points, and half its hit dice (at least one), and loses
+    /// its temporary hit points. Its actions are restored, every condition that doesn't last until
+    /// removed ends, and its exhaustion goes down by one level.
+    pub fn long_rest(&mut self, index: usize) -> bool {
+        let combatant = &mut self.combatants[index];
+        if combatant.hit_points <= 0 {
+            return false;
+        }
+
+        let before = combatant.hit_points;
+        combatant.hit_points = before.max(combatant.max_hit_points());
+        combatant.temporary_hit_points = 0;
+        if let CombatantKind::Character(character) = &combatant.kind {
+            let regained = (character.level / 2).max(1);
+            combatant.hit_dice_spent = combatant.hit_dice_spent.saturating_sub(regained);
+        }
+        combatant.actions = Action::default();
+        if let Some(regeneration) = &mut combatant.regeneration {
+            regeneration.suppressed = false;
+        }
+        let ended = combatant.conditions
+            .iter()
+            .filter(|condition| condition.duration != ConditionDuration::Forever)
+            .map(|condition| condition.kind)
+            .collect::<Vec<_>>();
+        let exhaustion = combatant.exhaustion;
+        let hit_points = Some((before, combatant.hit_points));
+
+        let combatant = combatant.name().to_string();
+        self.record(LogEvent::LongRest { combatant, hit_points });
+        for kind in ended {
+            self.remove_condition(index, kind);
+        }
+        if exhaustion > 0 {
+            self.set_exhaustion(index, exhaustion - 1);
+        }
+        true
+    }
+
+    /// Stop the regeneration of the combatant with the given index on its next turn, or restart
+    /// it. Returns `false` if the combatant doesn't regenerate.
+    pub fn suppress_regeneration(&mut self, index: usize, suppressed: bool) -> bool {
+        let Some(regeneration) = &mut self.combatants[index].regeneration else { return false };
+        regeneration.suppressed = suppressed;
+        true
+    }
+
+    /// Get the combatant that is currently taking their turn.
+    pub fn current_combatant(&self) -> &Combatant {
+        &self.combatants[self.turn]
+    }
+
+    /// Get mutable access to the combatant that is currently taking their turn.
+    pub fn current_combatant_mut(&mut self) -> &mut Combatant {
+        &mut self.combatants[self.turn]
+    }
+
+    /// Use an action for the current combatant. Returns `true` if the action was used, or `false`
+    /// if the combatant had no actions left to use.
+    ///
+    /// This function only decrements the number of actions available to the combatant, meaning the
+    /// combat log will not display any information about the action taken.
+    pub fn use_action(&mut self) -> bool {
+        let count = &mut self.combatants[self.turn].actions.actions;
+        if *count == 0 {
+            return false;
+        }
+        *count = count.saturating_sub(1);
+        true
+    }
+
+    /// Use a bonus action for the current combatant. Returns `true` if the bonus action was used,
+    /// or `false` if the combatant had no bonus actions left to use.
+    ///
+    /// This function only decrements the number of bonus actions available to the combatant,
+    /// meaning the combat log will not display any information about the bonus action taken.
+    pub fn use_bonus_action(&mut self) -> bool {
+        let count = &mut self.combatants[self.turn].actions.bonus_actions;
+        if *count == 0 {
+            return false;
+        }
+        *count = count.saturating_sub(1);
+        true
+    }
+
+    /// Use a reaction for the current combatant. Returns `true` if the reaction was used, or
+    /// `false` if the combatant had no reactions left to use.
+    ///
+    /// This function only decrements the number of reactions available to the combatant, meaning
+    /// the combat log will not display any information about the reaction taken.
+    pub fn use_reaction(&mut self) -> bool {
+        let count = &mut self.combatants[self.turn].actions.reactions;
+        if *count == 0 {
+            return false;
+        }
+        *count = count.saturating_sub(1);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use monster::Size;
+
+    /// Ensure that the tracker advances turns correctly.
+    #[test]
+    fn test_tracker_next_turn() {
+        let mut tracker = Tracker::new(vec![
+            Monster {
+                index: "goblin".to_string(),
+                name: "Goblin".to_string(),
+                ..Default::default()
+            }.into(),
+            Monster {
+                index: "ogre".to_string(),
+                name: "Ogre".to_string(),
+                ..Default::default()
+            }.into(),
+            Monster {
+                index: "tarrasque".to_string(),
+                name: "Tarrasque".to_string(),
+                ..Default::default()
+            }.into(),
+        ]);
+
+        assert_eq!(tracker.turn, 0);
+        assert_eq!(tracker.round, 0);
+
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 1);
+        assert_eq!(tracker.round, 0);
+
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 2);
+        assert_eq!(tracker.round, 0);
+
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 0);
+        assert_eq!(tracker.round, 1);
+    }
+
+    /// Ensure that with side initiative, every combatant on a side takes its turn before the next
+    /// side goes, and that removing the combatant with the turn passes it on in that order.
+    #[test]
+    fn side_initiative() {
+        let mut tracker = Tracker::new(
+            ["Goblin", "Fighter", "Ogre", "Wizard", "Commoner"]
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into()),
+        );
+        tracker.combatants[1].side = Side::Ally;
+        tracker.combatants[3].side = Side::Ally;
+        tracker.combatants[4].side = Side::Neutral;
+        let turn_order = TurnOrder::Sides(vec![Side::Enemy, Side::Ally]);
+        assert!(tracker.execute(TrackerCommand::SetTurnOrder(turn_order.clone())));
+        assert!(!tracker.execute(TrackerCommand::SetTurnOrder(turn_order)));
+        assert_eq!(tracker.turn_sequence(), [0, 2, 1, 3, 4]);
+
+        let mut turns = Vec::new();
+        for _ in 0..5 {
+            tracker.next_turn();
+            turns.push(tracker.turn);
+        }
+        assert_eq!(turns, [2, 1, 3, 4, 0]);
+        assert_eq!(tracker.round, 1);
+
+        tracker.next_turn();
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 1);
+        tracker.remove(1);
+        assert_eq!(tracker.combatants[tracker.turn].name(), "Wizard");
+    }
+
+    /// Ensure that in popcorn initiative, the turn can only pass to combatants who haven't acted
+    /// this round, until everyone has.
+    #[test]
+    fn popcorn_initiative() {
+        let mut tracker = Tracker::new(
+            ["Goblin", "Ogre", "Wolf"]
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into()),
+        );
+        tracker.set_turn_order(TurnOrder::Popcorn);
+        assert!(!tracker.pass_turn(0));
+        assert!(tracker.execute(TrackerCommand::PassTurn { next: 2 }));
+        assert_eq!(tracker.turn_sequence(), [0, 2, 1]);
+        assert_eq!(tracker.waiting(), [1]);
+        assert!(!tracker.pass_turn(0));
+
+        // the last to act picks who starts the next round, even themselves
+        assert!(tracker.pass_turn(1));
+        assert!(tracker.pass_turn(1));
+        assert_eq!((tracker.turn, tracker.round), (1, 1));
+        assert!(tracker.acted.is_empty());
+
+        // advancing without picking goes to the first combatant left
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 0);
+        tracker.remove(1);
+        assert!(tracker.acted.is_empty());
+        assert_eq!(tracker.waiting(), [1]);
+    }
+
+    /// Ensure that speed factor initiative adds the size and declared speed factors to the roll,
+    /// and that turns go from the highest initiative to the lowest, even when it is rolled again
+    /// before the round ends.
+    #[test]
+    fn speed_factor_initiative() {
+        let mut tracker = Tracker::new(["Goblin", "Ogre", "Wolf"].map(|name| {
+            Monster { name: name.to_string(), size: Size::Medium, ..Default::default() }.into()
+        }));
+        tracker.set_turn_order(TurnOrder::SpeedFactor);
+
+        let ogre = &mut tracker.combatants[1];
+        ogre.kind = Monster { size: Size::Large, ..Default::default() }.into();
+        ogre.speed_factors = vec![SpeedFactor::HeavyWeapon, SpeedFactor::TwoHandedWeapon];
+        let bonus = ogre.initiative_bonus();
+        assert_eq!(ogre.roll_speed_factor(Edition::Rules2014, |_| 10), 10 + bonus - 2 - 2 - 2);
+        assert_eq!(SpeedFactor::CastSpell(3).modifier(), -3);
+
+        for (index, initiative) in [(0, 5), (1, 15), (2, 10)] {
+            tracker.combatants[index].initiative = Some(initiative);
+        }
+        tracker.turn = 1;
+        assert_eq!(tracker.turn_sequence(), [1, 2, 0]);
+        tracker.next_turn();
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 0);
+
+        // the goblin rolls the highest initiative for the next round, but still starts it
+        tracker.combatants[0].initiative = Some(20);
+        tracker.next_turn();
+        assert_eq!((tracker.turn, tracker.round), (0, 1));
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 1);
+    }
+
+    /// Ensure that with combined turns, copies of the same monster next to each other take one
+    /// turn together, each starting and ending its own.
+    #[test]
+    fn combined_turns() {
+        let mut tracker = Tracker::new(
+            ["Goblin", "Goblin", "Ogre", "Goblin"]
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into()),
+        );
+        assert_eq!(tracker.turn_groups().len(), 4);
+        assert!(tracker.execute(TrackerCommand::SetCombinedTurns(true)));
+        assert_eq!(tracker.turn_groups(), [vec![0, 1], vec![2], vec![3]]);
+        assert_eq!(tracker.turn_members(), [0, 1]);
+
+        tracker.combatants[1].actions.actions = 0;
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 2);
+        assert_eq!(tracker.acted, [0, 1]);
+        tracker.next_turn();
+        tracker.next_turn();
+        assert_eq!((tracker.turn, tracker.round), (0, 1));
+        assert_eq!(tracker.combatants[1].actions.actions, 1);
+        let turns = tracker.log
+            .iter()
+            .filter(|entry| entry.round == 1 && matches!(entry.event, LogEvent::TurnStarted { .. }))
+            .count();
+        assert_eq!(turns, 2);
+
+        // the rest of the group keeps the turn
+        tracker.remove(0);
+        assert_eq!((tracker.turn, tracker.turn_members()), (0, vec![0]));
+    }
+
+    /// Ensure that turns pass over dead and unconscious monsters when the rules say so, but not
+    /// over characters, and not once the monsters are healed.
+    #[test]
+    fn skip_turns() {
+        let mut tracker = Tracker::new([
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+            Monster { name: "Ogre".to_string(), hit_points: 59, ..Default::default() }.into(),
+            Character { name: "Tav".to_string(), hit_points: 10, ..Default::default() }.into(),
+            Monster { name: "Orc".to_string(), hit_points: 15, ..Default::default() }.into(),
+        ]);
+        tracker.rules.skip_dead = true;
+        tracker.damage(1, 59, None);
+        tracker.damage(2, 10, None);
+        tracker.damage(3, 15, None);
+        tracker.combatants[3].regeneration = Regeneration::parse(
+            "The orc regains 10 hit points at the start of its turn.",
+        );
+
+        // the character rolls death saves, and the orc may regenerate
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 2);
+        assert_eq!(
+            tracker.log.iter().rev().nth(1).map(|entry| &entry.event),
+            Some(&LogEvent::TurnSkipped { combatant: "Ogre".to_string() }),
+        );
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 3);
+
+        // unconscious monsters are only skipped with their own rule
+        tracker.apply_condition(0, ConditionKind::Unconscious, ConditionDuration::Forever);
+        tracker.next_turn();
+        assert_eq!((tracker.turn, tracker.round), (0, 1));
+        tracker.rules.skip_unconscious = true;
+        tracker.next_turn();
+        assert_eq!(tracker.turn, 2);
+
+        // healed monsters take their turns again
+        tracker.damage(1, -10, None);
+        tracker.next_turn();
+        tracker.next_turn();
+        assert_eq!((tracker.turn, tracker.round), (1, 2));
+    }
+
+    /// Ensure that combatants can be moved between zones, and found by zone.
+    #[test]
+    fn zones() {
+        let mut tracker = Tracker::new(
+            ["Goblin", "Ogre", "Tarrasque"]
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into()),
+        );
+        let bridge = Some("Bridge".to_string());
+        assert!(tracker.execute(TrackerCommand::SetZone { index: 0, zone: bridge.clone() }));
+        assert!(tracker.execute(TrackerCommand::SetZone { index: 2, zone: bridge.clone() }));
+        assert!(!tracker.execute(TrackerCommand::SetZone { index: 2, zone: bridge }));
+        assert!(tracker.set_zone(1, Some("Altar".to_string())));
+        assert_eq!(tracker.zones(), ["Altar", "Bridge"]);
+        assert_eq!(tracker.in_zone("Bridge"), [0, 2]);
+
+        assert!(tracker.set_zone(1, None));
+        assert_eq!(tracker.zones(), ["Bridge"]);
+        assert_eq!(
+            tracker.log.last().map(|entry| entry.event.to_string()),
+            Some("Ogre left the zones".to_string()),
+        );
+    }
+
+    /// Ensure that tokens are placed on free squares, and that distances on the grid count
+    /// diagonals like straight lines.
+    #[test]
+    fn grid() {
+        let mut tracker = Tracker::new(
+            ["Goblin", "Ogre", "Tarrasque"]
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into()),
+        );
+        assert_eq!(tracker.free_square(), Position::new(0, 0));
+        assert!(tracker.execute(TrackerCommand::SetPosition {
+            index: 0,
+            position: Some(Position::new(0, 0)),
+        }));
+        assert_eq!(tracker.free_square(), Position::new(1, 0));
+        assert!(tracker.execute(TrackerCommand::SetPosition {
+            index: 1,
+            position: Some(Position::new(3, -2)),
+        }));
+        assert_eq!(tracker.distance(0, 1), Some(15));
+        assert_eq!(tracker.distance(0, 2), None);
+
+        assert!(tracker.execute(TrackerCommand::SetPosition { index: 0, position: None }));
+        assert!(!tracker.execute(TrackerCommand::SetPosition { index: 0, position: None }));
+        assert_eq!(tracker.distance(0, 1), None);
+    }
+
+    /// Ensure that each round counts as six seconds of in-game time, and that time added by hand
+    /// is logged.
+    #[test]
+    fn elapsed_time() {
+        let mut tracker = Tracker::new(
+            ["Goblin", "Ogre"]
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into()),
+        );
+        tracker.execute(TrackerCommand::NextTurn);
+        assert_eq!(tracker.elapsed, 0);
+        tracker.execute(TrackerCommand::NextTurn);
+        assert_eq!(tracker.elapsed, 6);
+
+        assert!(tracker.execute(TrackerCommand::PassTime { seconds: 5400 }));
+        assert_eq!(tracker.elapsed, 5406);
+        assert_eq!(
+            tracker.log.last().map(|entry| entry.event.to_string()),
+            Some("1 hour 30 minutes passed".to_string()),
+        );
+
+        // the clock stops at zero
+        assert!(tracker.execute(TrackerCommand::PassTime { seconds: -6000 }));
+        assert_eq!(tracker.elapsed, 0);
+        assert_eq!(
+            tracker.log.last().map(|entry| entry.event.to_string()),
+            Some("1 hour 30 minutes 6 seconds taken back".to_string()),
+        );
+        assert!(!tracker.execute(TrackerCommand::PassTime { seconds: -60 }));
+    }
+
+    /// Ensure that the XP of the defeated enemies is totaled from their stat blocks or challenge
+    /// ratings, and split between the player characters.
+    #[test]
+    fn xp_award() {
+        let goblin = Monster { name: "Goblin".to_string(), xp: 50, ..Default::default() };
+        let ogre = Monster { name: "Ogre".to_string(), challenge_rating: 2.0, ..Default::default() };
+        let mut tracker = Tracker::new([
+            Character { name: "Tav".to_string(), hit_points: 10, ..Default::default() }.into(),
+            Character { name: "Shadowheart".to_string(), hit_points: 10, ..Default::default() }
+                .into(),
+            goblin.clone().into(),
+            goblin.into(),
+            ogre.into(),
+        ]);
+        for index in 2..5 {
+            tracker.combatants[index].hit_points = 1;
+        }
+        assert!(!tracker.is_over());
+        assert!(!tracker.execute(TrackerCommand::AwardXp));
+
+        tracker.execute(TrackerCommand::Damage { index: 2, amount: 5, kind: None });
+        tracker.execute(TrackerCommand::Damage { index: 3, amount: 5, kind: None });
+        tracker.execute(TrackerCommand::Damage { index: 4, amount: 5, kind: None });
+        assert!(tracker.is_over());
+
+        let award = tracker.xp_award();
+        assert_eq!(
+            award.defeated,
+            [("Goblin".to_string(), 2, 100), ("Ogre".to_string(), 1, 450)],
+        );
+        assert_eq!((award.total(), award.share()), (550, 275));
+
+        assert!(tracker.execute(TrackerCommand::AwardXp));
+        assert_eq!(
+            tracker.log.last().map(|entry| entry.event.to_string()),
+            Some("Awarded 550 XP, 275 each to 2 characters".to_string()),
+        );
+    }
+
+    /// Ensure that damage, healing, and kills are credited to the combatant whose turn it is, and
+    /// follow renames.
+    #[test]
+    fn combat_stats() {
+        let mut tracker = Tracker::new([
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+            Character { name: "Tav".to_string(), hit_points: 10, ..Default::default() }.into(),
+            Character { name: "Shadowheart".to_string(), hit_points: 10, ..Default::default() }
+                .into(),
+        ]);
+        let stats = |name: &str, dealt, taken, healed, kills| CombatStats {
+            name: name.to_string(),
+            damage_dealt: dealt,
+            damage_taken: taken,
+            healing_done: healed,
+            kills,
+        };
+
+        // the opening turn counts before it is over, though the log doesn't show it started
+        tracker.execute(TrackerCommand::Damage { index: 1, amount: 2, kind: None });
+        assert_eq!(tracker.combat_stats(), [stats("Goblin", 2, 0, 0, 0), stats("Tav", 0, 2, 0, 0)]);
+
+        tracker.next_turn();
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 5, kind: None });
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 5, kind: None });
+
+        tracker.next_turn();
+        tracker.execute(TrackerCommand::Damage { index: 1, amount: 3, kind: None });
+        tracker.execute(TrackerCommand::Heal { index: 1, amount: 4 });
+        tracker.execute(TrackerCommand::Heal { index: 2, amount: 1 });
+        tracker.execute(TrackerCommand::Edit {
+            index: 1,
+            edit: CombatantEdit { name: Some("Lae'zel".to_string()), ..Default::default() },
+        });
+
+        assert_eq!(tracker.opening.as_deref(), Some("Goblin"));
+        assert_eq!(tracker.combat_stats(), [
+            stats("Goblin", 2, 10, 0, 0),
+            stats("Lae'zel", 10, 5, 0, 1),
+            stats("Shadowheart", 3, 0, 4, 0),
+        ]);
+    }
+
+    /// Ensure that hit points are replayed from the log at the end of each round, counting 0 below
+    /// 0 hit points and before joining.
+    #[test]
+    fn hit_points_by_round() {
+        let mut tracker = Tracker::new([
+            Character { name: "Tav".to_string(), hit_points: 10, ..Default::default() }.into(),
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+        ]);
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 4, kind: None });
+        tracker.execute(TrackerCommand::GrantTemporaryHitPoints { index: 0, amount: 5 });
+        tracker.next_turn();
+        tracker.next_turn();
+
+        // the temporary hit points absorb the damage
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 3, kind: None });
+        tracker.add(Character {
+            name: "Karlach".to_string(),
+            hit_points: 20,
+            ..Default::default()
+        });
+        tracker.next_turn();
+        tracker.next_turn();
+        tracker.next_turn();
+
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 10, kind: None });
+        tracker.execute(TrackerCommand::Damage { index: 2, amount: 5, kind: None });
+        tracker.execute(TrackerCommand::Edit {
+            index: 0,
+            edit: CombatantEdit { name: Some("Lae'zel".to_string()), ..Default::default() },
+        });
+        assert_eq!(tracker.round, 2);
+        assert_eq!(tracker.hit_points_by_round([0]), [10, 6, 6, 0]);
+        assert_eq!(tracker.hit_points_by_round([0, 2]), [10, 6, 26, 15]);
+    }
+
+    /// Ensure that the replayed hit points are the ones the combatants had, past temporary hit
+    /// points, the floor and minion rules, deaths, and rests.
+    #[test]
+    fn hit_points_by_round_exact() {
+        let monster = |name: &str, hit_points| {
+            Monster { name: name.to_string(), hit_points, ..Default::default() }.into()
+        };
+        let mut tracker = Tracker::new([
+            Character { name: "Tav".to_string(), hit_points: 10, ..Default::default() }.into(),
+            monster("Goblin", 7),
+            monster("Kobold", 5),
+            monster("Cultist", 9),
+        ]);
+        tracker.rules.floor_hit_points = true;
+        tracker.rules.minions = true;
+        tracker.combatants[2].minion = true;
+
+        tracker.execute(TrackerCommand::GrantTemporaryHitPoints { index: 0, amount: 5 });
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 8, kind: None });
+        tracker.execute(TrackerCommand::Damage { index: 1, amount: 25, kind: None });
+        tracker.execute(TrackerCommand::Damage { index: 2, amount: 1, kind: None });
+        tracker.execute(TrackerCommand::Kill { index: 3 });
+        (0..4).for_each(|_| tracker.next_turn());
+
+        tracker.execute(TrackerCommand::LongRest { index: 0 });
+        assert_eq!(tracker.round, 1);
+        assert_eq!(tracker.hit_points_by_round([0]), [10, 7, 10]);
+        assert_eq!(tracker.hit_points_by_round([1]), [7, 0, 0]);
+        assert_eq!(tracker.hit_points_by_round([2]), [5, 0, 0]);
+        assert_eq!(tracker.hit_points_by_round([3]), [9, 0, 0]);
+    }
+
+    /// Ensure that treasure is rolled on the table for the monster's challenge rating.
+    #[test]
+    fn loot() {
+        let roll = |sides| if sides == 100 { 50 } else { 3 };
+        let coins = loot::individual_treasure(0.25, roll);
+        assert_eq!(coins.to_string(), "12 sp");
+        let coins = loot::individual_treasure(12.0, roll);
+        assert_eq!(coins.to_string(), "30 pp, 600 gp");
+
+        let coins = loot::hoard_treasure(6.0, |_| 1);
+        assert_eq!(coins.to_string(), "30 pp, 600 gp, 2000 sp, 200 cp");
+        assert_eq!(Coins::default().to_string(), "no coins");
+    }
+
+    /// Ensure that the threat of the monsters still standing is rated against the party's XP
+    /// thresholds, with the multiplier for their number.
+    #[test]
+    fn threat() {
+        let goblin = Monster { name: "Goblin".to_string(), xp: 50, ..Default::default() };
+        let mut tracker = Tracker::new(vec![goblin.clone().into(), goblin.into()]);
+        assert_eq!(tracker.threat(), None);
+
+        for name in ["Tav", "Karlach", "Astarion"] {
+            let character = Character { name: name.to_string(), level: 1, ..Default::default() };
+            tracker.combatants.push(character.into());
+        }
+        tracker.combatants[0].hit_points = 7;
+        tracker.combatants[1].hit_points = 7;
+        let threat = tracker.threat().unwrap();
+        assert_eq!((threat.adjusted_xp, threat.thresholds), (150, [75, 150, 225, 300]));
+        assert_eq!(threat.difficulty(), Difficulty::Medium);
+
+        // a single goblin left
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 7, kind: None });
+        let threat = tracker.threat().unwrap();
+        assert_eq!((threat.adjusted_xp, threat.difficulty()), (50, Difficulty::Trivial));
+    }
+
+    /// Ensure that with the morale rule, monsters check their morale when their leader drops or
+    /// half their group is down, once each, and that fleeing is logged.
+    #[test]
+    fn morale() {
+        let monster = |name: &str| Combatant {
+            hit_points: 7,
+            ..Monster { name: name.to_string(), ..Default::default() }.into()
+        };
+        let mut tracker = Tracker::new(
+            vec![monster("Bugbear"), monster("Goblin"), monster("Goblin"), monster("Goblin")],
+        );
+        tracker.combatants[0].leader = true;
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 7, kind: None });
+        assert!(tracker.morale_checks.is_empty());
+
+        tracker.rules.morale = true;
+        tracker.combatants[0].hit_points = 7;
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 7, kind: None });
+        let checks = std::mem::take(&mut tracker.morale_checks);
+        assert_eq!(checks, [1, 2, 3].map(|index| MoraleCheck {
+            index,
+            reason: MoraleReason::LeaderDropped,
+        }));
+
+        // the first goblin down isn't half of the three
+        tracker.execute(TrackerCommand::SetFleeing { index: 3, fleeing: true });
+        tracker.execute(TrackerCommand::Damage { index: 1, amount: 7, kind: None });
+        assert!(tracker.morale_checks.is_empty());
+        tracker.execute(TrackerCommand::Damage { index: 2, amount: 7, kind: None });
+        assert!(tracker.morale_checks.is_empty(), "the last goblin is already fleeing");
+
+        tracker.execute(TrackerCommand::SetFleeing { index: 3, fleeing: false });
+        assert_eq!(tracker.log.last().unwrap().event.to_string(), "Goblin stopped fleeing");
+        tracker.combatants[1].hit_points = 7;
+        tracker.combatants[2].hit_points = 7;
+        tracker.execute(TrackerCommand::Damage { index: 1, amount: 7, kind: None });
+        tracker.execute(TrackerCommand::Damage { index: 2, amount: 7, kind: None });
+        assert_eq!(tracker.morale_checks, [
+            MoraleCheck { index: 3, reason: MoraleReason::GroupHalved },
+        ]);
+    }
+
+    /// Ensure that editing a combatant only changes the fields given, keeps its hit points under
+    /// the new maximum, and logs a rename.
+    #[test]
+    fn edit_combatant() {
+        let goblin = Monster { name: "Gobin".to_string(), hit_points: 7, ..Default::default() };
+        let mut tracker = Tracker::new(vec![goblin.into()]);
+        let edit = CombatantEdit {
+            name: Some("Goblin".to_string()),
+            max_hit_points: Some(5),
+            level: Some(3),
+            ..Default::default()
+        };
+        assert!(tracker.execute(TrackerCommand::Edit { index: 0, edit: edit.clone() }));
+        let combatant = &tracker.combatants[0];
+        assert_eq!((combatant.name(), combatant.hit_points, combatant.max_hit_points()), (
+            "Goblin", 5, 5,
+        ));
+        assert_eq!(tracker.log.last().unwrap().event.to_string(), "Gobin was renamed Goblin");
+
+        // the level is ignored for monsters, and the rest is already applied
+        assert!(!tracker.execute(TrackerCommand::Edit { index: 0, edit }));
+    }
+
+    /// Ensure that removing a combatant keeps the turn on the right combatant.
+    #[test]
+    fn remove_combatant() {
+        let mut tracker = Tracker::new(
+            ["Goblin", "Ogre", "Tarrasque"]
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into()),
+        );
+
+        tracker.turn = 2;
+        assert!(tracker.remove(0).is_some());
+        assert_eq!(tracker.current_combatant().name(), "Tarrasque");
+
+        assert!(tracker.remove(1).is_some());
+        assert_eq!(tracker.turn, 0);
+        assert_eq!(tracker.current_combatant().name(), "Ogre");
+
+        assert!(tracker.remove(0).is_none());
+    }
+
+    /// Ensure that groups follow their members when combatants are removed.
+    #[test]
+    fn selection_groups() {
+        let mut tracker = Tracker::new(
+            ["Fighter", "Goblin", "Wizard", "Ogre"]
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into()),
+        );
+
+        tracker.save_group("party", vec![2, 0, 2, 9]);
+        tracker.save_group("enemies", vec![1, 3]);
+        assert_eq!(tracker.groups["party"], [0, 2]);
+
+        tracker.remove(1);
+        assert_eq!(tracker.groups["party"], [0, 1]);
+        assert_eq!(tracker.groups["enemies"], [2]);
+
+        tracker.remove(2);
+        assert!(!tracker.groups.contains_key("enemies"));
+
+        tracker.save_group("party", Vec::new());
+        assert!(tracker.groups.is_empty());
+    }
+
+    /// Ensure that turns, expired conditions, and damage are recorded in the combat log.
+    #[test]
+    fn combat_log() {
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), ..Default::default() }.into(),
+            Monster { name: "Ogre".to_string(), ..Default::default() }.into(),
+        ]);
+        tracker.combatants[0].conditions.push(Condition {
+            kind: ConditionKind::Prone,
+            duration: ConditionDuration::Rounds(std::num::NonZeroU32::new(1).unwrap()),
+            source: ConditionSource::default(),
+        });
+
+        tracker.next_turn();
+        tracker.damage(0, 5, None);
+
+        let goblin = "Goblin".to_string();
+        assert_eq!(tracker.log, [
+            LogEntry {
+                round: 0,
+                event: LogEvent::ConditionEnded {
+                    combatant: goblin.clone(),
+                    condition: ConditionKind::Prone,
+                },
+            },
+            LogEntry { round: 0, event: LogEvent::TurnStarted { combatant: "Ogre".to_string() } },
+            LogEntry {
+                round: 0,
+                event: LogEvent::Damaged {
+                    combatant: goblin,
+                    amount: 5,
+                    kind: None,
+                    hit_points: Some((0, -5)),
+                    index: Some(0),
+                },
+            },
+        ]);
+    }
+
+    /// Ensure that hit points only go negative without the floor rule.
+    #[test]
+    fn floor_hit_points() {
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+        ]);
+        tracker.damage(0, 10, None);
+        assert_eq!(tracker.combatants[0].hit_points, -3);
+
+        tracker.combatants[0].hit_points = 7;
+        tracker.rules.floor_hit_points = true;
+        assert_eq!(tracker.damage(0, 10, None), 10);
+        assert_eq!(tracker.combatants[0].hit_points, 0);
+    }
+
+    /// Ensure that healing starts from 0 hit points, and stops at the maximum.
+    #[test]
+    fn heal() {
+        let goblin = Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() };
+        let mut tracker = Tracker::new(vec![goblin.into()]);
+        tracker.combatants[0].hit_points = -3;
+        assert_eq!(tracker.heal(0, 4), 4);
+        assert_eq!(tracker.combatants[0].hit_points, 4);
+        assert_eq!(tracker.log.last().unwrap().event.to_string(), "Goblin healed 4");
+
+        assert_eq!(tracker.heal(0, 10), 3);
+        assert_eq!(tracker.combatants[0].hit_points, 7);
+        assert!(!tracker.execute(TrackerCommand::Heal { index: 0, amount: 5 }));
+
+        // healing nothing leaves hit points below 0 alone
+        tracker.combatants[0].hit_points = -3;
+        let logged = tracker.log.len();
+        assert!(!tracker.execute(TrackerCommand::Heal { index: 0, amount: 0 }));
+        assert_eq!(tracker.combatants[0].hit_points, -3);
+        assert_eq!(tracker.log.len(), logged);
+    }
+
+    /// Ensure that temporary hit points don't stack, are lost before hit points, and are left
+    /// alone when hit points are set by hand.
+    #[test]
+    fn temporary_hit_points() {
+        let goblin = Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() };
+        let mut tracker = Tracker::new(vec![goblin.into()]);
+        let grant = |amount| TrackerCommand::GrantTemporaryHitPoints { index: 0, amount };
+        let hit_points = |tracker: &Tracker| {
+            (tracker.combatants[0].hit_points, tracker.combatants[0].temporary_hit_points)
+        };
+        assert!(tracker.execute(grant(5)));
+        assert!(!tracker.execute(grant(3)));
+        let event = tracker.log.last().unwrap().event.to_string();
+        assert_eq!(event, "Goblin has 5 temporary hit points");
+
+        assert_eq!(tracker.damage(0, 4, None), 4);
+        assert_eq!(hit_points(&tracker), (7, 1));
+        tracker.damage(0, 3, None);
+        assert_eq!(hit_points(&tracker), (5, 0));
+
+        tracker.execute(grant(2));
+        tracker.set_hit_points(0, 3);
+        assert_eq!(hit_points(&tracker), (3, 2));
+    }
+
+    /// Ensure that stabilizing leaves a combatant at 0 hit points until it takes damage or heals,
+    /// and that dead combatants aren't healed, and have their turns passed over.
+    #[test]
+    fn stabilize_and_kill() {
+        let mut tracker = Tracker::new(vec![
+            Character { name: "Tav".to_string(), hit_points: 10, ..Default::default() }.into(),
+            Character { name: "Shadowheart".to_string(), hit_points: 10, ..Default::default() }
+                .into(),
+        ]);
+        tracker.damage(0, 12, None);
+        assert!(tracker.execute(TrackerCommand::Stabilize { index: 0 }));
+        assert!(!tracker.execute(TrackerCommand::Stabilize { index: 0 }));
+        assert_eq!((tracker.combatants[0].hit_points, tracker.combatants[0].stable), (0, true));
+        assert_eq!(tracker.log.last().unwrap().event.to_string(), "Tav is stable");
+        tracker.damage(0, 1, None);
+        assert!(!tracker.combatants[0].stable);
+
+        // knocked out instead of killed
+        tracker.combatants[1].concentrating = true;
+        assert!(tracker.execute(TrackerCommand::Stabilize { index: 1 }));
+        let shadowheart = &tracker.combatants[1];
+        assert_eq!((shadowheart.hit_points, shadowheart.concentrating), (0, false));
+
+        assert!(tracker.execute(TrackerCommand::Kill { index: 0 }));
+        assert!(!tracker.execute(TrackerCommand::Kill { index: 0 }));
+        assert_eq!(tracker.log.last().unwrap().event.to_string(), "Tav died");
+        assert!(!tracker.execute(TrackerCommand::Heal { index: 0, amount: 5 }));
+        assert!(!tracker.execute(TrackerCommand::Stabilize { index: 0 }));
+        assert!(!tracker.skips_turn(0));
+        tracker.rules.skip_dead = true;
+        assert!(tracker.skips_turn(0));
+        assert!(!tracker.skips_turn(1));
+
+        tracker.set_hit_points(0, 1);
+        assert!(!tracker.combatants[0].dead);
+    }
+
+    /// Ensure that commands make their changes, and report the ones that can't be made.
+    #[test]
+    fn execute_commands() {
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+        ]);
+
+        assert!(tracker.execute(TrackerCommand::Damage { index: 0, amount: 5, kind: None }));
+        assert!(tracker.execute(TrackerCommand::Heal { index: 0, amount: 3 }));
+        assert_eq!(tracker.combatants[0].hit_points, 5);
+
+        let (kind, duration) = (ConditionKind::Prone, ConditionDuration::Forever);
+        assert!(tracker.execute(TrackerCommand::AddCondition { index: 0, kind, duration }));
+        assert!(!tracker.execute(TrackerCommand::AddCondition { index: 0, kind, duration }));
+        assert!(tracker.execute(TrackerCommand::RemoveCondition { index: 0, kind }));
+        assert!(!tracker.execute(TrackerCommand::RemoveCondition { index: 0, kind }));
+        assert!(tracker.combatants[0].conditions.is_empty());
+
+        assert!(tracker.execute(TrackerCommand::UseAction));
+        assert!(!tracker.execute(TrackerCommand::UseAction));
+        assert!(!tracker.execute(TrackerCommand::Remove { index: 0 }));
+        assert_eq!(tracker.log.last(), Some(&LogEntry {
+            round: 0,
+            event: LogEvent::ConditionEnded { combatant: "Goblin".to_string(), condition: kind },
+        }));
+    }
+
+    /// Ensure that commands changing nothing, or referring to combatants that aren't there, report
+    /// no change and leave the tracker alone.
+    #[test]
+    fn execute_no_change() {
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+        ]);
+
+        let side = tracker.combatants[0].side;
+        assert!(!tracker.execute(TrackerCommand::SetSide { index: 0, side }));
+        assert!(!tracker.execute(TrackerCommand::SetHitPoints { index: 0, value: 7 }));
+        assert!(!tracker.execute(TrackerCommand::Damage { index: 0, amount: 0, kind: None }));
+        assert!(!tracker.execute(TrackerCommand::SetMinion { index: 0, minion: false }));
+        assert!(!tracker.execute(TrackerCommand::SetNotes { index: 0, notes: String::new() }));
+        assert!(tracker.execute(TrackerCommand::SetInitiative { index: 0, value: Some(12) }));
+        assert!(!tracker.execute(TrackerCommand::SetInitiative { index: 0, value: Some(12) }));
+
+        assert!(!tracker.execute(TrackerCommand::Damage { index: 1, amount: 5, kind: None }));
+        assert!(!tracker.execute(TrackerCommand::SetLeader { index: 1, leader: true }));
+        assert!(!tracker.execute(TrackerCommand::EndEffect { index: 3, effect: 0 }));
+        assert!(!tracker.execute(TrackerCommand::PassTurn { next: 1 }));
+        assert_eq!(tracker.log.len(), 0);
+
+        let mut empty = Tracker::new(Vec::new());
+        assert!(!empty.execute(TrackerCommand::NextTurn));
+        assert!(!empty.execute(TrackerCommand::UseReaction));
+    }
+
+    /// Ensure that only player characters can be given Inspiration, and that it is logged.
+    #[test]
+    fn inspiration() {
+        let mut tracker = Tracker::new(vec![
+            Character { name: "Tav".to_string(), ..Default::default() }.into(),
+            Monster { name: "Goblin".to_string(), ..Default::default() }.into(),
+        ]);
+
+        assert!(tracker.execute(TrackerCommand::SetInspiration { index: 0, inspiration: true }));
+        assert!(!tracker.execute(TrackerCommand::SetInspiration { index: 0, inspiration: true }));
+        assert!(!tracker.execute(TrackerCommand::SetInspiration { index: 1, inspiration: true }));
+        assert!(tracker.combatants[0].inspiration);
+        assert!(!tracker.combatants[1].inspiration);
+
+        assert!(tracker.execute(TrackerCommand::SetInspiration { index: 0, inspiration: false }));
+        assert_eq!(tracker.log.last(), Some(&LogEntry {
+            round: 0,
+            event: LogEvent::Inspiration { combatant: "Tav".to_string(), gained: false },
+        }));
+    }
+
+    /// Ensure that short rests spend hit dice to heal up to the maximum, restore actions, and end
+    /// conditions that don't last until removed.
+    #[test]
+    fn short_rest() {
+        let mut tracker = Tracker::new(vec![
+            Character { name: "Tav".to_string(), level: 3, hit_points: 20, ..Default::default() }
+                .into(),
+            Monster { name: "Goblin".to_string(), ..Default::default() }.into(),
+        ]);
+        tracker.combatants[0].hit_points = 5;
+        tracker.use_action();
+        let duration = ConditionDuration::Minutes(std::num::NonZeroU32::new(1).unwrap());
+        tracker.apply_condition(0, ConditionKind::Poisoned, duration);
+        tracker.apply_condition(0, ConditionKind::Prone, ConditionDuration::Forever);
+
+        assert!(!tracker.execute(TrackerCommand::ShortRest { index: 0, hit_dice: 4, healing: 9 }));
+        assert!(tracker.execute(TrackerCommand::ShortRest { index: 0, hit_dice: 2, healing: 9 }));
+        let tav = &tracker.combatants[0];
+        assert_eq!((tav.hit_points, tav.hit_dice_left()), (14, 1));
+        assert_eq!(tav.actions.actions, 1);
+        assert_eq!(tav.conditions.iter().map(|c| c.kind).collect::<Vec<_>>(), [
+            ConditionKind::Prone,
+        ]);
+
+        assert!(tracker.execute(TrackerCommand::ShortRest { index: 0, hit_dice: 1, healing: 9 }));
+        assert_eq!(tracker.combatants[0].hit_points, 20);
+        assert!(!tracker.execute(TrackerCommand::ShortRest { index: 1, hit_dice: 1, healing: 3 }));
+        assert!(tracker.execute(TrackerCommand::ShortRest { index: 1, hit_dice: 0, healing: 0 }));
+    }
+
+    /// Ensure that long rests restore hit points and half the hit dice, and end exhaustion, but
+    /// only for combatants with hit points left.
+    #[test]
+    fn long_rest() {
+        let mut tracker = Tracker::new(vec![
+            Character { name: "Tav".to_string(), level: 5, hit_points: 40, ..Default::default() }
+                .into(),
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+        ]);
+        tracker.combatants[0].hit_points = 3;
+        tracker.combatants[0].hit_dice_spent = 5;
+        tracker.apply_condition(0, ConditionKind::Exhaustion, ConditionDuration::Forever);
+        tracker.apply_condition(0, ConditionKind::Prone, ConditionDuration::Forever);
+        tracker.combatants[1].hit_points = 0;
+
+        assert!(tracker.execute(TrackerCommand::LongRest { index: 0 }));
+        let tav = &tracker.combatants[0];
+        assert_eq!((tav.hit_points, tav.hit_dice_left()), (40, 2));
+        assert_eq!(tav.conditions.iter().map(|c| c.kind).collect::<Vec<_>>(), [
+            ConditionKind::Prone,
+        ]);
+
+        assert!(!tracker.execute(TrackerCommand::LongRest { index: 1 }));
+        assert_eq!(tracker.combatants[1].hit_points, 0);
+    }
+
+    /// Ensure that conditions aren't applied to combatants immune to them, and that the immunity
+    /// is logged instead.
+    #[test]
+    fn condition_immunity() {
+        let mut tracker = Tracker::new(vec![
             Monster {
-                index: "ogre".to_string(),
-                name: "Ogre".to_string(),
+                name: "Zombie".to_string(),
+                condition_immunities: vec![ConditionKind::Poisoned],
                 ..Default::default()
             }.into(),
+        ]);
+
+        let duration = ConditionDuration::Forever;
+        let kind = ConditionKind::Poisoned;
+        assert!(!tracker.execute(TrackerCommand::AddCondition { index: 0, kind, duration }));
+        assert!(tracker.combatants[0].conditions.is_empty());
+        assert_eq!(tracker.log.last(), Some(&LogEntry {
+            round: 0,
+            event: LogEvent::ConditionImmune { combatant: "Zombie".to_string(), condition: kind },
+        }));
+
+        let kind = ConditionKind::Prone;
+        assert!(tracker.execute(TrackerCommand::AddCondition { index: 0, kind, duration }));
+    }
+
+    /// Ensure that conditions bring the conditions they imply, and that ending them leaves those
+    /// behind to clear, unless another condition still implies them.
+    #[test]
+    fn implied_conditions() {
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), ..Default::default() }.into(),
+        ]);
+
+        let duration = ConditionDuration::Forever;
+        let kind = ConditionKind::Unconscious;
+        assert!(tracker.execute(TrackerCommand::AddCondition { index: 0, kind, duration }));
+        let kinds = tracker.combatants[0].conditions.iter().map(|c| c.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [kind, ConditionKind::Incapacitated, ConditionKind::Prone]);
+
+        // still incapacitated while stunned
+        let kind = ConditionKind::Stunned;
+        assert!(tracker.execute(TrackerCommand::AddCondition { index: 0, kind, duration }));
+        let kind = ConditionKind::Unconscious;
+        assert!(tracker.execute(TrackerCommand::RemoveCondition { index: 0, kind }));
+        assert_eq!(tracker.lingering, [Lingering {
+            index: 0,
+            ended: kind,
+            conditions: vec![ConditionKind::Prone],
+        }]);
+
+        tracker.lingering.clear();
+        let kind = ConditionKind::Stunned;
+        assert!(tracker.execute(TrackerCommand::RemoveCondition { index: 0, kind }));
+        assert_eq!(tracker.lingering, [Lingering {
+            index: 0,
+            ended: kind,
+            conditions: vec![ConditionKind::Incapacitated],
+        }]);
+    }
+
+    /// Ensure that grapples hold their targets until either side breaks them, and that the
+    /// grappler lets go when it is incapacitated or leaves.
+    #[test]
+    fn grapples() {
+        let mut tracker = Tracker::new(
+            ["Goblin", "Ogre", "Snake"]
+                .into_iter()
+                .map(|name| Monster { name: name.to_string(), ..Default::default() }.into())
+                .collect::<Vec<_>>(),
+        );
+        let grappled = |tracker: &Tracker, index: usize| {
+            tracker.combatants[index].conditions.iter().map(|c| c.kind).collect::<Vec<_>>()
+        };
+
+        let ogre = Grapple { grappler: 1, target: 0, restrains: false, escape_dc: Some(13) };
+        assert!(tracker.execute(TrackerCommand::Grapple(ogre.clone())));
+        assert!(!tracker.execute(TrackerCommand::Grapple(ogre.clone())));
+        let snake = Grapple { grappler: 2, target: 0, restrains: false, escape_dc: None };
+        assert!(tracker.execute(TrackerCommand::Grapple(snake)));
+        assert_eq!(grappled(&tracker, 0), [ConditionKind::Grappled]);
+
+        // still held by the snake once the ogre lets go, and free once the snake leaves
+        assert!(tracker.execute(TrackerCommand::Release { index: 1 }));
+        assert_eq!(grappled(&tracker, 0), [ConditionKind::Grappled]);
+        assert!(tracker.execute(TrackerCommand::Remove { index: 2 }));
+        assert!(tracker.grapples.is_empty());
+        assert!(grappled(&tracker, 0).is_empty());
+        assert!(!tracker.execute(TrackerCommand::Release { index: 0 }));
+
+        // escaping frees the target, and clearing the condition by hand ends the grapple
+        assert!(tracker.execute(TrackerCommand::Grapple(ogre.clone())));
+        assert!(tracker.execute(TrackerCommand::Release { index: 0 }));
+        assert!(grappled(&tracker, 0).is_empty());
+        assert!(tracker.execute(TrackerCommand::Grapple(ogre.clone())));
+        let kind = ConditionKind::Grappled;
+        assert!(tracker.execute(TrackerCommand::RemoveCondition { index: 0, kind }));
+        assert!(tracker.grapples.is_empty());
+
+        // knocking the grappler out frees the target
+        let restrain = Grapple { restrains: true, ..ogre };
+        assert!(tracker.execute(TrackerCommand::Grapple(restrain)));
+        assert_eq!(grappled(&tracker, 0), [ConditionKind::Restrained]);
+        let (kind, duration) = (ConditionKind::Unconscious, ConditionDuration::Forever);
+        assert!(tracker.execute(TrackerCommand::AddCondition { index: 1, kind, duration }));
+        assert!(tracker.grapples.is_empty());
+        assert!(grappled(&tracker, 0).is_empty());
+        assert!(tracker.log.iter().any(|entry| entry.event == LogEvent::Released {
+            grappler: "Ogre".to_string(),
+            target: "Goblin".to_string(),
+        }));
+    }
+
+    /// Ensure that end-of-turn effects trigger when their combatant's turn ends, resolving the
+    /// ones that don't need the table.
+    #[test]
+    fn end_of_turn_effects() {
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+            Monster { name: "Ogre".to_string(), hit_points: 59, ..Default::default() }.into(),
+        ]);
+        let save = Effect {
+            trigger: Trigger::EndOfTurn,
+            outcome: Outcome::Save { ability: AbilityKind::Wisdom, dc: 13 },
+            condition: Some(ConditionKind::Paralyzed),
+            source: ConditionSource::default(),
+        };
+        let burn = Effect {
+            trigger: Trigger::EndOfTurn,
+            outcome: Outcome::Damage { amount: "2".parse().unwrap(), kind: Some(DamageType::Fire) },
+            condition: None,
+            source: ConditionSource::default(),
+        };
+        let prone = Effect {
+            trigger: Trigger::EndOfTurn,
+            outcome: Outcome::End,
+            condition: Some(ConditionKind::Prone),
+            source: ConditionSource::default(),
+        };
+        assert!(tracker.execute(TrackerCommand::AddEffect { index: 0, effect: save.clone() }));
+        tracker.add_effect(0, burn);
+        tracker.add_effect(0, prone);
+        tracker.add_effect(1, save.clone());
+        // paralyzed brings incapacitated along
+        assert_eq!(tracker.combatants[0].conditions.len(), 3);
+
+        tracker.next_turn();
+        assert_eq!(tracker.combatants[0].hit_points, 5);
+        assert_eq!(tracker.combatants[0].effects.len(), 2);
+        assert_eq!(tracker.combatants[0].conditions.len(), 2);
+        assert_eq!(tracker.triggered, [Triggered { index: 0, effect: 0, details: save }]);
+
+        tracker.triggered.clear();
+        assert!(tracker.execute(TrackerCommand::EndEffect { index: 0, effect: 0 }));
+        assert!(!tracker.execute(TrackerCommand::EndEffect { index: 0, effect: 1 }));
+        let kinds = tracker.combatants[0].conditions.iter().map(|c| c.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [ConditionKind::Incapacitated]);
+        assert_eq!(tracker.log.last(), Some(&LogEntry {
+            round: 0,
+            event: LogEvent::ConditionEnded {
+                combatant: "Goblin".to_string(),
+                condition: ConditionKind::Paralyzed,
+            },
+        }));
+    }
+
+    /// Ensure that ongoing damage is taken at the start of its combatant's turn, applying its
+    /// resistances.
+    #[test]
+    fn start_of_turn_damage() {
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
             Monster {
-                index: "tarrasque".to_string(),
-                name: "Tarrasque".to_string(),
+                name: "Ogre".to_string(),
+                hit_points: 59,
+                damage_resistances: vec!["fire".to_string()],
                 ..Default::default()
             }.into(),
         ]);
+        let burn = Effect {
+            trigger: Trigger::StartOfTurn,
+            outcome: Outcome::Damage { amount: "5".parse().unwrap(), kind: Some(DamageType::Fire) },
+            condition: None,
+            source: ConditionSource::default(),
+        };
+        tracker.add_effect(0, burn.clone());
+        tracker.add_effect(1, burn);
 
-        assert_eq!(tracker.turn, 0);
-        assert_eq!(tracker.round, 0);
+        tracker.next_turn();
+        assert_eq!(tracker.combatants[0].hit_points, 7);
+        assert_eq!(tracker.combatants[1].hit_points, 57);
+        assert_eq!(tracker.log.last(), Some(&LogEntry {
+            round: 0,
+            event: LogEvent::Damaged {
+                combatant: "Ogre".to_string(),
+                amount: 2,
+                kind: Some(DamageType::Fire),
+                hit_points: Some((59, 57)),
+                index: Some(1),
+            },
+        }));
 
         tracker.next_turn();
-        assert_eq!(tracker.turn, 1);
-        assert_eq!(tracker.round, 0);
+        assert_eq!(tracker.combatants[0].hit_points, 2);
+    }
+
+    /// Ensure that regeneration heals at the start of its combatant's turn, unless damage of the
+    /// right type suppressed it.
+    #[test]
+    fn regeneration() {
+        let troll = Monster {
+            name: "Troll".to_string(),
+            hit_points: 84,
+            traits: vec![monster::Trait {
+                name: "Regeneration".to_string(),
+                desc: "The troll regains 10 hit points at the start of its turn. If the troll \
+                    takes acid or fire damage, this trait doesn't function at the start of the \
+                    troll's next turn.".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut tracker = Tracker::new(vec![
+            troll.into(),
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+        ]);
+        assert_eq!(tracker.combatants[0].regeneration, Some(Regeneration {
+            amount: 10,
+            needs_hit_points: false,
+            suppressed_by: vec![DamageType::Acid, DamageType::Fire],
+            suppressed: false,
+        }));
 
+        tracker.damage(0, 30, Some(DamageType::Slashing));
         tracker.next_turn();
-        assert_eq!(tracker.turn, 2);
-        assert_eq!(tracker.round, 0);
+        tracker.next_turn();
+        assert_eq!(tracker.combatants[0].hit_points, 64);
 
+        tracker.damage(0, 4, Some(DamageType::Fire));
         tracker.next_turn();
-        assert_eq!(tracker.turn, 0);
-        assert_eq!(tracker.round, 1);
+        tracker.next_turn();
+        assert_eq!(tracker.combatants[0].hit_points, 60);
+        assert!(!tracker.combatants[0].regeneration.as_ref().unwrap().suppressed);
+
+        // healing stops at the maximum
+        tracker.combatants[0].hit_points = 80;
+        tracker.next_turn();
+        tracker.next_turn();
+        assert_eq!(tracker.combatants[0].hit_points, 84);
+
+        let suppressed = true;
+        assert!(tracker.execute(TrackerCommand::SuppressRegeneration { index: 0, suppressed }));
+        assert!(!tracker.execute(TrackerCommand::SuppressRegeneration { index: 1, suppressed }));
+    }
+
+    /// Ensure that several conditions are applied to several combatants in a single change, which
+    /// subscribers are told about once.
+    #[test]
+    fn apply_several_conditions() {
+        use std::sync::{Arc, Mutex};
+
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+            Monster { name: "Ogre".to_string(), hit_points: 59, ..Default::default() }.into(),
+        ]);
+        let seen = Arc::new(Mutex::new(0));
+        tracker.subscribe({
+            let seen = seen.clone();
+            move |_| *seen.lock().unwrap() += 1
+        });
+
+        let command = |kinds: &[ConditionKind]| TrackerCommand::AddConditions {
+            indexes: vec![0, 1],
+            kinds: kinds.to_vec(),
+            duration: ConditionDuration::Forever,
+            source: ConditionSource::default(),
+        };
+        assert!(tracker.execute(command(&[ConditionKind::Charmed, ConditionKind::Incapacitated])));
+        assert_eq!(*seen.lock().unwrap(), 1);
+        assert_eq!(tracker.log.len(), 4);
+        for combatant in &tracker.combatants {
+            let kinds = combatant.conditions.iter().map(|c| c.kind).collect::<Vec<_>>();
+            assert_eq!(kinds, [ConditionKind::Charmed, ConditionKind::Incapacitated]);
+        }
+
+        assert!(!tracker.execute(command(&[ConditionKind::Charmed])));
+    }
+
+    /// Ensure that conditions keep what applied them, and that the conditions a combatant applied
+    /// are offered to be cleared once it drops.
+    #[test]
+    fn condition_sources() {
+        let mut tracker = Tracker::new(
+            ["Dragon", "Goblin", "Ogre"]
+                .map(|name| {
+                    Monster { name: name.to_string(), hit_points: 10, ..Default::default() }.into()
+                }),
+        );
+        let source = ConditionSource {
+            combatant: Some(0),
+            cause: Some("Frightful Presence".to_string()),
+        };
+        tracker.add_effect(1, Effect {
+            trigger: Trigger::EndOfTurn,
+            outcome: Outcome::Save { ability: AbilityKind::Wisdom, dc: 16 },
+            condition: Some(ConditionKind::Frightened),
+            source: source.clone(),
+        });
+        assert_eq!(tracker.combatants[1].conditions[0].source, source);
+        tracker.execute(TrackerCommand::AddConditions {
+            indexes: vec![0, 2],
+            kinds: vec![ConditionKind::Prone],
+            duration: ConditionDuration::Forever,
+            source: ConditionSource { combatant: Some(0), cause: None },
+        });
+        // the dragon knocked itself prone
+        assert!(tracker.combatants[0].conditions[0].source.is_empty());
+
+        tracker.damage(0, 10, None);
+        assert_eq!(tracker.orphaned, [Orphaned {
+            source: 0,
+            conditions: vec![(1, ConditionKind::Frightened), (2, ConditionKind::Prone)],
+        }]);
+        tracker.remove(1);
+        assert_eq!(tracker.orphaned[0].conditions, [(1, ConditionKind::Prone)]);
+        tracker.remove(0);
+        assert!(tracker.orphaned.is_empty());
+        assert!(tracker.combatants[0].conditions[0].source.is_empty());
+    }
+
+    /// Ensure that subscribers are told about every change made, until they unsubscribe.
+    #[test]
+    fn subscribe() {
+        use std::sync::{Arc, Mutex};
+
+        let mut tracker = Tracker::new(vec![
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+            Monster { name: "Ogre".to_string(), hit_points: 59, ..Default::default() }.into(),
+        ]);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let id = tracker.subscribe({
+            let seen = seen.clone();
+            move |event| seen.lock().unwrap().push(event.log.to_vec())
+        });
+
+        tracker.execute(TrackerCommand::Damage { index: 0, amount: 10, kind: None });
+        tracker.execute(TrackerCommand::SetNotes { index: 1, notes: "angry".to_string() });
+        // nothing changes, so subscribers aren't told
+        tracker.execute(TrackerCommand::Remove { index: 5 });
+        assert!(tracker.unsubscribe(id));
+        tracker.execute(TrackerCommand::NextTurn);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0], tracker.log[..2]);
+        assert!(seen[1].is_empty());
+        assert!(!tracker.unsubscribe(id));
+    }
+
+    /// Ensure that initiative rolls add the Dexterity modifier and every bonus, and keep the
+    /// higher of two rolls with advantage.
+    #[test]
+    fn initiative_modifiers() {
+        let scores = Ability { dexterity: 14, ..Default::default() };
+        let mut tracker = Tracker::new(vec![
+            Character { name: "Tav".to_string(), level: 5, scores, ..Default::default() }.into(),
+        ]);
+        assert_eq!(tracker.combatants[0].initiative_bonus(), 2);
+
+        let modifiers = ["+5", "jack", "adv"]
+            .into_iter()
+            .map(|modifier| modifier.parse().unwrap())
+            .collect::<Vec<InitiativeModifier>>();
+        assert!(tracker.execute(TrackerCommand::SetInitiativeModifiers { index: 0, modifiers }));
+        let tav = &tracker.combatants[0];
+        assert_eq!(tav.initiative_bonus(), 8);
+
+        let mut rolls = [4, 15].into_iter();
+        assert_eq!(tav.roll_initiative(Edition::Rules2014, |sides| {
+            assert_eq!(sides, 20);
+            rolls.next().unwrap()
+        }), 23);
+        assert_eq!(rolls.next(), None);
+    }
+
+    /// Ensure that summons are offered for removal when their summoner stops concentrating or
+    /// drops, and stay linked to it as combatants are removed.
+    #[test]
+    fn summons() {
+        let mut tracker = Tracker::new(vec![
+            Character { name: "Tav".to_string(), hit_points: 10, ..Default::default() }.into(),
+            Monster { name: "Goblin".to_string(), ..Default::default() }.into(),
+            Monster { name: "Wolf".to_string(), ..Default::default() }.into(),
+            Monster { name: "Wolf".to_string(), ..Default::default() }.into(),
+        ]);
+        assert!(!tracker.execute(TrackerCommand::SetSummoner { index: 2, summoner: Some(2) }));
+        for index in [2, 3] {
+            assert!(tracker.execute(TrackerCommand::SetSummoner { index, summoner: Some(0) }));
+        }
+        assert_eq!(tracker.summons(0), [2, 3]);
+
+        assert!(tracker.set_concentration(0, true));
+        assert!(!tracker.set_concentration(0, true));
+        let concentrating = false;
+        assert!(tracker.execute(TrackerCommand::SetConcentration { index: 0, concentrating }));
+        assert_eq!(tracker.dismissals, [Dismissal {
+            summoner: 0,
+            reason: DismissalReason::Concentration,
+            summons: vec![2, 3],
+        }]);
+        tracker.dismissals.clear();
+
+        tracker.remove(1);
+        assert_eq!(tracker.summons(0), [1, 2]);
+        tracker.set_concentration(0, true);
+        tracker.damage(0, 10, None);
+        assert!(!tracker.combatants[0].concentrating);
+        assert_eq!(tracker.dismissals, [Dismissal {
+            summoner: 0,
+            reason: DismissalReason::Downed,
+            summons: vec![1, 2],
+        }]);
+
+        tracker.remove(1);
+        assert_eq!(tracker.dismissals[0].summons, [1]);
+        tracker.remove(0);
+        assert!(tracker.dismissals.is_empty());
+        assert_eq!(tracker.combatants[0].summoner, None);
+    }
+
+    /// Ensure that minions drop to any damage with the minion rules, and carry the damage past
+    /// their hit points over to the next minions in their groups.
+    #[test]
+    fn minions() {
+        let goblin = || -> Combatant {
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into()
+        };
+        let mut tracker = Tracker::new(vec![goblin(), goblin(), goblin(), goblin(), goblin()]);
+        tracker.combatants[2].hit_points = 3;
+        for index in 0..4 {
+            assert!(tracker.execute(TrackerCommand::SetMinion { index, minion: true }));
+        }
+        tracker.save_group("goblins", vec![0, 1, 2, 3, 4]);
+
+        // without the rules, minions take damage like anyone else
+        tracker.damage(0, 1, None);
+        assert_eq!(tracker.combatants[0].hit_points, 6);
+
+        tracker.rules.minions = true;
+        assert_eq!(tracker.damage(0, 1, None), 1);
+        assert_eq!(tracker.combatants[0].hit_points, 0);
+
+        // 6 past the first minion drops the next two (3 + 3), and 3 isn't enough for the fourth
+        tracker.combatants[0].hit_points = 7;
+        tracker.combatants[1].hit_points = 3;
+        tracker.damage(0, 13, None);
+        let hit_points = tracker.combatants.iter().map(|c| c.hit_points).collect::<Vec<_>>();
+        assert_eq!(hit_points, [-6, 0, 0, 7, 7]);
+
+        // setting hit points by hand ignores the rules
+        tracker.set_hit_points(3, 5);
+        assert_eq!(tracker.combatants[3].hit_points, 5);
+    }
+
+    /// Ensure that combatants are bloodied at or below half their hit points, until they drop.
+    #[test]
+    fn bloodied() {
+        let mut ogre: Combatant =
+            Monster { name: "Ogre".to_string(), hit_points: 59, ..Default::default() }.into();
+        assert!(!ogre.is_bloodied());
+        ogre.damage(29);
+        assert!(!ogre.is_bloodied());
+        ogre.damage(1);
+        assert!(ogre.is_bloodied());
+        ogre.damage(29);
+        assert!(!ogre.is_bloodied());
+    }
+
+    /// Ensure that mob attacks hit as the mob attacks table says, and add up the damage of every
+    /// hit.
+    #[test]
+    fn mob_attack() {
+        let mob = MobAttack {
+            attackers: 20,
+            attack_bonus: 4,
+            damage: "1d6 + 2".parse().unwrap(),
+        };
+        assert_eq!(mob.roll_needed(16), 12);
+        assert_eq!(mob.hits(16), 10);
+        assert_eq!(mob.hits(9), 20);
+        assert_eq!(mob.hits(23), 2);
+        assert_eq!(mob.hits(30), 1);
+        assert_eq!(mob.damage(16).unwrap().to_string(), "10d6 + 20");
+
+        let mob = MobAttack { attackers: 4, ..mob };
+        assert_eq!(mob.damage(23), None);
+    }
+
+    /// Ensure that exhaustion levels and surprise follow the edition of the rules in play.
+    #[test]
+    fn editions() {
+        let mut tracker = Tracker::new(vec![
+            Character { name: "Tav".to_string(), hit_points: 10, ..Default::default() }.into(),
+            Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }.into(),
+        ]);
+
+        assert!(tracker.execute(TrackerCommand::SetExhaustion { index: 0, level: 2 }));
+        assert!(!tracker.execute(TrackerCommand::SetExhaustion { index: 0, level: 2 }));
+        assert_eq!(tracker.combatants[0].conditions[0].kind, ConditionKind::Exhaustion);
+        assert_eq!(
+            Edition::Rules2014.exhaustion_effects(2),
+            "disadvantage on ability checks, speed halved",
+        );
+        assert_eq!(Edition::Rules2024.exhaustion_effects(2), "-4 to d20 Tests, -10 ft. speed");
+        tracker.long_rest(0);
+        assert_eq!(tracker.combatants[0].exhaustion, 1);
+        tracker.remove_condition(0, ConditionKind::Exhaustion);
+        assert_eq!(tracker.combatants[0].exhaustion, 0);
+
+        // in the 2014 rules, surprised combatants can't act on their first turn
+        assert!(tracker.execute(TrackerCommand::SetSurprised { index: 1, surprised: true }));
+        assert_eq!(tracker.combatants[1].actions.reactions, 0);
+        tracker.next_turn();
+        let actions = tracker.combatants[1].actions;
+        assert_eq!((actions.actions, actions.bonus_actions, actions.reactions), (0, 0, 0));
+        tracker.next_turn();
+        assert!(!tracker.combatants[1].surprised);
+        assert_eq!(tracker.combatants[1].actions.reactions, 1);
+
+        // in the 2024 rules, they roll initiative with disadvantage instead
+        tracker.rules.edition = Edition::Rules2024;
+        tracker.set_surprised(1, true);
+        let mut rolls = [15, 4].into_iter();
+        let goblin = &tracker.combatants[1];
+        assert_eq!(goblin.roll_initiative(Edition::Rules2024, |_| rolls.next().unwrap()), -1);
+        tracker.next_turn();
+        assert_eq!(tracker.combatants[1].actions.actions, 1);
     }
 }
@@ -0,0 +1,294 @@
+use crate::{ConditionKind, DamageType};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Something that happened during combat.
+///
+/// Combatants are referred to by name, so the log stays readable after combatants are removed or
+/// reordered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LogEvent {
+    /// A combatant's turn started.
+    TurnStarted { combatant: String },
+
+    /// A combatant took damage (or was healed, if the amount is negative), changing its hit points
+    /// from the first of the given pair to the second. The amount includes any damage absorbed by
+    /// temporary hit points or stopped at 0 hit points.
+    ///
+    /// Events that change hit points record them this way, so they can be replayed exactly. Logs
+    /// saved before the hit points were recorded don't have them.
+    ///
+    /// The index of the combatant is kept too, to tell apart combatants with the same name when
+    /// reacting to the event right away. Indexes shift as combatants are removed, so it isn't
+    /// saved.
+    Damaged {
+        combatant: String,
+        amount: i32,
+        kind: Option<DamageType>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        hit_points: Option<(i32, i32)>,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        index: Option<usize>,
+    },
+
+    /// A combatant's hit points dropped to 0 or below.
+    Downed { combatant: String },
+
+    /// A combatant was stabilized at 0 hit points.
+    Stabilized {
+        combatant: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        hit_points: Option<(i32, i32)>,
+    },
+
+    /// A combatant was marked dead.
+    Died {
+        combatant: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        hit_points: Option<(i32, i32)>,
+    },
+
+    /// A combatant was given temporary hit points.
+    TemporaryHitPoints { combatant: String, amount: i32 },
+
+    /// A condition was applied to a combatant.
+    ConditionApplied { combatant: String, condition: ConditionKind },
+
+    /// A condition wasn't applied to a combatant, because it is immune to it.
+    ConditionImmune { combatant: String, condition: ConditionKind },
+
+    /// A condition on a combatant expired.
+    ConditionEnded { combatant: String, condition: ConditionKind },
+
+    /// A combatant grappled (or restrained) another.
+    Grappled { grappler: String, target: String, condition: ConditionKind },
+
+    /// A grapple ended, from either side.
+    Released { grappler: String, target: String },
+
+    /// A player character was given Inspiration, or spent it.
+    Inspiration { combatant: String, gained: bool },
+
+    /// A combatant's exhaustion level changed.
+    Exhaustion { combatant: String, level: u32 },
+
+    /// A surprised combatant couldn't act on its turn.
+    Surprised { combatant: String },
+
+    /// A combatant started fleeing the fight, or stopped.
+    Fleeing { combatant: String, fleeing: bool },
+
+    /// A combatant started or stopped concentrating on a spell.
+    Concentration { combatant: String, concentrating: bool },
+
+    /// A combatant was summoned by another, and is linked to it.
+    Summoned { summoner: String, combatant: String },
+
+    /// A combatant finished a short rest, spending hit dice to regain hit points.
+    ShortRest { combatant: String, hit_dice: u32, healed: i32 },
+
+    /// A combatant finished a long rest.
+    LongRest {
+        combatant: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        hit_points: Option<(i32, i32)>,
+    },
+
+    /// A combatant's stats were changed by hand, along with its name if it changed.
+    Edited {
+        combatant: String,
+        name: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        hit_points: Option<(i32, i32)>,
+    },
+
+    /// A combatant joined the combat.
+    Joined { combatant: String },
+
+    /// A combatant was removed from the combat.
+    Left { combatant: String },
+
+    /// An effect on a combatant triggered.
+    EffectTriggered { combatant: String, effect: String },
+
+    /// An effect on a combatant ended.
+    EffectEnded { combatant: String, effect: String },
+
+    /// A combatant's regeneration didn't work at the start of its turn.
+    RegenerationSuppressed { combatant: String },
+
+    /// The way turns pass from one combatant to the next changed.
+    TurnOrder { turn_order: String },
+
+    /// Copies of the same monster started or stopped taking their turns together.
+    CombinedTurns { combined: bool },
+
+    /// A combatant moved to a zone, or out of every zone.
+    Moved { combatant: String, zone: Option<String> },
+
+    /// A combatant's turn was passed over, since it can't take it (see
+    /// [`Rules::skip_dead`](crate::Rules::skip_dead)).
+    TurnSkipped { combatant: String },
+
+    /// In-game time was added by hand (or taken back, if the number of seconds is negative),
+    /// such as a rest between encounters.
+    TimePassed { seconds: i64 },
+
+    /// The XP earned for the monsters defeated was awarded, split between the player characters.
+    XpAwarded { total: i32, share: i32, characters: usize },
+}
+
+impl fmt::Display for LogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogEvent::TurnStarted { combatant } => write!(f, "{}'s turn", combatant),
+            LogEvent::Damaged { combatant, amount, kind, .. } if *amount < 0 => {
+                write!(f, "{} healed {}", combatant, -amount)?;
+                kind.map_or(Ok(()), |kind| write!(f, " ({})", kind.name()))
+            },
+            LogEvent::Damaged { combatant, amount, kind, .. } => {
+                write!(f, "{} took {}", combatant, amount)?;
+                kind.map_or(Ok(()), |kind| write!(f, " {}", kind.name()))?;
+                write!(f, " damage")
+            },
+            LogEvent::Downed { combatant } => write!(f, "{} dropped to 0 hit points", combatant),
+            LogEvent::Stabilized { combatant, .. } => write!(f, "{} is stable", combatant),
+            LogEvent::Died { combatant, .. } => write!(f, "{} died", combatant),
+            LogEvent::TemporaryHitPoints { combatant, amount } => {
+                write!(f, "{} has {} temporary hit points", combatant, amount)
+            },
+            LogEvent::ConditionApplied { combatant, condition } => {
+                write!(f, "{} is {}", combatant, condition)
+            },
+            LogEvent::ConditionImmune { combatant, condition } => {
+                write!(f, "{} is immune to {}", combatant, condition)
+            },
+            LogEvent::ConditionEnded { combatant, condition } => {
+                write!(f, "{} is no longer {}", combatant, condition)
+            },
+            LogEvent::Grappled { grappler, target, condition: ConditionKind::Restrained } => {
+                write!(f, "{} restrained {}", grappler, target)
+            },
+            LogEvent::Grappled { grappler, target, .. } => {
+                write!(f, "{} grappled {}", grappler, target)
+            },
+            LogEvent::Released { grappler, target } => {
+                write!(f, "{} is free of {}", target, grappler)
+            },
+            LogEvent::Inspiration { combatant, gained: true } => {
+                write!(f, "{} has Inspiration", combatant)
+            },
+            LogEvent::Inspiration { combatant, gained: false } => {
+                write!(f, "{} spent Inspiration", combatant)
+            },
+            LogEvent::Exhaustion { combatant, level: 0 } => {
+                write!(f, "{} is no longer exhausted", combatant)
+            },
+            LogEvent::Exhaustion { combatant, level } => {
+                write!(f, "{} has exhaustion level {}", combatant, level)
+            },
+            LogEvent::Surprised { combatant } => {
+                write!(f, "{} is surprised and can't act", combatant)
+            },
+            LogEvent::Fleeing { combatant, fleeing: true } => write!(f, "{} flees", combatant),
+            LogEvent::Fleeing { combatant, fleeing: false } => {
+                write!(f, "{} stopped fleeing", combatant)
+            },
+            LogEvent::Concentration { combatant, concentrating: true } => {
+                write!(f, "{} is concentrating", combatant)
+            },
+            LogEvent::Concentration { combatant, concentrating: false } => {
+                write!(f, "{} stopped concentrating", combatant)
+            },
+            LogEvent::Summoned { summoner, combatant } => {
+                write!(f, "{} summoned {}", summoner, combatant)
+            },
+            LogEvent::ShortRest { combatant, hit_dice: 0, .. } => {
+                write!(f, "{} took a short rest", combatant)
+            },
+            LogEvent::ShortRest { combatant, hit_dice, healed } => {
+                let dice = if *hit_dice == 1 { "hit die" } else { "hit dice" };
+                write!(f, "{} took a short rest, spending {} {}", combatant, hit_dice, dice)?;
+                write!(f, " to heal {}", healed)
+            },
+            LogEvent::LongRest { combatant, .. } => write!(f, "{} took a long rest", combatant),
+            LogEvent::Edited { combatant, name, .. } if combatant == name => {
+                write!(f, "{}'s stats were edited", combatant)
+            },
+            LogEvent::Edited { combatant, name, .. } => {
+                write!(f, "{} was renamed {}", combatant, name)
+            },
+            LogEvent::Joined { combatant } => write!(f, "{} joined the combat", combatant),
+            LogEvent::Left { combatant } => write!(f, "{} left the combat", combatant),
+            LogEvent::EffectTriggered { combatant, effect } => {
+                write!(f, "{}: {}", combatant, effect)
+            },
+            LogEvent::EffectEnded { combatant, effect } => {
+                write!(f, "{} is no longer affected by {}", combatant, effect)
+            },
+            LogEvent::RegenerationSuppressed { combatant } => {
+                write!(f, "{} doesn't regenerate", combatant)
+            },
+            LogEvent::TurnOrder { turn_order } => write!(f, "Turns follow {}", turn_order),
+            LogEvent::CombinedTurns { combined: true } => {
+                write!(f, "Identical monsters take their turns together")
+            },
+            LogEvent::CombinedTurns { combined: false } => {
+                write!(f, "Identical monsters take their turns one by one")
+            },
+            LogEvent::Moved { combatant, zone: Some(zone) } => {
+                write!(f, "{} moved to {}", combatant, zone)
+            },
+            LogEvent::Moved { combatant, zone: None } => {
+                write!(f, "{} left the zones", combatant)
+            },
+            LogEvent::TurnSkipped { combatant } => write!(f, "{}'s turn was skipped", combatant),
+            LogEvent::TimePassed { seconds } if *seconds < 0 => {
+                write!(f, "{} taken back", fmt_duration(seconds.unsigned_abs()))
+            },
+            LogEvent::TimePassed { seconds } => {
+                write!(f, "{} passed", fmt_duration(seconds.unsigned_abs()))
+            },
+            LogEvent::XpAwarded { total, characters: 0, .. } => write!(f, "Awarded {} XP", total),
+            LogEvent::XpAwarded { total, characters: 1, .. } => {
+                write!(f, "Awarded {} XP to 1 character", total)
+            },
+            LogEvent::XpAwarded { total, share, characters } => {
+                write!(f, "Awarded {} XP, {} each to {} characters", total, share, characters)
+            },
+        }
+    }
+}
+
+/// Formats a number of seconds in words, from the largest unit down, such as `1 hour 30 minutes`.
+fn fmt_duration(seconds: u64) -> String {
+    let units = [(3600, "hour"), (60, "minute"), (1, "second")];
+    let parts = units
+        .into_iter()
+        .scan(seconds, |left, (size, name)| {
+            let count = *left / size;
+            *left %= size;
+            Some((count, name))
+        })
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, name)| match count {
+            1 => format!("1 {}", name),
+            _ => format!("{} {}s", count, name),
+        })
+        .collect::<Vec<_>>();
+    if parts.is_empty() { "0 seconds".to_string() } else { parts.join(" ") }
+}
+
+/// An event in the combat log and the round it happened in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct LogEntry {
+    /// The round the event happened in, starting at 0.
+    pub round: usize,
+
+    /// What happened.
+    pub event: LogEvent,
+}
@@ -0,0 +1,171 @@
+use crate::DiceExpression;
+
+/// A kind of coin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Coin {
+    Copper,
+    Silver,
+    Electrum,
+    Gold,
+    Platinum,
+}
+
+impl Coin {
+    /// Every kind of coin, from the most valuable to the least.
+    pub const ALL: [Coin; 5] = [
+        Coin::Platinum,
+        Coin::Gold,
+        Coin::Electrum,
+        Coin::Silver,
+        Coin::Copper,
+    ];
+
+    /// Returns the abbreviation of the coin, such as `gp`.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Coin::Copper => "cp",
+            Coin::Silver => "sp",
+            Coin::Electrum => "ep",
+            Coin::Gold => "gp",
+            Coin::Platinum => "pp",
+        }
+    }
+}
+
+/// A pile of coins, counted by kind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Coins([u32; 5]);
+
+impl Coins {
+    /// Returns how many coins of the given kind are in the pile.
+    pub fn get(&self, coin: Coin) -> u32 {
+        self.0[coin as usize]
+    }
+
+    /// Adds coins of the given kind to the pile.
+    pub fn add(&mut self, coin: Coin, count: u32) {
+        self.0[coin as usize] += count;
+    }
+
+    /// Adds every coin of another pile to this one.
+    pub fn merge(&mut self, other: Coins) {
+        for coin in Coin::ALL {
+            self.add(coin, other.get(coin));
+        }
+    }
+
+    /// Returns `true` if there are no coins in the pile.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|count| *count == 0)
+    }
+}
+
+/// Coins are listed from the most valuable to the least, such as `12 gp, 30 sp`.
+impl std::fmt::Display for Coins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no coins");
+        }
+        let parts = Coin::ALL
+            .into_iter()
+            .filter(|coin| self.get(*coin) > 0)
+            .map(|coin| format!("{} {}", self.get(coin), coin.abbreviation()))
+            .collect::<Vec<_>>();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Coins rolled as a number of d6, multiplied, such as `4d6 x 100 cp`.
+type CoinRoll = (u32, u32, Coin);
+
+/// A row of a treasure table: the highest d100 roll it covers, and the coins it gives.
+type Row = (u32, &'static [CoinRoll]);
+
+/// The Individual Treasure tables of the Dungeon Master's Guide, by challenge rating: 0-4, 5-10,
+/// 11-16, and 17 or more.
+const INDIVIDUAL: [&[Row]; 4] = [
+    &[
+        (30, &[(5, 1, Coin::Copper)]),
+        (60, &[(4, 1, Coin::Silver)]),
+        (70, &[(3, 1, Coin::Electrum)]),
+        (95, &[(3, 1, Coin::Gold)]),
+        (100, &[(1, 1, Coin::Platinum)]),
+    ],
+    &[
+        (30, &[(4, 100, Coin::Copper), (1, 10, Coin::Electrum)]),
+        (60, &[(6, 10, Coin::Silver), (2, 10, Coin::Gold)]),
+        (70, &[(3, 10, Coin::Electrum), (2, 10, Coin::Gold)]),
+        (95, &[(4, 10, Coin::Gold)]),
+        (100, &[(2, 10, Coin::Gold), (3, 1, Coin::Platinum)]),
+    ],
+    &[
+        (20, &[(4, 100, Coin::Silver), (1, 100, Coin::Gold)]),
+        (35, &[(1, 100, Coin::Electrum), (1, 100, Coin::Gold)]),
+        (75, &[(2, 100, Coin::Gold), (1, 10, Coin::Platinum)]),
+        (100, &[(2, 100, Coin::Gold), (2, 10, Coin::Platinum)]),
+    ],
+    &[
+        (15, &[(2, 1000, Coin::Electrum), (8, 100, Coin::Gold)]),
+        (55, &[(1, 1000, Coin::Gold), (1, 100, Coin::Platinum)]),
+        (100, &[(1, 1000, Coin::Gold), (2, 100, Coin::Platinum)]),
+    ],
+];
+
+/// The coins of the Treasure Hoard tables of the Dungeon Master's Guide, by challenge rating,
+/// like [`INDIVIDUAL`].
+const HOARD: [&[CoinRoll]; 4] = [
+    &[(6, 100, Coin::Copper), (3, 100, Coin::Silver), (2, 10, Coin::Gold)],
+    &[
+        (2, 100, Coin::Copper),
+        (2, 1000, Coin::Silver),
+        (6, 100, Coin::Gold),
+        (3, 10, Coin::Platinum),
+    ],
+    &[(4, 1000, Coin::Gold), (5, 100, Coin::Platinum)],
+    &[(12, 1000, Coin::Gold), (8, 1000, Coin::Platinum)],
+];
+
+/// Returns the treasure tables' column for the given challenge rating.
+fn band(challenge_rating: f32) -> usize {
+    match challenge_rating {
+        cr if cr < 5.0 => 0,
+        cr if cr < 11.0 => 1,
+        cr if cr < 17.0 => 2,
+        _ => 3,
+    }
+}
+
+/// Returns the range of challenge ratings of the treasure tables' column for the given challenge
+/// rating, such as `5-10`.
+pub fn challenge_band(challenge_rating: f32) -> &'static str {
+    ["0-4", "5-10", "11-16", "17+"][band(challenge_rating)]
+}
+
+/// Rolls the coins of a row of a treasure table.
+fn roll_coins(rolls: &[CoinRoll], roll: &mut impl FnMut(u32) -> u32) -> Coins {
+    let mut coins = Coins::default();
+    for &(count, multiplier, coin) in rolls {
+        let dice = DiceExpression::new(count, 6, 0).roll(&mut *roll);
+        coins.add(coin, dice as u32 * multiplier);
+    }
+    coins
+}
+
+/// Rolls the coins a single monster of the given challenge rating carries, on the Individual
+/// Treasure table for its challenge rating. `roll` rolls a die with the given number of sides.
+pub fn individual_treasure(challenge_rating: f32, mut roll: impl FnMut(u32) -> u32) -> Coins {
+    let d100 = roll(100);
+    let table = INDIVIDUAL[band(challenge_rating)];
+    let (_, rolls) = table
+        .iter()
+        .find(|(highest, _)| d100 <= *highest)
+        .unwrap_or(&table[table.len() - 1]);
+    roll_coins(rolls, &mut roll)
+}
+
+/// Rolls the coins of a treasure hoard guarded by a monster of the given challenge rating, on
+/// the Treasure Hoard table for its challenge rating. Its gems, art objects, and magic items are
+/// left to the table's other columns.
+pub fn hoard_treasure(challenge_rating: f32, mut roll: impl FnMut(u32) -> u32) -> Coins {
+    roll_coins(HOARD[band(challenge_rating)], &mut roll)
+}
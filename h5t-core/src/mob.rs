@@ -0,0 +1,53 @@
+use crate::DiceExpression;
+
+/// A mob of identical attackers, such as twenty skeletons, attacking one target at once.
+///
+/// Instead of rolling every attack, mobs use the mob attacks table from the Dungeon Master's Guide
+/// (chapter 8, "Handling Mobs"): the lower the roll each attacker needs, the fewer attackers it
+/// takes for one of them to hit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MobAttack {
+    /// The number of attackers.
+    pub attackers: u32,
+
+    /// The attack bonus of each attacker.
+    pub attack_bonus: i32,
+
+    /// The damage of each attack that hits.
+    pub damage: DiceExpression,
+}
+
+impl MobAttack {
+    /// Returns the d20 roll each attacker needs to hit the given armor class.
+    pub fn roll_needed(&self, armor_class: u32) -> i32 {
+        armor_class as i32 - self.attack_bonus
+    }
+
+    /// Returns how many attackers it takes for one of them to hit the given armor class, from the
+    /// mob attacks table. A natural 20 always hits, so it never takes more than 20.
+    pub fn attackers_per_hit(&self, armor_class: u32) -> u32 {
+        match self.roll_needed(armor_class) {
+            ..=5 => 1,
+            6..=12 => 2,
+            13..=14 => 3,
+            15..=16 => 4,
+            17..=18 => 5,
+            19 => 10,
+            _ => 20,
+        }
+    }
+
+    /// Returns how many attackers hit the given armor class.
+    pub fn hits(&self, armor_class: u32) -> u32 {
+        self.attackers / self.attackers_per_hit(armor_class)
+    }
+
+    /// Returns the damage dealt to the given armor class, adding up the damage of every attacker
+    /// that hits. Returns [`None`] if none of them hit.
+    pub fn damage(&self, armor_class: u32) -> Option<DiceExpression> {
+        match self.hits(armor_class) {
+            0 => None,
+            hits => Some(self.damage.times(hits)),
+        }
+    }
+}
@@ -1,8 +1,12 @@
-use crate::{ability::{Modifier, Score, Skill}, Ability};
+use crate::{ability::{Modifier, Score, Skill}, Ability, ConditionKind, DamageModifier, DamageType};
+use crate::score_to_modifier;
+use crate::effect::Regeneration;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize};
 
 /// The source of a monster's armor class value.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum ArmorClassSource {
     /// The armor class is calculated from the monster's Dexterity modifier (i.e., 10 + DEX mod).
     #[default]
@@ -16,7 +20,8 @@ pub enum ArmorClassSource {
 }
 
 /// A monster's armor class.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct ArmorClass {
     /// The source of the armor class value.
     pub source: ArmorClassSource,
@@ -25,6 +30,7 @@ pub struct ArmorClass {
     pub value: u32,
 }
 
+#[cfg(feature = "serde")]
 fn deserialize_armor_class<'de, D>(d: D) -> Result<ArmorClass, D::Error>
 where D: Deserializer<'de>
 {
@@ -33,6 +39,8 @@ where D: Deserializer<'de>
     // {"type": "natural" | "dex", "value": 12}
     //
     // look for the first and use it
+    //
+    // saved encounters contain the serialized `ArmorClass` instead
 
     #[derive(Debug, Deserialize)]
     struct AcData {
@@ -40,7 +48,17 @@ where D: Deserializer<'de>
         value: u32,
     }
 
-    let data = Vec::<AcData>::deserialize(d)?;
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum AcFormat {
+        Api(Vec<AcData>),
+        Saved(ArmorClass),
+    }
+
+    let data = match AcFormat::deserialize(d)? {
+        AcFormat::Api(data) => data,
+        AcFormat::Saved(armor_class) => return Ok(armor_class),
+    };
     data.into_iter()
         .find_map(|data| {
             let source = match data.r#type.as_str() {
@@ -57,7 +75,8 @@ where D: Deserializer<'de>
 /// A creature's speed on all types of movement.
 ///
 /// Each field is given as a descriptive string, such as "30 ft.".
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Speed {
     /// Basic movement speed.
     pub walk: Option<String>,
@@ -75,8 +94,55 @@ pub struct Speed {
     pub swim: Option<String>,
 }
 
+/// A creature's special senses, and its passive Perception score.
+///
+/// Each sense is given as a descriptive string of its range, such as "60 ft.".
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Senses {
+    /// Range within which the creature perceives its surroundings without sight.
+    pub blindsight: Option<String>,
+
+    /// Range within which the creature sees in darkness as if it were dim light.
+    pub darkvision: Option<String>,
+
+    /// Range within which the creature detects vibrations through the ground.
+    pub tremorsense: Option<String>,
+
+    /// Range within which the creature sees through darkness, illusions, and invisibility.
+    pub truesight: Option<String>,
+
+    /// The creature's passive Perception score, as given by its stat block. When it isn't given,
+    /// it is computed from the creature's Wisdom and Perception proficiency.
+    pub passive_perception: Option<Score>,
+}
+
+/// Lists the senses with their ranges, such as "darkvision 60 ft., tremorsense 30 ft.", leaving
+/// out passive Perception.
+impl std::fmt::Display for Senses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let senses = [
+            ("blindsight", &self.blindsight),
+            ("darkvision", &self.darkvision),
+            ("tremorsense", &self.tremorsense),
+            ("truesight", &self.truesight),
+        ];
+        let mut first = true;
+        for (name, range) in senses {
+            let Some(range) = range else { continue };
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} {}", name, range)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
 /// A creature's size.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Size {
     #[default]
     Tiny,
@@ -88,8 +154,9 @@ pub enum Size {
 }
 
 /// A creature's type.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Type {
     Aberration,
     Beast,
@@ -107,12 +174,72 @@ pub enum Type {
     Undead,
 
     #[default]
-    #[serde(other)] // TODO: capture the value of the "other" case
+    #[cfg_attr(feature = "serde", serde(other))] // TODO: capture the value of the "other" case
     Other,
 }
 
+impl Type {
+    /// Every creature type.
+    pub const ALL: [Type; 15] = [
+        Type::Aberration,
+        Type::Beast,
+        Type::Celestial,
+        Type::Construct,
+        Type::Dragon,
+        Type::Elemental,
+        Type::Fey,
+        Type::Fiend,
+        Type::Giant,
+        Type::Humanoid,
+        Type::Monstrosity,
+        Type::Ooze,
+        Type::Plant,
+        Type::Undead,
+        Type::Other,
+    ];
+
+    /// Returns the type's name, in lowercase.
+    pub fn name(self) -> &'static str {
+        match self {
+            Type::Aberration => "aberration",
+            Type::Beast => "beast",
+            Type::Celestial => "celestial",
+            Type::Construct => "construct",
+            Type::Dragon => "dragon",
+            Type::Elemental => "elemental",
+            Type::Fey => "fey",
+            Type::Fiend => "fiend",
+            Type::Giant => "giant",
+            Type::Humanoid => "humanoid",
+            Type::Monstrosity => "monstrosity",
+            Type::Ooze => "ooze",
+            Type::Plant => "plant",
+            Type::Undead => "undead",
+            Type::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for Type {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Type::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown creature type `{}`", s))
+    }
+}
+
 /// A monster's proficiencies.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Proficiencies {
     /// The monster's skill proficiencies.
     ///
@@ -120,7 +247,7 @@ pub struct Proficiencies {
     /// its proficiency bonus plus its ability modifier for the relevant ability score. Otherwise,
     /// the value will be `None`, and the monster will use the ability modifier alone to calculate
     /// the skill check.
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub skills: Skill<Option<Modifier>>,
 
     /// The monster's saving throw proficiencies.
@@ -129,10 +256,11 @@ pub struct Proficiencies {
     /// contain its proficiency bonus plus its ability modifier for the relevant ability score.
     /// Otherwise, the value will be `None`, and the monster will use the ability modifier alone to
     /// calculate the saving throw.
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub saving_throws: Ability<Option<Modifier>>,
 }
 
+#[cfg(feature = "serde")]
 fn deserialize_proficiencies<'de, D>(d: D) -> Result<Proficiencies, D::Error>
 where D: Deserializer<'de>
 {
@@ -140,6 +268,8 @@ where D: Deserializer<'de>
     // like
     //
     // {"value": 11, "proficiency": {"index": "saving-throw-wis", ...}}
+    //
+    // saved encounters contain the serialized `Proficiencies` instead
 
     #[derive(Debug, Deserialize)]
     struct ProfDataInner {
@@ -152,8 +282,18 @@ where D: Deserializer<'de>
         proficiency: ProfDataInner,
     }
 
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum ProfFormat {
+        Api(Vec<ProfData>),
+        Saved(Proficiencies),
+    }
+
     let mut proficiencies = Proficiencies::default();
-    let data = Vec::<ProfData>::deserialize(d)?;
+    let data = match ProfFormat::deserialize(d)? {
+        ProfFormat::Api(data) => data,
+        ProfFormat::Saved(proficiencies) => return Ok(proficiencies),
+    };
     for prof in data {
         let modifier = Some(prof.value as i32);
         match prof.proficiency.index.as_str() {
@@ -189,7 +329,8 @@ where D: Deserializer<'de>
 }
 
 /// A trait that a monster has.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Trait {
     /// The name of the trait.
     pub name: String,
@@ -198,10 +339,42 @@ pub struct Trait {
     pub desc: String,
 
     /// The usage of the trait.
-    #[serde(default, deserialize_with = "deserialize_usage")]
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "deserialize_usage"))]
     pub usage: Usage,
 }
 
+#[cfg(feature = "serde")]
+fn deserialize_condition_immunities<'de, D>(d: D) -> Result<Vec<ConditionKind>, D::Error>
+where D: Deserializer<'de>
+{
+    // api provides an array of references to the conditions:
+    //
+    // {"index": "poisoned", "name": "Poisoned", "url": "/api/conditions/poisoned"}
+    //
+    // saved encounters contain the serialized `ConditionKind`s instead
+
+    #[derive(Debug, Deserialize)]
+    struct ConditionRef {
+        index: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum ConditionFormat {
+        Saved(Vec<ConditionKind>),
+        Api(Vec<ConditionRef>),
+    }
+
+    match ConditionFormat::deserialize(d)? {
+        ConditionFormat::Saved(kinds) => Ok(kinds),
+        ConditionFormat::Api(refs) => Ok(refs
+            .into_iter()
+            .filter_map(|condition| condition.index.parse().ok())
+            .collect()),
+    }
+}
+
+#[cfg(feature = "serde")]
 fn deserialize_usage<'de, D>(d: D) -> Result<Usage, D::Error>
 where D: Deserializer<'de>
 {
@@ -210,6 +383,8 @@ where D: Deserializer<'de>
     // {"type": "per day", "times": 3}
     // or
     // {"type": "recharge after rest", "rest_types": ["short", "long"]}
+    //
+    // saved encounters contain the serialized `Usage` instead
 
     #[derive(Debug, Deserialize)]
     struct UsageData {
@@ -217,7 +392,17 @@ where D: Deserializer<'de>
         rest_types: Option<Vec<String>>,
     }
 
-    let data = UsageData::deserialize(d)?;
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum UsageFormat {
+        Saved(Usage),
+        Api(UsageData),
+    }
+
+    let data = match UsageFormat::deserialize(d)? {
+        UsageFormat::Api(data) => data,
+        UsageFormat::Saved(usage) => return Ok(usage),
+    };
     match (data.times, data.rest_types) {
         (Some(times), _) if times > 0 => Ok(Usage::PerDay(times)),
         (_, Some(rest_types)) if !rest_types.is_empty() => {
@@ -235,7 +420,8 @@ where D: Deserializer<'de>
 }
 
 /// Usage constraints for a trait.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Usage {
     /// The trait has a limited number of usages per day. Effectively, this is a limit to how many
     /// times the trait can be used in this combat encounter.
@@ -250,12 +436,13 @@ pub enum Usage {
     /// There is no constraint; the trait can be used at will, or it is a passive ability that is
     /// always active.
     #[default]
-    #[serde(other)]
+    #[cfg_attr(feature = "serde", serde(other))]
     AtWill,
 }
 
 /// A pre-made monster from the System Reference Document (SRD), or a custom monster.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Monster {
     /// The monster's index, used for identification.
     pub index: String,
@@ -267,7 +454,7 @@ pub struct Monster {
     pub alignment: String,
 
     /// The monster's ability scores, used for calculating modifiers.
-    #[serde(flatten)]
+    #[cfg_attr(feature = "serde", serde(flatten))]
     pub scores: Ability<Score>,
 
     /// The monster's size, used to determine the amount of space it occupies on the battlefield.
@@ -281,7 +468,7 @@ pub struct Monster {
     pub subtype: Option<String>,
 
     /// The monster's armor class, the amount needed to hit it with an attack.
-    #[serde(deserialize_with = "deserialize_armor_class")]
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_armor_class"))]
     pub armor_class: ArmorClass,
 
     /// The monster's hit points.
@@ -290,11 +477,41 @@ pub struct Monster {
     /// The expression to roll for the monster's hit points.
     pub hit_points_roll: String,
 
+    /// The damage types the monster takes double damage from.
+    ///
+    /// Entries with extra conditions (e.g. "bludgeoning from nonmagical attacks") are kept as
+    /// written, and are only shown to the DM, never applied automatically.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub damage_vulnerabilities: Vec<String>,
+
+    /// The damage types the monster takes half damage from.
+    ///
+    /// Entries with extra conditions are kept as written, and are never applied automatically.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub damage_resistances: Vec<String>,
+
+    /// The damage types the monster takes no damage from.
+    ///
+    /// Entries with extra conditions are kept as written, and are never applied automatically.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub damage_immunities: Vec<String>,
+
+    /// The conditions that can't be applied to the monster.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "deserialize_condition_immunities")
+    )]
+    pub condition_immunities: Vec<ConditionKind>,
+
     /// The different speeds the monster has, such as walking, flying, or swimming.
     pub speed: Speed,
 
+    /// The monster's special senses, such as darkvision, and its passive Perception.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub senses: Senses,
+
     // The monster's proficiencies, including its skill and saving throw proficiencies.
-    #[serde(default, deserialize_with = "deserialize_proficiencies")]
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "deserialize_proficiencies"))]
     pub proficiencies: Proficiencies,
 
     /// The monster's challenge rating. Can be `0.0`, `0.125`, `0.25`, `0.5`, or an integer from `1`
@@ -310,13 +527,89 @@ pub struct Monster {
 
     /// The monster's traits that provide it with various benefits or drawbacks. This includes
     /// things like Legendary Resistances, Lair Actions, etc.
-    #[serde(rename = "special_abilities")]
+    #[cfg_attr(feature = "serde", serde(rename = "special_abilities"))]
     pub traits: Vec<Trait>,
 }
 
+/// The XP of each challenge rating from 1 to 30, by challenge rating minus 1.
+const CHALLENGE_XP: [i32; 30] = [
+    200, 450, 700, 1100, 1800, 2300, 2900, 3900, 5000, 5900, 7200, 8400, 10000, 11500, 13000,
+    15000, 18000, 20000, 22000, 25000, 33000, 41000, 50000, 62000, 75000, 90000, 105000, 120000,
+    135000, 155000,
+];
+
+/// Returns the XP for defeating a monster of the given challenge rating, as listed in the
+/// Monster Manual. Challenge ratings above 30 are worth as much as 30.
+pub fn challenge_xp(challenge_rating: f32) -> i32 {
+    match challenge_rating {
+        cr if cr <= 0.0 => 10,
+        cr if cr <= 0.125 => 25,
+        cr if cr <= 0.25 => 50,
+        cr if cr < 1.0 => 100,
+        cr => CHALLENGE_XP[(cr as usize).min(30) - 1],
+    }
+}
+
+impl Monster {
+    /// Returns the monster's regeneration, read from its Regeneration trait, if it has one.
+    pub fn regeneration(&self) -> Option<Regeneration> {
+        self.traits
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case("regeneration"))
+            .and_then(|t| Regeneration::parse(&t.desc))
+    }
+
+    /// Returns the monster's passive Perception score, from its [senses](Monster::senses) if its
+    /// stat block gives it, or else from its Perception modifier.
+    pub fn passive_perception(&self) -> Score {
+        let wisdom = score_to_modifier(self.scores.wisdom);
+        let perception = self.proficiencies.skills.perception.unwrap_or(wisdom);
+        self.senses.passive_perception.unwrap_or(10 + perception)
+    }
+
+    /// Returns the monster's saving throw modifiers: the ones its stat block gives, or else its
+    /// ability modifiers.
+    pub fn saving_throw_modifiers(&self) -> Ability<Modifier> {
+        let modifiers = self.scores.modifiers();
+        let saves = &self.proficiencies.saving_throws;
+        Ability {
+            strength: saves.strength.unwrap_or(modifiers.strength),
+            dexterity: saves.dexterity.unwrap_or(modifiers.dexterity),
+            constitution: saves.constitution.unwrap_or(modifiers.constitution),
+            intelligence: saves.intelligence.unwrap_or(modifiers.intelligence),
+            wisdom: saves.wisdom.unwrap_or(modifiers.wisdom),
+            charisma: saves.charisma.unwrap_or(modifiers.charisma),
+        }
+    }
+
+    /// Returns the XP the party gains for defeating the monster: the XP its stat block gives,
+    /// or the XP of its challenge rating if the stat block has none.
+    pub fn xp_value(&self) -> i32 {
+        if self.xp > 0 { self.xp } else { challenge_xp(self.challenge_rating) }
+    }
+
+    /// Returns how the monster is affected by the given damage type.
+    ///
+    /// Only entries that name the damage type alone are taken into account.
+    pub fn damage_modifier(&self, kind: DamageType) -> DamageModifier {
+        let has = |list: &[String]| list.iter().any(|entry| entry.eq_ignore_ascii_case(kind.name()));
+
+        if has(&self.damage_immunities) {
+            return DamageModifier::Immune;
+        }
+
+        // resistance and vulnerability to the same damage type cancel out
+        match (has(&self.damage_resistances), has(&self.damage_vulnerabilities)) {
+            (true, false) => DamageModifier::Resistant,
+            (false, true) => DamageModifier::Vulnerable,
+            _ => DamageModifier::Normal,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::score_to_modifier;
+    use crate::{score_to_modifier, DamageModifier, DamageType, Monster};
 
     /// Ensure the modifier calculation is correct.
     #[test]
@@ -358,4 +651,43 @@ mod tests {
             assert_eq!(score_to_modifier(*score), *modifier);
         }
     }
+
+    /// Ensure monsters loaded from the API data can be saved and loaded again unchanged.
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        use crate::ConditionKind;
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/monsters.json");
+        let file = std::fs::File::open(path).unwrap();
+        let monsters = serde_json::from_reader::<_, Vec<Monster>>(file).unwrap();
+        let zombie = monsters.iter().find(|monster| monster.name == "Zombie").unwrap();
+        assert_eq!(zombie.condition_immunities, vec![ConditionKind::Poisoned]);
+        assert_eq!(zombie.senses.to_string(), "darkvision 60 ft.");
+        assert_eq!(zombie.passive_perception(), 8);
+
+        let saved = serde_json::to_string(&monsters).unwrap();
+        let loaded = serde_json::from_str::<Vec<Monster>>(&saved).unwrap();
+        assert_eq!(format!("{:?}", monsters), format!("{:?}", loaded));
+    }
+
+    /// Ensure damage modifiers only use entries that name the damage type alone.
+    #[test]
+    fn damage_modifiers() {
+        let monster = Monster {
+            damage_vulnerabilities: vec!["fire".to_string()],
+            damage_resistances: vec![
+                "cold".to_string(),
+                "bludgeoning, piercing, and slashing from nonmagical weapons".to_string(),
+            ],
+            damage_immunities: vec!["Poison".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(monster.damage_modifier(DamageType::Fire), DamageModifier::Vulnerable);
+        assert_eq!(monster.damage_modifier(DamageType::Cold), DamageModifier::Resistant);
+        assert_eq!(monster.damage_modifier(DamageType::Poison), DamageModifier::Immune);
+        assert_eq!(monster.damage_modifier(DamageType::Slashing), DamageModifier::Normal);
+        assert_eq!(DamageModifier::Resistant.apply(7), 3);
+    }
 }
@@ -0,0 +1,40 @@
+use std::fmt;
+
+/// A monster that has to make a morale saving throw, from the morale variant rule of the
+/// Dungeon Master's Guide: a DC 10 Wisdom saving throw, or it flees.
+///
+/// The tracker collects these in [`Tracker::morale_checks`](crate::Tracker::morale_checks) with
+/// [`Rules::morale`](crate::Rules::morale), for the UI to offer to roll them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoraleCheck {
+    /// The index of the monster making the saving throw.
+    pub index: usize,
+
+    /// What shook the monster's morale.
+    pub reason: MoraleReason,
+}
+
+impl MoraleCheck {
+    /// The DC of the saving throw.
+    pub const DC: i32 = 10;
+}
+
+/// What shakes a monster's morale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoraleReason {
+    /// A [leader](crate::Combatant::leader) on its side dropped to 0 hit points.
+    LeaderDropped,
+
+    /// Half of its group dropped to 0 hit points: the copies of the same monster, or the members
+    /// of a named group it is in.
+    GroupHalved,
+}
+
+impl fmt::Display for MoraleReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoraleReason::LeaderDropped => write!(f, "its leader dropped"),
+            MoraleReason::GroupHalved => write!(f, "half its group is down"),
+        }
+    }
+}
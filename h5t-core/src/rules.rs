@@ -0,0 +1,89 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Optional rules that change how the tracker resolves combat, chosen by the table.
+///
+/// The default rules keep the tracker's standard behavior.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Rules {
+    /// Whether damage stops at 0 hit points, as in the rules as written. Otherwise, hit points go
+    /// negative, which shows how far past 0 a combatant was pushed.
+    pub floor_hit_points: bool,
+
+    /// Whether combatants flagged as [minions](crate::Combatant::minion) drop to any damage, and
+    /// carry the damage past their hit points over to the other minions in their groups, as in
+    /// the minion rules from MCDM's Flee, Mortals!.
+    pub minions: bool,
+
+    /// The edition of the rules, for the rules the 2024 revision changed.
+    pub edition: Edition,
+
+    /// Whether turns pass over monsters at 0 hit points, which are dead. Characters still take
+    /// their turns, to roll death saving throws, and so do monsters that regenerate without hit
+    /// points, which may get back up. Combatants [marked dead](crate::Combatant::dead) are passed
+    /// over either way.
+    pub skip_dead: bool,
+
+    /// Whether turns pass over [unconscious](crate::ConditionKind::Unconscious) monsters.
+    pub skip_unconscious: bool,
+
+    /// Whether monsters check their morale when their [leader](crate::Combatant::leader) drops
+    /// or half their group is down, as in the morale variant rule of the Dungeon Master's Guide.
+    pub morale: bool,
+}
+
+/// An edition of the fifth edition rules, for the rules the 2024 revision changed: exhaustion,
+/// surprise, and the wording of conditions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Edition {
+    /// The 2014 rules, as in the SRD 5.1.
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "2014"))]
+    Rules2014,
+
+    /// The 2024 revision, as in the SRD 5.2.
+    #[cfg_attr(feature = "serde", serde(rename = "2024"))]
+    Rules2024,
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Edition::Rules2014 => write!(f, "2014 rules"),
+            Edition::Rules2024 => write!(f, "2024 rules"),
+        }
+    }
+}
+
+/// The cumulative effects of each level of exhaustion in the 2014 rules.
+const EXHAUSTION_2014: [&str; 6] = [
+    "disadvantage on ability checks",
+    "speed halved",
+    "disadvantage on attack rolls and saving throws",
+    "hit point maximum halved",
+    "speed reduced to 0",
+    "death",
+];
+
+impl Edition {
+    /// The highest level of exhaustion, which kills the creature in both editions.
+    pub const MAX_EXHAUSTION: u32 = 6;
+
+    /// Describes the effects of the given level of exhaustion.
+    ///
+    /// In the 2014 rules, each level adds an effect to the ones before it. In the 2024 rules,
+    /// each level takes 2 off d20 Tests and 5 feet off speed.
+    pub fn exhaustion_effects(self, level: u32) -> String {
+        match self {
+            _ if level >= Self::MAX_EXHAUSTION => "death".to_string(),
+            Edition::Rules2014 => EXHAUSTION_2014[..level as usize].join(", "),
+            Edition::Rules2024 => {
+                format!("-{} to d20 Tests, -{} ft. speed", 2 * level, 5 * level)
+            },
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{Combatant, ConditionDuration, ConditionKind, DamageType, LogEntry, LogEvent, Side};
+use crate::{Tracker, TrackerCommand};
+
+/// A combat encounter driven without a user interface, for frontends such as a GUI, a web page,
+/// or a chat bot.
+///
+/// A session wraps a [`Tracker`] with a target selection, like the one made in the terminal
+/// interface's label mode. Actions apply to the selected targets (or to the combatant whose turn
+/// it is, if nothing is selected), then clear the selection. Every change is made with
+/// [`Tracker::execute`], so it is recorded in the combat log and passed to subscribers.
+#[derive(Debug)]
+pub struct Session {
+    /// The encounter being run.
+    tracker: Tracker,
+
+    /// The indexes of the selected combatants, sorted and without duplicates.
+    selection: Vec<usize>,
+}
+
+impl From<Tracker> for Session {
+    fn from(tracker: Tracker) -> Self {
+        Self::new(tracker)
+    }
+}
+
+impl Session {
+    /// Create a session running the given encounter, with nothing selected.
+    pub fn new(tracker: Tracker) -> Self {
+        Self { tracker, selection: Vec::new() }
+    }
+
+    /// Returns the encounter being run.
+    pub fn tracker(&self) -> &Tracker {
+        &self.tracker
+    }
+
+    /// Returns the encounter being run, for changes the session has no method for.
+    ///
+    /// Removing combatants this way can leave the selection pointing at the wrong combatants, so
+    /// prefer [`Session::remove`].
+    pub fn tracker_mut(&mut self) -> &mut Tracker {
+        &mut self.tracker
+    }
+
+    /// Ends the session, returning the encounter.
+    pub fn into_tracker(self) -> Tracker {
+        self.tracker
+    }
+
+    /// Returns the combat log, from oldest to newest event.
+    pub fn log(&self) -> &[LogEntry] {
+        &self.tracker.log
+    }
+
+    /// Returns the combatant whose turn it is.
+    pub fn current_combatant(&self) -> &Combatant {
+        self.tracker.current_combatant()
+    }
+
+    // -- Target Selection -- //
+
+    /// Returns the indexes of the selected combatants, in initiative order.
+    pub fn selection(&self) -> &[usize] {
+        &self.selection
+    }
+
+    /// Adds the combatants with the given indexes to the selection. Indexes that are out of
+    /// bounds are ignored.
+    pub fn select(&mut self, indexes: impl IntoIterator<Item = usize>) {
+        let len = self.tracker.combatants.len();
+        self.selection.extend(indexes.into_iter().filter(|index| *index < len));
+        self.selection.sort_unstable();
+        self.selection.dedup();
+    }
+
+    /// Adds the members of the named group to the selection. Returns `false` if there is no such
+    /// group.
+    pub fn select_group(&mut self, name: &str) -> bool {
+        let Some(members) = self.tracker.groups.get(name).cloned() else { return false };
+        self.select(members);
+        true
+    }
+
+    /// Adds every combatant on the given side to the selection.
+    pub fn select_side(&mut self, side: Side) {
+        let members = self.tracker.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.side == side)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        self.select(members);
+    }
+
+    /// Clears the selection.
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+    }
+
+    /// Returns the targets of the next action: the selected combatants, or the combatant whose turn
+    /// it is if nothing is selected. An empty encounter has no targets.
+    pub fn targets(&self) -> Vec<usize> {
+        if self.selection.is_empty() {
+            (self.tracker.turn < self.tracker.combatants.len())
+                .then_some(self.tracker.turn)
+                .into_iter()
+                .collect()
+        } else {
+            self.selection.clone()
+        }
+    }
+
+    /// Returns the targets of the next action and clears the selection.
+    fn take_targets(&mut self) -> Vec<usize> {
+        let targets = self.targets();
+        self.selection.clear();
+        targets
+    }
+
+    // -- Actions -- //
+
+    /// Damages the targets, applying their resistances, vulnerabilities, and immunities. Negative
+    /// damage heals them.
+    ///
+    /// Returns the damage actually taken by each target, in the same order as
+    /// [`Session::targets`].
+    pub fn damage(&mut self, amount: i32, kind: Option<DamageType>) -> Vec<i32> {
+        self.take_targets()
+            .into_iter()
+            .map(|index| {
+                let start = self.tracker.log.len();
+                self.tracker.execute(TrackerCommand::Damage { index, amount, kind });
+                self.tracker.log[start..]
+                    .iter()
+                    .find_map(|entry| match entry.event {
+                        LogEvent::Damaged { amount, .. } => Some(amount),
+                        _ => None,
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Heals the targets by the given amount, up to their maximum hit points. Dead targets aren't
+    /// healed.
+    pub fn heal(&mut self, amount: i32) {
+        for index in self.take_targets() {
+            self.tracker.execute(TrackerCommand::Heal { index, amount });
+        }
+    }
+
+    /// Sets the hit points of the targets.
+    pub fn set_hit_points(&mut self, value: i32) {
+        for index in self.take_targets() {
+            self.tracker.execute(TrackerCommand::SetHitPoints { index, value });
+        }
+    }
+
+    /// Applies a condition to the targets. Targets that already have it keep it, with the longer
+    /// of the two durations.
+    pub fn apply_condition(&mut self, kind: ConditionKind, duration: ConditionDuration) {
+        for index in self.take_targets() {
+            self.tracker.execute(TrackerCommand::AddCondition { index, kind, duration });
+        }
+    }
+
+    /// Moves the targets to the given side.
+    pub fn set_side(&mut self, side: Side) {
+        for index in self.take_targets() {
+            self.tracker.execute(TrackerCommand::SetSide { index, side });
+        }
+    }
+
+    /// Saves the targets as a named group, replacing any group with the same name.
+    pub fn save_group(&mut self, name: impl Into<String>) {
+        let members = self.take_targets();
+        self.tracker.execute(TrackerCommand::SaveGroup { name: name.into(), members });
+    }
+
+    // -- Turns -- //
+
+    /// Advances to the next combatant's turn, expiring conditions and restoring actions. The
+    /// selection is kept.
+    pub fn next_turn(&mut self) {
+        self.tracker.execute(TrackerCommand::NextTurn);
+    }
+
+    /// Adds a combatant to the end of the initiative order.
+    pub fn add(&mut self, combatant: impl Into<Combatant>) {
+        self.tracker.execute(TrackerCommand::Add(Box::new(combatant.into())));
+    }
+
+    /// Removes the combatant with the given index from the initiative order, keeping the rest of
+    /// the selection on the same combatants. Returns [`None`] if the combatant can't be removed
+    /// (see [`Tracker::remove`]).
+    pub fn remove(&mut self, index: usize) -> Option<Combatant> {
+        let combatant = self.tracker.combatants.get(index)?.clone();
+        if !self.tracker.execute(TrackerCommand::Remove { index }) {
+            return None;
+        }
+        self.selection.retain(|selected| *selected != index);
+        self.selection.iter_mut().filter(|selected| **selected > index).for_each(|s| *s -= 1);
+        Some(combatant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Monster;
+
+    /// Ensure that actions apply to the selection, or the current combatant, and are logged.
+    #[test]
+    fn session_targets() {
+        let mut session = Session::new(Tracker::new(
+            ["Fighter", "Goblin", "Ogre"].map(|name| {
+                Monster { name: name.to_string(), hit_points: 20, ..Default::default() }.into()
+            }),
+        ));
+
+        session.select([2, 1, 7]);
+        assert_eq!(session.selection(), [1, 2]);
+        assert_eq!(session.damage(5, None), [5, 5]);
+        assert!(session.selection().is_empty());
+        assert_eq!(session.tracker().combatants[1].hit_points, 15);
+        assert_eq!(session.tracker().combatants[0].hit_points, 20);
+
+        // with nothing selected, the current combatant is the target
+        session.apply_condition(ConditionKind::Prone, ConditionDuration::Forever);
+        assert_eq!(session.tracker().combatants[0].conditions.len(), 1);
+
+        session.next_turn();
+        session.set_hit_points(3);
+        assert_eq!(session.tracker().combatants[1].hit_points, 3);
+        assert!(matches!(
+            session.log().last().map(|entry| &entry.event),
+            Some(LogEvent::Damaged { amount: 12, .. }),
+        ));
+
+        session.select([2]);
+        session.remove(1);
+        assert_eq!(session.selection(), [1]);
+    }
+
+    /// Ensure that actions on an empty encounter have no targets, instead of panicking.
+    #[test]
+    fn session_empty() {
+        let mut session = Session::new(Tracker::new(Vec::new()));
+        assert!(session.targets().is_empty());
+        assert!(session.damage(5, None).is_empty());
+        session.heal(5);
+        session.apply_condition(ConditionKind::Prone, ConditionDuration::Forever);
+        assert!(session.log().is_empty());
+    }
+
+    /// Ensure that healing stops at the targets' maximum hit points, and leaves the dead alone.
+    #[test]
+    fn session_heal() {
+        let mut session = Session::new(Tracker::new(
+            ["Fighter", "Goblin"].map(|name| {
+                Monster { name: name.to_string(), hit_points: 20, ..Default::default() }.into()
+            }),
+        ));
+        session.select([0, 1]);
+        session.damage(15, None);
+        session.tracker.execute(TrackerCommand::Kill { index: 1 });
+
+        session.select([0, 1]);
+        session.heal(30);
+        assert!(session.selection().is_empty());
+        assert_eq!(session.tracker().combatants[0].hit_points, 20);
+        assert_eq!(session.tracker().combatants[1].hit_points, 0);
+        assert!(session.tracker().combatants[1].dead);
+        assert_eq!(session.log().last().unwrap().event.to_string(), "Fighter healed 15");
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{LogEntry, LogEvent};
+
+/// What a combatant did over an encounter, tallied from the combat log by
+/// [`Tracker::combat_stats`](crate::Tracker::combat_stats).
+///
+/// The log doesn't say who dealt damage or healing, so it is credited to the combatant whose turn
+/// it was.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CombatStats {
+    /// The combatant's name, the latest one if it was renamed.
+    pub name: String,
+
+    /// The damage dealt to other combatants on the combatant's turns.
+    pub damage_dealt: i32,
+
+    /// The damage taken, including the damage absorbed by temporary hit points.
+    pub damage_taken: i32,
+
+    /// The hit points restored on the combatant's turns, its own included.
+    pub healing_done: i32,
+
+    /// The other combatants dropped to 0 hit points on the combatant's turns.
+    pub kills: u32,
+}
+
+/// Tallies the stats of every combatant named in the given log, in the order they first appear.
+/// Combatants with the same name are tallied together.
+///
+/// The log only notes the turns that started after the first, so the combatant taking the opening
+/// turn is given too, if known. Otherwise, damage and healing from before the first logged turn
+/// are only counted for their targets.
+pub fn tally(log: &[LogEntry], opening: Option<&str>) -> Vec<CombatStats> {
+    let mut stats = Vec::<CombatStats>::new();
+    let mut actor = opening.map(|name| entry(&mut stats, name));
+
+    /// Returns the position of the stats of the combatant with the given name, adding them if
+    /// needed.
+    fn entry(stats: &mut Vec<CombatStats>, name: &str) -> usize {
+        stats.iter().position(|stats| stats.name == name).unwrap_or_else(|| {
+            stats.push(CombatStats { name: name.to_string(), ..Default::default() });
+            stats.len() - 1
+        })
+    }
+
+    for LogEntry { event, .. } in log {
+        match event {
+            LogEvent::TurnStarted { combatant } => actor = Some(entry(&mut stats, combatant)),
+            LogEvent::Damaged { combatant, amount, .. } if *amount < 0 => {
+                entry(&mut stats, combatant);
+                if let Some(actor) = actor {
+                    stats[actor].healing_done -= amount;
+                }
+            },
+            LogEvent::Damaged { combatant, amount, .. } => {
+                let target = entry(&mut stats, combatant);
+                stats[target].damage_taken += amount;
+                if let Some(actor) = actor.filter(|&actor| actor != target) {
+                    stats[actor].damage_dealt += amount;
+                }
+            },
+            LogEvent::Downed { combatant } => {
+                let target = entry(&mut stats, combatant);
+                if let Some(actor) = actor.filter(|&actor| actor != target) {
+                    stats[actor].kills += 1;
+                }
+            },
+            LogEvent::Edited { combatant, name, .. } if combatant != name => {
+                let Some(old) = stats.iter().position(|stats| stats.name == *combatant) else {
+                    continue;
+                };
+                match stats.iter().position(|stats| stats.name == *name) {
+                    // merge into the combatant that already has the new name
+                    Some(new) => {
+                        let old_stats = stats.remove(old);
+                        let new = if new > old { new - 1 } else { new };
+                        stats[new].damage_dealt += old_stats.damage_dealt;
+                        stats[new].damage_taken += old_stats.damage_taken;
+                        stats[new].healing_done += old_stats.healing_done;
+                        stats[new].kills += old_stats.kills;
+                        actor = actor.map(|actor| match actor {
+                            actor if actor == old => new,
+                            actor if actor > old => actor - 1,
+                            actor => actor,
+                        });
+                    },
+                    None => stats[old].name = name.clone(),
+                }
+            },
+            _ => (),
+        }
+    }
+
+    stats
+}
+
+/// Returns the combined hit points of the given combatants, named with their current names and
+/// with their current hit points, before the first round and at the end of every round up to the
+/// given one (the last value being their hit points now). Hit points below 0 count as 0.
+///
+/// Each combatant's hit points are replayed from the log, and count as 0 before it joined the
+/// combat. Combatants with the same name can't be told apart in the log.
+pub fn hit_points_by_round(log: &[LogEntry], combatants: &[(&str, i32)], round: usize) -> Vec<i32> {
+    let mut points = vec![0; round + 2];
+    for &(name, hit_points) in combatants {
+        for (point, hit_points) in points.iter_mut().zip(history(log, name, hit_points, round)) {
+            *point += hit_points.max(0);
+        }
+    }
+    points
+}
+
+/// How an event in the log changed a combatant's hit points.
+enum Change {
+    /// The hit points before and after the event.
+    Exact(i32, i32),
+
+    /// The hit points gained (or lost, if negative), for events that don't record the hit points
+    /// themselves, such as damage in logs saved before they were recorded.
+    By(i32),
+}
+
+impl Change {
+    /// Returns how the given event changed the hit points of the combatant with the given name,
+    /// if it did.
+    fn of(event: &LogEvent, name: &str) -> Option<Self> {
+        match event {
+            LogEvent::Damaged { combatant, hit_points: Some((before, after)), .. }
+            | LogEvent::Stabilized { combatant, hit_points: Some((before, after)) }
+            | LogEvent::Died { combatant, hit_points: Some((before, after)) }
+            | LogEvent::LongRest { combatant, hit_points: Some((before, after)) }
+            | LogEvent::Edited { combatant, hit_points: Some((before, after)), .. }
+                if combatant == name => Some(Self::Exact(*before, *after)),
+            LogEvent::Damaged { combatant, amount, .. } if combatant == name => {
+                Some(Self::By(-amount))
+            },
+            LogEvent::ShortRest { combatant, healed, .. } if combatant == name => {
+                Some(Self::By(*healed))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Returns the hit points of one combatant before the first round and at the end of every round
+/// up to the given one, for [`hit_points_by_round`].
+fn history<'a>(log: &'a [LogEntry], mut name: &'a str, hit_points: i32, round: usize) -> Vec<i32> {
+    // walk back to the hit points and name the combatant started with, and when it joined
+    let mut start = hit_points;
+    let mut joined = None;
+    for (position, LogEntry { event, .. }) in log.iter().enumerate().rev() {
+        // an edit renames the combatant after changing its hit points
+        if let LogEvent::Edited { combatant, name: new, .. } = event
+            && new == name
+        {
+            name = combatant;
+        }
+        match Change::of(event, name) {
+            Some(Change::Exact(before, _)) => start = before,
+            Some(Change::By(gained)) => start -= gained,
+            None => (),
+        }
+        if let LogEvent::Joined { combatant } = event
+            && combatant == name
+        {
+            joined = Some(position);
+            break;
+        }
+    }
+
+    // then replay the log, noting the hit points each time a round ends
+    let mut current = joined.is_none().then_some(start);
+    let mut points = vec![current.unwrap_or_default()];
+    for (position, LogEntry { round, event }) in log.iter().enumerate() {
+        points.resize(round + 1, current.unwrap_or_default());
+        if joined == Some(position) {
+            current = Some(start);
+        }
+        current = match (current, Change::of(event, name)) {
+            (Some(_), Some(Change::Exact(_, after))) => Some(after),
+            (Some(current), Some(Change::By(gained))) => Some(current + gained),
+            (current, _) => current,
+        };
+        if let LogEvent::Edited { combatant, name: new, .. } = event
+            && combatant == name
+        {
+            name = new;
+        }
+    }
+    points.resize(round + 1, current.unwrap_or_default());
+    points.push(hit_points);
+    points
+}
@@ -0,0 +1,78 @@
+use crate::{Changed, LogEntry, LogEvent, TrackerCommand};
+
+/// A change made to the encounter, passed to the callbacks given to
+/// [`Tracker::subscribe`](crate::Tracker::subscribe).
+#[derive(Clone, Copy, Debug)]
+pub struct TrackerEvent<'a> {
+    /// The command that made the change.
+    pub command: &'a TrackerCommand,
+
+    /// The combat log entries recorded by the change, from oldest to newest. Some changes, such
+    /// as editing notes, don't record any.
+    pub log: &'a [LogEntry],
+}
+
+impl TrackerEvent<'_> {
+    /// Returns the combatants the change affected. This is what the command can change (see
+    /// [`TrackerCommand::changed`]), unless damage to a [minion](crate::Rules::minions) carried
+    /// over to the other minions in its groups, which can be anywhere in the initiative order.
+    pub fn changed(&self) -> Changed {
+        let damaged = self.log
+            .iter()
+            .filter(|entry| matches!(entry.event, LogEvent::Damaged { .. }))
+            .count();
+        if damaged > 1 {
+            Changed::All
+        } else {
+            self.command.changed()
+        }
+    }
+}
+
+/// Identifies a callback given to [`Tracker::subscribe`](crate::Tracker::subscribe), to remove
+/// it later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(usize);
+
+/// A callback given to [`Tracker::subscribe`](crate::Tracker::subscribe).
+type Callback = Box<dyn FnMut(&TrackerEvent) + Send>;
+
+/// The callbacks subscribed to a tracker's changes.
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    /// The ID given to the next callback.
+    next_id: usize,
+
+    /// The callbacks, in the order they were subscribed.
+    callbacks: Vec<(SubscriptionId, Callback)>,
+}
+
+impl std::fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscribers").field("count", &self.callbacks.len()).finish()
+    }
+}
+
+impl Subscribers {
+    /// Adds a callback, returning its ID.
+    pub(crate) fn add(&mut self, callback: Callback) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.callbacks.push((id, callback));
+        id
+    }
+
+    /// Removes the callback with the given ID. Returns `false` if there is none.
+    pub(crate) fn remove(&mut self, id: SubscriptionId) -> bool {
+        let count = self.callbacks.len();
+        self.callbacks.retain(|(callback_id, _)| *callback_id != id);
+        self.callbacks.len() != count
+    }
+
+    /// Calls every callback with the event, in the order they were subscribed.
+    pub(crate) fn notify(&mut self, event: &TrackerEvent) {
+        for (_, callback) in &mut self.callbacks {
+            callback(event);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+
+/// Summons left without what kept them around, such as a spiritual weapon whose cleric dropped
+/// to 0 hit points, or conjured animals whose druid stopped concentrating.
+///
+/// The summons aren't removed right away, since some outlast their summoner (a conjured animal
+/// only vanishes once concentration ends, not when the druid drops). The tracker collects these
+/// in [`Tracker::dismissals`](crate::Tracker::dismissals), for the UI to offer to remove them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dismissal {
+    /// The index of the summoner.
+    pub summoner: usize,
+
+    /// What happened to the summoner.
+    pub reason: DismissalReason,
+
+    /// The indexes of the summons linked to the summoner, in turn order.
+    pub summons: Vec<usize>,
+}
+
+/// What happened to a summoner that may end its summons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DismissalReason {
+    /// The summoner stopped concentrating.
+    Concentration,
+
+    /// The summoner dropped to 0 hit points.
+    Downed,
+}
+
+impl fmt::Display for DismissalReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DismissalReason::Concentration => write!(f, "stopped concentrating"),
+            DismissalReason::Downed => write!(f, "dropped to 0 hit points"),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/// The XP earned for the monsters defeated in an encounter, and how it is split between the
+/// player characters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct XpAward {
+    /// The name of each kind of monster defeated, how many were defeated, and the XP they are
+    /// worth together, in turn order.
+    pub defeated: Vec<(String, usize, i32)>,
+
+    /// The names of the player characters sharing the XP, in turn order.
+    pub characters: Vec<String>,
+}
+
+impl XpAward {
+    /// Returns the XP of every monster defeated.
+    pub fn total(&self) -> i32 {
+        self.defeated.iter().map(|(_, _, xp)| xp).sum()
+    }
+
+    /// Returns the XP each player character earns, rounded down. With no characters, this is
+    /// the total.
+    pub fn share(&self) -> i32 {
+        self.total() / self.characters.len().max(1) as i32
+    }
+}
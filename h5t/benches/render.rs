@@ -0,0 +1,79 @@
+//! Benchmarks for drawing large encounters, such as armies and swarms.
+//!
+//! Run with `cargo bench -p h5t`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use h5t::testing::TestUi;
+use h5t::theme::Theme;
+use h5t::config::ViewMode;
+use h5t::pages::Pages;
+use h5t::widgets::tracker::make_combat_table;
+use h5t::widgets::TrackerWidget;
+use h5t_core::{Combatant, Compendium, Tracker};
+
+/// The encounter sizes benchmarked.
+const SIZES: [usize; 3] = [50, 500, 2000];
+
+/// Creates an encounter with `size` combatants, cycling through the bundled compendium.
+fn encounter(size: usize) -> Tracker {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/monsters.json");
+    let file = std::fs::File::open(path).unwrap();
+    let compendium = Compendium::from_reader(std::io::BufReader::new(file)).unwrap();
+    Tracker::new(compendium
+        .monsters()
+        .iter()
+        .cycle()
+        .take(size)
+        .map(|monster| Combatant::from(monster.clone()))
+        .collect::<Vec<_>>())
+}
+
+/// Resizing the pages of every combatant, as done on every resize.
+fn pages(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Pages::resize");
+    for size in SIZES {
+        let mut pages = Pages::new((0..size).collect(), 32, ViewMode::Pages);
+        pages.go_to(usize::MAX);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            let mut page_size = 32;
+            b.iter(|| {
+                page_size = if page_size == 32 { 20 } else { 32 };
+                pages.resize(black_box(page_size))
+            });
+        });
+    }
+    group.finish();
+}
+
+/// Building the table of a full page, as done on every frame.
+fn table(c: &mut Criterion) {
+    let theme = Theme::default();
+    let mut group = c.benchmark_group("make_combat_table");
+    for size in SIZES {
+        let tracker = encounter(size);
+        let pages = Pages::new((0..size).collect(), 32, ViewMode::Pages);
+        let last = pages.page(pages.count() - 1);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| {
+                let widget = TrackerWidget::new(&tracker, last, true, &theme);
+                make_combat_table(black_box(widget), 120)
+            });
+        });
+    }
+    group.finish();
+}
+
+/// Drawing the whole screen.
+fn frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame");
+    for size in SIZES {
+        let mut ui = TestUi::new(encounter(size));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| ui.ui_mut().draw().unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, pages, table, frame);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+// -- Imports -- //
+
+use serde::Deserialize;
+
+use std::io::Write;
+
+// -- Turn Alerts -- //
+
+/// Alerts to raise when a combatant's turn begins, so that players can tell it's their turn
+/// without watching the screen.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TurnAlerts {
+    /// Whether to ring the terminal bell.
+    pub bell: bool,
+
+    /// Whether to show a desktop notification. This only works if h5t was built with the
+    /// `desktop-notifications` feature.
+    pub desktop: bool,
+
+    /// The names of the combatants to raise alerts for, ignoring case. If empty, alerts are
+    /// raised for every combatant.
+    pub combatants: Vec<String>,
+}
+
+impl TurnAlerts {
+    /// Returns whether alerts are raised when the combatant with the given name starts their turn.
+    pub fn applies_to(&self, name: &str) -> bool {
+        (self.bell || self.desktop)
+            && (self.combatants.is_empty()
+                || self.combatants.iter().any(|combatant| combatant.eq_ignore_ascii_case(name)))
+    }
+
+    /// Raises the configured alerts for the start of the named combatant's turn, if they apply to
+    /// that combatant. Failing to raise an alert isn't an error worth interrupting the session, so
+    /// it is only logged.
+    pub fn raise(&self, name: &str, round: usize) {
+        if !self.applies_to(name) {
+            return;
+        }
+
+        if self.bell {
+            let mut stdout = std::io::stdout();
+            if let Err(err) = stdout.write_all(b"\x07").and_then(|_| stdout.flush()) {
+                tracing::warn!("could not ring the bell: {}", err);
+            }
+        }
+
+        #[cfg(feature = "desktop-notifications")]
+        if self.desktop {
+            let shown = notify_rust::Notification::new()
+                .summary(&format!("{}'s turn", name))
+                .body(&format!("Round {}", round + 1))
+                .appname("h5t")
+                .show();
+            if let Err(err) = shown {
+                tracing::warn!("could not show a desktop notification: {}", err);
+            }
+        }
+
+        #[cfg(not(feature = "desktop-notifications"))]
+        let _ = round;
+    }
+}
@@ -0,0 +1,79 @@
+// -- Imports -- //
+
+use h5t_core::{LogEvent, Tracker};
+use itertools::Itertools;
+
+// -- Announcer -- //
+
+/// Describes what changed in the encounter in short plain-text sentences, such as
+/// "Round 2. Turn: Orc 3. HP 14/30, Poisoned.", for use with a screen reader.
+#[derive(Clone, Debug, Default)]
+pub struct Announcer {
+    /// The latest announcement.
+    message: String,
+
+    /// Number of combat log entries already announced.
+    log_seen: usize,
+
+    /// The round and turn last announced.
+    turn: Option<(usize, usize)>,
+}
+
+impl Announcer {
+    /// Returns the latest announcement.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Forgets what was announced, so that the next update describes the current turn of a newly
+    /// loaded encounter without repeating its combat log.
+    pub fn reset(&mut self, tracker: &Tracker) {
+        self.log_seen = tracker.log.len();
+        self.turn = None;
+    }
+
+    /// Announces the turn if it changed since the last update, followed by the combat log entries
+    /// added since then. The previous announcement is kept if nothing changed.
+    pub fn update(&mut self, tracker: &Tracker) {
+        let mut sentences = Vec::new();
+
+        let turn = (tracker.round, tracker.turn);
+        if self.turn != Some(turn) {
+            sentences.push(turn_summary(tracker));
+            self.turn = Some(turn);
+        }
+
+        let start = self.log_seen.min(tracker.log.len());
+        sentences.extend(tracker.log[start..]
+            .iter()
+            // already covered by the turn summary
+            .filter(|entry| !matches!(entry.event, LogEvent::TurnStarted { .. }))
+            .map(|entry| format!("{}.", entry.event)));
+        self.log_seen = tracker.log.len();
+
+        if !sentences.is_empty() {
+            self.message = sentences.join(" ");
+        }
+    }
+}
+
+/// Describes the round, whose turn it is, and that combatant's hit points and conditions.
+fn turn_summary(tracker: &Tracker) -> String {
+    let Some(combatant) = tracker.combatants.get(tracker.turn) else {
+        return "No combatants.".to_string();
+    };
+
+    let mut summary = format!(
+        "Round {}. Turn: {}. HP {}/{}",
+        tracker.round + 1,
+        combatant.name(),
+        combatant.hit_points,
+        combatant.max_hit_points(),
+    );
+    if !combatant.conditions.is_empty() {
+        summary.push_str(", ");
+        summary.push_str(&combatant.conditions.iter().map(|condition| condition.kind).join(", "));
+    }
+    summary.push('.');
+    summary
+}
@@ -0,0 +1,142 @@
+// -- Imports -- //
+
+use crate::config::Config;
+use crate::layout::Stacking;
+use crate::theme::{Preset, Theme};
+
+use clap::{Args, Parser, Subcommand};
+use h5t_core::monster::Type;
+
+use std::path::PathBuf;
+
+// -- Command Line -- //
+
+/// A terminal initiative tracker for D&D 5e.
+#[derive(Debug, Parser)]
+#[command(name = "h5t", version)]
+pub struct Cli {
+    /// What to do. Without a command, h5t starts an encounter with every monster in the
+    /// compendium.
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+
+    #[command(flatten)]
+    pub options: GlobalOptions,
+}
+
+/// The commands h5t can run.
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Run a saved encounter.
+    Run {
+        /// The encounter file, as written by `:save`.
+        encounter: PathBuf,
+    },
+
+    /// Start a new encounter with monsters from the compendium.
+    New {
+        /// The names of the monsters to add, once per copy (`goblin goblin ogre`).
+        #[arg(required = true)]
+        monsters: Vec<String>,
+
+        /// Use side initiative: allies take their turns first, then enemies.
+        #[arg(long)]
+        side_initiative: bool,
+    },
+
+    /// Start an encounter with the monsters in a JSON file.
+    ///
+    /// The file uses the compendium's format, a list of monsters as provided by
+    /// https://www.dnd5eapi.co/.
+    Import {
+        /// The file to import.
+        file: PathBuf,
+
+        /// Use side initiative: allies take their turns first, then enemies.
+        #[arg(long)]
+        side_initiative: bool,
+    },
+
+    /// Look up monsters in the compendium without starting the tracker.
+    #[command(subcommand)]
+    Compendium(CompendiumCommand),
+}
+
+/// The commands that look up monsters in the compendium.
+#[derive(Debug, Subcommand)]
+pub enum CompendiumCommand {
+    /// Print the monsters whose name starts with the given text, ignoring case.
+    Search {
+        /// The start of the monster's name.
+        name: String,
+
+        /// Only print monsters of this creature type, such as `dragon`.
+        #[arg(long = "type")]
+        kind: Option<Type>,
+
+        /// Only print monsters with at least this challenge rating.
+        #[arg(long)]
+        min_cr: Option<f32>,
+
+        /// Only print monsters with at most this challenge rating.
+        #[arg(long)]
+        max_cr: Option<f32>,
+    },
+}
+
+/// Options that apply to every command.
+#[derive(Debug, Args)]
+pub struct GlobalOptions {
+    /// The config file to use, instead of `~/.config/h5t/config.toml`.
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// The theme preset to use, replacing the colors of the config.
+    #[arg(long, global = true)]
+    pub theme: Option<Preset>,
+
+    /// How to stack the panels.
+    #[arg(long, global = true)]
+    pub layout: Option<Stacking>,
+
+    /// Draw with ASCII characters only.
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// Draw without colors. Also turned on by the `NO_COLOR` environment variable.
+    #[arg(long, global = true)]
+    pub monochrome: bool,
+
+    /// Describe changes to the encounter in plain text, for screen readers.
+    #[arg(long, global = true)]
+    pub screen_reader: bool,
+
+    /// Also write the debug panel's log to this file.
+    #[arg(long, global = true)]
+    pub log_file: Option<PathBuf>,
+}
+
+impl GlobalOptions {
+    /// Applies the options on top of the config. Options only turn modes on, even if the config
+    /// turns them off.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(preset) = self.theme {
+            config.theme = Theme {
+                ascii: config.theme.ascii,
+                monochrome: config.theme.monochrome,
+                ..Theme::preset(preset)
+            };
+        }
+        if let Some(stacking) = self.layout {
+            config.layout.stacking = stacking;
+        }
+
+        config.theme.ascii |= self.ascii;
+        // https://no-color.org
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        config.theme.monochrome |= self.monochrome || no_color;
+        config.screen_reader |= self.screen_reader;
+        // box-drawing characters are read out one by one
+        config.theme.ascii |= config.screen_reader;
+    }
+}
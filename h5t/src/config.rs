@@ -0,0 +1,192 @@
+// -- Imports -- //
+
+use crate::alert::TurnAlerts;
+use crate::error::{Error, Result};
+use crate::keymap::Keymap;
+use crate::layout::{Column, PanelLayout};
+use crate::macros::Macro;
+use crate::theme::Theme;
+
+use h5t_core::Rules;
+use ratatui::layout::Rect;
+use serde::Deserialize;
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+// -- Config -- //
+
+/// User configuration, loaded from a TOML file (or a JSON file, which older versions used).
+///
+/// Every field is optional in the file, and falls back to its default value when missing.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Key bindings.
+    pub keymap: Keymap,
+
+    /// Colors and border styles.
+    pub theme: Theme,
+
+    /// How the combatant list is split when it doesn't fit on screen.
+    pub view: ViewMode,
+
+    /// The panels of the main screen.
+    pub layout: PanelLayout,
+
+    /// Whether to switch to the page of the combatant whose turn it is when the turn advances.
+    pub follow_turn: bool,
+
+    /// Whether to briefly flash the row of the combatant whose turn it is when the turn advances.
+    pub flash_turn: bool,
+
+    /// Alerts raised when a combatant's turn begins.
+    pub turn_alerts: TurnAlerts,
+
+    /// Whether to show the current mode and its most useful keys at the bottom of the screen.
+    pub status_bar: bool,
+
+    /// Whether to describe changes to the encounter in plain text on a line above the status bar,
+    /// with the terminal cursor on it, for screen readers.
+    pub screen_reader: bool,
+
+    /// The optional columns of the tracker table, from most to least important. Columns are only
+    /// shown while the tracker is wide enough for them.
+    pub columns: Vec<Column>,
+
+    /// The screen width, in columns, below which the compact layout is used.
+    pub compact_width: u16,
+
+    /// The screen height, in lines, below which the compact layout is used.
+    pub compact_height: u16,
+
+    /// The monster files that make up the compendium, in the format provided by
+    /// <https://www.dnd5eapi.co/>. Monsters from later files are listed after earlier ones.
+    pub compendium: Vec<PathBuf>,
+
+    /// (optional) A SQLite database to keep the compendium in. The monster files are imported
+    /// into it when they change, instead of being read every time h5t starts, and monsters are
+    /// searched through its indexes. Worth it for compendiums of thousands of monsters.
+    pub compendium_db: Option<PathBuf>,
+
+    /// How often the encounter is saved in the background.
+    pub autosave: Autosave,
+
+    /// The optional rules in play.
+    pub rules: Rules,
+
+    /// The names of the built-in plugins to use, such as `unconscious`.
+    pub plugins: Vec<String>,
+
+    /// Rhai scripts to run as plugins, reacting to what happens in the encounter.
+    pub scripts: Vec<PathBuf>,
+
+    /// Sequences of keys played with one key. Macros recorded with `:record` are kept in the
+    /// settings instead, and replace the ones here with the same name or key.
+    pub macros: Vec<Macro>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            view: ViewMode::default(),
+            layout: PanelLayout::default(),
+            follow_turn: true,
+            flash_turn: true,
+            turn_alerts: TurnAlerts::default(),
+            status_bar: true,
+            screen_reader: false,
+            columns: Column::ALL.to_vec(),
+            compact_width: 100,
+            compact_height: 24,
+            compendium: vec![PathBuf::from("data/monsters.json")],
+            compendium_db: None,
+            autosave: Autosave::default(),
+            rules: Rules::default(),
+            plugins: Vec::new(),
+            scripts: Vec::new(),
+            macros: Vec::new(),
+        }
+    }
+}
+
+/// Saving the encounter in the background, so that a crash or a closed terminal loses little.
+///
+/// Autosaves go to their own file, and don't count as saving the encounter when quitting.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Autosave {
+    /// The number of seconds between autosaves, or `0` to turn autosaving off.
+    pub interval: u64,
+
+    /// The file to autosave to.
+    pub path: PathBuf,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self { interval: 0, path: PathBuf::from("h5t-autosave.json") }
+    }
+}
+
+/// How the combatant list is split when it doesn't fit on screen.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewMode {
+    /// Split into pages that are switched between.
+    #[default]
+    Pages,
+
+    /// Scroll through the list one row at a time, with a scrollbar.
+    Scroll,
+}
+
+impl Config {
+    /// The path of the JSON config file used by older versions, relative to the working
+    /// directory.
+    pub const LEGACY_PATH: &'static str = "h5t.json";
+
+    /// Returns the path of the config file to use when none is given: `h5t/config.toml` in the
+    /// user's config directory (`~/.config` on Linux), or the older `h5t.json` if only that one
+    /// exists.
+    pub fn default_path() -> PathBuf {
+        let path = dirs::config_dir().unwrap_or_default().join("h5t").join("config.toml");
+        if !path.exists() && Path::new(Self::LEGACY_PATH).exists() {
+            return PathBuf::from(Self::LEGACY_PATH);
+        }
+        path
+    }
+
+    /// Returns the part of the screen the panels are drawn in, leaving room for the status bar and
+    /// the screen reader line.
+    pub fn panels_area(&self, area: Rect) -> Rect {
+        let reserved = self.status_bar as u16 + self.screen_reader as u16;
+        Rect { height: area.height.saturating_sub(reserved), ..area }
+    }
+
+    /// Returns whether the screen is too small for the configured layout, given its size.
+    pub fn is_compact(&self, area: Rect) -> bool {
+        area.width < self.compact_width || area.height < self.compact_height
+    }
+
+    /// Load the config from the given file, which is read as JSON if its extension is `.json`,
+    /// and as TOML otherwise.
+    ///
+    /// If the file doesn't exist, the default config is returned.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(Error::io(path)(err)),
+        };
+
+        if path.extension().is_some_and(|extension| extension == "json") {
+            serde_json::from_str(&contents).map_err(Error::parse(path))
+        } else {
+            toml::from_str(&contents).map_err(Error::parse(path))
+        }
+    }
+}
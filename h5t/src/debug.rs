@@ -0,0 +1,140 @@
+// -- Imports -- //
+
+use crate::error::{Error, Result};
+
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::filter::LevelFilter;
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// -- Debug Log -- //
+
+/// The most recent log lines, kept in memory for the debug panel.
+///
+/// Cloning a [`DebugLog`] shares the same lines, so one clone can be handed to the logger while
+/// the UI reads from another.
+#[derive(Clone, Debug, Default)]
+pub struct DebugLog {
+    /// The log lines, from oldest to newest.
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl DebugLog {
+    /// The number of lines kept. Older lines are dropped.
+    pub const CAPACITY: usize = 200;
+
+    /// Returns up to `count` of the newest log lines, from oldest to newest.
+    pub fn recent(&self, count: usize) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        lines.iter().skip(lines.len().saturating_sub(count)).cloned().collect()
+    }
+
+    /// Adds a line, dropping the oldest one if the log is full.
+    fn push(&self, line: String) {
+        let mut lines = self.lines.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if lines.len() == Self::CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+impl<'a> MakeWriter<'a> for DebugLog {
+    type Writer = LineWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LineWriter { log: self.clone(), buffer: Vec::new() }
+    }
+}
+
+/// Collects the text of one log event, and adds its lines to a [`DebugLog`] once it is written.
+#[derive(Debug)]
+pub struct LineWriter {
+    /// The log to add the lines to.
+    log: DebugLog,
+
+    /// The text written so far.
+    buffer: Vec<u8>,
+}
+
+impl Write for LineWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LineWriter {
+    fn drop(&mut self) {
+        String::from_utf8_lossy(&self.buffer)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .for_each(|line| self.log.push(line.to_string()));
+    }
+}
+
+/// Starts logging debug events into the returned [`DebugLog`], and also into the given file if
+/// there is one.
+pub fn init(log_file: Option<&Path>) -> Result<DebugLog> {
+    let log = DebugLog::default();
+    let file = log_file
+        .map(|path| std::fs::File::create(path).map_err(Error::io(path)))
+        .transpose()?
+        .map(|file| tracing_subscriber::fmt::layer().with_ansi(false).with_writer(Arc::new(file)));
+
+    tracing_subscriber::registry()
+        .with(LevelFilter::DEBUG)
+        .with(tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .without_time()
+            .with_target(false)
+            .with_writer(log.clone()))
+        .with(file)
+        .init();
+    Ok(log)
+}
+
+// -- Frame Timing -- //
+
+/// How long the most recent frames took to draw.
+#[derive(Clone, Debug, Default)]
+pub struct FrameTimes {
+    /// The draw times, from oldest to newest.
+    times: VecDeque<Duration>,
+}
+
+impl FrameTimes {
+    /// The number of frames averaged over.
+    const FRAMES: usize = 30;
+
+    /// Records how long a frame took to draw.
+    pub fn record(&mut self, time: Duration) {
+        if self.times.len() == Self::FRAMES {
+            self.times.pop_front();
+        }
+        self.times.push_back(time);
+    }
+
+    /// Returns how long the last frame took to draw.
+    pub fn last(&self) -> Duration {
+        self.times.back().copied().unwrap_or_default()
+    }
+
+    /// Returns the average draw time of the recent frames.
+    pub fn average(&self) -> Duration {
+        match self.times.len() {
+            0 => Duration::ZERO,
+            len => self.times.iter().sum::<Duration>() / len as u32,
+        }
+    }
+}
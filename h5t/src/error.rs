@@ -0,0 +1,84 @@
+// -- Imports -- //
+
+use std::fmt;
+use std::path::PathBuf;
+
+// -- Error -- //
+
+/// An error that either stops h5t, or is shown in an error popup if h5t can carry on.
+#[derive(Debug)]
+pub enum Error {
+    /// The command line is invalid, or names something that doesn't exist.
+    Usage(String),
+
+    /// Drawing to or reading from the terminal failed. h5t can't carry on after this.
+    Terminal(std::io::Error),
+
+    /// A file couldn't be read or written.
+    Io { path: PathBuf, source: std::io::Error },
+
+    /// A file isn't valid JSON or TOML, or has invalid values.
+    Parse { path: PathBuf, source: Box<dyn std::error::Error + Send + Sync> },
+
+    /// The compendium database couldn't be opened, or the monster files couldn't be imported
+    /// into it.
+    Compendium(h5t_core::DatabaseError),
+}
+
+/// A [`Result`](std::result::Result) whose error is an h5t [`Error`].
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Returns a function that wraps an IO error on the given file, for use with `map_err`.
+    pub fn io(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { path, source }
+    }
+
+    /// Returns a function that wraps a parse error in the given file, for use with `map_err`.
+    pub fn parse<E>(path: impl Into<PathBuf>) -> impl FnOnce(E) -> Self
+    where E: std::error::Error + Send + Sync + 'static
+    {
+        let path = path.into();
+        move |source| Self::Parse { path, source: Box::new(source) }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::Terminal(error)
+    }
+}
+
+impl From<h5t_core::DatabaseError> for Error {
+    fn from(error: h5t_core::DatabaseError) -> Self {
+        Self::Compendium(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(message) => write!(f, "{}", message),
+            Self::Terminal(source) => write!(f, "terminal error: {}", source),
+            Self::Io { path, source } => {
+                write!(f, "could not access {}: {}", path.display(), source)
+            },
+            Self::Parse { path, source } => {
+                write!(f, "could not parse {}: {}", path.display(), source)
+            },
+            Self::Compendium(source) => write!(f, "compendium database: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Usage(_) => None,
+            Self::Terminal(source) | Self::Io { source, .. } => Some(source),
+            Self::Parse { source, .. } => Some(source.as_ref()),
+            Self::Compendium(source) => Some(source),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Where the UI's input comes from.
+//!
+//! [`Ui`](crate::ui::Ui) reads its input from an [`EventSource`]: the terminal when h5t runs, or
+//! a queue of scripted events in tests, so the whole run loop (label mode and action states
+//! included) runs the same way without a terminal.
+
+// -- Imports -- //
+
+use crossterm::event::{poll, read, Event, KeyEvent, MouseEvent, MouseEventKind};
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// -- Events -- //
+
+/// Input for the UI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputEvent {
+    /// A key was pressed.
+    Key(KeyEvent),
+    /// The mouse was clicked or scrolled.
+    Mouse(MouseEvent),
+    /// Text was pasted.
+    Paste(String),
+    /// The terminal was resized.
+    Resize,
+    /// No input arrived in time, so timers such as notifications and animations are updated.
+    Tick,
+    /// There is no more input, which stops [`Ui::run`](crate::ui::Ui::run) and cancels label mode.
+    Ended,
+}
+
+/// A source of input for the UI.
+pub trait EventSource {
+    /// Returns the next event, or [`InputEvent::Tick`] if none arrives within `timeout`.
+    fn next_event(&mut self, timeout: Duration) -> InputEvent;
+}
+
+// -- Terminal -- //
+
+/// Reads input from the terminal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn next_event(&mut self, timeout: Duration) -> InputEvent {
+        loop {
+            if !poll(timeout).unwrap_or(false) {
+                return InputEvent::Tick;
+            }
+
+            let Ok(event) = read() else { continue };
+            return match event {
+                Event::Key(key) => InputEvent::Key(key),
+                Event::Paste(text) => InputEvent::Paste(text),
+                // moving the mouse or releasing a button does nothing
+                Event::Mouse(mouse) if matches!(
+                    mouse.kind,
+                    MouseEventKind::Down(_) | MouseEventKind::ScrollUp | MouseEventKind::ScrollDown,
+                ) => InputEvent::Mouse(mouse),
+                Event::Resize(_, _) => InputEvent::Resize,
+                _ => continue,
+            };
+        }
+    }
+}
+
+// -- Scripted -- //
+
+/// Input sent ahead of time, as by tests. Once it runs out, every event is [`InputEvent::Ended`]
+/// until more is sent, so [`Ui::run`](crate::ui::Ui::run) returns and can be called again.
+#[derive(Clone, Debug, Default)]
+pub struct ScriptedEvents {
+    /// The events not handled yet, from first to last.
+    events: VecDeque<InputEvent>,
+}
+
+impl ScriptedEvents {
+    /// Adds events to the end of the queue.
+    pub fn send(&mut self, events: impl IntoIterator<Item = InputEvent>) {
+        self.events.extend(events);
+    }
+
+    /// Returns the number of events not handled yet.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if every event was handled.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    fn next_event(&mut self, _: Duration) -> InputEvent {
+        self.events.pop_front().unwrap_or(InputEvent::Ended)
+    }
+}
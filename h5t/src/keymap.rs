@@ -0,0 +1,833 @@
+// -- Imports -- //
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use itertools::Itertools;
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::hash::Hash;
+use std::path::Path;
+use std::str::FromStr;
+
+// -- Keys -- //
+
+/// A key (plus modifiers) that can be bound to a command.
+///
+/// Keys are written in the config file as their name, optionally prefixed with `ctrl-` and / or
+/// `alt-`. For example: `"n"`, `"N"`, `"ctrl-n"`, `"up"`, `"enter"`, `"space"`, `"f5"`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Key {
+    /// The key that was pressed.
+    pub code: KeyCode,
+
+    /// Whether the control key must be held.
+    pub ctrl: bool,
+
+    /// Whether the alt key must be held.
+    pub alt: bool,
+}
+
+impl Key {
+    /// Create a key binding without modifiers.
+    pub const fn new(code: KeyCode) -> Self {
+        Self { code, ctrl: false, alt: false }
+    }
+
+    /// Create a key binding for a character without modifiers.
+    pub const fn char(c: char) -> Self {
+        Self::new(KeyCode::Char(c))
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Self {
+            code: event.code,
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+            alt: event.modifiers.contains(KeyModifiers::ALT),
+        }
+    }
+}
+
+impl From<Key> for KeyEvent {
+    fn from(key: Key) -> Self {
+        let mut modifiers = KeyModifiers::NONE;
+        modifiers.set(KeyModifiers::CONTROL, key.ctrl);
+        modifiers.set(KeyModifiers::ALT, key.alt);
+        KeyEvent::new(key.code, modifiers)
+    }
+}
+
+/// Named keys, in the form they are written in the config file.
+const KEY_NAMES: [(&str, KeyCode); 15] = [
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("space", KeyCode::Char(' ')),
+];
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut key = Key::new(KeyCode::Null);
+        let mut rest = s;
+
+        // a trailing "-" is the minus key, not a modifier separator
+        while let Some((modifier, tail)) = rest
+            .split_once('-')
+            .filter(|(_, tail)| !tail.is_empty())
+        {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => key.ctrl = true,
+                "alt" => key.alt = true,
+                _ => return Err(format!("unknown modifier `{}` in key `{}`", modifier, s)),
+            }
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        key.code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => {
+                let name = rest.to_ascii_lowercase();
+                if let Some((_, code)) = KEY_NAMES.iter().find(|(n, _)| *n == name) {
+                    *code
+                } else if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    KeyCode::F(n)
+                } else {
+                    return Err(format!("unknown key `{}`", s));
+                }
+            }
+        };
+
+        Ok(key)
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl { write!(f, "ctrl-")?; }
+        if self.alt { write!(f, "alt-")?; }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            code => match KEY_NAMES.iter().find(|(_, c)| *c == code) {
+                Some((name, _)) => write!(f, "{}", name),
+                None => write!(f, "{:?}", code),
+            },
+        }
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        key.to_string()
+    }
+}
+
+/// One or more keys that must be pressed in order to run a command.
+///
+/// Sequences are written in the config file as their keys separated by spaces, such as `"g g"`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeySequence(Vec<Key>);
+
+impl KeySequence {
+    /// Create a sequence of the given keys.
+    pub fn new(keys: impl Into<Vec<Key>>) -> Self {
+        Self(keys.into())
+    }
+
+    /// Returns the keys of the sequence, in the order they must be pressed.
+    pub fn keys(&self) -> &[Key] {
+        &self.0
+    }
+}
+
+impl From<Key> for KeySequence {
+    fn from(key: Key) -> Self {
+        Self(vec![key])
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s.split_whitespace().map(str::parse).collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err("empty key sequence".to_string());
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl TryFrom<String> for KeySequence {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.iter().join(" "))
+    }
+}
+
+impl From<KeySequence> for String {
+    fn from(sequence: KeySequence) -> Self {
+        sequence.to_string()
+    }
+}
+
+// -- Commands -- //
+
+/// Commands that can be bound to keys on the main screen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Command {
+    PreviousPage,
+    NextPage,
+    FirstPage,
+    LastPage,
+    CursorUp,
+    CursorDown,
+    ClearCursor,
+    ApplyCondition,
+    ApplyDamage,
+    Heal,
+    GrantTemporaryHitPoints,
+    RepeatAction,
+    EditHitPoints,
+    EditCombatant,
+    Stabilize,
+    Kill,
+    MoveToken,
+    UseAction,
+    UseBonusAction,
+    UseReaction,
+    ToggleInspiration,
+    ToggleInfoBlock,
+    ToggleInfoPanel,
+    ToggleLogPanel,
+    ToggleNotesPanel,
+    ToggleGridPanel,
+    ToggleGraphPanel,
+    ToggleStacking,
+    GrowTracker,
+    ShrinkTracker,
+    CycleSortOrder,
+    NextTurn,
+    RemoveCombatant,
+    EditNotes,
+    EditEncounterNotes,
+    OpenCommandLine,
+    Search,
+    ShowHelp,
+    ShowConditionLegend,
+    StopRecording,
+    ToggleDebugPanel,
+    Quit,
+}
+
+/// Commands that can be bound to keys in label mode.
+///
+/// Keys bound in label mode take priority over the labels themselves.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelCommand {
+    Confirm,
+    Cancel,
+    PreviousPage,
+    NextPage,
+    SelectPage,
+    SelectAll,
+    InvertSelection,
+    CycleGroup,
+    CycleZone,
+    SelectLast,
+}
+
+/// Commands that can be bound to keys in popups, such as the damage and condition prompts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PopupCommand {
+    Confirm,
+    Back,
+    Erase,
+    Complete,
+    Help,
+}
+
+/// A command that can be bound to keys.
+pub trait Bindable: Copy + Eq + Hash + 'static {
+    /// Every command, in the order they are listed in the help overlay.
+    const ALL: &'static [Self];
+
+    /// A short description of the command.
+    fn description(self) -> &'static str;
+}
+
+impl Bindable for Command {
+    const ALL: &'static [Self] = &[
+        Command::PreviousPage,
+        Command::NextPage,
+        Command::FirstPage,
+        Command::LastPage,
+        Command::CursorUp,
+        Command::CursorDown,
+        Command::ClearCursor,
+        Command::ApplyCondition,
+        Command::ApplyDamage,
+        Command::Heal,
+        Command::GrantTemporaryHitPoints,
+        Command::RepeatAction,
+        Command::EditHitPoints,
+        Command::EditCombatant,
+        Command::Stabilize,
+        Command::Kill,
+        Command::MoveToken,
+        Command::UseAction,
+        Command::UseBonusAction,
+        Command::UseReaction,
+        Command::ToggleInspiration,
+        Command::ToggleInfoBlock,
+        Command::ToggleInfoPanel,
+        Command::ToggleLogPanel,
+        Command::ToggleNotesPanel,
+        Command::ToggleGridPanel,
+        Command::ToggleGraphPanel,
+        Command::ToggleStacking,
+        Command::GrowTracker,
+        Command::ShrinkTracker,
+        Command::CycleSortOrder,
+        Command::NextTurn,
+        Command::RemoveCombatant,
+        Command::EditNotes,
+        Command::EditEncounterNotes,
+        Command::OpenCommandLine,
+        Command::Search,
+        Command::ShowHelp,
+        Command::ShowConditionLegend,
+        Command::StopRecording,
+        Command::ToggleDebugPanel,
+        Command::Quit,
+    ];
+
+    fn description(self) -> &'static str {
+        match self {
+            Command::PreviousPage => "Previous page",
+            Command::NextPage => "Next page",
+            Command::FirstPage => "First page (or page N)",
+            Command::LastPage => "Last page (or page N)",
+            Command::CursorUp => "Move cursor up",
+            Command::CursorDown => "Move cursor down",
+            Command::ClearCursor => "Clear cursor (back to current turn)",
+            Command::ApplyCondition => "Select targets (default: cursor) and apply conditions",
+            Command::ApplyDamage => "Select targets (default: cursor) and apply damage",
+            Command::Heal => "Select targets (default: cursor) and heal",
+            Command::GrantTemporaryHitPoints => "Select targets (default: cursor) and grant temp HP",
+            Command::RepeatAction => "Repeat last action on new targets",
+            Command::EditHitPoints => "Set hit points of cursor / current turn",
+            Command::EditCombatant => "Edit name, HP, AC, initiative of cursor / current turn",
+            Command::Stabilize => "Stabilize cursor / current turn",
+            Command::Kill => "Mark cursor / current turn dead",
+            Command::MoveToken => "Move token of cursor / current turn on the grid",
+            Command::UseAction => "Use action",
+            Command::UseBonusAction => "Use bonus action",
+            Command::UseReaction => "Use reaction",
+            Command::ToggleInspiration => "Give / spend Inspiration",
+            Command::ToggleInfoBlock => "Toggle info block mode",
+            Command::ToggleInfoPanel => "Show / hide info panel",
+            Command::ToggleLogPanel => "Show / hide combat log panel",
+            Command::ToggleNotesPanel => "Show / hide notes panel",
+            Command::ToggleGridPanel => "Show / hide battle grid panel",
+            Command::ToggleGraphPanel => "Show / hide HP graph panel",
+            Command::ToggleStacking => "Stack panels horizontally / vertically",
+            Command::GrowTracker => "Grow tracker panel",
+            Command::ShrinkTracker => "Shrink tracker panel",
+            Command::CycleSortOrder => "Cycle sort order (initiative, HP, name, side, zone)",
+            Command::NextTurn => "Advance turn",
+            Command::RemoveCombatant => "Remove cursor / current turn's combatant",
+            Command::EditNotes => "Edit notes of cursor / current turn",
+            Command::EditEncounterNotes => "Edit encounter notes",
+            Command::OpenCommandLine => "Open command line",
+            Command::Search => "Filter combatants by name or condition",
+            Command::ShowHelp => "Show help",
+            Command::ShowConditionLegend => "Show condition legend",
+            Command::StopRecording => "Stop recording a macro (start with :record)",
+            Command::ToggleDebugPanel => "Show / hide debug panel (log and frame timing)",
+            Command::Quit => "Quit",
+        }
+    }
+}
+
+impl Bindable for LabelCommand {
+    const ALL: &'static [Self] = &[
+        LabelCommand::Confirm,
+        LabelCommand::Cancel,
+        LabelCommand::PreviousPage,
+        LabelCommand::NextPage,
+        LabelCommand::SelectPage,
+        LabelCommand::SelectAll,
+        LabelCommand::InvertSelection,
+        LabelCommand::CycleGroup,
+        LabelCommand::CycleZone,
+        LabelCommand::SelectLast,
+    ];
+
+    fn description(self) -> &'static str {
+        match self {
+            LabelCommand::Confirm => "Confirm selection",
+            LabelCommand::Cancel => "Cancel selection",
+            LabelCommand::PreviousPage => "Previous page",
+            LabelCommand::NextPage => "Next page",
+            LabelCommand::SelectPage => "Select every combatant on the page",
+            LabelCommand::SelectAll => "Select every combatant on every page",
+            LabelCommand::InvertSelection => "Invert selection on every page",
+            LabelCommand::CycleGroup => "Select the next saved group",
+            LabelCommand::CycleZone => "Select the combatants in the next zone",
+            LabelCommand::SelectLast => "Select the last targets again",
+        }
+    }
+}
+
+impl Bindable for PopupCommand {
+    const ALL: &'static [Self] = &[
+        PopupCommand::Confirm,
+        PopupCommand::Back,
+        PopupCommand::Erase,
+        PopupCommand::Complete,
+        PopupCommand::Help,
+    ];
+
+    fn description(self) -> &'static str {
+        match self {
+            PopupCommand::Confirm => "Confirm / next field",
+            PopupCommand::Back => "Cancel / previous field",
+            PopupCommand::Erase => "Erase last character",
+            PopupCommand::Complete => "Complete command",
+            PopupCommand::Help => "Show help over the popup",
+        }
+    }
+}
+
+// -- Labels -- //
+
+/// The keyboard layout that labels are picked for.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+    Dvorak,
+    Colemak,
+    Azerty,
+}
+
+impl KeyboardLayout {
+    /// Returns the labels used for label mode and popup options, in the order they are given out.
+    ///
+    /// Every layout uses the same physical keys, going column by column across the keyboard, so
+    /// labels stay physically close to each other. AZERTY swaps its dead and accented keys for
+    /// other unshifted keys.
+    pub const fn labels(self) -> &'static str {
+        match self {
+            KeyboardLayout::Qwerty => "qazwsxedcrfvtgbyhnujmik,ol.p;/[']",
+            KeyboardLayout::Dvorak => "'a;,oq.ejpukyixfdbghmctwrnvlsz/-=",
+            KeyboardLayout::Colemak => "qazwrxfscptvgdbjhklnmue,yi.;o/[']",
+            KeyboardLayout::Azerty => "aqwzsxedcrfvtgbyhnuj,ik;ol:pm!$=*",
+        }
+    }
+}
+
+// -- Keymap -- //
+
+/// The result of looking up the keys typed so far in a set of [`Bindings`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Lookup<C> {
+    /// The keys are bound to this command.
+    Command(C),
+
+    /// The keys are the start of at least one longer sequence.
+    Prefix,
+
+    /// No sequence starts with the keys.
+    Unbound,
+}
+
+/// The keys bound to each command of one context.
+#[derive(Clone, Debug)]
+pub struct Bindings<C> {
+    keys: HashMap<C, Vec<KeySequence>>,
+}
+
+impl<C: Bindable> Bindings<C> {
+    /// Returns the command bound to the given key, if any.
+    ///
+    /// Only sequences of a single key are matched.
+    pub fn get(&self, key: impl Into<Key>) -> Option<C> {
+        match self.lookup(&[key.into()]) {
+            Lookup::Command(command) => Some(command),
+            Lookup::Prefix | Lookup::Unbound => None,
+        }
+    }
+
+    /// Looks up the keys typed so far.
+    pub fn lookup(&self, keys: &[Key]) -> Lookup<C> {
+        let bound = C::ALL.iter()
+            .copied()
+            .find(|command| self.keys_for(*command).iter().any(|s| s.keys() == keys));
+        if let Some(command) = bound {
+            return Lookup::Command(command);
+        }
+
+        let is_prefix = self.keys
+            .values()
+            .flatten()
+            .any(|s| s.keys().len() > keys.len() && s.keys().starts_with(keys));
+        if is_prefix { Lookup::Prefix } else { Lookup::Unbound }
+    }
+
+    /// Returns the key sequences bound to the given command.
+    pub fn keys_for(&self, command: C) -> &[KeySequence] {
+        self.keys.get(&command).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Iterates over every command and the key sequences bound to it, in display order.
+    pub fn iter(&self) -> impl Iterator<Item = (C, &[KeySequence])> {
+        C::ALL.iter().map(|command| (*command, self.keys_for(*command)))
+    }
+
+    /// Binds another key sequence to a command.
+    fn bind(mut self, command: C, sequence: KeySequence) -> Self {
+        self.keys.entry(command).or_default().push(sequence);
+        self
+    }
+
+    /// Replaces the keys of every command present in `overrides`.
+    fn extend(&mut self, overrides: HashMap<C, Vec<KeySequence>>) {
+        self.keys.extend(overrides);
+    }
+}
+
+impl<C: Bindable> FromIterator<(C, Key)> for Bindings<C> {
+    fn from_iter<T: IntoIterator<Item = (C, Key)>>(iter: T) -> Self {
+        let mut keys = HashMap::<C, Vec<KeySequence>>::new();
+        for (command, key) in iter {
+            keys.entry(command).or_default().push(key.into());
+        }
+        Self { keys }
+    }
+}
+
+/// Every key binding of the application, grouped by the context they are active in.
+///
+/// In the config file, each context maps command names to a list of keys. Commands that are not
+/// listed keep their default keys, and an empty list unbinds a command. On the main screen, keys
+/// can also be sequences of several keys separated by spaces:
+///
+/// ```json
+/// { "keymap": { "normal": { "next_turn": ["space", "n"], "first_page": ["g g"] } } }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+#[serde(from = "KeymapOverrides")]
+pub struct Keymap {
+    /// Bindings on the main screen.
+    pub normal: Bindings<Command>,
+
+    /// Bindings in label mode.
+    pub label: Bindings<LabelCommand>,
+
+    /// Bindings in popups.
+    pub popup: Bindings<PopupCommand>,
+
+    /// The keyboard layout that labels are picked for.
+    pub layout: KeyboardLayout,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            normal: Bindings::from_iter([
+                (Command::PreviousPage, Key::new(KeyCode::Up)),
+                (Command::NextPage, Key::new(KeyCode::Down)),
+                (Command::FirstPage, Key::new(KeyCode::Home)),
+                (Command::LastPage, Key::char('G')),
+                (Command::LastPage, Key::new(KeyCode::End)),
+                (Command::CursorUp, Key::char('k')),
+                (Command::CursorDown, Key::char('j')),
+                (Command::ClearCursor, Key::new(KeyCode::Esc)),
+                (Command::ApplyCondition, Key::char('c')),
+                (Command::ApplyDamage, Key::char('d')),
+                (Command::Heal, Key::char('h')),
+                (Command::GrantTemporaryHitPoints, Key::char('t')),
+                (Command::RepeatAction, Key::char('.')),
+                (Command::EditHitPoints, Key::char('H')),
+                (Command::EditCombatant, Key::char('C')),
+                (Command::Stabilize, Key::char('S')),
+                (Command::Kill, Key::char('K')),
+                (Command::MoveToken, Key::char('m')),
+                (Command::UseAction, Key::char('a')),
+                (Command::UseBonusAction, Key::char('b')),
+                (Command::UseReaction, Key::char('r')),
+                (Command::ToggleInspiration, Key::char('I')),
+                (Command::ToggleInfoBlock, Key::char('s')),
+                (Command::ToggleInfoPanel, Key::char('i')),
+                (Command::ToggleLogPanel, Key::char('l')),
+                (Command::ToggleNotesPanel, Key::char('N')),
+                (Command::ToggleGridPanel, Key::char('M')),
+                (Command::ToggleGraphPanel, Key::char('P')),
+                (Command::ToggleStacking, Key::char('v')),
+                (Command::GrowTracker, Key::char('>')),
+                (Command::ShrinkTracker, Key::char('<')),
+                (Command::CycleSortOrder, Key::char('o')),
+                (Command::NextTurn, Key::char('n')),
+                (Command::RemoveCombatant, Key::char('x')),
+                (Command::EditNotes, Key::char('e')),
+                (Command::EditEncounterNotes, Key::char('E')),
+                (Command::OpenCommandLine, Key::char(':')),
+                (Command::Search, Key::char('/')),
+                (Command::ShowHelp, Key::char('?')),
+                (Command::ShowConditionLegend, Key::char('L')),
+                (Command::StopRecording, Key::char('Q')),
+                (Command::ToggleDebugPanel, Key::new(KeyCode::F(12))),
+                (Command::Quit, Key::char('q')),
+            ])
+            .bind(Command::FirstPage, KeySequence::new([Key::char('g'), Key::char('g')])),
+            label: Bindings::from_iter([
+                (LabelCommand::Confirm, Key::new(KeyCode::Enter)),
+                (LabelCommand::Cancel, Key::new(KeyCode::Esc)),
+                (LabelCommand::PreviousPage, Key::new(KeyCode::Up)),
+                (LabelCommand::NextPage, Key::new(KeyCode::Down)),
+                (LabelCommand::SelectPage, Key::char('A')),
+                (LabelCommand::SelectAll, Key { ctrl: true, ..Key::char('a') }),
+                (LabelCommand::InvertSelection, Key::char('I')),
+                (LabelCommand::CycleGroup, Key::char('G')),
+                (LabelCommand::CycleZone, Key::char('Z')),
+                (LabelCommand::SelectLast, Key::char('R')),
+            ]),
+            popup: Bindings::from_iter([
+                (PopupCommand::Confirm, Key::new(KeyCode::Enter)),
+                (PopupCommand::Back, Key::new(KeyCode::Esc)),
+                (PopupCommand::Erase, Key::new(KeyCode::Backspace)),
+                (PopupCommand::Complete, Key::new(KeyCode::Tab)),
+                (PopupCommand::Help, Key::new(KeyCode::F(1))),
+            ]),
+            layout: KeyboardLayout::default(),
+        }
+    }
+}
+
+/// The keymap as written in the config file, which only overrides some of the default bindings.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct KeymapOverrides {
+    normal: HashMap<Command, Vec<KeySequence>>,
+    label: HashMap<LabelCommand, Vec<KeySequence>>,
+    popup: HashMap<PopupCommand, Vec<KeySequence>>,
+    layout: KeyboardLayout,
+}
+
+impl From<KeymapOverrides> for Keymap {
+    fn from(overrides: KeymapOverrides) -> Self {
+        let mut keymap = Keymap::default();
+        keymap.normal.extend(overrides.normal);
+        keymap.label.extend(overrides.label);
+        keymap.popup.extend(overrides.popup);
+        keymap.layout = overrides.layout;
+        keymap
+    }
+}
+
+// -- Help -- //
+
+/// One context of the keymap, as listed in the help overlay.
+#[derive(Clone, Debug)]
+pub struct HelpSection {
+    /// The name of the context.
+    pub title: &'static str,
+
+    /// The keys of each entry (joined by commas), and what they do.
+    pub entries: Vec<(String, &'static str)>,
+}
+
+impl HelpSection {
+    /// Creates a section listing every command of one context.
+    fn new<C: Bindable>(title: &'static str, bindings: &Bindings<C>) -> Self {
+        let entries = bindings
+            .iter()
+            .map(|(command, keys)| {
+                let keys = if keys.is_empty() {
+                    "(unbound)".to_string()
+                } else {
+                    keys.iter().join(", ")
+                };
+                (keys, command.description())
+            })
+            .collect();
+        Self { title, entries }
+    }
+
+    /// Adds an entry for keys that aren't bound through the keymap.
+    fn with(mut self, keys: impl Into<String>, description: &'static str) -> Self {
+        self.entries.push((keys.into(), description));
+        self
+    }
+}
+
+/// The format of a [`cheat_sheet`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SheetFormat {
+    /// A Markdown document, with a table per context.
+    Markdown,
+
+    /// Plain text, with the keys and descriptions in aligned columns.
+    Text,
+}
+
+impl SheetFormat {
+    /// Returns the format for a file with the given path: Markdown if its extension is `.md`,
+    /// and plain text otherwise.
+    pub fn of(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("md") => SheetFormat::Markdown,
+            _ => SheetFormat::Text,
+        }
+    }
+}
+
+/// Renders key bindings as a cheat sheet to print, with a section for each context, in the order
+/// of the help overlay.
+pub fn cheat_sheet(sections: &[HelpSection], format: SheetFormat) -> String {
+    let mut sheet = String::new();
+    match format {
+        SheetFormat::Markdown => {
+            sheet.push_str("# h5t key bindings\n");
+            for section in sections {
+                sheet.push_str(&format!("\n## {}\n\n", section.title));
+                sheet.push_str("| Keys | Command |\n| --- | --- |\n");
+                for (keys, description) in &section.entries {
+                    // pipes would end the cell, and backticks the code span
+                    let keys = keys.replace('|', "\\|");
+                    let keys = if keys.contains('`') {
+                        format!("`` {} ``", keys)
+                    } else {
+                        format!("`{}`", keys)
+                    };
+                    let description = description.replace('|', "\\|");
+                    sheet.push_str(&format!("| {} | {} |\n", keys, description));
+                }
+            }
+        },
+        SheetFormat::Text => {
+            let title = "h5t key bindings";
+            sheet.push_str(&format!("{}\n{}\n", title, "=".repeat(title.len())));
+            for section in sections {
+                let title = section.title;
+                sheet.push_str(&format!("\n{}\n{}\n", title, "-".repeat(title.chars().count())));
+                let width = section.entries
+                    .iter()
+                    .map(|(keys, _)| keys.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                for (keys, description) in &section.entries {
+                    sheet.push_str(&format!("{:<width$}  {}\n", keys, description));
+                }
+            }
+        },
+    }
+    sheet
+}
+
+impl Keymap {
+    /// Returns the labels for the configured keyboard layout.
+    pub fn labels(&self) -> &'static str {
+        self.layout.labels()
+    }
+
+    /// Returns every key binding grouped by the context it is active in.
+    pub fn help(&self) -> Vec<HelpSection> {
+        let labels = format!("{}...", self.labels().chars().take(3).join(", "));
+        vec![
+            HelpSection::new("Main screen", &self.normal)
+                .with("1-9...", "Count for the next command (e.g. 3 n)"),
+            HelpSection::new("Label mode", &self.label)
+                .with(labels.clone(), "Toggle labeled combatant"),
+            HelpSection::new("Popups", &self.popup)
+                .with(labels, "Toggle / select labeled option")
+                .with("any character", "Type into input field"),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensure that sequences are split into their keys on whitespace, and written back the same
+    /// way.
+    #[test]
+    fn multi_key_sequences() {
+        let sequence = "g  g".parse::<KeySequence>().unwrap();
+        assert_eq!(sequence.keys(), [Key::char('g'), Key::char('g')]);
+        assert_eq!(sequence.to_string(), "g g");
+
+        let sequence = "ctrl-w space f5".parse::<KeySequence>().unwrap();
+        let ctrl_w = Key { ctrl: true, ..Key::char('w') };
+        assert_eq!(sequence.keys(), [ctrl_w, Key::char(' '), Key::new(KeyCode::F(5))]);
+        assert_eq!(sequence.to_string(), "ctrl-w space f5");
+    }
+
+    /// Ensure that modifiers are parsed in any order and case, and that a trailing `-` is the minus
+    /// key.
+    #[test]
+    fn modifiers() {
+        let ctrl_alt_x = Key { ctrl: true, alt: true, ..Key::char('x') };
+        assert_eq!("ctrl-alt-x".parse(), Ok(ctrl_alt_x));
+        assert_eq!("ALT-Ctrl-x".parse(), Ok(ctrl_alt_x));
+        assert_eq!("alt-Enter".parse(), Ok(Key { alt: true, ..Key::new(KeyCode::Enter) }));
+        assert_eq!("N".parse(), Ok(Key::char('N')));
+        assert_eq!("-".parse(), Ok(Key::char('-')));
+        assert_eq!("ctrl--".parse(), Ok(Key { ctrl: true, ..Key::char('-') }));
+        assert_eq!(ctrl_alt_x.to_string(), "ctrl-alt-x");
+    }
+
+    /// Ensure that unknown keys and modifiers, and empty sequences, are errors.
+    #[test]
+    fn invalid_keys() {
+        let error = "unknown modifier `shift` in key `shift-a`";
+        assert_eq!("shift-a".parse::<Key>(), Err(error.into()));
+        assert_eq!("ctrl-bogus".parse::<Key>(), Err("unknown key `ctrl-bogus`".into()));
+        assert_eq!("".parse::<Key>(), Err("unknown key ``".into()));
+        assert_eq!("g fx".parse::<KeySequence>(), Err("unknown key `fx`".into()));
+        assert_eq!(" ".parse::<KeySequence>(), Err("empty key sequence".into()));
+    }
+}
@@ -0,0 +1,279 @@
+// -- Imports -- //
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::{Deserialize, Serialize};
+
+// -- Panels -- //
+
+/// A panel of the main screen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Panel {
+    /// The initiative tracker table.
+    Tracker,
+
+    /// The combat state or stat block of a combatant.
+    Info,
+
+    /// The combat log.
+    Log,
+
+    /// The notes of a combatant and of the encounter.
+    Notes,
+
+    /// The battle grid, with the combatants placed on it.
+    Grid,
+
+    /// A graph of the party's hit points over the rounds, or a combatant's.
+    Graph,
+}
+
+/// An optional column of the tracker table, shown when the tracker is wide enough.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    /// The combatant's armor class.
+    ArmorClass,
+
+    /// The combatant's initiative roll.
+    Initiative,
+
+    /// The combatant's passive Perception score.
+    PassivePerception,
+
+    /// The zone the combatant is in.
+    Zone,
+
+    /// The first line of the combatant's notes.
+    Notes,
+}
+
+impl Column {
+    /// Every optional column, in the default order.
+    pub const ALL: [Column; 5] = [
+        Column::ArmorClass,
+        Column::Initiative,
+        Column::PassivePerception,
+        Column::Zone,
+        Column::Notes,
+    ];
+
+    /// Returns the header of the column.
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::ArmorClass => "AC",
+            Column::Initiative => "Init",
+            Column::PassivePerception => "PP",
+            Column::Zone => "Zone",
+            Column::Notes => "Notes",
+        }
+    }
+
+    /// Returns the width of the column, in cells.
+    pub fn width(self) -> u16 {
+        match self {
+            Column::ArmorClass | Column::Initiative | Column::PassivePerception => 4,
+            Column::Zone => 12,
+            Column::Notes => 20,
+        }
+    }
+}
+
+/// How the panels of the main screen are stacked.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Stacking {
+    /// Side by side, from left to right.
+    #[default]
+    Horizontal,
+
+    /// On top of each other, from top to bottom.
+    Vertical,
+}
+
+/// One panel of the layout and the share of the screen it takes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PanelConfig {
+    /// The panel to show.
+    pub panel: Panel,
+
+    /// The size of the panel relative to the other panels. Two panels with a ratio of `1` split
+    /// the screen in half.
+    #[serde(default = "PanelConfig::default_ratio")]
+    pub ratio: u16,
+
+    /// Whether the panel starts hidden (it can be shown at runtime).
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl PanelConfig {
+    /// Show a panel with a ratio of `1`.
+    pub const fn new(panel: Panel) -> Self {
+        Self { panel, ratio: Self::default_ratio(), hidden: false }
+    }
+
+    const fn default_ratio() -> u16 {
+        1
+    }
+}
+
+// -- Layout -- //
+
+/// The panels of the main screen, in the order they are drawn.
+///
+/// In the config file, panels that aren't listed are never shown:
+///
+/// ```json
+/// { "layout": { "stacking": "vertical", "panels": [{ "panel": "tracker", "ratio": 2 }] } }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PanelLayout {
+    /// How the panels are stacked.
+    pub stacking: Stacking,
+
+    /// The panels, in the order they are drawn.
+    pub panels: Vec<PanelConfig>,
+
+    /// Whether the screen is too small for the panels, so only the tracker is shown, with the
+    /// info panel stacked under it if toggled on. Set at runtime from the size of the screen.
+    #[serde(skip)]
+    pub compact: bool,
+
+    /// Whether the info panel is shown in compact mode.
+    #[serde(skip)]
+    pub compact_info: bool,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            stacking: Stacking::default(),
+            panels: vec![
+                PanelConfig::new(Panel::Tracker),
+                PanelConfig::new(Panel::Info),
+                PanelConfig { hidden: true, ..PanelConfig::new(Panel::Log) },
+                PanelConfig { hidden: true, ..PanelConfig::new(Panel::Notes) },
+                PanelConfig { hidden: true, ..PanelConfig::new(Panel::Grid) },
+                PanelConfig { hidden: true, ..PanelConfig::new(Panel::Graph) },
+            ],
+            compact: false,
+            compact_info: false,
+        }
+    }
+}
+
+impl PanelLayout {
+    /// Splits the frame between the visible panels.
+    pub fn areas(&self, area: Rect) -> Vec<(Panel, Rect)> {
+        if self.compact {
+            if !self.compact_info {
+                return vec![(Panel::Tracker, area)];
+            }
+            let [tracker, info] = Layout::vertical([Constraint::Fill(1); 2]).areas(area);
+            return vec![(Panel::Tracker, tracker), (Panel::Info, info)];
+        }
+
+        let visible = self.panels.iter().filter(|p| !p.hidden).collect::<Vec<_>>();
+        let total = visible.iter().map(|p| p.ratio as u32).sum::<u32>().max(1);
+
+        let direction = match self.stacking {
+            Stacking::Horizontal => Direction::Horizontal,
+            Stacking::Vertical => Direction::Vertical,
+        };
+        let areas = Layout::default()
+            .direction(direction)
+            .constraints(visible.iter().map(|p| Constraint::Ratio(p.ratio as u32, total)))
+            .split(area);
+
+        visible.into_iter().map(|p| p.panel).zip(areas.iter().copied()).collect()
+    }
+
+    /// Returns the area of the given panel, if it is visible.
+    pub fn area_of(&self, panel: Panel, area: Rect) -> Option<Rect> {
+        self.areas(area).into_iter().find(|(p, _)| *p == panel).map(|(_, area)| area)
+    }
+
+    /// Switches between horizontal and vertical stacking.
+    pub fn toggle_stacking(&mut self) {
+        self.stacking = match self.stacking {
+            Stacking::Horizontal => Stacking::Vertical,
+            Stacking::Vertical => Stacking::Horizontal,
+        };
+    }
+
+    /// Grows a panel by `percent` percent of the screen, taking the space from the next visible
+    /// panel (or the previous one for the last panel). No panel shrinks below 10%.
+    ///
+    /// The ratios of the visible panels become percentages of the screen. Panels can't be resized
+    /// in compact mode.
+    pub fn resize(&mut self, panel: Panel, percent: i16) {
+        const MIN_PERCENT: i16 = 10;
+
+        if self.compact {
+            return;
+        }
+
+        let visible = self.panels
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !p.hidden)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        let Some(position) = visible.iter().position(|i| self.panels[*i].panel == panel) else {
+            return
+        };
+        let neighbor = match visible.get(position + 1) {
+            Some(neighbor) => *neighbor,
+            None if position > 0 => visible[position - 1],
+            None => return,
+        };
+
+        // convert the ratios to percentages, so each step is the same size
+        let total = visible.iter().map(|i| self.panels[*i].ratio as u32).sum::<u32>().max(1);
+        for i in &visible {
+            self.panels[*i].ratio = (self.panels[*i].ratio as u32 * 100 / total) as u16;
+        }
+
+        let grown = self.panels[visible[position]].ratio as i16;
+        let shrunk = self.panels[neighbor].ratio as i16;
+        let percent = percent.min(shrunk - MIN_PERCENT).max(MIN_PERCENT - grown);
+        if grown + percent < MIN_PERCENT || shrunk - percent < MIN_PERCENT {
+            return; // not enough room to resize
+        }
+
+        self.panels[visible[position]].ratio = (grown + percent) as u16;
+        self.panels[neighbor].ratio = (shrunk - percent) as u16;
+    }
+
+    /// Returns the ratio of every panel in the layout.
+    pub fn ratios(&self) -> impl Iterator<Item = (Panel, u16)> + '_ {
+        self.panels.iter().map(|p| (p.panel, p.ratio))
+    }
+
+    /// Sets the ratio of a panel, if it is in the layout.
+    pub fn set_ratio(&mut self, panel: Panel, ratio: u16) {
+        if let Some(config) = self.panels.iter_mut().find(|p| p.panel == panel) {
+            config.ratio = ratio;
+        }
+    }
+
+    /// Shows or hides a panel. Panels that aren't in the layout can't be shown.
+    ///
+    /// In compact mode, only the info panel can be shown, and toggling it doesn't change the
+    /// layout used when the screen is large enough again.
+    pub fn toggle(&mut self, panel: Panel) {
+        if self.compact {
+            if panel == Panel::Info {
+                self.compact_info = !self.compact_info;
+            }
+            return;
+        }
+
+        if let Some(config) = self.panels.iter_mut().find(|p| p.panel == panel) {
+            config.hidden = !config.hidden;
+        }
+    }
+}
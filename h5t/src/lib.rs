@@ -0,0 +1,28 @@
+//! A terminal initiative tracker for D&D 5e, built on [`h5t_core`].
+//!
+//! The `h5t` binary is a thin wrapper around [`ui::Ui`]. The library is exposed so that forks can
+//! drive the UI in their own tests with [`testing::TestUi`].
+
+// -- Modules -- //
+
+mod alert;
+mod announcer;
+pub mod cli;
+pub mod config;
+pub mod debug;
+pub mod error;
+pub mod input;
+pub mod keymap;
+pub mod layout;
+pub mod macros;
+pub mod pages;
+pub mod plugin;
+pub mod selectable;
+mod sequence;
+pub mod settings;
+pub mod state;
+mod tasks;
+pub mod testing;
+pub mod theme;
+pub mod ui;
+pub mod widgets;
@@ -0,0 +1,85 @@
+//! Macros: sequences of keys replayed as if they were typed again.
+//!
+//! Macros are recorded with the `:record` command, or written in the config file, and each one is
+//! played by pressing its key on the main screen (or with the `:macro` command). A macro can do
+//! anything its keys do, label mode and popups included, so a dragon's breath can select its
+//! targets and deal its damage with one key.
+
+// -- Imports -- //
+
+use crate::keymap::{Key, KeySequence};
+
+use serde::{Deserialize, Serialize};
+
+// -- Macros -- //
+
+/// A named sequence of keys, played by pressing its key on the main screen.
+///
+/// In the config file, macros are listed with their keys written like those of the keymap:
+///
+/// ```toml
+/// [[macros]]
+/// name = "dragon breath"
+/// key = "f5"
+/// keys = "d A enter 1 2 d 6 enter"
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Macro {
+    /// The name of the macro, to play it with the `:macro` command.
+    pub name: String,
+
+    /// The key that plays the macro on the main screen, which takes priority over the command
+    /// bound to it.
+    pub key: Key,
+
+    /// The keys replayed, in order.
+    pub keys: KeySequence,
+}
+
+/// Adds a macro to a list, replacing the one with the same name or key, if any.
+pub fn insert(macros: &mut Vec<Macro>, new: Macro) {
+    macros.retain(|existing| existing.name != new.name && existing.key != new.key);
+    macros.push(new);
+}
+
+/// A macro being recorded, from the keys typed after the `:record` command.
+#[derive(Clone, Debug)]
+pub struct Recording {
+    /// The name of the macro.
+    name: String,
+
+    /// The key that will play the macro.
+    key: Key,
+
+    /// The keys typed so far.
+    keys: Vec<Key>,
+}
+
+impl Recording {
+    /// Starts recording a macro with the given name, played with the given key.
+    pub fn new(name: String, key: Key) -> Self {
+        Self { name, key, keys: Vec::new() }
+    }
+
+    /// Returns the name of the macro being recorded.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Records a key typed.
+    pub fn push(&mut self, key: impl Into<Key>) {
+        self.keys.push(key.into());
+    }
+
+    /// Stops recording, leaving out the keys that stopped it, which are the last keys typed if
+    /// they match one of the given sequences. Returns `None` if no other key was typed.
+    pub fn finish(mut self, stop: &[KeySequence]) -> Option<Macro> {
+        if let Some(sequence) = stop.iter().find(|sequence| self.keys.ends_with(sequence.keys())) {
+            self.keys.truncate(self.keys.len() - sequence.keys().len());
+        }
+        if self.keys.is_empty() { return None }
+
+        Some(Macro { name: self.name, key: self.key, keys: KeySequence::new(self.keys) })
+    }
+}
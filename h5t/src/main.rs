@@ -1,30 +1,170 @@
-// -- Modules -- //
+// -- Imports -- //
 
-mod selectable;
-mod state;
-mod ui;
-mod widgets;
+use h5t::cli::{Cli, CliCommand, CompendiumCommand};
+use h5t::config::Config;
+use h5t::debug;
+use h5t::error::{Error, Result};
+use h5t::input::TerminalEvents;
+use h5t::plugin::{Plugins, Script};
+use h5t::settings::Settings;
+use h5t::ui::Ui;
+use h5t::widgets::stat_block::fmt_cr;
+use h5t_core::{
+    CombatantKind, Compendium, CompendiumDb, MonsterQuery, MonsterSource, Side, Tracker, TurnOrder,
+};
 
-// -- Imports -- //
+use clap::Parser;
 
-use ui::Ui;
-use h5t_core::{CombatantKind, Monster, Tracker};
+use std::path::Path;
+use std::process::ExitCode;
 
 // -- Main -- //
 
-fn main() {
-    // NOTE: monster JSON data provided courtesy of https://www.dnd5eapi.co/
-    let file = std::fs::File::open("data/monsters.json").unwrap();
-    let monsters = serde_json::from_reader::<_, Vec<Monster>>(file).unwrap();
-    // println!("{:#?}", monsters);
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            // by now the terminal is restored, so the error isn't drawn over
+            eprintln!("h5t: {}", err);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+/// Sets up and runs the tracker, returning any error that stopped it.
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+    let debug_log = debug::init(cli.options.log_file.as_deref())?;
+    tracing::info!("starting h5t {}", env!("CARGO_PKG_VERSION"));
 
-    let mut tracker = Ui::new(
-        ratatui::init(),
-        Tracker::new(monsters
+    let config_path = cli.options.config.clone().unwrap_or_else(Config::default_path);
+    let mut config = Config::load(&config_path)?;
+    tracing::info!(path = %config_path.display(), "loaded config");
+    cli.options.apply(&mut config);
+
+    let compendium = open_compendium(&config)?;
+    tracing::info!(monsters = compendium.count(), "loaded compendium");
+
+    let side_initiative = matches!(
+        cli.command,
+        Some(CliCommand::New { side_initiative: true, .. })
+            | Some(CliCommand::Import { side_initiative: true, .. }),
+    );
+    let mut tracker = match cli.command {
+        Some(CliCommand::Compendium(CompendiumCommand::Search { name, kind, min_cr, max_cr })) => {
+            let query = MonsterQuery { name, kind, min_challenge: min_cr, max_challenge: max_cr };
+            print_search(compendium.as_ref(), &query);
+            return Ok(());
+        },
+        Some(CliCommand::Run { encounter }) => load_encounter(&encounter)?,
+        Some(CliCommand::New { monsters, .. }) => Tracker::new(monsters
+            .iter()
+            .map(|name| match compendium.find(name) {
+                Some(monster) => Ok(monster.into()),
+                None => Err(Error::Usage(format!("no monster named `{}`", name))),
+            })
+            .collect::<Result<Vec<_>>>()?),
+        Some(CliCommand::Import { file, .. }) => {
+            let imported = load_compendium(&file)?;
+            if imported.monsters().is_empty() {
+                return Err(Error::Usage(format!("{} has no monsters", file.display())));
+            }
+            Tracker::new(imported.monsters().iter().map(|m| m.clone().into()).collect::<Vec<_>>())
+        },
+        None => Tracker::new(compendium
+            .query(&MonsterQuery::default())
             .into_iter()
             .map(|m| CombatantKind::Monster(m).into())
             .collect::<Vec<_>>()),
+    };
+    if side_initiative {
+        tracker.turn_order = TurnOrder::Sides(vec![Side::Ally, Side::Enemy]);
+    }
+
+    let mut plugins = Plugins::from_names(&config.plugins)?;
+    for path in &config.scripts {
+        plugins.add(Box::new(Script::load(path)?));
+    }
+    let settings = Settings::load(Settings::PATH);
+    settings.apply(&mut config);
+
+    let terminal = ratatui::try_init()?;
+    let ui = crossterm::execute!(
+        std::io::stdout(),
+        crossterm::event::EnableMouseCapture,
+        crossterm::event::EnableBracketedPaste,
+    ).map_err(Error::from).and_then(|_| Ui::<_, TerminalEvents>::new(
+        terminal,
+        tracker,
+        config,
+        settings,
+        compendium,
+        debug_log,
+        plugins,
+    ));
+
+    let result = ui.and_then(|mut ui| ui.run());
+    let _ = crossterm::execute!(
+        std::io::stdout(),
+        crossterm::event::DisableMouseCapture,
+        crossterm::event::DisableBracketedPaste,
     );
+    ratatui::restore();
+    result
+}
+
+/// Loads the monster files of the config, or opens the compendium database and imports the ones
+/// that changed into it, if the config has one.
+fn open_compendium(config: &Config) -> Result<Box<dyn MonsterSource>> {
+    if let Some(path) = &config.compendium_db {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(Error::io(parent))?;
+        }
+        let mut db = CompendiumDb::open(path)?;
+        let imported = db.sync(&config.compendium)?;
+        tracing::info!(path = %path.display(), imported, "opened compendium database");
+        return Ok(Box::new(db));
+    }
+
+    // NOTE: monster JSON data provided courtesy of https://www.dnd5eapi.co/
+    let mut compendium = Compendium::default();
+    for path in &config.compendium {
+        compendium.extend(load_compendium(path)?);
+    }
+    Ok(Box::new(compendium))
+}
+
+/// Loads a list of monsters in the format provided by <https://www.dnd5eapi.co/>.
+fn load_compendium(path: &Path) -> Result<Compendium> {
+    let file = std::fs::File::open(path).map_err(Error::io(path))?;
+    Compendium::from_reader(std::io::BufReader::new(file)).map_err(Error::parse(path))
+}
+
+/// Loads an encounter saved with `:save`, which must have at least one combatant.
+fn load_encounter(path: &Path) -> Result<Tracker> {
+    let file = std::fs::File::open(path).map_err(Error::io(path))?;
+    let tracker = serde_json::from_reader::<_, Tracker>(std::io::BufReader::new(file))
+        .map_err(Error::parse(path))?;
+    if tracker.combatants.is_empty() {
+        return Err(Error::Usage(format!("{} has no combatants", path.display())));
+    }
+    Ok(tracker)
+}
 
-    tracker.run();
+/// Prints the monsters matching the query, one per line.
+fn print_search(compendium: &dyn MonsterSource, query: &MonsterQuery) {
+    let monsters = compendium.query(query);
+    for monster in &monsters {
+        println!(
+            "{}: CR {}, AC {}, HP {} ({})",
+            monster.name,
+            fmt_cr(monster.challenge_rating, monster.xp),
+            monster.armor_class.value,
+            monster.hit_points,
+            monster.hit_points_roll,
+        );
+    }
+    if monsters.is_empty() {
+        println!("No monsters found starting with `{}`.", query.name);
+    }
 }
@@ -0,0 +1,329 @@
+//! The combatants shown in the tracker, split into pages that fit its height.
+//!
+//! Pages aren't stored, but sliced out of the list of visible combatants when they are needed, so
+//! resizing the tracker only changes the page size, and adding a combatant only appends to the
+//! list. Combatants selected in label mode are kept by their index in the tracker, so the
+//! selection survives whatever the pages look like.
+
+// -- Imports -- //
+
+use crate::config::ViewMode;
+
+use std::collections::BTreeSet;
+
+// -- Page -- //
+
+/// No combatants selected, for pages drawn without a selection.
+static NO_SELECTION: BTreeSet<usize> = BTreeSet::new();
+
+/// One page of the tracker: the combatants drawn in its rows, and which of them are selected.
+#[derive(Copy, Clone, Debug)]
+pub struct Page<'a> {
+    /// The page number, from 0.
+    id: usize,
+
+    /// The index of the page's first row among the rows of every page.
+    first: usize,
+
+    /// The indexes of the combatants on the page, in the order they are drawn.
+    combatants: &'a [usize],
+
+    /// The indexes of the selected combatants, on this page or not.
+    selected: &'a BTreeSet<usize>,
+}
+
+impl Default for Page<'_> {
+    fn default() -> Self {
+        Self { id: 0, first: 0, combatants: &[], selected: &NO_SELECTION }
+    }
+}
+
+impl<'a> Page<'a> {
+    /// Returns the page number, from 0.
+    pub fn get_id(&self) -> usize { self.id }
+
+    /// Returns the index of the page's first row among the rows of every page.
+    pub fn first_row(&self) -> usize { self.first }
+
+    /// Returns the indexes of the combatants on the page, in the order they are drawn.
+    pub fn get_combatants(&self) -> &'a [usize] { self.combatants }
+
+    /// Returns whether the combatant with the given index is selected.
+    pub fn is_selected(&self, combatant: usize) -> bool {
+        self.selected.contains(&combatant)
+    }
+
+    /// Returns the label drawn on the given row, if the row is on the page.
+    ///
+    /// labels - The labels of the keyboard layout, in order.
+    pub fn label_of_row(&self, labels: &str, row: usize) -> Option<char> {
+        if row < self.combatants.len() { labels.chars().nth(row) } else { None }
+    }
+
+    /// Returns the index of the combatant with the given label, if the label is on the page.
+    ///
+    /// labels - The labels of the keyboard layout, in order.
+    pub fn combatant_of_label(&self, labels: &str, label: char) -> Option<usize> {
+        // only the labels drawn on the page can be selected
+        let row = labels.chars().take(self.combatants.len()).position(|c| c == label)?;
+        Some(self.combatants[row])
+    }
+}
+
+// -- Pages -- //
+
+/// The combatants shown in the tracker, in the order they are shown, split into pages.
+///
+/// In scroll view mode, there is a single page holding the rows scrolled into view.
+#[derive(Clone, Debug)]
+pub struct Pages {
+    /// The indexes of the visible combatants, in the order they are shown.
+    combatants: Vec<usize>,
+
+    /// The number of rows on a page.
+    page_size: usize,
+
+    /// Whether the rows are split into pages or scrolled through.
+    view: ViewMode,
+
+    /// The index of the first row shown, which is the first row of a page in page view mode.
+    first: usize,
+
+    /// The indexes of the combatants selected in label mode.
+    selected: BTreeSet<usize>,
+
+    /// The indexes of the combatants last confirmed in label mode, to select them again.
+    last: BTreeSet<usize>,
+}
+
+impl Pages {
+    /// Splits the given combatants into pages of `page_size` rows.
+    pub fn new(combatants: Vec<usize>, page_size: usize, view: ViewMode) -> Self {
+        Self {
+            combatants,
+            page_size,
+            view,
+            first: 0,
+            selected: BTreeSet::new(),
+            last: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the number of rows on a page.
+    pub fn page_size(&self) -> usize { self.page_size }
+
+    /// Returns the indexes of the visible combatants, in the order they are shown.
+    pub fn visible(&self) -> &[usize] { &self.combatants }
+
+    /// Returns the index of the first row shown.
+    pub fn first_row(&self) -> usize { self.first }
+
+    /// Returns the number of pages, which is at least one unless pages have no rows.
+    pub fn count(&self) -> usize {
+        if self.page_size == 0 { 0 } else { self.combatants.len().div_ceil(self.page_size).max(1) }
+    }
+
+    /// Returns the number of the current page, from 0.
+    pub fn current(&self) -> usize {
+        self.first.checked_div(self.page_size).unwrap_or(0)
+    }
+
+    /// Returns the page with the given number, if there is one.
+    pub fn page(&self, id: usize) -> Option<Page<'_>> {
+        (id < self.count()).then(|| self.page_at(id, id * self.page_size))
+    }
+
+    /// Returns the page drawn in the tracker: the current page, or the rows scrolled into view.
+    pub fn shown(&self) -> Option<Page<'_>> {
+        (self.count() > 0).then(|| self.page_at(self.current(), self.first))
+    }
+
+    /// Returns the page whose rows start at `first`.
+    fn page_at(&self, id: usize, first: usize) -> Page<'_> {
+        let start = first.min(self.combatants.len());
+        let end = (start + self.page_size).min(self.combatants.len());
+        Page { id, first, combatants: &self.combatants[start..end], selected: &self.selected }
+    }
+
+    /// Changes the number of rows on a page, keeping the first row shown on screen. Returns
+    /// whether it changed.
+    pub fn resize(&mut self, page_size: usize) -> bool {
+        if self.page_size == page_size { return false }
+
+        self.page_size = page_size;
+        self.clamp();
+        true
+    }
+
+    /// Replaces the visible combatants, such as when they are filtered or sorted differently.
+    /// Combatants that aren't visible anymore are unselected.
+    pub fn set_combatants(&mut self, combatants: Vec<usize>) {
+        self.combatants = combatants;
+        let selected = std::mem::take(&mut self.selected);
+        self.selected = self.visible_among(selected);
+        self.clamp();
+    }
+
+    /// Shows a combatant added to the tracker after the visible ones.
+    pub fn push(&mut self, combatant: usize) {
+        self.combatants.push(combatant);
+    }
+
+    /// Forgets a combatant removed from the tracker, moving the combatants after it in the tracker
+    /// down one index.
+    pub fn remove(&mut self, combatant: usize) {
+        let shift = |index: usize| if index > combatant { index - 1 } else { index };
+
+        self.combatants.retain(|index| *index != combatant);
+        self.combatants.iter_mut().for_each(|index| *index = shift(*index));
+        self.selected = std::mem::take(&mut self.selected)
+            .into_iter()
+            .filter(|index| *index != combatant)
+            .map(shift)
+            .collect();
+        self.last = std::mem::take(&mut self.last)
+            .into_iter()
+            .filter(|index| *index != combatant)
+            .map(shift)
+            .collect();
+        self.clamp();
+    }
+
+    /// Keeps the first row shown within the rows: at the start of a page in page view mode, and
+    /// with a full page below it (if there are enough rows) in scroll view mode.
+    fn clamp(&mut self) {
+        match self.view {
+            ViewMode::Pages => {
+                let last = self.count().saturating_sub(1);
+                self.first = self.current().min(last) * self.page_size;
+            },
+            ViewMode::Scroll => {
+                self.first = self.first.min(self.combatants.len().saturating_sub(self.page_size));
+            },
+        }
+    }
+
+    // Navigation //
+
+    /// Switches to the previous page, if there is one.
+    ///
+    /// In scroll view mode, scrolls up one row instead.
+    pub fn previous(&mut self) {
+        let step = if self.view == ViewMode::Scroll { 1 } else { self.page_size };
+        self.first = self.first.saturating_sub(step);
+    }
+
+    /// Switches to the next page, if there is one.
+    ///
+    /// In scroll view mode, scrolls down one row instead.
+    pub fn next(&mut self) {
+        let step = if self.view == ViewMode::Scroll { 1 } else { self.page_size };
+        self.first += step;
+        self.clamp();
+    }
+
+    /// Switches to the given page, or the last page if there aren't that many.
+    ///
+    /// In scroll view mode, scrolls so the given page's first row is at the top instead.
+    pub fn go_to(&mut self, page: usize) {
+        self.first = page.saturating_mul(self.page_size);
+        self.clamp();
+    }
+
+    /// Switches to the page of the given combatant, or scrolls it into view in scroll view mode.
+    /// Nothing happens if the combatant isn't visible.
+    pub fn show(&mut self, combatant: usize) {
+        let Some(row) = self.combatants.iter().position(|index| *index == combatant) else {
+            return
+        };
+
+        match self.view {
+            ViewMode::Pages => self.first = row - row % self.page_size.max(1),
+            ViewMode::Scroll => {
+                let page_size = self.page_size.max(1);
+                if row < self.first {
+                    self.first = row;
+                } else if row >= self.first + page_size {
+                    self.first = row + 1 - page_size;
+                }
+            },
+        }
+    }
+
+    // Selection //
+
+    /// Toggles the selection of the combatant with the given label on the page shown.
+    pub fn toggle_label(&mut self, labels: &str, label: char) {
+        let Some(combatant) = self.shown().and_then(|page| page.combatant_of_label(labels, label))
+        else { return };
+
+        self.toggle(combatant);
+    }
+
+    /// Toggles the selection of the combatant with the given index, such as one whose row was
+    /// clicked.
+    pub fn toggle(&mut self, combatant: usize) {
+        if !self.selected.remove(&combatant) {
+            self.selected.insert(combatant);
+        }
+    }
+
+    /// Selects every combatant on the page shown.
+    pub fn select_shown(&mut self) {
+        let Some(page) = self.shown() else { return };
+        let combatants = page.get_combatants().to_vec();
+        self.selected.extend(combatants);
+    }
+
+    /// Selects every visible combatant.
+    pub fn select_all(&mut self) {
+        self.selected = self.combatants.iter().copied().collect();
+    }
+
+    /// Toggles the selection of every visible combatant.
+    pub fn invert_selection(&mut self) {
+        self.selected = self.combatants
+            .iter()
+            .copied()
+            .filter(|index| !self.selected.contains(index))
+            .collect();
+    }
+
+    /// Replaces the selection with the given combatants, leaving out the ones that aren't visible.
+    pub fn select(&mut self, members: &[usize]) {
+        self.selected = self.visible_among(members.iter().copied().collect());
+    }
+
+    /// Replaces the selection with the combatants last confirmed, leaving out the ones that aren't
+    /// visible. Returns `false` if no selection was confirmed yet.
+    pub fn select_last(&mut self) -> bool {
+        if self.last.is_empty() { return false }
+
+        self.selected = self.visible_among(self.last.clone());
+        true
+    }
+
+    /// Returns the given combatants that are visible.
+    fn visible_among(&self, mut combatants: BTreeSet<usize>) -> BTreeSet<usize> {
+        if combatants.is_empty() { return combatants }
+
+        let visible = self.combatants.iter().copied().collect::<BTreeSet<_>>();
+        combatants.retain(|index| visible.contains(index));
+        combatants
+    }
+
+    /// Takes the confirmed selection, returning the indexes of the selected combatants in order.
+    /// Unless it is empty, it is remembered for [`Pages::select_last`].
+    pub fn take_selection(&mut self) -> Vec<usize> {
+        let selected = std::mem::take(&mut self.selected);
+        if !selected.is_empty() {
+            self.last = selected.clone();
+        }
+        selected.into_iter().collect()
+    }
+
+    /// Clears the selection without remembering it, such as when it is cancelled.
+    pub fn clear_selection(&mut self) {
+        self.selected.clear();
+    }
+}
@@ -0,0 +1,164 @@
+// -- Modules -- //
+
+pub mod script;
+pub mod unconscious;
+
+// -- Imports -- //
+
+use crate::error::{Error, Result};
+use crate::keymap::{HelpSection, Key, Keymap};
+use crate::state::{Drawable, InputHandler};
+use crate::widgets::Hint;
+
+use h5t_core::{LogEvent, Tracker};
+
+use std::fmt::Debug;
+
+// -- Exports -- //
+
+pub use script::Script;
+pub use unconscious::Unconscious;
+
+// -- Plugins -- //
+
+/// An extension that adds its own modes to the main screen, or reacts to what happens in the
+/// encounter, without changes to the [`Ui`](crate::ui::Ui).
+///
+/// Plugins are listed by name in the `plugins` section of the config, and created by
+/// [`builtin`]. A plugin binds keys with [`Plugin::register_keybinds`]; pressing one of them
+/// opens the state the plugin creates with [`Plugin::create_state`], which then works like the
+/// built-in action states.
+pub trait Plugin: Debug {
+    /// The name of the plugin, as written in the config.
+    fn name(&self) -> &'static str;
+
+    /// Binds keys on the main screen to the plugin's states. Called once, when the plugin is
+    /// added.
+    fn register_keybinds(&self, _: &mut PluginKeybinds) {}
+
+    /// Creates the state with the given name, as registered with a keybind, for the given target
+    /// (the cursor's combatant, or the current turn's). Returns [`None`] if the state can't be
+    /// opened right now.
+    fn create_state(&mut self, _: &str, _: &Tracker, _: usize) -> Option<Box<dyn PluginState>> {
+        None
+    }
+
+    /// Called for every event recorded in the combat log, in order, with the tracker as it is
+    /// after the change. Changes made here are logged too, and passed to every plugin in turn.
+    fn on_event(&mut self, _: &LogEvent, _: &mut Tracker) {}
+}
+
+/// A mode opened by a [`Plugin`], drawn and given input like the built-in action states.
+pub trait PluginState: Drawable + InputHandler + Debug {
+    /// Returns the name of the mode and its most useful keys, for the status bar.
+    fn status(&self, keymap: &Keymap) -> (&'static str, Vec<Hint>);
+
+    /// Applies the mode's changes to the tracker, when it is exited without cancelling.
+    fn apply(self: Box<Self>, tracker: &mut Tracker);
+}
+
+/// A key bound by a plugin, and the state it opens.
+#[derive(Clone, Debug)]
+struct PluginKeybind {
+    /// The index of the plugin that registered the key.
+    plugin: usize,
+
+    /// The key, which is checked before the keymap's bindings.
+    key: Key,
+
+    /// The name of the state to create.
+    state: &'static str,
+
+    /// What the key does, for the help overlay.
+    description: &'static str,
+}
+
+/// The keys bound by every plugin, collected by [`Plugin::register_keybinds`].
+#[derive(Debug, Default)]
+pub struct PluginKeybinds {
+    /// The plugin currently registering keys.
+    plugin: usize,
+
+    /// The keys, in the order they were registered.
+    keybinds: Vec<PluginKeybind>,
+}
+
+impl PluginKeybinds {
+    /// Binds a key on the main screen to the state with the given name. Keys bound by plugins
+    /// take priority over the keymap, so they should be keys the keymap leaves free.
+    pub fn register_keybind(&mut self, key: Key, state: &'static str, description: &'static str) {
+        let plugin = self.plugin;
+        self.keybinds.push(PluginKeybind { plugin, key, state, description });
+    }
+}
+
+/// The plugins in use, and the keys they bound.
+#[derive(Debug, Default)]
+pub struct Plugins {
+    /// The plugins, in the order they were added.
+    plugins: Vec<Box<dyn Plugin>>,
+
+    /// The keys bound by the plugins.
+    keybinds: PluginKeybinds,
+}
+
+impl Plugins {
+    /// Creates the built-in plugins with the given names.
+    pub fn from_names(names: &[String]) -> Result<Self> {
+        let mut plugins = Self::default();
+        for name in names {
+            // healing is built in now, so configs listing the old plugin keep working
+            if name == "heal" {
+                tracing::warn!("the heal plugin is built in now, `h` heals without it");
+                continue;
+            }
+            let plugin = builtin(name)
+                .ok_or_else(|| Error::Usage(format!("no plugin named `{}`", name)))?;
+            plugins.add(plugin);
+        }
+        Ok(plugins)
+    }
+
+    /// Adds a plugin, registering its keys.
+    pub fn add(&mut self, plugin: Box<dyn Plugin>) {
+        self.keybinds.plugin = self.plugins.len();
+        plugin.register_keybinds(&mut self.keybinds);
+        tracing::info!(plugin = plugin.name(), "added plugin");
+        self.plugins.push(plugin);
+    }
+
+    /// Creates the state bound to the given key, if a plugin bound it.
+    pub fn create_state(
+        &mut self,
+        key: Key,
+        tracker: &Tracker,
+        target: usize,
+    ) -> Option<Box<dyn PluginState>> {
+        let keybind = self.keybinds.keybinds.iter().find(|keybind| keybind.key == key)?;
+        self.plugins[keybind.plugin].create_state(keybind.state, tracker, target)
+    }
+
+    /// Passes an event to every plugin, in the order they were added.
+    pub fn on_event(&mut self, event: &LogEvent, tracker: &mut Tracker) {
+        for plugin in &mut self.plugins {
+            plugin.on_event(event, tracker);
+        }
+    }
+
+    /// Returns the keys bound by plugins, for the help overlay, if there are any.
+    pub fn help(&self) -> Option<HelpSection> {
+        let entries = self.keybinds.keybinds
+            .iter()
+            .map(|keybind| (keybind.key.to_string(), keybind.description))
+            .collect::<Vec<_>>();
+        (!entries.is_empty()).then_some(HelpSection { title: "Plugins", entries })
+    }
+}
+
+/// Creates the built-in plugin with the given name.
+pub fn builtin(name: &str) -> Option<Box<dyn Plugin>> {
+    match name {
+        "unconscious" => Some(Box::new(Unconscious)),
+        _ => None,
+    }
+}
@@ -0,0 +1,265 @@
+use crate::error::{Error, Result};
+use crate::selectable::Selectable;
+use h5t_core::{
+    Changed, ConditionDuration, ConditionKind, DamageType, DiceExpression, LogEvent, Tracker,
+    TrackerCommand,
+};
+use rand::Rng;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST, INT};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use super::Plugin;
+
+/// The result of a function called by a script.
+type ScriptResult<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+/// A plugin that runs the hooks of a [Rhai](https://rhai.rs) script when combat log events are
+/// recorded, so tables can automate house rules.
+///
+/// A script defines any of these functions, which are given the combatant the event is about as
+/// a map (`index`, `name`, `hit_points`, `max_hit_points`, `armor_class`, `conditions`):
+///
+/// ```rhai
+/// fn on_turn_start(combatant) {}
+/// fn on_damage(combatant, amount, kind) {} // `kind` is "" for untyped damage
+/// fn on_condition_applied(combatant, condition) {}
+/// ```
+///
+/// Scripts can't touch the tracker directly. They call `damage`, `heal`, `set_hit_points`, and
+/// `apply_condition` on a combatant map, which are applied once the hook returns, through the
+/// normal damage pipeline and combat log. `roll("1d4")` rolls dice, and `print` writes to the log.
+/// A script's hooks don't run for the events its own changes cause.
+pub struct Script {
+    /// The file the script was loaded from.
+    path: PathBuf,
+
+    /// The engine the script runs in, limited so a runaway script can't hang the UI.
+    engine: Engine,
+
+    /// The compiled script.
+    ast: AST,
+
+    /// The changes the running hook requested, made once it returns.
+    commands: Rc<RefCell<Vec<TrackerCommand>>>,
+
+    /// Events caused by the script's own changes, which are skipped when they come around.
+    own_events: VecDeque<LogEvent>,
+}
+
+impl std::fmt::Debug for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Script").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+impl Script {
+    /// The number of operations a hook can run before it is stopped.
+    const MAX_OPERATIONS: u64 = 100_000;
+
+    /// Loads and compiles the script in the given file.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let source = std::fs::read_to_string(&path).map_err(Error::io(&path))?;
+
+        let commands = Rc::new(RefCell::new(Vec::new()));
+        let engine = engine(&path, &commands);
+        let ast = engine.compile(source).map_err(Error::parse(&path))?;
+        Ok(Self { path, engine, ast, commands, own_events: VecDeque::new() })
+    }
+
+    /// Calls the hook with the given name if the script defines it, then makes the changes it
+    /// requested. Changes to combatants that don't exist, such as through a map the script made up,
+    /// are skipped.
+    fn call(&mut self, hook: &str, args: impl rhai::FuncArgs, tracker: &mut Tracker) {
+        if !self.ast.iter_functions().any(|function| function.name == hook) {
+            return;
+        }
+
+        // only the functions are used, so the script's top level doesn't run on every event
+        let options = CallFnOptions::new().eval_ast(false);
+        let result = self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &self.ast, hook, args);
+        let commands = std::mem::take(&mut *self.commands.borrow_mut());
+        if let Err(err) = result {
+            tracing::warn!(script = %self.path.display(), "{} failed: {}", hook, err);
+            return;
+        }
+
+        let start = tracker.log.len();
+        for command in commands {
+            if let Changed::Combatant(index) = command.changed()
+                && index >= tracker.combatants.len()
+            {
+                let script = self.path.display();
+                tracing::warn!(script = %script, "{} changed a missing combatant {}", hook, index);
+                continue;
+            }
+            tracker.execute(command);
+        }
+        self.own_events.extend(tracker.log[start..].iter().map(|entry| entry.event.clone()));
+    }
+}
+
+impl Plugin for Script {
+    fn name(&self) -> &'static str {
+        "script"
+    }
+
+    fn on_event(&mut self, event: &LogEvent, tracker: &mut Tracker) {
+        if self.own_events.front() == Some(event) {
+            self.own_events.pop_front();
+            return;
+        }
+
+        match event {
+            LogEvent::TurnStarted { combatant } => {
+                // the turn may have moved on since, if several turns were advanced at once
+                let index = Some(tracker.turn)
+                    .filter(|turn| tracker.combatants[*turn].name() == combatant)
+                    .or_else(|| find(tracker, combatant));
+                let Some(index) = index else { return };
+                self.call("on_turn_start", (combatant_map(tracker, index),), tracker);
+            },
+            LogEvent::Damaged { combatant, amount, kind, index, .. } => {
+                // the index tells apart combatants with the same name, unless it has shifted since
+                let index = index
+                    .filter(|index| {
+                        tracker.combatants.get(*index).is_some_and(|c| c.name() == combatant)
+                    })
+                    .or_else(|| find(tracker, combatant));
+                let Some(index) = index else { return };
+                let kind = kind.map(|kind| kind.name().to_string()).unwrap_or_default();
+                let args = (combatant_map(tracker, index), *amount as INT, kind);
+                self.call("on_damage", args, tracker);
+            },
+            LogEvent::ConditionApplied { combatant, condition } => {
+                let index = tracker.combatants
+                    .iter()
+                    .position(|c| {
+                        c.name() == combatant && c.conditions.iter().any(|c| c.kind == *condition)
+                    })
+                    .or_else(|| find(tracker, combatant));
+                let Some(index) = index else { return };
+                let args = (combatant_map(tracker, index), condition.to_string());
+                self.call("on_condition_applied", args, tracker);
+            },
+            _ => (),
+        }
+    }
+}
+
+/// Returns the index of the first combatant with the given name.
+fn find(tracker: &Tracker, name: &str) -> Option<usize> {
+    tracker.combatants.iter().position(|combatant| combatant.name() == name)
+}
+
+/// Describes a combatant to a script.
+fn combatant_map(tracker: &Tracker, index: usize) -> Map {
+    let combatant = &tracker.combatants[index];
+    let conditions = combatant.conditions
+        .iter()
+        .map(|condition| Dynamic::from(condition.kind.to_string()))
+        .collect::<rhai::Array>();
+
+    let mut map = Map::new();
+    map.insert("index".into(), (index as INT).into());
+    map.insert("name".into(), combatant.name().to_string().into());
+    map.insert("hit_points".into(), (combatant.hit_points as INT).into());
+    map.insert("max_hit_points".into(), (combatant.max_hit_points() as INT).into());
+    map.insert("armor_class".into(), (combatant.armor_class() as INT).into());
+    map.insert("conditions".into(), conditions.into());
+    map
+}
+
+/// Creates an engine with the functions scripts can call, which queue their changes in
+/// `commands`.
+fn engine(path: &Path, commands: &Rc<RefCell<Vec<TrackerCommand>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(Script::MAX_OPERATIONS);
+    engine.set_max_call_levels(32);
+
+    // printing to the terminal would draw over the UI
+    let script = path.display().to_string();
+    engine.on_print(move |text| tracing::info!(script = %script, "{}", text));
+    let script = path.display().to_string();
+    engine.on_debug(move |text, _, _| tracing::debug!(script = %script, "{}", text));
+
+    engine.register_fn("roll", |expression: &str| -> ScriptResult<INT> {
+        let expression = expression.parse::<DiceExpression>()?;
+        let mut rng = rand::rng();
+        Ok(expression.roll(|sides| rng.random_range(1..=sides)) as INT)
+    });
+
+    let queue = commands.clone();
+    engine.register_fn("damage", move |combatant: Map, amount: INT| -> ScriptResult<()> {
+        let index = index_of(&combatant)?;
+        let amount = amount as i32;
+        queue.borrow_mut().push(TrackerCommand::Damage { index, amount, kind: None });
+        Ok(())
+    });
+    let queue = commands.clone();
+    engine.register_fn(
+        "damage",
+        move |combatant: Map, amount: INT, kind: &str| -> ScriptResult<()> {
+            let index = index_of(&combatant)?;
+            let kind = Some(kind.parse::<DamageType>()?);
+            queue.borrow_mut().push(TrackerCommand::Damage { index, amount: amount as i32, kind });
+            Ok(())
+        },
+    );
+    let queue = commands.clone();
+    engine.register_fn("heal", move |combatant: Map, amount: INT| -> ScriptResult<()> {
+        let index = index_of(&combatant)?;
+        let amount = amount as i32;
+        queue.borrow_mut().push(TrackerCommand::Heal { index, amount });
+        Ok(())
+    });
+    let queue = commands.clone();
+    engine.register_fn("set_hit_points", move |combatant: Map, value: INT| -> ScriptResult<()> {
+        let index = index_of(&combatant)?;
+        queue.borrow_mut().push(TrackerCommand::SetHitPoints { index, value: value as i32 });
+        Ok(())
+    });
+    let queue = commands.clone();
+    engine.register_fn("apply_condition", move |combatant: Map, name: &str| -> ScriptResult<()> {
+        let index = index_of(&combatant)?;
+        let kind = condition(name)?;
+        let duration = ConditionDuration::Forever;
+        queue.borrow_mut().push(TrackerCommand::AddCondition { index, kind, duration });
+        Ok(())
+    });
+    let queue = commands.clone();
+    engine.register_fn(
+        "apply_condition",
+        move |combatant: Map, name: &str, rounds: INT| -> ScriptResult<()> {
+            let index = index_of(&combatant)?;
+            let kind = condition(name)?;
+            let rounds = u32::try_from(rounds).ok().and_then(NonZeroU32::new);
+            let Some(rounds) = rounds else { return Err("rounds must be positive".into()) };
+            let duration = ConditionDuration::Rounds(rounds);
+            queue.borrow_mut().push(TrackerCommand::AddCondition { index, kind, duration });
+            Ok(())
+        },
+    );
+
+    engine
+}
+
+/// Returns the index of a combatant map given to a hook.
+fn index_of(combatant: &Map) -> ScriptResult<usize> {
+    combatant
+        .get("index")
+        .and_then(|index| index.as_int().ok())
+        .and_then(|index| usize::try_from(index).ok())
+        .ok_or_else(|| "expected a combatant given to a hook".into())
+}
+
+/// Parses the name of a condition, ignoring case.
+fn condition(name: &str) -> ScriptResult<ConditionKind> {
+    ConditionKind::variants()
+        .find(|kind| kind.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("unknown condition `{}`", name).into())
+}
@@ -0,0 +1,25 @@
+use h5t_core::{ConditionDuration, ConditionKind, LogEvent, Tracker, TrackerCommand};
+use super::Plugin;
+
+/// A plugin that knocks combatants unconscious when they drop to 0 hit points.
+#[derive(Debug)]
+pub struct Unconscious;
+
+impl Plugin for Unconscious {
+    fn name(&self) -> &'static str {
+        "unconscious"
+    }
+
+    fn on_event(&mut self, event: &LogEvent, tracker: &mut Tracker) {
+        let LogEvent::Downed { combatant } = event else { return };
+        // the combatant may share its name, so pick one that is actually down
+        let index = tracker.combatants
+            .iter()
+            .position(|c| c.name() == combatant && c.hit_points <= 0);
+        if let Some(index) = index {
+            let kind = ConditionKind::Unconscious;
+            let duration = ConditionDuration::Forever;
+            tracker.execute(TrackerCommand::AddCondition { index, kind, duration });
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use h5t_core::ConditionKind;
+use h5t_core::{ConditionKind, SpeedFactor};
 use std::{fmt::Display, hash::Hash};
 
 /// Marker type for enums that can be used with [`Tracker::multi_select_enum`].
-pub(crate) trait Selectable: Copy + Hash + Eq + Display {
+pub trait Selectable: Copy + Hash + Eq + Display {
     /// The number of variants in the enum.
     const N: usize;
 
@@ -33,3 +33,11 @@ impl Selectable for ConditionKind {
         ].into_iter()
     }
 }
+
+impl Selectable for SpeedFactor {
+    const N: usize = 13;
+
+    fn variants() -> impl Iterator<Item = Self> {
+        SpeedFactor::ALL.into_iter()
+    }
+}
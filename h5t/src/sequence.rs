@@ -0,0 +1,78 @@
+// -- Imports -- //
+
+use crate::keymap::{Bindings, Command, Key, Lookup};
+
+use crossterm::event::KeyCode;
+
+// -- Input Sequence -- //
+
+/// The result of typing a key on the main screen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Parsed {
+    /// The keys typed so far are a count or the start of a key sequence.
+    Pending,
+
+    /// The keys typed so far form a command, with the count typed before it (if any).
+    Command { command: Command, count: Option<usize> },
+
+    /// The keys typed so far aren't bound to anything, and were discarded.
+    Unbound,
+}
+
+/// Parses the keys typed on the main screen into commands, vim style.
+///
+/// Digits typed before a command are its count (`3 n` advances three turns), and commands can be
+/// bound to sequences of several keys (`g g` goes to the first page).
+#[derive(Clone, Debug, Default)]
+pub struct InputSequence {
+    /// The count typed so far.
+    count: Option<usize>,
+
+    /// The keys of the command typed so far.
+    keys: Vec<Key>,
+}
+
+impl InputSequence {
+    /// Adds a key to the sequence, returning the command it completes, if any.
+    pub fn push(&mut self, key: impl Into<Key>, bindings: &Bindings<Command>) -> Parsed {
+        let key = key.into();
+
+        // a digit is part of the count unless it is bound itself, and the count can't start
+        // with 0 (like in vim)
+        if let Some(digit) = self.count_digit(key)
+            && bindings.lookup(&[key]) == Lookup::Unbound
+        {
+            self.count = Some(self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            return Parsed::Pending;
+        }
+
+        self.keys.push(key);
+        match bindings.lookup(&self.keys) {
+            Lookup::Command(command) => {
+                let count = self.count.take();
+                self.keys.clear();
+                Parsed::Command { command, count }
+            },
+            Lookup::Prefix => Parsed::Pending,
+            Lookup::Unbound => {
+                self.count = None;
+                self.keys.clear();
+                Parsed::Unbound
+            },
+        }
+    }
+
+    /// Returns whether no count or keys have been typed since the last command.
+    pub fn is_empty(&self) -> bool {
+        self.count.is_none() && self.keys.is_empty()
+    }
+
+    /// Returns the value of the key if it would continue the count.
+    fn count_digit(&self, key: Key) -> Option<usize> {
+        let KeyCode::Char(c) = key.code else { return None };
+        if key.ctrl || key.alt || !self.keys.is_empty() || (c == '0' && self.count.is_none()) {
+            return None;
+        }
+        c.to_digit(10).map(|digit| digit as usize)
+    }
+}
@@ -0,0 +1,73 @@
+// -- Imports -- //
+
+use crate::config::Config;
+use crate::layout::{Panel, PanelLayout};
+use crate::macros::{self, Macro};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// -- Settings -- //
+
+/// Settings changed at runtime, which are saved so they persist between sessions.
+///
+/// Unlike the [`Config`](crate::config::Config), this file is written by h5t and isn't meant to be
+/// edited by hand. Settings override the values of the config.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The ratio of each panel, after resizing them.
+    pub panel_ratios: HashMap<Panel, u16>,
+
+    /// The macros recorded with `:record`.
+    pub macros: Vec<Macro>,
+
+    /// The file the settings were loaded from, and are saved to. Settings that weren't loaded
+    /// from a file, such as in tests, aren't saved.
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Settings {
+    /// The default path of the settings file, relative to the working directory.
+    pub const PATH: &'static str = "h5t-settings.json";
+
+    /// Load the settings from the given file.
+    ///
+    /// Settings are only a convenience, so if the file is missing or invalid, the default
+    /// settings are returned.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let settings = std::fs::File::open(path)
+            .ok()
+            .and_then(|file| serde_json::from_reader(std::io::BufReader::new(file)).ok())
+            .unwrap_or_default();
+        Self { path: Some(path.to_path_buf()), ..settings }
+    }
+
+    /// Save the settings to the file they were loaded from, if any.
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), self)?;
+        Ok(())
+    }
+
+    /// Applies the settings on top of the config: the panel ratios to its layout, and the
+    /// recorded macros to its macros.
+    pub fn apply(&self, config: &mut Config) {
+        for (panel, ratio) in &self.panel_ratios {
+            config.layout.set_ratio(*panel, *ratio);
+        }
+        for recorded in &self.macros {
+            macros::insert(&mut config.macros, recorded.clone());
+        }
+    }
+
+    /// Remembers the panel ratios of the given layout.
+    pub fn update(&mut self, layout: &PanelLayout) {
+        self.panel_ratios = layout.ratios().collect();
+    }
+}
@@ -1,15 +1,16 @@
 // -- Imports -- //
 
-use crate::state::AfterKey;
+use crate::keymap::{Keymap, PopupCommand};
+use crate::state::{AfterKey, Drawable, InputHandler};
 use crate::selectable::Selectable;
-use crate::ui::{LABELS, LabelSelection, Page};
+use crate::theme::Theme;
 use crate::widgets::popup::{popup_area, Multiselect, Select};
 
-use h5t_core::{Condition, ConditionDuration, ConditionKind};
+use h5t_core::{ConditionDuration, ConditionKind, ConditionSource, TrackerCommand};
 
 use ratatui::prelude::*;
 use ratatui::layout::Flex;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 
 use std::num::NonZeroU32;
 use std::collections::{HashMap, HashSet};
@@ -62,60 +63,135 @@ impl std::fmt::Display for Unit {
 // -- Apply Condition -- //
 
 /// State for applying conditions to combatants.
+///
+/// Any number of conditions can be toggled before choosing their duration, and they are all
+/// applied to every target in a single change.
 #[derive(Clone, Debug, Default)]
 pub struct ApplyCondition {
+    /// The combatants to apply the conditions to.
+    combatants: Vec<usize>,
+
+    /// The combatant applying the conditions, if any.
+    source: Option<usize>,
+
     /// The conditions to apply to combatants.
     conditions: HashSet<ConditionKind>,
 
     /// Indicates which form field is currently selected.
     selected: Field,
 
-    /// Value of the input field for the duration.
+    /// Value of the input field for the duration: the number of rounds or minutes, typed while
+    /// choosing the duration. Empty means 1.
     value: String,
 
     /// Duration of the conditions.
     unit: Unit,
+
+    /// The labels of the options, from the keyboard layout.
+    labels: &'static str,
 }
 
 impl ApplyCondition {
-    /// Draw the state to the given [`Frame`].
-    pub fn draw(&self, frame: &mut Frame) {
-        let area = frame.area();
+    /// Create a new [`ApplyCondition`] state for the given combatants, labelling the options with
+    /// the given labels.
+    pub fn new(combatants: Vec<usize>, labels: &'static str) -> Self {
+        Self { combatants, labels, ..Default::default() }
+    }
+
+    /// Record the combatant with the given index as the one applying the conditions.
+    pub fn with_source(mut self, source: usize) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Returns a copy of the state that applies the same conditions, for as long and from the
+    /// same source, to other combatants.
+    pub fn retarget(&self, combatants: Vec<usize>) -> Self {
+        Self { combatants, ..self.clone() }
+    }
+
+    /// Computes the areas of the conditions and duration popups within the frame area.
+    fn areas(area: Rect) -> [Rect; 2] {
         let area = popup_area(area, Flex::Center, Flex::End, (area.width, area.height / 3), 0);
-        let [conditions, duration] = Layout::horizontal([
+        Layout::horizontal([
                 Constraint::Percentage(50),
                 Constraint::Percentage(50),
             ])
             .flex(Flex::Center)
-            .areas(area);
-        frame.render_widget(Multiselect::new(
+            .areas(area)
+    }
+
+    /// Creates the popup for selecting conditions.
+    fn conditions_widget<'a>(&'a self, theme: &'a Theme) -> Multiselect<'a, ConditionKind> {
+        Multiselect::new(
             "Select condition(s)",
             &self.conditions,
             self.selected == Field::Conditions,
-        ), conditions);
-        frame.render_widget(Select::new(
-            "For how long?",
+            theme,
+        )
+            .with_labels(self.labels)
+    }
+
+    /// Returns the number of rounds or minutes typed in the duration field.
+    fn count(&self) -> NonZeroU32 {
+        self.value.parse().unwrap_or(NonZeroU32::MIN)
+    }
+
+    /// Returns the title of the duration popup, with the number typed so far.
+    fn duration_prompt(&self) -> String {
+        match self.value.is_empty() {
+            true => "For how long? (type a number)".to_string(),
+            false => format!("For how long? {}", self.value),
+        }
+    }
+
+    /// Creates the popup for selecting the duration, titled with the given prompt.
+    fn duration_widget<'a>(&'a self, prompt: &'a str, theme: &'a Theme) -> Select<'a, Unit> {
+        Select::new(
+            prompt,
             &self.unit,
             self.selected == Field::Duration,
-        ), duration);
+            theme,
+        )
+            .with_labels(self.labels)
     }
 
-    /// Handle a key event.
-    pub fn handle_key(&mut self, key: KeyEvent) -> AfterKey {
+    /// Apply the conditions to the tracker, in the order they are listed.
+    pub fn apply(&self, tracker: &mut h5t_core::Tracker) {
+        let duration = match self.unit {
+            Unit::Turn => ConditionDuration::UntilNextTurn,
+            Unit::Round => ConditionDuration::Rounds(self.count()),
+            Unit::Minute => ConditionDuration::Minutes(self.count()),
+            Unit::Forever => ConditionDuration::Forever,
+        };
+
+        let kinds = ConditionKind::variants()
+            .filter(|kind| self.conditions.contains(kind))
+            .collect::<Vec<_>>();
+        if kinds.is_empty() { return }
+
+        let indexes = self.combatants.clone();
+        let source = ConditionSource { combatant: self.source, cause: None };
+        tracker.execute(TrackerCommand::AddConditions { indexes, kinds, duration, source });
+    }
+}
+
+impl InputHandler for ApplyCondition {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
         // generate labels for all conditions
         if self.selected == Field::Conditions {
-            let label_to_option = LABELS
+            let label_to_option = self.labels
                 .chars()
                 .zip(ConditionKind::variants())
                 .collect::<HashMap<_, _>>();
 
-            match key.code {
-                KeyCode::Esc => return AfterKey::Exit,
-                KeyCode::Enter => {
+            match (keymap.popup.get(key), key.code) {
+                (Some(PopupCommand::Back), _) => return AfterKey::Cancel,
+                (Some(PopupCommand::Confirm), _) => {
                     self.selected = Field::Duration;
                     return AfterKey::Stay;
                 },
-                KeyCode::Char(label) => {
+                (None, KeyCode::Char(label)) => {
                     let selected = &mut self.conditions;
                     if let Some(option) = label_to_option.get(&label) {
                         if selected.contains(option) {
@@ -128,18 +204,25 @@ impl ApplyCondition {
                 _ => (),
             }
         } else {
-            let label_to_option = LABELS
+            let label_to_option = self.labels
                 .chars()
                 .zip(Unit::variants())
                 .collect::<HashMap<_, _>>();
 
-            match key.code {
-                KeyCode::Esc => {
+            match (keymap.popup.get(key), key.code) {
+                (Some(PopupCommand::Back), _) => {
                     self.selected = Field::Conditions;
                     return AfterKey::Stay;
                 },
-                KeyCode::Enter => return AfterKey::Exit,
-                KeyCode::Char(label) => {
+                (Some(PopupCommand::Confirm), _) => return AfterKey::Exit,
+                (Some(PopupCommand::Erase), _) => { self.value.pop(); },
+                // a count of rounds or minutes, which can't start with 0
+                (None, KeyCode::Char(digit @ '0'..='9'))
+                    if self.value.len() < 4 && !(self.value.is_empty() && digit == '0') =>
+                {
+                    self.value.push(digit);
+                },
+                (None, KeyCode::Char(label)) => {
                     let selected = &mut self.unit;
                     if let Some(option) = label_to_option.get(&label) {
                         *selected = *option;
@@ -152,44 +235,34 @@ impl ApplyCondition {
         AfterKey::Stay
     }
 
-    /// Apply the conditions to the tracker.
-    pub fn apply(&self, tracker: &mut h5t_core::Tracker) {
-        for condition in &self.conditions {
-            // TODO: get the duration from the input field
-            let duration = match self.unit {
-                Unit::Turn => ConditionDuration::UntilNextTurn,
-                Unit::Round => ConditionDuration::Rounds(NonZeroU32::new(1).unwrap()),
-                Unit::Minute => ConditionDuration::Minutes(NonZeroU32::new(1).unwrap()),
-                Unit::Forever => ConditionDuration::Forever,
-            };
-
-            // if the condition is already present, override its length if the new one is longer
-            // otherwise, add the condition
-            let existing_condition = tracker
-                .current_combatant_mut()
-                .conditions
-                .iter_mut()
-                .find(|c| c.kind == *condition);
-
-            if let Some(existing_condition) = existing_condition {
-                // override its length if the new one is longer
-                if let ConditionDuration::Forever = existing_condition.duration {
-                    continue;
-                }
-                if let ConditionDuration::Rounds(existing_duration) = existing_condition.duration {
-                    if let ConditionDuration::Rounds(new_duration) = duration {
-                        if new_duration > existing_duration {
-                            existing_condition.duration = duration;
-                        }
-                    }
-                }
-            } else {
-                // add new condition
-                tracker.current_combatant_mut().conditions.push(Condition {
-                    kind: *condition,
-                    duration,
-                });
+    /// Clicking a condition toggles it, and clicking a duration selects it.
+    fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect, theme: &Theme) -> AfterKey {
+        let MouseEventKind::Down(MouseButton::Left) = mouse.kind else { return AfterKey::Stay };
+        let position = Position::new(mouse.column, mouse.row);
+        let [conditions, duration] = Self::areas(area);
+
+        if let Some(option) = self.conditions_widget(theme).option_at(conditions, position) {
+            self.selected = Field::Conditions;
+            if !self.conditions.remove(&option) {
+                self.conditions.insert(option);
             }
+            return AfterKey::Stay;
         }
+        let prompt = self.duration_prompt();
+        if let Some(option) = self.duration_widget(&prompt, theme).option_at(duration, position) {
+            self.selected = Field::Duration;
+            self.unit = option;
+        }
+
+        AfterKey::Stay
+    }
+}
+
+impl Drawable for ApplyCondition {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let [conditions, duration] = Self::areas(frame.area());
+        frame.render_widget(self.conditions_widget(theme), conditions);
+        let prompt = self.duration_prompt();
+        frame.render_widget(self.duration_widget(&prompt, theme), duration);
     }
 }
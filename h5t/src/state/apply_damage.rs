@@ -1,19 +1,107 @@
-use crate::widgets::popup::Input as InputWidget;
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+use crate::widgets::popup::NumericInput;
+use crossterm::event::KeyEvent;
+use h5t_core::{DamageModifier, DamageType, Tracker, TrackerCommand};
 use ratatui::prelude::*;
-use super::AfterKey;
+use super::{AfterKey, Drawable, InputHandler};
+
+/// What damage did to one of its targets, to sum up damage dealt to several at once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DamageResult {
+    /// The index of the target.
+    pub index: usize,
+
+    /// How the target's resistances, vulnerabilities, and immunities changed the damage.
+    pub modifier: DamageModifier,
+
+    /// The damage the target's temporary hit points absorbed.
+    pub absorbed: i32,
+
+    /// The hit points the target lost, or regained if negative.
+    pub lost: i32,
+}
+
+impl DamageResult {
+    /// Returns a line describing the damage the target took, and how it is doing now, such as
+    /// `Goblin: 8 damage (3 to temp HP), 2/7 HP, bloodied`.
+    pub fn describe(&self, tracker: &Tracker) -> String {
+        let combatant = &tracker.combatants[self.index];
+        let mut notes = Vec::new();
+        if self.absorbed > 0 {
+            notes.push(format!("{} to temp HP", self.absorbed));
+        }
+        match self.modifier {
+            DamageModifier::Normal => (),
+            DamageModifier::Resistant => notes.push("resistant".to_string()),
+            DamageModifier::Vulnerable => notes.push("vulnerable".to_string()),
+            DamageModifier::Immune => notes.push("immune".to_string()),
+        }
+
+        let mut line = format!("{}: ", combatant.name());
+        if self.lost < 0 {
+            line.push_str(&format!("healed {}", -self.lost));
+        } else {
+            line.push_str(&format!("{} damage", self.absorbed + self.lost));
+        }
+        if !notes.is_empty() {
+            line.push_str(&format!(" ({})", notes.join(", ")));
+        }
+        line.push_str(&format!(", {}/{} HP", combatant.hit_points, combatant.max_hit_points()));
+
+        let status = if combatant.dead {
+            Some("dead")
+        } else if combatant.hit_points <= 0 {
+            Some("down")
+        } else if combatant.is_bloodied() {
+            Some("bloodied")
+        } else {
+            None
+        };
+        if let Some(status) = status {
+            line.push_str(&format!(", {}", status));
+        }
+        line
+    }
+}
+
+/// Deal damage to each target in turn, returning what it did to each of them.
+pub fn damage_targets(
+    tracker: &mut Tracker,
+    targets: &[usize],
+    amount: i32,
+    kind: Option<DamageType>,
+) -> Vec<DamageResult> {
+    targets
+        .iter()
+        .map(|&index| {
+            let before = &tracker.combatants[index];
+            let (hit_points, temporary) = (before.hit_points, before.temporary_hit_points);
+            let modifier = kind.map_or(DamageModifier::Normal, |kind| before.damage_modifier(kind));
+            tracker.execute(TrackerCommand::Damage { index, amount, kind });
+
+            let after = &tracker.combatants[index];
+            DamageResult {
+                index,
+                modifier,
+                absorbed: temporary - after.temporary_hit_points,
+                lost: hit_points - after.hit_points,
+            }
+        })
+        .collect()
+}
 
 /// State for applying damage to combatants.
-#[derive(Clone, Debug, Default)]
+///
+/// The damage can be a dice expression, which is rolled once for every target. Negative damage
+/// heals the targets.
+#[derive(Clone, Debug)]
 pub struct ApplyDamage {
     /// The combatant indices to apply damage to.
     combatants: Vec<usize>,
 
-    /// Color of the input field, which changes based on if the input is a valid number.
-    color: Color,
-
-    /// The value of the input field.
-    value: String,
+    /// The damage amount input field.
+    input: NumericInput,
 }
 
 impl ApplyDamage {
@@ -21,50 +109,34 @@ impl ApplyDamage {
     pub fn new(combatants: Vec<usize>) -> Self {
         Self {
             combatants,
-            color: Color::Reset,
-            value: String::new(),
+            // long enough for a few dice, e.g. `8d6 + 2d4 + 5`
+            input: NumericInput::new("Damage amount", 14).with_range(-9999, 9999).with_dice(),
         }
     }
 
-    /// Draw the state to the given [`Frame`].
-    pub fn draw(&self, frame: &mut Frame) {
-        frame.render_widget(InputWidget::new(
-            self.color,
-            "Damage amount",
-            &self.value,
-            4, // damage is usually 1-2 digits
-        ), frame.area());
+    /// Returns a copy of the state aimed at other combatants. Dice in the damage are rolled again
+    /// when it is applied.
+    pub fn retarget(&self, combatants: Vec<usize>) -> Self {
+        Self { combatants, ..self.clone() }
     }
 
-    /// Handle a key event.
-    pub fn handle_key(&mut self, key: KeyEvent) -> AfterKey {
-        match key.code {
-            KeyCode::Enter => return AfterKey::Exit,
-            KeyCode::Char(c) => {
-                if self.value.len() >= 4 {
-                    self.color = Color::Yellow;
-                    return AfterKey::Stay;
-                }
-                self.value.push(c);
-            },
-            KeyCode::Backspace => { self.value.pop(); },
-            _ => (),
-        }
-
-        let valid = self.value.trim().parse::<i32>().is_ok();
-        self.color = if valid { Color::Reset } else { Color::Red };
+    /// Apply the damage to the tracker, returning what it did to each target.
+    pub fn apply(&self, tracker: &mut Tracker) -> Vec<DamageResult> {
+        let value = self.input.value().unwrap_or(0);
+		if value == 0 { return Vec::new() }
+		
+        damage_targets(tracker, &self.combatants, value, None)
+    }
+}
 
-        AfterKey::Stay
+impl InputHandler for ApplyDamage {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        self.input.handle_key(key, keymap)
     }
+}
 
-    /// Apply the damage to the tracker.
-    pub fn apply(&self, tracker: &mut h5t_core::Tracker) {
-        let value = self.value.trim().parse::<i32>().unwrap_or(0);
-		if value == 0 { return }
-		
-        for combatant_idx in &self.combatants {
-            let combatant = &mut tracker.combatants[*combatant_idx];
-            combatant.damage(value);
-        }
+impl Drawable for ApplyDamage {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        self.input.draw(frame, theme);
     }
 }
@@ -0,0 +1,57 @@
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+use crate::widgets::popup::NumericInput;
+use crossterm::event::KeyEvent;
+use h5t_core::{Tracker, TrackerCommand};
+use ratatui::prelude::*;
+use super::{AfterKey, Drawable, InputHandler};
+
+/// State for healing combatants, the counterpart of [`ApplyDamage`](super::ApplyDamage).
+///
+/// The healing can be a dice expression, which is rolled once for every target.
+#[derive(Clone, Debug)]
+pub struct ApplyHealing {
+    /// The combatant indices to heal.
+    combatants: Vec<usize>,
+
+    /// The healing amount input field.
+    input: NumericInput,
+}
+
+impl ApplyHealing {
+    /// Create an [`ApplyHealing`] state with the given combatants.
+    pub fn new(combatants: Vec<usize>) -> Self {
+        Self {
+            combatants,
+            // long enough for a few dice, e.g. `2d8 + 1d4 + 3`
+            input: NumericInput::new("Healing amount", 14).with_range(0, 9999).with_dice(),
+        }
+    }
+
+    /// Returns a copy of the state that heals other combatants by the same amount or dice.
+    pub fn retarget(&self, combatants: Vec<usize>) -> Self {
+        Self { combatants, ..self.clone() }
+    }
+
+    /// Heal the combatants, up to their maximum hit points.
+    pub fn apply(&self, tracker: &mut Tracker) {
+        let amount = self.input.value().unwrap_or(0);
+        if amount == 0 { return }
+
+        for &index in &self.combatants {
+            tracker.execute(TrackerCommand::Heal { index, amount });
+        }
+    }
+}
+
+impl InputHandler for ApplyHealing {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        self.input.handle_key(key, keymap)
+    }
+}
+
+impl Drawable for ApplyHealing {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        self.input.draw(frame, theme);
+    }
+}
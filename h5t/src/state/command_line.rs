@@ -0,0 +1,651 @@
+// -- Imports -- //
+
+use crate::keymap::{Key, Keymap, PopupCommand};
+use crate::state::{AfterKey, Drawable, InputHandler};
+use crate::theme::Theme;
+use crate::widgets::TextInput;
+
+use h5t_core::{
+    AbilityKind, ConditionKind, ConditionSource, DamageType, Effect, InitiativeModifier, MobAttack,
+    Outcome, Side, Trigger, TurnOrder,
+};
+
+use ratatui::prelude::*;
+use ratatui::widgets::*;
+use crossterm::event::KeyEvent;
+
+use std::path::PathBuf;
+
+// -- Commands -- //
+
+/// A command typed into the command line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaletteCommand {
+    /// `:damage <amount> [type]` Select targets and damage them.
+    Damage { amount: i32, kind: Option<DamageType> },
+
+    /// `:add <monster> [xN]` Add one or more copies of a monster from the compendium.
+    Add { monster: String, count: usize },
+
+    /// `:save <file>` Save the encounter to a file.
+    Save(PathBuf),
+
+    /// `:load <file>` Replace the encounter with one loaded from a file.
+    Load(PathBuf),
+
+    /// `:side <ally|neutral|enemy>` Select targets and move them to a side.
+    Side(Side),
+
+    /// `:init <value>` Set the initiative roll of the cursor / current turn's combatant.
+    Initiative(i32),
+
+    /// `:init roll` Select combatants and roll their initiative, with their initiative modifiers.
+    RollInitiative,
+
+    /// `:init mod [modifier...]` Replace the initiative modifiers of the cursor / current turn's
+    /// combatant: a bonus such as `+5`, `half` for half the proficiency bonus, and `adv` for
+    /// advantage. Without modifiers, they are cleared.
+    InitiativeModifiers(Vec<InitiativeModifier>),
+
+    /// `:order <individual | sides <side...> | popcorn | speed>` Change how turns pass: in
+    /// initiative order, side by side with the sides going in the given order, to whoever the
+    /// current combatant picks, or by initiative rolled again each round with speed factors.
+    TurnOrder(TurnOrder),
+
+    /// `:order sides` Switch to side initiative, rolling a d20 for the allies and the enemies to
+    /// see which side goes first.
+    RollSideInitiative,
+
+    /// `:combine <on|off>` Have copies of the same monster next to each other in the turn order
+    /// take their turns together, or one by one.
+    CombineTurns(bool),
+
+    /// `:group <name>` Select combatants and save them as a named group.
+    Group(String),
+
+    /// `:zone [name]` Select combatants and move them to a zone, or out of every zone without a
+    /// name.
+    Zone(Option<String>),
+
+    /// `:time <[-]duration>` Add in-game time to the clock, such as `10m` or `1h30m`, or take it
+    /// back with a minus sign.
+    PassTime(i64),
+
+    /// `:xp` Show the XP earned for the defeated enemies and each player character's share, and
+    /// offer to record the award in the combat log.
+    Xp,
+
+    /// `:stats` Show every combatant's damage dealt and taken, healing done, and kills over the
+    /// encounter, from the combat log.
+    Stats,
+
+    /// `:loot [hoard]` Roll the coins the defeated enemies carry on the individual treasure
+    /// tables, or a treasure hoard for the highest challenge rating among them.
+    Loot { hoard: bool },
+
+    /// `:distance` Select combatants and show their distance on the battle grid from the cursor /
+    /// current turn's combatant.
+    Distance,
+
+    /// `:effect [start|end] <save <ability> <dc> | damage <amount> [type] | end> [condition]
+    /// [from <cause>]` Select targets and attach an effect to them, triggering at the start or end
+    /// (the default) of their turns. The current turn's combatant is recorded as its source.
+    Effect(Effect),
+
+    /// `:regen <on|off>` Select targets and restart or stop their regeneration on their next turn.
+    Regenerate(bool),
+
+    /// `:grapple [restrain] [dc]` Select targets grappled (or restrained) by the cursor / current
+    /// turn's combatant, with the DC to escape.
+    Grapple { restrains: bool, escape_dc: Option<u32> },
+
+    /// `:release` Select combatants and end every grapple they are part of, on either side.
+    Release,
+
+    /// `:rest short [hit dice]` Select combatants and have them finish a short rest, each
+    /// spending up to the given number of hit dice to heal.
+    ShortRest { hit_dice: u32 },
+
+    /// `:rest long` Select combatants and have them finish a long rest, after confirming.
+    LongRest,
+
+    /// `:summon [monster] [xN]` Add one or more copies of a monster summoned by the cursor /
+    /// current turn's combatant, on its side, or select combatants it summoned without a monster.
+    Summon { monster: Option<String>, count: usize },
+
+    /// `:concentrate <on|off>` Select combatants and start or stop their concentration.
+    Concentrate(bool),
+
+    /// `:exhaustion <level>` Select combatants and set their exhaustion level, 0 to end it.
+    Exhaustion(u32),
+
+    /// `:surprise <on|off>` Select combatants and surprise them, or stop surprising them.
+    Surprise(bool),
+
+    /// `:minion <on|off>` Select combatants and flag or unflag them as minions.
+    Minion(bool),
+
+    /// `:leader <on|off>` Select combatants and flag or unflag them as leaders of their sides.
+    Leader(bool),
+
+    /// `:flee <on|off>` Select combatants and have them flee the fight, or stop fleeing.
+    Flee(bool),
+
+    /// `:mob <attackers> <attack bonus> <damage> [type]` Select targets attacked by a mob of
+    /// identical attackers, and confirm the damage of the attackers that hit each one.
+    Mob { attack: MobAttack, kind: Option<DamageType> },
+
+    /// `:record <key> <name>` Start recording the keys typed next as a macro, played with the
+    /// given key, until recording is stopped.
+    Record { key: Key, name: String },
+
+    /// `:macro <name>` Play the macro with the given name.
+    Macro(String),
+
+    /// `:keys <file>` Write every key binding to a cheat sheet, in Markdown if the file ends with
+    /// `.md`, and in plain text otherwise.
+    Keys(PathBuf),
+}
+
+/// The name of every command, used for completion.
+const COMMAND_NAMES: [&str; 31] = [
+    "damage", "add", "save", "load", "side", "init", "order", "combine", "group", "zone",
+    "distance", "time", "xp", "loot", "effect", "regen", "grapple", "release", "rest", "summon", "concentrate",
+    "exhaustion", "surprise", "minion", "leader", "flee", "mob", "record", "macro", "keys", "stats",
+];
+
+/// The outcome of every effect, used for completion. `end` is also when effects trigger by
+/// default, and `start` is the other time they can trigger.
+const OUTCOME_NAMES: [&str; 3] = ["save", "damage", "end"];
+
+/// How to write the `effect` command.
+const EFFECT_USAGE: &str = "usage: effect [start|end] <save <ability> <dc> | damage <amount> \
+    [type] | end> [condition] [from <cause>]";
+
+/// The name of every side, used for completion.
+const SIDE_NAMES: [&str; 3] = ["ally", "neutral", "enemy"];
+
+impl PaletteCommand {
+    /// Parse a command from the text typed into the command line (without the leading `:`).
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        let Some(name) = words.next() else { return Err("no command given".to_string()) };
+        let args = words.collect::<Vec<_>>();
+
+        match name {
+            "damage" | "d" => {
+                let (amount, kind) = match args.as_slice() {
+                    [amount] => (amount, None),
+                    [amount, kind] => (amount, Some(kind.parse()?)),
+                    _ => return Err("usage: damage <amount> [type]".to_string()),
+                };
+                let amount = amount
+                    .parse()
+                    .map_err(|_| format!("invalid damage amount `{}`", amount))?;
+                Ok(Self::Damage { amount, kind })
+            },
+            "add" | "a" => match Self::parse_monster(&args)? {
+                (Some(monster), count) => Ok(Self::Add { monster, count }),
+                (None, _) => Err("usage: add <monster> [xN]".to_string()),
+            },
+            "summon" => match Self::parse_monster(&args)? {
+                (None, count) if count != 1 => Err("usage: summon [monster] [xN]".to_string()),
+                (monster, count) => Ok(Self::Summon { monster, count }),
+            },
+            "concentrate" => match args.as_slice() {
+                ["on"] => Ok(Self::Concentrate(true)),
+                ["off"] => Ok(Self::Concentrate(false)),
+                _ => Err("usage: concentrate <on|off>".to_string()),
+            },
+            "exhaustion" => match args.as_slice() {
+                [level] => level
+                    .parse()
+                    .map(Self::Exhaustion)
+                    .map_err(|_| format!("invalid exhaustion level `{}`", level)),
+                _ => Err("usage: exhaustion <level>".to_string()),
+            },
+            "surprise" => match args.as_slice() {
+                ["on"] => Ok(Self::Surprise(true)),
+                ["off"] => Ok(Self::Surprise(false)),
+                _ => Err("usage: surprise <on|off>".to_string()),
+            },
+            "minion" => match args.as_slice() {
+                ["on"] => Ok(Self::Minion(true)),
+                ["off"] => Ok(Self::Minion(false)),
+                _ => Err("usage: minion <on|off>".to_string()),
+            },
+            "leader" => match args.as_slice() {
+                ["on"] => Ok(Self::Leader(true)),
+                ["off"] => Ok(Self::Leader(false)),
+                _ => Err("usage: leader <on|off>".to_string()),
+            },
+            "flee" => match args.as_slice() {
+                ["on"] => Ok(Self::Flee(true)),
+                ["off"] => Ok(Self::Flee(false)),
+                _ => Err("usage: flee <on|off>".to_string()),
+            },
+            "mob" => {
+                let (attackers, attack_bonus, damage, kind) = match args.as_slice() {
+                    [attackers, bonus, damage] => (attackers, bonus, damage, None),
+                    [attackers, bonus, damage, kind] => {
+                        (attackers, bonus, damage, Some(kind.parse()?))
+                    },
+                    _ => {
+                        let usage = "usage: mob <attackers> <attack bonus> <damage> [type]";
+                        return Err(usage.to_string());
+                    },
+                };
+                let attack = MobAttack {
+                    attackers: attackers
+                        .parse()
+                        .map_err(|_| format!("invalid number of attackers `{}`", attackers))?,
+                    attack_bonus: attack_bonus
+                        .parse()
+                        .map_err(|_| format!("invalid attack bonus `{}`", attack_bonus))?,
+                    damage: damage.parse()?,
+                };
+                Ok(Self::Mob { attack, kind })
+            },
+            "save" | "w" => match args.as_slice() {
+                [path] => Ok(Self::Save(path.into())),
+                _ => Err("usage: save <file>".to_string()),
+            },
+            "load" | "e" => match args.as_slice() {
+                [path] => Ok(Self::Load(path.into())),
+                _ => Err("usage: load <file>".to_string()),
+            },
+            "side" => match args.as_slice() {
+                [side] => Ok(Self::Side(side.parse()?)),
+                _ => Err("usage: side <ally|neutral|enemy>".to_string()),
+            },
+            "order" => match args.as_slice() {
+                ["individual"] => Ok(Self::TurnOrder(TurnOrder::Individual)),
+                ["popcorn"] => Ok(Self::TurnOrder(TurnOrder::Popcorn)),
+                ["speed"] => Ok(Self::TurnOrder(TurnOrder::SpeedFactor)),
+                ["sides"] => Ok(Self::RollSideInitiative),
+                ["sides", sides @ ..] => sides
+                    .iter()
+                    .map(|side| side.parse())
+                    .collect::<Result<_, _>>()
+                    .map(|sides| Self::TurnOrder(TurnOrder::Sides(sides))),
+                _ => {
+                    let usage = "usage: order <individual | sides [side...] | popcorn | speed>";
+                    Err(usage.to_string())
+                },
+            },
+            "combine" => match args.as_slice() {
+                ["on"] => Ok(Self::CombineTurns(true)),
+                ["off"] => Ok(Self::CombineTurns(false)),
+                _ => Err("usage: combine <on|off>".to_string()),
+            },
+            "init" => match args.as_slice() {
+                ["roll"] => Ok(Self::RollInitiative),
+                ["mod", modifiers @ ..] => modifiers
+                    .iter()
+                    .map(|modifier| modifier.parse())
+                    .collect::<Result<_, _>>()
+                    .map(Self::InitiativeModifiers),
+                [value] => value
+                    .parse()
+                    .map(Self::Initiative)
+                    .map_err(|_| format!("invalid initiative `{}`", value)),
+                _ => Err("usage: init <value | roll | mod [modifier...]>".to_string()),
+            },
+            "group" | "g" if !args.is_empty() => Ok(Self::Group(args.join(" "))),
+            "group" | "g" => Err("usage: group <name>".to_string()),
+            "zone" => Ok(Self::Zone((!args.is_empty()).then(|| args.join(" ")))),
+            "xp" if args.is_empty() => Ok(Self::Xp),
+            "xp" => Err("usage: xp".to_string()),
+            "stats" if args.is_empty() => Ok(Self::Stats),
+            "stats" => Err("usage: stats".to_string()),
+            "loot" => match args.as_slice() {
+                [] => Ok(Self::Loot { hoard: false }),
+                ["hoard"] => Ok(Self::Loot { hoard: true }),
+                _ => Err("usage: loot [hoard]".to_string()),
+            },
+            "distance" if args.is_empty() => Ok(Self::Distance),
+            "distance" => Err("usage: distance".to_string()),
+            "time" => match args.as_slice() {
+                [duration] => Self::parse_duration(duration).map(Self::PassTime),
+                _ => Err("usage: time <[-]duration>".to_string()),
+            },
+            "effect" => Self::parse_effect(&args).map(Self::Effect),
+            "regen" => match args.as_slice() {
+                ["on"] => Ok(Self::Regenerate(true)),
+                ["off"] => Ok(Self::Regenerate(false)),
+                _ => Err("usage: regen <on|off>".to_string()),
+            },
+            "grapple" => {
+                let (restrains, args) = match args.as_slice() {
+                    ["restrain", rest @ ..] => (true, rest),
+                    rest => (false, rest),
+                };
+                let escape_dc = match args {
+                    [] => None,
+                    [dc] => Some(dc.parse().map_err(|_| format!("invalid escape DC `{}`", dc))?),
+                    _ => return Err("usage: grapple [restrain] [dc]".to_string()),
+                };
+                Ok(Self::Grapple { restrains, escape_dc })
+            },
+            "rest" => match args.as_slice() {
+                ["long"] => Ok(Self::LongRest),
+                ["short"] => Ok(Self::ShortRest { hit_dice: 0 }),
+                ["short", count] => count
+                    .parse()
+                    .map(|hit_dice| Self::ShortRest { hit_dice })
+                    .map_err(|_| format!("invalid hit dice count `{}`", count)),
+                _ => Err("usage: rest <short [hit dice] | long>".to_string()),
+            },
+            "release" if args.is_empty() => Ok(Self::Release),
+            "release" => Err("usage: release".to_string()),
+            "record" => match args.as_slice() {
+                [key, name @ ..] if !name.is_empty() => {
+                    Ok(Self::Record { key: key.parse()?, name: name.join(" ") })
+                },
+                _ => Err("usage: record <key> <name>".to_string()),
+            },
+            "macro" if !args.is_empty() => Ok(Self::Macro(args.join(" "))),
+            "macro" => Err("usage: macro <name>".to_string()),
+            "keys" => match args.as_slice() {
+                [path] => Ok(Self::Keys(path.into())),
+                _ => Err("usage: keys <file>".to_string()),
+            },
+            _ => Err(format!("unknown command `{}`", name)),
+        }
+    }
+}
+
+impl PaletteCommand {
+    /// Parse a monster name and how many copies of it to add, written as `<monster> [xN]`. The
+    /// name is [`None`] if no words are left before the count.
+    fn parse_monster(args: &[&str]) -> Result<(Option<String>, usize), String> {
+        // the monster name can contain spaces, so only the last word can be the count, and a
+        // lone word is only a count if it is a number (`summon x2`, but `add xorn`)
+        let is_count = |last: &str, rest: &[&str]| {
+            last.starts_with('x') && (!rest.is_empty() || last[1..].parse::<usize>().is_ok())
+        };
+        let (count, name) = match args.split_last() {
+            Some((last, rest)) if is_count(last, rest) => {
+                let count = last[1..]
+                    .parse()
+                    .map_err(|_| format!("invalid monster count `{}`", last))?;
+                (count, rest)
+            },
+            _ => (1, args),
+        };
+        Ok(((!name.is_empty()).then(|| name.join(" ")), count))
+    }
+
+    /// Parse a duration written as numbers followed by their unit (`s`, `m`, `h`, or `d`), such
+    /// as `1h30m`, into a number of seconds, negative with a leading minus sign.
+    fn parse_duration(arg: &str) -> Result<i64, String> {
+        let invalid = || format!("invalid duration `{}`, such as `10m`, `1h30m` or `-6s`", arg);
+        let (sign, rest) = match arg.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, arg.strip_prefix('+').unwrap_or(arg)),
+        };
+
+        let mut seconds = 0i64;
+        let mut number = String::new();
+        for c in rest.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let unit = match c.to_ascii_lowercase() {
+                's' => 1,
+                'm' => 60,
+                'h' => 3600,
+                'd' => 86400,
+                _ => return Err(invalid()),
+            };
+            seconds = number
+                .parse::<i64>()
+                .ok()
+                .and_then(|count| count.checked_mul(unit))
+                .and_then(|part| part.checked_add(seconds))
+                .ok_or_else(invalid)?;
+            number.clear();
+        }
+        if rest.is_empty() || !number.is_empty() {
+            return Err(invalid());
+        }
+        Ok(sign * seconds)
+    }
+
+    /// Parse the arguments of the `effect` command. When the effect triggers, if given, is the
+    /// first word, and the condition it causes, if any, is the last word before the cause, which
+    /// is everything after `from`.
+    fn parse_effect(args: &[&str]) -> Result<Effect, String> {
+        let (args, cause) = match args.iter().position(|arg| *arg == "from") {
+            Some(from) if from + 1 < args.len() => {
+                (&args[..from], Some(args[from + 1..].join(" ")))
+            },
+            Some(_) => return Err(EFFECT_USAGE.to_string()),
+            None => (args, None),
+        };
+        let (trigger, args) = match args {
+            ["start", rest @ ..] => (Trigger::StartOfTurn, rest),
+            // `end` is also an outcome, so it's only the trigger if an outcome follows
+            ["end", rest @ ..] if rest.first().is_some_and(|next| OUTCOME_NAMES.contains(next)) => {
+                (Trigger::EndOfTurn, rest)
+            },
+            _ => (Trigger::EndOfTurn, args),
+        };
+        let (condition, args) = match args.split_last() {
+            Some((last, rest)) if last.parse::<ConditionKind>().is_ok() => {
+                (last.parse().ok(), rest)
+            },
+            _ => (None, args),
+        };
+
+        let outcome = match args {
+            ["save", ability, dc] => Outcome::Save {
+                ability: ability.parse::<AbilityKind>()?,
+                dc: dc.parse().map_err(|_| format!("invalid save DC `{}`", dc))?,
+            },
+            ["damage", amount] => Outcome::Damage { amount: amount.parse()?, kind: None },
+            ["damage", amount, kind] => Outcome::Damage {
+                amount: amount.parse()?,
+                kind: Some(kind.parse()?),
+            },
+            ["end"] => Outcome::End,
+            _ => return Err(EFFECT_USAGE.to_string()),
+        };
+
+        let source = ConditionSource { combatant: None, cause };
+        Ok(Effect { trigger, outcome, condition, source })
+    }
+}
+
+// -- Command Line -- //
+
+/// State for typing a command into the command line at the bottom of the screen.
+#[derive(Clone, Debug, Default)]
+pub struct CommandLine {
+    /// The text typed so far, without the leading `:`.
+    input: TextInput,
+
+    /// The names of the monsters that can be added, used for completion.
+    monsters: Vec<String>,
+
+    /// The names of the macros that can be played, used for completion.
+    macros: Vec<String>,
+
+    /// Completions for the word being typed, shown after pressing the complete key.
+    completions: Vec<String>,
+
+    /// An error from parsing or running the last command.
+    error: Option<String>,
+
+    /// The parsed command, once the user confirms a valid one.
+    command: Option<PaletteCommand>,
+}
+
+impl CommandLine {
+    /// Create an empty [`CommandLine`], completing monster names from the given list.
+    pub fn new(monsters: Vec<String>) -> Self {
+        Self { monsters, ..Default::default() }
+    }
+
+    /// Create a [`CommandLine`] that shows an error from running a command, with the command's
+    /// text still typed in so it can be fixed.
+    pub fn with_error(monsters: Vec<String>, input: String, error: String) -> Self {
+        Self { input: TextInput::new(input), monsters, error: Some(error), ..Default::default() }
+    }
+
+    /// Complete macro names from the given list.
+    pub fn with_macros(mut self, macros: Vec<String>) -> Self {
+        self.macros = macros;
+        self
+    }
+
+    /// Allow recalling the given previous commands, from oldest to newest.
+    pub fn with_history(mut self, history: Vec<String>) -> Self {
+        self.input = self.input.with_history(history);
+        self
+    }
+
+    /// Returns the text typed into the command line.
+    pub fn input(&self) -> &str {
+        self.input.value()
+    }
+
+    /// Takes the command that was confirmed, if any.
+    pub fn into_command(self) -> Option<PaletteCommand> {
+        self.command
+    }
+
+    /// Returns the possible values of the word being typed, given the words before it.
+    fn candidates(&self, previous: &[&str]) -> Vec<String> {
+        match previous {
+            [] => COMMAND_NAMES.iter().map(|s| s.to_string()).collect(),
+            ["damage" | "d", _] | ["mob", _, _, _] => {
+                DamageType::ALL.iter().map(|t| t.to_string()).collect()
+            },
+            ["add" | "a" | "summon", ..] => self.monsters.clone(),
+            ["macro", ..] => self.macros.clone(),
+            ["concentrate" | "surprise" | "minion" | "leader" | "flee" | "combine"] => {
+                vec!["on".to_string(), "off".to_string()]
+            },
+            ["side"] | ["order", "sides", ..] => SIDE_NAMES.iter().map(|s| s.to_string()).collect(),
+            ["order"] => ["individual", "sides", "popcorn", "speed"].map(String::from).to_vec(),
+            ["effect"] => ["start"].iter().chain(&OUTCOME_NAMES).map(|s| s.to_string()).collect(),
+            ["regen"] => vec!["on".to_string(), "off".to_string()],
+            ["grapple"] => vec!["restrain".to_string()],
+            ["loot"] => vec!["hoard".to_string()],
+            ["rest"] => vec!["short".to_string(), "long".to_string()],
+            ["init"] => vec!["roll".to_string(), "mod".to_string()],
+            ["init", "mod", ..] => vec!["half".to_string(), "adv".to_string()],
+            ["effect", "start" | "end"] => OUTCOME_NAMES.iter().map(|s| s.to_string()).collect(),
+            ["effect", .., "save"] => {
+                AbilityKind::ALL.iter().map(|a| a.name().to_string()).collect()
+            },
+            ["effect", .., "damage", _] => DamageType::ALL.iter().map(|t| t.to_string()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Completes the word being typed as far as possible, and lists the remaining options.
+    ///
+    /// Monster and macro names can contain spaces, so everything after `add`, `summon`, or `macro`
+    /// is completed as one word.
+    fn complete(&mut self) {
+        let input = self.input.value();
+        let (previous, word) = match input.split_once(' ') {
+            Some((name @ ("add" | "a" | "summon" | "macro"), rest)) => {
+                (vec![name], rest.trim_start())
+            },
+            _ => {
+                let mut words = input.split(' ').collect::<Vec<_>>();
+                let word = words.pop().unwrap_or_default();
+                (words.into_iter().filter(|w| !w.is_empty()).collect(), word)
+            },
+        };
+
+        let matches = self.candidates(&previous)
+            .into_iter()
+            .filter(|c| c.get(..word.len()).is_some_and(|s| s.eq_ignore_ascii_case(word)))
+            .collect::<Vec<_>>();
+
+        let Some(first) = matches.first() else {
+            self.completions.clear();
+            return;
+        };
+
+        // longest prefix shared by every match
+        let common = matches.iter().fold(first.as_str(), |common, m| {
+            let len = common
+                .char_indices()
+                .zip(m.chars())
+                .take_while(|((_, a), b)| a.eq_ignore_ascii_case(b))
+                .last()
+                .map(|((i, a), _)| i + a.len_utf8())
+                .unwrap_or(0);
+            &common[..len]
+        });
+
+        let start = input.len() - word.len();
+        let mut completed = input[..start].to_string();
+        completed.push_str(common);
+        if matches.len() == 1 {
+            completed.push(' ');
+        }
+        self.input.set_value(completed);
+        self.completions = if matches.len() == 1 { Vec::new() } else { matches };
+    }
+}
+
+impl InputHandler for CommandLine {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        match keymap.popup.get(key) {
+            Some(PopupCommand::Confirm) => match PaletteCommand::parse(self.input.value()) {
+                Ok(command) => {
+                    self.command = Some(command);
+                    return AfterKey::Exit;
+                },
+                Err(error) => self.error = Some(error),
+            },
+            Some(PopupCommand::Back) => return AfterKey::Cancel,
+            // erasing past the start closes the command line, like in vim
+            Some(PopupCommand::Erase) if self.input.value().is_empty() => return AfterKey::Cancel,
+            Some(PopupCommand::Complete) => self.complete(),
+            _ => if self.input.handle_key(key, keymap) {
+                self.error = None;
+                self.completions.clear();
+            },
+        }
+
+        AfterKey::Stay
+    }
+
+    /// Insert pasted text at the cursor.
+    fn handle_paste(&mut self, text: &str) {
+        self.input.insert_str(text);
+        self.error = None;
+        self.completions.clear();
+    }
+}
+
+impl Drawable for CommandLine {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let area = frame.area();
+        let [hint, line] = Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+            .flex(ratatui::layout::Flex::End)
+            .areas(area);
+
+        let hint_text = if let Some(error) = &self.error {
+            Line::styled(error.as_str(), Color::Red)
+        } else if !self.completions.is_empty() {
+            Line::styled(self.completions.join("  "), theme.inactive)
+        } else {
+            Line::default()
+        };
+        if hint_text.width() > 0 {
+            frame.render_widget(Clear, hint);
+            frame.render_widget(hint_text, hint);
+        }
+
+        self.input.render(frame, line, ":", theme);
+    }
+}
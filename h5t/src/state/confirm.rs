@@ -0,0 +1,217 @@
+// -- Imports -- //
+
+use crate::keymap::{Keymap, PopupCommand};
+use crate::state::{AfterKey, Drawable, InputHandler};
+use crate::theme::Theme;
+use crate::widgets::Hint;
+use crate::widgets::popup::{dialog, Dialog};
+
+use h5t_core::{ConditionKind, DamageType, DiceExpression};
+
+use ratatui::prelude::*;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+
+use std::path::PathBuf;
+
+// -- Confirm -- //
+
+/// An operation waiting for confirmation, such as a destructive one, or resolving an effect.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfirmAction {
+    /// Remove the combatant with the given index.
+    RemoveCombatant(usize),
+
+    /// Mark the combatant with the given index dead.
+    Kill(usize),
+
+    /// Quit, discarding unsaved changes.
+    Quit,
+
+    /// Save the encounter, overwriting an existing file.
+    Overwrite(PathBuf),
+
+    /// Write the key bindings cheat sheet, overwriting an existing file.
+    OverwriteKeys(PathBuf),
+
+    /// End an effect on a combatant, once its saving throw succeeded.
+    EndEffect { index: usize, effect: usize },
+
+    /// Roll damage from an effect, and deal it to a combatant.
+    RollDamage { index: usize, amount: DiceExpression, kind: Option<DamageType> },
+
+    /// Clear the conditions that came with a condition that ended on a combatant.
+    ClearConditions { index: usize, conditions: Vec<ConditionKind> },
+
+    /// End the conditions on the combatants with the given indexes, once their source dropped.
+    EndConditions(Vec<(usize, ConditionKind)>),
+
+    /// Remove the summons with the given indexes, in turn order, once their summoner is gone.
+    RemoveSummons(Vec<usize>),
+
+    /// Roll a monster's morale saving throw, and have it flee if it fails.
+    MoraleSave(usize),
+
+    /// Have the combatants with the given indexes finish a long rest.
+    LongRest(Vec<usize>),
+
+    /// Record the XP earned for the defeated enemies in the combat log.
+    AwardXp,
+
+    /// Nothing, the dialog only reported an error.
+    Dismiss,
+}
+
+impl ConfirmAction {
+    /// Shifts the indexes of the combatants the action applies to once the combatant with the
+    /// given index is removed, as [`Tracker::remove`](h5t_core::Tracker::remove) does with the
+    /// indexes it keeps. Returns `false` if the action has nothing left to apply to.
+    pub fn forget(&mut self, removed: usize) -> bool {
+        /// Shifts an index after the removed combatant. Returns `false` if it was the removed one.
+        fn shift(index: &mut usize, removed: usize) -> bool {
+            if *index == removed {
+                return false;
+            }
+            if *index > removed {
+                *index -= 1;
+            }
+            true
+        }
+
+        match self {
+            Self::RemoveCombatant(index)
+            | Self::Kill(index)
+            | Self::EndEffect { index, .. }
+            | Self::RollDamage { index, .. }
+            | Self::ClearConditions { index, .. }
+            | Self::MoraleSave(index) => shift(index, removed),
+            Self::EndConditions(conditions) => {
+                conditions.retain_mut(|(index, _)| shift(index, removed));
+                !conditions.is_empty()
+            },
+            Self::RemoveSummons(indexes) | Self::LongRest(indexes) => {
+                indexes.retain_mut(|index| shift(index, removed));
+                !indexes.is_empty()
+            },
+            Self::Quit
+            | Self::Overwrite(_)
+            | Self::OverwriteKeys(_)
+            | Self::AwardXp
+            | Self::Dismiss => true,
+        }
+    }
+}
+
+/// The buttons of a confirmation dialog, from left to right.
+const BUTTONS: [&str; 2] = ["Cancel", "Confirm"];
+
+/// The only button of an alert, which closes it.
+const ALERT_BUTTONS: [&str; 1] = ["OK"];
+
+/// State for a modal dialog that asks for confirmation before a destructive operation.
+///
+/// Confirming exits the state, cancelling cancels it. The focus starts on the cancel button, and
+/// moves with the arrow keys or `Tab`. Alerts only have an OK button, and close either way.
+#[derive(Clone, Debug)]
+pub struct Confirm {
+    /// The title of the dialog.
+    title: String,
+
+    /// The question to ask.
+    message: String,
+
+    /// The labels of the buttons, from left to right. The last one confirms.
+    buttons: &'static [&'static str],
+
+    /// The index of the focused button.
+    focus: usize,
+
+    /// The operation to run if confirmed.
+    action: ConfirmAction,
+}
+
+impl Confirm {
+    /// Create a [`Confirm`] dialog asking before running the given operation.
+    pub fn new(title: impl Into<String>, message: impl Into<String>, action: ConfirmAction) -> Self {
+        Self { title: title.into(), message: message.into(), buttons: &BUTTONS, focus: 0, action }
+    }
+
+    /// Create a dialog that only shows a message, such as an error, until it is closed.
+    pub fn alert(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            buttons: &ALERT_BUTTONS,
+            focus: 0,
+            action: ConfirmAction::Dismiss,
+        }
+    }
+
+    /// Returns the index of the button that confirms.
+    fn confirm_button(&self) -> usize {
+        self.buttons.len() - 1
+    }
+
+    /// Shifts the indexes the operation applies to once a combatant is removed (see
+    /// [`ConfirmAction::forget`]). Returns `false` if the dialog has nothing left to ask about.
+    pub fn forget(&mut self, removed: usize) -> bool {
+        self.action.forget(removed)
+    }
+
+    /// Returns the operation to run if confirmed.
+    pub fn into_action(self) -> ConfirmAction {
+        self.action
+    }
+
+    /// Returns the most useful keys in the dialog, for the status bar.
+    pub fn hints(&self, keymap: &Keymap) -> Vec<Hint> {
+        if self.action == ConfirmAction::Dismiss {
+            return Hint::bound(&keymap.popup, PopupCommand::Confirm, "close")
+                .into_iter()
+                .collect();
+        }
+
+        let mut hints = vec![Hint::new("y", "confirm"), Hint::new("n", "cancel")];
+        hints.extend(Hint::bound(&keymap.popup, PopupCommand::Complete, "switch button"));
+        hints.extend(Hint::bound(&keymap.popup, PopupCommand::Confirm, "press button"));
+        hints
+    }
+}
+
+impl InputHandler for Confirm {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        match (keymap.popup.get(key), key.code) {
+            (Some(PopupCommand::Confirm), _) if self.focus == self.confirm_button() => {
+                AfterKey::Exit
+            },
+            (Some(PopupCommand::Confirm | PopupCommand::Back), _) => AfterKey::Cancel,
+            (Some(PopupCommand::Complete), _) | (None, KeyCode::Left | KeyCode::Right) => {
+                self.focus = (self.focus + 1) % self.buttons.len();
+                AfterKey::Stay
+            },
+            (None, KeyCode::Char('y')) => AfterKey::Exit,
+            (None, KeyCode::Char('n')) => AfterKey::Cancel,
+            _ => AfterKey::Stay,
+        }
+    }
+
+    /// Clicking a button presses it.
+    fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect, _: &Theme) -> AfterKey {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return AfterKey::Stay;
+        }
+
+        let position = Position::new(mouse.column, mouse.row);
+        match dialog::button_at(area, &self.title, &self.message, self.buttons, position) {
+            Some(button) if button == self.confirm_button() => AfterKey::Exit,
+            Some(_) => AfterKey::Cancel,
+            None => AfterKey::Stay,
+        }
+    }
+}
+
+impl Drawable for Confirm {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let dialog = Dialog::new(&self.title, &self.message, self.buttons, self.focus, theme);
+        frame.render_widget(dialog, frame.area());
+    }
+}
@@ -0,0 +1,135 @@
+// -- Imports -- //
+
+use crate::keymap::{Keymap, PopupCommand};
+use crate::state::{AfterKey, Drawable, InputHandler};
+use crate::selectable::Selectable;
+use crate::theme::Theme;
+use crate::widgets::popup::Multiselect;
+
+use h5t_core::{SpeedFactor, Tracker, TrackerCommand};
+
+use ratatui::prelude::*;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use rand::Rng;
+
+use std::collections::{HashMap, HashSet};
+
+// -- Declare Speed Factors -- //
+
+/// State for the start of a round in speed factor initiative, where every combatant declares
+/// what it will do, one after the other, before initiative is rolled again.
+#[derive(Clone, Debug, Default)]
+pub struct DeclareSpeedFactors {
+    /// The combatants declaring, by index, with their names.
+    combatants: Vec<(usize, String)>,
+
+    /// The speed factors each combatant declared, in the same order.
+    declared: Vec<HashSet<SpeedFactor>>,
+
+    /// The position of the combatant declaring now.
+    current: usize,
+
+    /// The labels of the options, from the keyboard layout.
+    labels: &'static str,
+}
+
+impl DeclareSpeedFactors {
+    /// Create a new [`DeclareSpeedFactors`] state for every combatant in the tracker, starting
+    /// from what they declared last round, and labelling the options with the given labels.
+    pub fn new(tracker: &Tracker, labels: &'static str) -> Self {
+        let combatants = tracker.combatants
+            .iter()
+            .enumerate()
+            .map(|(index, combatant)| (index, combatant.name().to_string()))
+            .collect();
+        let declared = tracker.combatants
+            .iter()
+            .map(|combatant| combatant.speed_factors.iter().copied().collect())
+            .collect();
+        Self { combatants, declared, labels, ..Default::default() }
+    }
+
+    /// Returns the prompt naming the combatant declaring now.
+    fn prompt(&self) -> String {
+        let name = &self.combatants[self.current].1;
+        format!("{} declares, {} of {}", name, self.current + 1, self.combatants.len())
+    }
+
+    /// Toggles a speed factor of the combatant declaring now.
+    fn toggle(&mut self, factor: SpeedFactor) {
+        let declared = &mut self.declared[self.current];
+        if !declared.remove(&factor) {
+            declared.insert(factor);
+        }
+    }
+
+    /// Records what every combatant declared, rolls their initiative for the round, and starts
+    /// the round.
+    pub fn apply(&self, tracker: &mut Tracker) {
+        let mut rng = rand::rng();
+        let edition = tracker.rules.edition;
+        for (&(index, _), declared) in self.combatants.iter().zip(&self.declared) {
+            let speed_factors = SpeedFactor::ALL
+                .into_iter()
+                .filter(|factor| declared.contains(factor))
+                .collect();
+            tracker.execute(TrackerCommand::DeclareSpeedFactors { index, speed_factors });
+
+            let value = tracker.combatants[index]
+                .roll_speed_factor(edition, |sides| rng.random_range(1..=sides));
+            tracker.execute(TrackerCommand::SetInitiative { index, value: Some(value) });
+        }
+        tracker.execute(TrackerCommand::NextTurn);
+    }
+}
+
+impl InputHandler for DeclareSpeedFactors {
+    /// Labels toggle speed factors, confirming moves on to the next combatant (or rolls
+    /// initiative after the last one), and going back returns to the previous one.
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        let label_to_option = self.labels
+            .chars()
+            .zip(SpeedFactor::variants())
+            .collect::<HashMap<_, _>>();
+
+        match (keymap.popup.get(key), key.code) {
+            (Some(PopupCommand::Back), _) if self.current == 0 => return AfterKey::Cancel,
+            (Some(PopupCommand::Back), _) => self.current -= 1,
+            (Some(PopupCommand::Confirm), _) if self.current + 1 == self.combatants.len() => {
+                return AfterKey::Exit;
+            },
+            (Some(PopupCommand::Confirm), _) => self.current += 1,
+            (None, KeyCode::Char(label)) => {
+                if let Some(&option) = label_to_option.get(&label) {
+                    self.toggle(option);
+                }
+            },
+            _ => (),
+        }
+
+        AfterKey::Stay
+    }
+
+    /// Clicking a speed factor toggles it.
+    fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect, theme: &Theme) -> AfterKey {
+        let MouseEventKind::Down(MouseButton::Left) = mouse.kind else { return AfterKey::Stay };
+        let position = Position::new(mouse.column, mouse.row);
+        let prompt = self.prompt();
+        let widget = Multiselect::new(&prompt, &self.declared[self.current], true, theme)
+            .with_labels(self.labels);
+        if let Some(option) = widget.option_at(area, position) {
+            self.toggle(option);
+        }
+
+        AfterKey::Stay
+    }
+}
+
+impl Drawable for DeclareSpeedFactors {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let prompt = self.prompt();
+        let widget = Multiselect::new(&prompt, &self.declared[self.current], true, theme)
+            .with_labels(self.labels);
+        frame.render_widget(widget, frame.area());
+    }
+}
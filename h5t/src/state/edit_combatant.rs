@@ -0,0 +1,249 @@
+// -- Imports -- //
+
+use crate::keymap::{Keymap, PopupCommand};
+use crate::state::{AfterKey, Drawable, InputHandler};
+use crate::theme::Theme;
+use crate::widgets::TextInput;
+use crate::widgets::popup::popup_area;
+
+use h5t_core::{CombatantEdit, CombatantKind, Tracker, TrackerCommand};
+
+use ratatui::layout::Flex;
+use ratatui::prelude::*;
+use ratatui::widgets::*;
+use crossterm::event::{KeyCode, KeyEvent};
+
+// -- Edit Combatant -- //
+
+/// A field of the [`EditCombatant`] form.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Field {
+    Name,
+    HitPoints,
+    MaxHitPoints,
+    ArmorClass,
+    Initiative,
+    /// Only for monsters.
+    ChallengeRating,
+    /// Only for player characters.
+    Level,
+}
+
+impl Field {
+    /// Returns the label shown before the field.
+    fn label(self) -> &'static str {
+        match self {
+            Field::Name => "Name",
+            Field::HitPoints => "Hit points",
+            Field::MaxHitPoints => "Max hit points",
+            Field::ArmorClass => "Armor class",
+            Field::Initiative => "Initiative",
+            Field::ChallengeRating => "Challenge",
+            Field::Level => "Level",
+        }
+    }
+}
+
+/// The values typed into an [`EditCombatant`] form, once they are all valid.
+struct Values {
+    /// The stats changed with [`TrackerCommand::Edit`].
+    edit: CombatantEdit,
+
+    /// The current hit points.
+    hit_points: i32,
+
+    /// The initiative roll, if any.
+    initiative: Option<i32>,
+}
+
+/// Parses a number typed into a field, naming the field if it isn't valid.
+fn parse_number<T: std::str::FromStr>(field: Field, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid {} `{}`", field.label().to_lowercase(), value.trim()))
+}
+
+/// Parses a challenge rating, either as a number or a fraction like `1/4`.
+fn parse_challenge_rating(value: &str) -> Result<f32, String> {
+    let challenge_rating = match value.trim().split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator = parse_number::<f32>(Field::ChallengeRating, numerator)?;
+            let denominator = parse_number::<f32>(Field::ChallengeRating, denominator)?;
+            numerator / denominator
+        },
+        None => parse_number(Field::ChallengeRating, value)?,
+    };
+    if !challenge_rating.is_finite() || challenge_rating < 0.0 {
+        return Err(format!("invalid challenge `{}`", value.trim()));
+    }
+    Ok(challenge_rating)
+}
+
+/// Formats a challenge rating, with fractions like `1/4` below 1.
+fn fmt_challenge_rating(challenge_rating: f32) -> String {
+    if challenge_rating > 0.0 && challenge_rating < 1.0 {
+        format!("1/{}", 1.0 / challenge_rating)
+    } else {
+        challenge_rating.to_string()
+    }
+}
+
+/// State for a form to change a combatant's name, hit points, armor class, initiative, and the
+/// stats only its kind has (a monster's challenge rating, or a character's level) in place.
+///
+/// Tab or the arrow keys move between fields. Confirming checks every field, and saves them if
+/// they are all valid.
+#[derive(Clone, Debug)]
+pub struct EditCombatant {
+    /// The index of the combatant to edit.
+    combatant: usize,
+
+    /// The title of the form.
+    title: String,
+
+    /// The fields of the form, in order, starting with the combatant's current values.
+    fields: Vec<(Field, TextInput)>,
+
+    /// The position of the focused field.
+    focus: usize,
+
+    /// Why the values can't be saved, if confirming failed.
+    error: Option<String>,
+}
+
+impl EditCombatant {
+    /// Create an [`EditCombatant`] state for the given combatant.
+    pub fn new(combatant: usize, tracker: &Tracker) -> Self {
+        let target = &tracker.combatants[combatant];
+        let mut fields = vec![
+            (Field::Name, target.name().to_string()),
+            (Field::HitPoints, target.hit_points.to_string()),
+            (Field::MaxHitPoints, target.max_hit_points().to_string()),
+            (Field::ArmorClass, target.armor_class().to_string()),
+            (Field::Initiative, target.initiative.map_or_else(String::new, |i| i.to_string())),
+        ];
+        fields.push(match &target.kind {
+            CombatantKind::Monster(monster) => {
+                (Field::ChallengeRating, fmt_challenge_rating(monster.challenge_rating))
+            },
+            CombatantKind::Character(character) => (Field::Level, character.level.to_string()),
+        });
+
+        Self {
+            combatant,
+            title: format!("Edit: {}", target.name()),
+            fields: fields
+                .into_iter()
+                .map(|(field, value)| (field, TextInput::new(value)))
+                .collect(),
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Checks every field, returning the values typed, or why one of them isn't valid.
+    fn values(&self) -> Result<Values, String> {
+        let mut values = Values { edit: CombatantEdit::default(), hit_points: 0, initiative: None };
+        for (field, input) in &self.fields {
+            let value = input.value().trim();
+            match field {
+                Field::Name if value.is_empty() => return Err("the name can't be empty".into()),
+                Field::Name => values.edit.name = Some(value.to_string()),
+                Field::HitPoints => values.hit_points = parse_number(*field, value)?,
+                Field::MaxHitPoints => {
+                    let max = parse_number(*field, value)?;
+                    if max <= 0 {
+                        return Err("the max hit points must be above 0".into());
+                    }
+                    values.edit.max_hit_points = Some(max);
+                },
+                Field::ArmorClass => values.edit.armor_class = Some(parse_number(*field, value)?),
+                Field::Initiative if value.is_empty() => values.initiative = None,
+                Field::Initiative => values.initiative = Some(parse_number(*field, value)?),
+                Field::ChallengeRating => {
+                    values.edit.challenge_rating = Some(parse_challenge_rating(value)?);
+                },
+                Field::Level => values.edit.level = Some(parse_number(*field, value)?),
+            }
+        }
+        Ok(values)
+    }
+
+    /// Save the values to the tracker. The hit points are set after the new maximum, and logged
+    /// as damage or healing.
+    pub fn apply(&self, tracker: &mut Tracker) {
+        let Ok(Values { edit, hit_points, initiative }) = self.values() else { return };
+        let index = self.combatant;
+        tracker.execute(TrackerCommand::Edit { index, edit });
+        if tracker.combatants[index].hit_points != hit_points {
+            tracker.execute(TrackerCommand::SetHitPoints { index, value: hit_points });
+        }
+        if tracker.combatants[index].initiative != initiative {
+            tracker.execute(TrackerCommand::SetInitiative { index, value: initiative });
+        }
+    }
+}
+
+impl InputHandler for EditCombatant {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        let last = self.fields.len() - 1;
+        match (keymap.popup.get(key), key.code) {
+            (Some(PopupCommand::Back), _) => return AfterKey::Cancel,
+            (Some(PopupCommand::Confirm), _) => match self.values() {
+                Ok(_) => return AfterKey::Exit,
+                Err(error) => self.error = Some(error),
+            },
+            (Some(PopupCommand::Complete), _) | (None, KeyCode::Down) => {
+                self.focus = if self.focus == last { 0 } else { self.focus + 1 };
+            },
+            (None, KeyCode::BackTab | KeyCode::Up) => {
+                self.focus = if self.focus == 0 { last } else { self.focus - 1 };
+            },
+            _ => { self.fields[self.focus].1.handle_key(key, keymap); },
+        }
+
+        AfterKey::Stay
+    }
+
+    /// Insert pasted text into the focused field.
+    fn handle_paste(&mut self, text: &str) {
+        self.fields[self.focus].1.insert_str(text);
+    }
+}
+
+impl Drawable for EditCombatant {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        // 2 for borders, and a blank line before the error
+        let height = self.fields.len() as u16 + 2 + if self.error.is_some() { 2 } else { 0 };
+        let area = popup_area(frame.area(), Flex::Center, Flex::Center, (44, height), 0);
+        frame.render_widget(Clear, area);
+
+        let mut block = Block::bordered()
+            .border_set(theme.border_set())
+            .border_style(Style::default().fg(theme.border))
+            .title(self.title.as_str())
+            .padding(Padding::horizontal(1));
+        if self.error.is_none() {
+            block = block.title_bottom("Enter to save");
+        }
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let width = self.fields.iter().map(|(field, _)| field.label().len()).max().unwrap_or(0);
+        for (position, (field, input)) in self.fields.iter().enumerate() {
+            let row = Rect { y: inner.y + position as u16, height: 1, ..inner };
+            let prefix = format!("{:>width$}: ", field.label());
+            if position == self.focus {
+                input.render(frame, row, &prefix, theme);
+            } else {
+                frame.render_widget(Paragraph::new(format!("{}{}", prefix, input.value())), row);
+            }
+        }
+
+        if let Some(error) = &self.error {
+            let row = Rect { y: inner.bottom() - 1, height: 1, ..inner };
+            frame.render_widget(Paragraph::new(error.as_str()).fg(Color::Red), row);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+use crate::widgets::popup::NumericInput;
+use crossterm::event::KeyEvent;
+use h5t_core::{Tracker, TrackerCommand};
+use ratatui::prelude::*;
+use super::{AfterKey, Drawable, InputHandler};
+
+/// State for setting a combatant's current hit points directly.
+#[derive(Clone, Debug)]
+pub struct EditHitPoints {
+    /// The index of the combatant to edit.
+    combatant: usize,
+
+    /// The hit points input field, starting with the current hit points.
+    input: NumericInput,
+}
+
+impl EditHitPoints {
+    /// Create an [`EditHitPoints`] state for the given combatant.
+    pub fn new(combatant: usize, tracker: &Tracker) -> Self {
+        let input = NumericInput::new("Hit points", 5)
+            .with_range(0, 99999)
+            .with_value(tracker.combatants[combatant].hit_points);
+        Self { combatant, input }
+    }
+
+    /// Set the hit points, logging the difference as damage or healing.
+    pub fn apply(&self, tracker: &mut Tracker) {
+        let Some(value) = self.input.value() else { return };
+        tracker.execute(TrackerCommand::SetHitPoints { index: self.combatant, value });
+    }
+}
+
+impl InputHandler for EditHitPoints {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        self.input.handle_key(key, keymap)
+    }
+}
+
+impl Drawable for EditHitPoints {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        self.input.draw(frame, theme);
+    }
+}
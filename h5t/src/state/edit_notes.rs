@@ -0,0 +1,115 @@
+// -- Imports -- //
+
+use crate::keymap::{Keymap, PopupCommand};
+use crate::state::{AfterKey, Drawable, InputHandler};
+use crate::theme::Theme;
+use crate::widgets::popup::popup_area;
+
+use h5t_core::{Tracker, TrackerCommand};
+
+use ratatui::layout::Flex;
+use ratatui::prelude::*;
+use ratatui::widgets::*;
+use crossterm::event::{KeyCode, KeyEvent};
+
+// -- Edit Notes -- //
+
+/// The notes being edited.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NotesTarget {
+    /// The notes of the combatant with the given index.
+    Combatant(usize),
+
+    /// The notes of the whole encounter.
+    Encounter,
+}
+
+/// State for editing notes in a multi-line editor.
+///
+/// Enter starts a new line. Leaving the editor saves the notes.
+#[derive(Clone, Debug)]
+pub struct EditNotes {
+    /// The notes being edited.
+    target: NotesTarget,
+
+    /// The title of the editor.
+    title: String,
+
+    /// The text typed so far.
+    text: String,
+}
+
+impl EditNotes {
+    /// Create an [`EditNotes`] state for the given notes, starting with their current text.
+    pub fn new(target: NotesTarget, tracker: &Tracker) -> Self {
+        let (title, text) = match target {
+            NotesTarget::Combatant(index) => {
+                let combatant = &tracker.combatants[index];
+                (format!("Notes: {}", combatant.name()), combatant.notes.clone())
+            },
+            NotesTarget::Encounter => ("Encounter Notes".to_string(), tracker.notes.clone()),
+        };
+        Self { target, title, text }
+    }
+
+    /// Save the notes to the tracker.
+    pub fn apply(self, tracker: &mut Tracker) {
+        let notes = self.text;
+        tracker.execute(match self.target {
+            NotesTarget::Combatant(index) => TrackerCommand::SetNotes { index, notes },
+            NotesTarget::Encounter => TrackerCommand::SetEncounterNotes { notes },
+        });
+    }
+}
+
+impl InputHandler for EditNotes {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        match (keymap.popup.get(key), key.code) {
+            (Some(PopupCommand::Back), _) => return AfterKey::Exit,
+            (Some(PopupCommand::Erase), _) => { self.text.pop(); },
+            (_, KeyCode::Enter) => self.text.push('\n'),
+            (None, KeyCode::Char(c)) => self.text.push(c),
+            _ => (),
+        }
+
+        AfterKey::Stay
+    }
+
+    /// Insert pasted text at the end of the notes.
+    fn handle_paste(&mut self, text: &str) {
+        self.text.push_str(&text.replace("\r\n", "\n").replace('\r', "\n"));
+    }
+}
+
+impl Drawable for EditNotes {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let area = popup_area(frame.area(), Flex::Center, Flex::Center, (60, 12), 0);
+        frame.render_widget(Clear, area);
+
+        let block = Block::bordered()
+            .border_set(theme.border_set())
+            .border_style(Style::default().fg(theme.border))
+            .title(self.title.as_str())
+            .title_bottom("Esc to save")
+            .padding(Padding::horizontal(1));
+        let text_area = block.inner(area);
+        frame.render_widget(block, area);
+
+        // keep the last lines in view
+        let lines = self.text.split('\n').collect::<Vec<_>>();
+        let start = lines.len().saturating_sub(text_area.height as usize);
+        frame.render_widget(Paragraph::new(lines[start..].join("\n")), text_area);
+
+        // display fake cursor
+        let last = lines.last().copied().unwrap_or_default();
+        let cursor = Position::new(
+            text_area.x + last.chars().count() as u16,
+            text_area.y + (lines.len() - start - 1) as u16,
+        );
+        if text_area.contains(cursor)
+            && let Some(cell) = frame.buffer_mut().cell_mut(cursor)
+        {
+            cell.set_style(theme.highlight(theme.cursor));
+        }
+    }
+}
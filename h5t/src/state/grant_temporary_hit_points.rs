@@ -0,0 +1,57 @@
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+use crate::widgets::popup::NumericInput;
+use crossterm::event::KeyEvent;
+use h5t_core::{Tracker, TrackerCommand};
+use ratatui::prelude::*;
+use super::{AfterKey, Drawable, InputHandler};
+
+/// State for giving combatants temporary hit points, such as from Inspiring Leader or Heroism.
+///
+/// The amount can be a dice expression, which is rolled once for every target. Targets that
+/// already have as many temporary hit points or more keep theirs, since they don't stack.
+#[derive(Clone, Debug)]
+pub struct GrantTemporaryHitPoints {
+    /// The combatant indices to give temporary hit points to.
+    combatants: Vec<usize>,
+
+    /// The temporary hit points input field.
+    input: NumericInput,
+}
+
+impl GrantTemporaryHitPoints {
+    /// Create a [`GrantTemporaryHitPoints`] state with the given combatants.
+    pub fn new(combatants: Vec<usize>) -> Self {
+        Self {
+            combatants,
+            input: NumericInput::new("Temporary hit points", 14).with_range(0, 9999).with_dice(),
+        }
+    }
+
+    /// Returns a copy of the state that grants the same temporary hit points to other combatants.
+    pub fn retarget(&self, combatants: Vec<usize>) -> Self {
+        Self { combatants, ..self.clone() }
+    }
+
+    /// Give the temporary hit points to the combatants.
+    pub fn apply(&self, tracker: &mut Tracker) {
+        let amount = self.input.value().unwrap_or(0);
+        if amount == 0 { return }
+
+        for &index in &self.combatants {
+            tracker.execute(TrackerCommand::GrantTemporaryHitPoints { index, amount });
+        }
+    }
+}
+
+impl InputHandler for GrantTemporaryHitPoints {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        self.input.handle_key(key, keymap)
+    }
+}
+
+impl Drawable for GrantTemporaryHitPoints {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        self.input.draw(frame, theme);
+    }
+}
@@ -2,15 +2,78 @@
 
 pub mod apply_condition;
 pub mod apply_damage;
+pub mod apply_healing;
+pub mod command_line;
+pub mod confirm;
+pub mod declare_speed_factors;
+pub mod edit_combatant;
+pub mod edit_hit_points;
+pub mod edit_notes;
+pub mod grant_temporary_hit_points;
+pub mod move_token;
+pub mod search;
+pub mod show_help;
+pub mod show_legend;
+pub mod show_stats;
 
 // -- Imports -- //
 
+use crate::keymap::{Keymap, PopupCommand};
+use crate::plugin::PluginState;
+use crate::theme::Theme;
+use crate::widgets::Hint;
+
+use crossterm::event::{KeyEvent, MouseEvent};
 use h5t_core::Tracker;
+use ratatui::layout::Rect;
 
 // -- Exports -- //
 
-pub use apply_damage::ApplyDamage;
+pub use apply_damage::{ApplyDamage, DamageResult};
+pub use apply_healing::ApplyHealing;
 pub use apply_condition::ApplyCondition;
+pub use command_line::{CommandLine, PaletteCommand};
+pub use confirm::{Confirm, ConfirmAction};
+pub use declare_speed_factors::DeclareSpeedFactors;
+pub use edit_combatant::EditCombatant;
+pub use edit_hit_points::EditHitPoints;
+pub use edit_notes::{EditNotes, NotesTarget};
+pub use grant_temporary_hit_points::GrantTemporaryHitPoints;
+pub use move_token::MoveToken;
+pub use search::Search;
+pub use show_help::ShowHelp;
+pub use show_legend::ShowLegend;
+pub use show_stats::ShowStats;
+
+/// Something that draws itself over the whole frame, such as an [`ActionState`] or a
+/// [`Confirm`] dialog.
+///
+/// Each state places itself in the frame, so the UI draws whichever states are active without
+/// knowing what they are.
+pub trait Drawable {
+    /// Draw the state to the given [`Frame`](ratatui::Frame).
+    fn draw(&self, frame: &mut ratatui::Frame, theme: &Theme);
+}
+
+/// Something that reacts to input while it is the topmost state, such as an [`ActionState`] or a
+/// [`Confirm`] dialog.
+///
+/// What the handler returns tells the UI whether to keep sending it input.
+pub trait InputHandler {
+    /// Handle a key event, using the popup bindings of the given keymap.
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey;
+
+    /// Handle a mouse event. `area` is the area of the frame the state is drawn in, with the given
+    /// theme.
+    ///
+    /// By default, the mouse does nothing.
+    fn handle_mouse(&mut self, _: MouseEvent, _: Rect, _: &Theme) -> AfterKey {
+        AfterKey::Stay
+    }
+
+    /// Handle pasted text. By default, it is ignored, which suits states without a text field.
+    fn handle_paste(&mut self, _: &str) {}
+}
 
 /// What to do after handling a key event.
 #[derive(Default)]
@@ -20,34 +83,214 @@ pub enum AfterKey {
     Stay,
     /// Exit and hand control back to the main loop.
     Exit,
+    /// Exit without applying the action.
+    Cancel,
 }
 
 /// State of an action being applied through the [`Tracker`].
 ///
 /// `::Condition()` Applying a condition. <br>
-/// `::Damage()` Applying damage.
-#[derive(Debug, Clone)]
+/// `::Damage()` Applying damage. <br>
+/// `::Heal()` Healing. <br>
+/// `::TemporaryHitPoints()` Giving temporary hit points. <br>
+/// `::HitPoints()` Setting hit points. <br>
+/// `::EditCombatant()` Editing a combatant's stats. <br>
+/// `::Token()` Moving a token on the battle grid. <br>
+/// `::SpeedFactors()` Declaring speed factors at the start of a round. <br>
+/// `::Command()` Typing a command. <br>
+/// `::Search()` Typing a search. <br>
+/// `::Notes()` Editing notes. <br>
+/// `::Help()` Displaying the help overlay. <br>
+/// `::Legend()` Displaying the condition legend. <br>
+/// `::Stats()` Displaying the combat stats. <br>
+/// `::Plugin()` A mode added by a plugin.
+///
+/// The built-in states are a closed enum, rather than boxed [`Drawable`] and [`InputHandler`]
+/// trait objects, because the UI matches on them: it reads the command line's text and the search
+/// query once they exit, sums up the damage each target took, starts the round once the speed
+/// factors are declared, and draws the path of a token being moved. Boxing them would trade those
+/// matches for downcasts. Drawing and input only go through `as_drawable` and
+/// `as_input_handler`, so a new built-in state adds one line to each. States that only need
+/// drawing, input, a status, and [`apply`](PluginState::apply) belong in a
+/// [`Plugin`](crate::plugin::Plugin), whose boxed [`PluginState`] needs no change here at all.
+#[derive(Debug)]
 pub enum ActionState {
     /// Applying a condition to combatant(s).
 	Condition(ApplyCondition),
     /// Applying damage to combatant(s).
 	Damage(ApplyDamage),
+    /// Healing combatant(s).
+	Heal(ApplyHealing),
+    /// Giving combatant(s) temporary hit points.
+	TemporaryHitPoints(GrantTemporaryHitPoints),
+    /// Setting a combatant's hit points.
+	HitPoints(EditHitPoints),
+    /// Editing a combatant's name, hit points, armor class, initiative, and kind-specific stats.
+	EditCombatant(EditCombatant),
+    /// Moving a combatant's token on the battle grid.
+	Token(MoveToken),
+    /// Declaring every combatant's speed factors at the start of a round.
+	SpeedFactors(DeclareSpeedFactors),
+    /// Typing a command (the command is run by the UI once the state exits).
+	Command(CommandLine),
+    /// Typing a search (the combatant list is filtered by the UI once the state exits).
+	Search(Search),
+    /// Editing a combatant's or the encounter's notes.
+	Notes(EditNotes),
+    /// Displaying the help overlay (doesn't modify the tracker).
+	Help(ShowHelp),
+    /// Displaying the condition badge legend (doesn't modify the tracker).
+	Legend(ShowLegend),
+    /// Displaying every combatant's combat stats (doesn't modify the tracker).
+	Stats(ShowStats),
+    /// A mode added by a plugin.
+	Plugin(Box<dyn PluginState>),
+}
+
+impl Drawable for ActionState {
+    fn draw(&self, frame: &mut ratatui::Frame, theme: &Theme) {
+        self.as_drawable().draw(frame, theme);
+    }
+}
+
+impl InputHandler for ActionState {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        self.as_input_handler().handle_key(key, keymap)
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect, theme: &Theme) -> AfterKey {
+        self.as_input_handler().handle_mouse(mouse, area, theme)
+    }
+
+    fn handle_paste(&mut self, text: &str) {
+        self.as_input_handler().handle_paste(text);
+    }
 }
 
 impl ActionState {
-    /// Allow the state to draw itself.
-    pub fn draw(&self, frame: &mut ratatui::Frame) {
+    /// Returns the state being applied, to draw it.
+    fn as_drawable(&self) -> &dyn Drawable {
         match self {
-            Self::Condition(state) => state.draw(frame),
-            Self::Damage(state) => state.draw(frame),
+            Self::Condition(state) => state,
+            Self::Damage(state) => state,
+            Self::Heal(state) => state,
+            Self::TemporaryHitPoints(state) => state,
+            Self::HitPoints(state) => state,
+            Self::EditCombatant(state) => state,
+            Self::Token(state) => state,
+            Self::SpeedFactors(state) => state,
+            Self::Command(state) => state,
+            Self::Search(state) => state,
+            Self::Notes(state) => state,
+            Self::Help(state) => state,
+            Self::Legend(state) => state,
+            Self::Stats(state) => state,
+            Self::Plugin(state) => state.as_ref(),
         }
     }
 
-    /// Handle a key event.
-    pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> AfterKey {
+    /// Returns the state being applied, to pass input to it.
+    fn as_input_handler(&mut self) -> &mut dyn InputHandler {
+        match self {
+            Self::Condition(state) => state,
+            Self::Damage(state) => state,
+            Self::Heal(state) => state,
+            Self::TemporaryHitPoints(state) => state,
+            Self::HitPoints(state) => state,
+            Self::EditCombatant(state) => state,
+            Self::Token(state) => state,
+            Self::SpeedFactors(state) => state,
+            Self::Command(state) => state,
+            Self::Search(state) => state,
+            Self::Notes(state) => state,
+            Self::Help(state) => state,
+            Self::Legend(state) => state,
+            Self::Stats(state) => state,
+            Self::Plugin(state) => state.as_mut(),
+        }
+    }
+
+    /// Returns the name of the state and the most useful keys in it, for the status bar.
+    pub fn status(&self, keymap: &Keymap) -> (&'static str, Vec<Hint>) {
+        let popup = |command, label| Hint::bound(&keymap.popup, command, label);
+        let (mode, hints) = match self {
+            Self::Condition(_) => ("CONDITION", vec![
+                Some(Hint::new("a-z", "toggle")),
+                popup(PopupCommand::Confirm, "next field"),
+                popup(PopupCommand::Back, "cancel"),
+            ]),
+            Self::Damage(_) => ("DAMAGE", vec![
+                popup(PopupCommand::Confirm, "apply"),
+                popup(PopupCommand::Back, "cancel"),
+                Some(Hint::new("-N", "heal")),
+                Some(Hint::new("2d6+3", "roll")),
+            ]),
+            Self::Heal(_) => ("HEAL", vec![
+                popup(PopupCommand::Confirm, "heal"),
+                popup(PopupCommand::Back, "cancel"),
+                Some(Hint::new("2d4+2", "roll")),
+            ]),
+            Self::TemporaryHitPoints(_) => ("TEMP HP", vec![
+                popup(PopupCommand::Confirm, "grant"),
+                popup(PopupCommand::Back, "cancel"),
+                Some(Hint::new("1d4+4", "roll")),
+            ]),
+            Self::HitPoints(_) => ("HIT POINTS", vec![
+                popup(PopupCommand::Confirm, "set"),
+                popup(PopupCommand::Back, "cancel"),
+            ]),
+            Self::EditCombatant(_) => ("EDIT", vec![
+                popup(PopupCommand::Complete, "next field"),
+                Some(Hint::new("up/down", "move")),
+                popup(PopupCommand::Confirm, "save"),
+                popup(PopupCommand::Back, "cancel"),
+            ]),
+            Self::Token(_) => ("MOVE", vec![
+                Some(Hint::new("hjkl", "move")),
+                Some(Hint::new("yubn", "diagonal")),
+                popup(PopupCommand::Confirm, "place"),
+                popup(PopupCommand::Back, "cancel"),
+            ]),
+            Self::SpeedFactors(_) => ("DECLARE", vec![
+                Some(Hint::new("a-z", "toggle")),
+                popup(PopupCommand::Confirm, "next combatant"),
+                popup(PopupCommand::Back, "previous"),
+            ]),
+            Self::Command(_) => ("COMMAND", vec![
+                popup(PopupCommand::Confirm, "run"),
+                popup(PopupCommand::Complete, "complete"),
+                Some(Hint::new("up/down", "history")),
+                popup(PopupCommand::Back, "cancel"),
+            ]),
+            Self::Search(_) => ("SEARCH", vec![
+                popup(PopupCommand::Confirm, "filter"),
+                Some(Hint::new("up/down", "history")),
+                popup(PopupCommand::Back, "cancel"),
+            ]),
+            Self::Notes(_) => ("NOTES", vec![
+                popup(PopupCommand::Back, "save"),
+                Some(Hint::new("enter", "new line")),
+            ]),
+            Self::Help(_) => ("HELP", vec![Some(Hint::new("any key", "close"))]),
+            Self::Legend(_) => ("LEGEND", vec![Some(Hint::new("any key", "close"))]),
+            Self::Stats(_) => ("STATS", vec![Some(Hint::new("any key", "close"))]),
+            Self::Plugin(state) => return state.status(keymap),
+        };
+
+        (mode, hints.into_iter().flatten().collect())
+    }
+
+    /// Returns a copy of the action aimed at other combatants, for actions that have targets
+    /// (damage, healing, temporary hit points, and conditions), or `None` for the others.
+    pub fn retarget(&self, targets: Vec<usize>) -> Option<Self> {
         match self {
-            Self::Condition(state) => state.handle_key(key),
-            Self::Damage(state) => state.handle_key(key),
+            Self::Condition(state) => Some(Self::Condition(state.retarget(targets))),
+            Self::Damage(state) => Some(Self::Damage(state.retarget(targets))),
+            Self::Heal(state) => Some(Self::Heal(state.retarget(targets))),
+            Self::TemporaryHitPoints(state) => {
+                Some(Self::TemporaryHitPoints(state.retarget(targets)))
+            },
+            _ => None,
         }
     }
 
@@ -55,7 +298,20 @@ impl ActionState {
     pub fn apply(self, tracker: &mut Tracker) {
         match self {
             Self::Condition(state) => state.apply(tracker),
-            Self::Damage(state) => state.apply(tracker),
+            Self::Damage(state) => { state.apply(tracker); },
+            Self::Heal(state) => state.apply(tracker),
+            Self::TemporaryHitPoints(state) => state.apply(tracker),
+            Self::HitPoints(state) => state.apply(tracker),
+            Self::EditCombatant(state) => state.apply(tracker),
+            Self::Token(state) => state.apply(tracker),
+            Self::SpeedFactors(state) => state.apply(tracker),
+            Self::Notes(state) => state.apply(tracker),
+            Self::Plugin(state) => state.apply(tracker),
+            Self::Command(_)
+            | Self::Search(_)
+            | Self::Help(_)
+            | Self::Legend(_)
+            | Self::Stats(_) => (),
         }
     }
 }
@@ -0,0 +1,71 @@
+use crate::keymap::{Keymap, PopupCommand};
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent};
+use h5t_core::{Position, Tracker, TrackerCommand};
+use ratatui::prelude::*;
+use super::{AfterKey, Drawable, InputHandler};
+
+/// State for moving a combatant's token on the battle grid, square by square.
+///
+/// The grid panel draws the token where it is being moved, so the state itself draws nothing.
+#[derive(Clone, Debug)]
+pub struct MoveToken {
+    /// The index of the combatant to move.
+    combatant: usize,
+
+    /// The square the token started on.
+    start: Position,
+
+    /// The square the token is being moved to.
+    square: Position,
+}
+
+impl MoveToken {
+    /// Create a [`MoveToken`] state for the given combatant. A combatant that isn't on the grid
+    /// yet starts on the first free square.
+    pub fn new(combatant: usize, tracker: &Tracker) -> Self {
+        let start = tracker.combatants[combatant].position.unwrap_or_else(|| tracker.free_square());
+        Self { combatant, start, square: start }
+    }
+
+    /// Returns the index of the combatant being moved.
+    pub fn combatant(&self) -> usize {
+        self.combatant
+    }
+
+    /// Returns the square the token started on, and the square it is being moved to.
+    pub fn squares(&self) -> (Position, Position) {
+        (self.start, self.square)
+    }
+
+    /// Place the token on the square it was moved to.
+    pub fn apply(&self, tracker: &mut Tracker) {
+        let (index, position) = (self.combatant, Some(self.square));
+        tracker.execute(TrackerCommand::SetPosition { index, position });
+    }
+}
+
+impl InputHandler for MoveToken {
+    /// The arrow keys and `hjkl` move the token, and `yubn` move it diagonally, as in roguelikes.
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        let (dx, dy) = match (keymap.popup.get(key), key.code) {
+            (Some(PopupCommand::Confirm), _) => return AfterKey::Exit,
+            (Some(PopupCommand::Back), _) => return AfterKey::Cancel,
+            (_, KeyCode::Left | KeyCode::Char('h')) => (-1, 0),
+            (_, KeyCode::Down | KeyCode::Char('j')) => (0, 1),
+            (_, KeyCode::Up | KeyCode::Char('k')) => (0, -1),
+            (_, KeyCode::Right | KeyCode::Char('l')) => (1, 0),
+            (_, KeyCode::Char('y')) => (-1, -1),
+            (_, KeyCode::Char('u')) => (1, -1),
+            (_, KeyCode::Char('b')) => (-1, 1),
+            (_, KeyCode::Char('n')) => (1, 1),
+            _ => return AfterKey::Stay,
+        };
+        self.square = self.square.offset(dx, dy);
+        AfterKey::Stay
+    }
+}
+
+impl Drawable for MoveToken {
+    fn draw(&self, _: &mut Frame, _: &Theme) {}
+}
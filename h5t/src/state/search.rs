@@ -0,0 +1,71 @@
+// -- Imports -- //
+
+use crate::keymap::{Keymap, PopupCommand};
+use crate::state::{AfterKey, Drawable, InputHandler};
+use crate::theme::Theme;
+use crate::widgets::TextInput;
+
+use h5t_core::Combatant;
+
+use ratatui::prelude::*;
+use crossterm::event::KeyEvent;
+
+// -- Search -- //
+
+/// Returns true if the combatant's name or one of its conditions contains the query, ignoring
+/// case.
+pub fn matches(combatant: &Combatant, query: &str) -> bool {
+    let query = query.to_lowercase();
+    combatant.name().to_lowercase().contains(&query)
+        || combatant.conditions
+            .iter()
+            .any(|condition| condition.kind.to_string().to_lowercase().contains(&query))
+}
+
+/// State for typing a search at the bottom of the screen, which filters the combatant list.
+#[derive(Clone, Debug, Default)]
+pub struct Search {
+    /// The text typed so far, without the leading `/`.
+    query: TextInput,
+}
+
+impl Search {
+    /// Create a [`Search`] starting with the given query, so the current filter can be edited,
+    /// and the previous searches, from oldest to newest.
+    pub fn new(query: impl Into<String>, history: Vec<String>) -> Self {
+        Self { query: TextInput::new(query).with_history(history) }
+    }
+
+    /// Returns the text typed so far.
+    pub fn query(&self) -> &str {
+        self.query.value()
+    }
+}
+
+impl InputHandler for Search {
+    fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        match keymap.popup.get(key) {
+            Some(PopupCommand::Confirm) => AfterKey::Exit,
+            Some(PopupCommand::Back) => AfterKey::Cancel,
+            _ => {
+                self.query.handle_key(key, keymap);
+                AfterKey::Stay
+            },
+        }
+    }
+
+    /// Insert pasted text at the cursor.
+    fn handle_paste(&mut self, text: &str) {
+        self.query.insert_str(text);
+    }
+}
+
+impl Drawable for Search {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let [line] = Layout::vertical([Constraint::Length(1)])
+            .flex(ratatui::layout::Flex::End)
+            .areas(frame.area());
+
+        self.query.render(frame, line, "/", theme);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::keymap::{HelpSection, Keymap};
+use crate::theme::Theme;
+use crate::widgets::popup::Help;
+use crossterm::event::{KeyEvent, MouseEvent};
+use ratatui::prelude::*;
+use super::{AfterKey, Drawable, InputHandler};
+
+/// State for displaying the help overlay.
+#[derive(Clone, Debug)]
+pub struct ShowHelp {
+    /// The groups of key bindings to display.
+    sections: Vec<HelpSection>,
+}
+
+impl ShowHelp {
+    /// Create a [`ShowHelp`] state displaying the given key bindings.
+    pub fn new(sections: Vec<HelpSection>) -> Self {
+        Self { sections }
+    }
+}
+
+impl InputHandler for ShowHelp {
+    /// Any key closes the popup.
+    fn handle_key(&mut self, _: KeyEvent, _: &Keymap) -> AfterKey {
+        AfterKey::Exit
+    }
+
+    /// Clicking anywhere closes the popup.
+    fn handle_mouse(&mut self, _: MouseEvent, _: Rect, _: &Theme) -> AfterKey {
+        AfterKey::Exit
+    }
+}
+
+impl Drawable for ShowHelp {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        frame.render_widget(Help::new(&self.sections, theme), frame.area());
+    }
+}
@@ -0,0 +1,30 @@
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+use crate::widgets::popup::ConditionLegend;
+use crossterm::event::{KeyEvent, MouseEvent};
+use h5t_core::Edition;
+use ratatui::prelude::*;
+use super::{AfterKey, Drawable, InputHandler};
+
+/// State for displaying the condition badge legend, with what each condition does in the given
+/// edition of the rules.
+#[derive(Clone, Debug, Default)]
+pub struct ShowLegend(pub Edition);
+
+impl InputHandler for ShowLegend {
+    /// Any key closes the popup.
+    fn handle_key(&mut self, _: KeyEvent, _: &Keymap) -> AfterKey {
+        AfterKey::Exit
+    }
+
+    /// Clicking anywhere closes the popup.
+    fn handle_mouse(&mut self, _: MouseEvent, _: Rect, _: &Theme) -> AfterKey {
+        AfterKey::Exit
+    }
+}
+
+impl Drawable for ShowLegend {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        frame.render_widget(ConditionLegend::new(theme, self.0), frame.area());
+    }
+}
@@ -0,0 +1,39 @@
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+use crate::widgets::popup::CombatStatsTable;
+use crossterm::event::{KeyEvent, MouseEvent};
+use h5t_core::{CombatStats, Tracker};
+use ratatui::prelude::*;
+use super::{AfterKey, Drawable, InputHandler};
+
+/// State for displaying every combatant's damage dealt and taken, healing done, and kills over
+/// the encounter, tallied from the combat log.
+#[derive(Clone, Debug, Default)]
+pub struct ShowStats(pub Vec<CombatStats>);
+
+impl ShowStats {
+    /// Create a [`ShowStats`] state with the stats of the given tracker, most damage dealt first.
+    pub fn new(tracker: &Tracker) -> Self {
+        let mut stats = tracker.combat_stats();
+        stats.sort_by_key(|stats| std::cmp::Reverse(stats.damage_dealt));
+        Self(stats)
+    }
+}
+
+impl InputHandler for ShowStats {
+    /// Any key closes the popup.
+    fn handle_key(&mut self, _: KeyEvent, _: &Keymap) -> AfterKey {
+        AfterKey::Exit
+    }
+
+    /// Clicking anywhere closes the popup.
+    fn handle_mouse(&mut self, _: MouseEvent, _: Rect, _: &Theme) -> AfterKey {
+        AfterKey::Exit
+    }
+}
+
+impl Drawable for ShowStats {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        frame.render_widget(CombatStatsTable::new(theme, &self.0), frame.area());
+    }
+}
@@ -0,0 +1,92 @@
+// -- Imports -- //
+
+use h5t_core::Tracker;
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+// -- Tasks -- //
+
+/// The result of work done on a worker thread, delivered to the main loop as an event.
+#[derive(Debug)]
+pub enum TaskResult {
+    /// An encounter was written to a file. `snapshot` is the serialized encounter that was saved,
+    /// to tell whether it changed since.
+    Saved { path: PathBuf, snapshot: String, result: Result<(), String> },
+
+    /// An encounter was written to the autosave file, which doesn't count as saving it.
+    Autosaved { path: PathBuf, result: Result<(), String> },
+
+    /// An encounter was read from a file. The tracker is boxed, since it is much larger than the
+    /// other results.
+    Loaded { path: PathBuf, result: Result<Box<Tracker>, String> },
+}
+
+/// Runs slow work, such as file IO, on worker threads so the UI stays responsive, and collects
+/// the results for the main loop.
+#[derive(Debug)]
+pub struct Tasks {
+    /// Cloned into every worker thread to send its result back.
+    sender: Sender<TaskResult>,
+
+    /// Results of finished work, in the order it finished.
+    receiver: Receiver<TaskResult>,
+}
+
+impl Default for Tasks {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self { sender, receiver }
+    }
+}
+
+impl Tasks {
+    /// Runs the given work on a new thread. Its result is returned by [`Tasks::try_recv`] once it
+    /// finishes.
+    pub fn spawn(&self, work: impl FnOnce() -> TaskResult + Send + 'static) {
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            // the receiver only goes away when the UI does, and then nobody needs the result
+            let _ = sender.send(work());
+        });
+    }
+
+    /// Returns the result of the next finished piece of work, if any, without blocking.
+    pub fn try_recv(&self) -> Option<TaskResult> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Writes the serialized encounter to the given file.
+pub fn save(path: PathBuf, contents: String, snapshot: String) -> TaskResult {
+    let result = write(&path, contents);
+    TaskResult::Saved { path, snapshot, result }
+}
+
+/// Writes the serialized encounter to the autosave file.
+pub fn autosave(path: PathBuf, contents: String) -> TaskResult {
+    let result = write(&path, contents);
+    TaskResult::Autosaved { path, result }
+}
+
+/// Writes the contents to the given file, replacing it if it exists.
+fn write(path: &Path, contents: String) -> Result<(), String> {
+    std::fs::write(path, contents)
+        .map_err(|err| format!("could not save {}: {}", path.display(), err))
+}
+
+/// Reads an encounter from the given file, which must have at least one combatant.
+pub fn load(path: PathBuf) -> TaskResult {
+    let read = || {
+        let file = std::fs::File::open(&path)
+            .map_err(|err| format!("could not load {}: {}", path.display(), err))?;
+        let tracker = serde_json::from_reader::<_, Tracker>(std::io::BufReader::new(file))
+            .map_err(|err| format!("could not load {}: {}", path.display(), err))?;
+        if tracker.combatants.is_empty() {
+            return Err(format!("{} has no combatants", path.display()));
+        }
+        Ok(Box::new(tracker))
+    };
+    let result = read();
+    TaskResult::Loaded { path, result }
+}
@@ -0,0 +1,205 @@
+//! Utilities for testing the UI without a terminal.
+//!
+//! A [`TestUi`] draws to a [`TestBackend`] and is driven by scripted keys, written like the keys
+//! of the config file. What ends up on screen can be compared against a snapshot file with
+//! [`TestUi::assert_snapshot`], or checked directly:
+//!
+//! ```
+//! use h5t::testing::TestUi;
+//! use h5t_core::{Monster, Tracker};
+//!
+//! let goblin = Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() };
+//! let ogre = Monster { name: "Ogre".to_string(), hit_points: 59, ..Default::default() };
+//! let mut ui = TestUi::new(Tracker::new(vec![goblin.into(), ogre.into()]));
+//! ui.press("n n");
+//! assert_eq!(ui.tracker().round, 1);
+//! assert!(ui.screen().contains("Round: 2"));
+//! ```
+
+// -- Imports -- //
+
+use crate::config::Config;
+use crate::debug::DebugLog;
+use crate::input::{InputEvent, ScriptedEvents};
+use crate::keymap::{Key, KeySequence};
+use crate::plugin::Plugins;
+use crate::settings::Settings;
+use crate::ui::Ui;
+
+use crossterm::event::KeyEvent;
+use h5t_core::{Compendium, Tracker};
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::Terminal;
+
+use std::path::Path;
+
+// -- Test UI -- //
+
+/// The environment variable that makes [`TestUi::assert_snapshot`] write snapshots instead of
+/// comparing against them.
+pub const UPDATE_SNAPSHOTS: &str = "H5T_UPDATE_SNAPSHOTS";
+
+/// A [`Ui`] drawn to a [`TestBackend`] and driven by [`ScriptedEvents`], for tests of what's on
+/// screen.
+///
+/// Each input method handles its input, then draws the screen once more if anything changed, so
+/// [`TestUi::screen`] shows the result. Keys that open label mode must be sent along with the
+/// selection, since label mode is cancelled once the input runs out. Saving and loading run on
+/// worker threads, whose results are only handled if they finish while input is still being
+/// handled.
+#[derive(Debug)]
+pub struct TestUi {
+    /// The UI under test.
+    ui: Ui<TestBackend, ScriptedEvents>,
+}
+
+impl TestUi {
+    /// The width of the screen, unless it is resized.
+    pub const WIDTH: u16 = 120;
+
+    /// The height of the screen, unless it is resized.
+    pub const HEIGHT: u16 = 40;
+
+    /// Creates a UI for the given encounter, with the default config, an empty compendium, and no
+    /// plugins.
+    pub fn new(tracker: Tracker) -> Self {
+        Self::with_parts(tracker, Config::default(), Compendium::default(), Plugins::default())
+    }
+
+    /// Creates a UI for the given encounter, with the given config, compendium, and plugins.
+    pub fn with_parts(
+        tracker: Tracker,
+        config: Config,
+        compendium: Compendium,
+        plugins: Plugins,
+    ) -> Self {
+        let terminal = Terminal::new(TestBackend::new(Self::WIDTH, Self::HEIGHT))
+            .expect("the test backend can't fail");
+        let ui = Ui::new(
+            terminal,
+            tracker,
+            config,
+            Settings::default(),
+            Box::new(compendium),
+            DebugLog::default(),
+            plugins,
+        ).expect("the test backend can't fail");
+
+        let mut test_ui = Self { ui };
+        test_ui.send([]);
+        test_ui
+    }
+
+    /// Presses the keys of a sequence, separated by spaces, such as `"3 j"` or `"ctrl-n enter"`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key isn't written as in the config file.
+    pub fn press(&mut self, keys: &str) {
+        let sequence = keys.parse::<KeySequence>()
+            .unwrap_or_else(|err| panic!("bad key sequence `{}`: {}", keys, err));
+        self.send(sequence.keys().iter().map(|key| InputEvent::Key(KeyEvent::from(*key))));
+    }
+
+    /// Types each character of the text as a key press, spaces included.
+    pub fn type_text(&mut self, text: &str) {
+        self.send(text.chars().map(|c| InputEvent::Key(Key::char(c).into())));
+    }
+
+    /// Pastes the text, as a terminal does with bracketed paste.
+    pub fn paste(&mut self, text: &str) {
+        self.send([InputEvent::Paste(text.to_string())]);
+    }
+
+    /// Advances timers, such as notifications and the flash on the new turn's row, by one tick.
+    pub fn tick(&mut self) {
+        self.send([InputEvent::Tick]);
+    }
+
+    /// Resizes the screen.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.ui.terminal.backend_mut().resize(width, height);
+        self.send([InputEvent::Resize]);
+    }
+
+    /// Handles the events, such as mouse clicks, then lets the UI draw the result.
+    pub fn send(&mut self, events: impl IntoIterator<Item = InputEvent>) {
+        self.ui.events_mut().send(events);
+        if let Err(err) = self.ui.run() {
+            panic!("the UI stopped with an error: {}", err);
+        }
+    }
+
+    /// Returns the encounter.
+    pub fn tracker(&self) -> &Tracker {
+        &self.ui.tracker
+    }
+
+    /// Returns the UI, to inspect or change it directly.
+    pub fn ui_mut(&mut self) -> &mut Ui<TestBackend, ScriptedEvents> {
+        &mut self.ui
+    }
+
+    /// Returns the screen as drawn, with styles.
+    pub fn buffer(&self) -> &Buffer {
+        self.ui.terminal.backend().buffer()
+    }
+
+    /// Returns the text on screen, one line per row, without trailing spaces.
+    pub fn screen(&self) -> String {
+        let buffer = self.buffer();
+        let width = buffer.area.width as usize;
+        let mut screen = buffer.content
+            .chunks(width.max(1))
+            .map(|row| {
+                let line = row.iter().map(|cell| cell.symbol()).collect::<String>();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        screen.push('\n');
+        screen
+    }
+
+    /// Compares the text on screen against the snapshot in the given file, which is written
+    /// instead if it doesn't exist yet or if [`UPDATE_SNAPSHOTS`] is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the screen differs from the snapshot, listing the rows that changed.
+    pub fn assert_snapshot(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let screen = self.screen();
+        let expected = match std::fs::read_to_string(path) {
+            Ok(expected) if std::env::var_os(UPDATE_SNAPSHOTS).is_none() => expected,
+            _ => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .unwrap_or_else(|err| panic!("can't create {}: {}", parent.display(), err));
+                }
+                std::fs::write(path, &screen)
+                    .unwrap_or_else(|err| panic!("can't write {}: {}", path.display(), err));
+                return;
+            },
+        };
+
+        if screen != expected {
+            let mut diff = String::new();
+            let rows = screen.lines().count().max(expected.lines().count());
+            for row in 0..rows {
+                let old = expected.lines().nth(row).unwrap_or_default();
+                let new = screen.lines().nth(row).unwrap_or_default();
+                if old != new {
+                    diff.push_str(&format!("{:>3} - {}\n{:>3} + {}\n", row, old, row, new));
+                }
+            }
+            panic!(
+                "the screen differs from {} (set {}=1 to update it):\n{}",
+                path.display(),
+                UPDATE_SNAPSHOTS,
+                diff,
+            );
+        }
+    }
+}
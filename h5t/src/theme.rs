@@ -0,0 +1,278 @@
+// -- Imports -- //
+
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::border;
+use ratatui::widgets::BorderType;
+use serde::Deserialize;
+
+// -- Presets -- //
+
+/// A builtin theme that custom colors are applied on top of.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// Light text on a dark terminal background.
+    #[default]
+    Dark,
+
+    /// Dark text on a light terminal background.
+    Light,
+
+    /// Saturated colors and thick borders.
+    HighContrast,
+}
+
+/// The shape of widget borders.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BorderStyle {
+    Plain,
+    #[default]
+    Rounded,
+    Double,
+    Thick,
+}
+
+impl From<BorderStyle> for BorderType {
+    fn from(style: BorderStyle) -> Self {
+        match style {
+            BorderStyle::Plain => BorderType::Plain,
+            BorderStyle::Rounded => BorderType::Rounded,
+            BorderStyle::Double => BorderType::Double,
+            BorderStyle::Thick => BorderType::Thick,
+        }
+    }
+}
+
+// -- Theme -- //
+
+/// The colors and border styles used to draw the UI.
+///
+/// In the config file, a theme is a preset plus any colors to override. Colors can be given as
+/// names (`"red"`, `"light-blue"`) or hex codes (`"#ff8800"`):
+///
+/// ```json
+/// { "theme": { "preset": "light", "current_turn": "#a0c8ff" } }
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(from = "ThemeOverrides")]
+pub struct Theme {
+    /// The shape of widget borders.
+    pub border_type: BorderType,
+
+    /// Whether to draw borders, gauges, and symbols with ASCII characters only, for terminals and
+    /// fonts that can't display box-drawing and block characters.
+    pub ascii: bool,
+
+    /// Whether to draw without colors, showing selection, the current turn, and downed
+    /// combatants with symbols and text attributes instead.
+    pub monochrome: bool,
+
+    /// Color of widget borders.
+    pub border: Color,
+
+    /// Color of text in popups that have focus.
+    pub text: Color,
+
+    /// Color of text and borders of popups that don't have focus.
+    pub inactive: Color,
+
+    /// Color of the cursor in input fields.
+    pub cursor: Color,
+
+    /// Color of a combatant's remaining actions.
+    pub action: Color,
+
+    /// Color of a combatant's remaining bonus actions.
+    pub bonus_action: Color,
+
+    /// Color of a combatant's remaining reactions.
+    pub reaction: Color,
+
+    /// Background color of the combatant whose turn it is.
+    pub current_turn: Color,
+
+    /// Background color of selected combatants and options.
+    pub selected: Color,
+
+    /// Background color of the combatant under the cursor.
+    pub cursor_row: Color,
+
+    /// Background color of selected options in popups that don't have focus.
+    pub selected_inactive: Color,
+
+    /// Background color of bloodied combatants, at or below half their hit points.
+    pub bloodied: Color,
+
+    /// Background color of combatants fleeing the fight.
+    pub fleeing: Color,
+
+    /// Background color of dead combatants.
+    pub dead: Color,
+
+    /// Background color of every other row in striped tables.
+    pub stripe: Color,
+}
+
+/// Borders drawn with ASCII characters only.
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+impl Theme {
+    /// Returns the characters to draw widget borders with.
+    pub fn border_set(&self) -> border::Set {
+        if self.ascii { ASCII_BORDER } else { self.border_type.to_border_set() }
+    }
+
+    /// Returns the style of a highlighted row or cell with the given background color, or
+    /// reversed text in monochrome mode.
+    pub fn highlight(&self, background: Color) -> Style {
+        if self.monochrome {
+            Style::new().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::new().bg(background)
+        }
+    }
+
+    /// Removes every color from the buffer in monochrome mode, keeping text attributes.
+    pub fn strip_colors(&self, buf: &mut Buffer) {
+        if !self.monochrome {
+            return;
+        }
+        for cell in &mut buf.content {
+            cell.set_fg(Color::Reset).set_bg(Color::Reset);
+        }
+    }
+
+    /// Returns the colors of a builtin theme.
+    pub fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Dark => Self {
+                border_type: BorderType::Rounded,
+                ascii: false,
+                monochrome: false,
+                border: Color::White,
+                text: Color::White,
+                inactive: Color::Rgb(128, 128, 128),
+                cursor: Color::White,
+                action: Color::Green,
+                bonus_action: Color::Rgb(255, 165, 0),
+                reaction: Color::Magenta,
+                current_turn: Color::Rgb(0, 48, 130),
+                selected: Color::Rgb(128, 85, 0),
+                cursor_row: Color::Rgb(60, 60, 80),
+                selected_inactive: Color::Rgb(64, 42, 0),
+                bloodied: Color::Rgb(70, 35, 0),
+                fleeing: Color::Rgb(0, 60, 60),
+                dead: Color::Rgb(100, 0, 0),
+                stripe: Color::DarkGray,
+            },
+            Preset::Light => Self {
+                border_type: BorderType::Rounded,
+                ascii: false,
+                monochrome: false,
+                border: Color::Black,
+                text: Color::Black,
+                inactive: Color::Rgb(140, 140, 140),
+                cursor: Color::Black,
+                action: Color::Rgb(0, 128, 0),
+                bonus_action: Color::Rgb(200, 100, 0),
+                reaction: Color::Rgb(160, 0, 160),
+                current_turn: Color::Rgb(170, 200, 255),
+                selected: Color::Rgb(255, 215, 140),
+                cursor_row: Color::Rgb(205, 205, 230),
+                selected_inactive: Color::Rgb(245, 230, 200),
+                bloodied: Color::Rgb(255, 215, 180),
+                fleeing: Color::Rgb(180, 235, 235),
+                dead: Color::Rgb(255, 170, 170),
+                stripe: Color::Rgb(225, 225, 225),
+            },
+            Preset::HighContrast => Self {
+                border_type: BorderType::Thick,
+                ascii: false,
+                monochrome: false,
+                border: Color::White,
+                text: Color::White,
+                inactive: Color::Gray,
+                cursor: Color::Yellow,
+                action: Color::LightGreen,
+                bonus_action: Color::LightYellow,
+                reaction: Color::LightMagenta,
+                current_turn: Color::Rgb(0, 0, 200),
+                selected: Color::Rgb(170, 85, 0),
+                cursor_row: Color::Rgb(90, 90, 90),
+                selected_inactive: Color::Rgb(85, 42, 0),
+                bloodied: Color::Rgb(120, 60, 0),
+                fleeing: Color::Rgb(0, 110, 110),
+                dead: Color::Rgb(170, 0, 0),
+                stripe: Color::Rgb(60, 60, 60),
+            },
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(Preset::default())
+    }
+}
+
+/// The theme as written in the config file: a preset and the colors that differ from it.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeOverrides {
+    preset: Preset,
+    border_type: Option<BorderStyle>,
+    ascii: bool,
+    monochrome: bool,
+    border: Option<Color>,
+    text: Option<Color>,
+    inactive: Option<Color>,
+    cursor: Option<Color>,
+    action: Option<Color>,
+    bonus_action: Option<Color>,
+    reaction: Option<Color>,
+    current_turn: Option<Color>,
+    selected: Option<Color>,
+    cursor_row: Option<Color>,
+    selected_inactive: Option<Color>,
+    bloodied: Option<Color>,
+    fleeing: Option<Color>,
+    dead: Option<Color>,
+    stripe: Option<Color>,
+}
+
+impl From<ThemeOverrides> for Theme {
+    fn from(overrides: ThemeOverrides) -> Self {
+        let theme = Theme::preset(overrides.preset);
+        Self {
+            border_type: overrides.border_type.map(Into::into).unwrap_or(theme.border_type),
+            ascii: overrides.ascii,
+            monochrome: overrides.monochrome,
+            border: overrides.border.unwrap_or(theme.border),
+            text: overrides.text.unwrap_or(theme.text),
+            inactive: overrides.inactive.unwrap_or(theme.inactive),
+            cursor: overrides.cursor.unwrap_or(theme.cursor),
+            action: overrides.action.unwrap_or(theme.action),
+            bonus_action: overrides.bonus_action.unwrap_or(theme.bonus_action),
+            reaction: overrides.reaction.unwrap_or(theme.reaction),
+            current_turn: overrides.current_turn.unwrap_or(theme.current_turn),
+            selected: overrides.selected.unwrap_or(theme.selected),
+            cursor_row: overrides.cursor_row.unwrap_or(theme.cursor_row),
+            selected_inactive: overrides.selected_inactive.unwrap_or(theme.selected_inactive),
+            bloodied: overrides.bloodied.unwrap_or(theme.bloodied),
+            fleeing: overrides.fleeing.unwrap_or(theme.fleeing),
+            dead: overrides.dead.unwrap_or(theme.dead),
+            stripe: overrides.stripe.unwrap_or(theme.stripe),
+        }
+    }
+}
@@ -1,134 +1,48 @@
 // -- Imports -- //
 
-use crate::widgets::{max_combatants_visible, CombatantBlock, StatBlock, TrackerWidget};
-use crate::state::{AfterKey, ActionState, ApplyCondition, ApplyDamage};
+use crate::announcer::Announcer;
+use crate::debug::{DebugLog, FrameTimes};
+use crate::error::{Error, Result};
+use crate::tasks::{self, TaskResult, Tasks};
+use crate::widgets::{
+	max_combatants_visible, tracker_row_at, CharacterSheet, CombatantBlock, DebugPanel, GridWidget,
+	Hint, HitPointsGraph, LogPanel, NotesPanel, StatBlock, StatusBar, Toast, Toasts, TrackerWidget,
+};
+use crate::widgets::text_input::push_history;
+use crate::widgets::tracker::{RowCache, FLASH_TICKS};
+use crate::state::{
+	AfterKey, ActionState, ApplyCondition, ApplyDamage, ApplyHealing, CommandLine, Confirm,
+	ConfirmAction, DamageResult, DeclareSpeedFactors, Drawable, EditCombatant, EditHitPoints,
+	EditNotes, GrantTemporaryHitPoints, InputHandler, MoveToken, NotesTarget, PaletteCommand,
+	Search, ShowHelp, ShowLegend, ShowStats,
+};
+use crate::state::{apply_damage, search};
+use crate::config::{Config, ViewMode};
+use crate::layout::Panel;
+use crate::macros::{self, Macro, Recording};
+use crate::pages::Pages;
+use crate::settings::Settings;
+use crate::keymap::{self, Command, HelpSection, LabelCommand, PopupCommand, SheetFormat};
+use crate::plugin::Plugins;
+use crate::input::{EventSource, InputEvent, TerminalEvents};
+use crate::sequence::{InputSequence, Parsed};
 
-use h5t_core::{Combatant, CombatantKind, Tracker};
+use h5t_core::{
+	loot, Changed, Coins, Combatant, CombatantKind, Dismissal, Grapple, Lingering, LogEvent,
+	MonsterSource, MoraleCheck, Orphaned, Outcome, Side, Tracker, TrackerCommand, Triggered,
+	TurnOrder,
+};
 
 use ratatui::prelude::*;
-use crossterm::event::{read, Event, KeyCode, KeyEvent};
+use rand::Rng;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 
-// -- Label Selection -- //
-
-/// Labels used for label mode. The tracker will choose labels from this string in sequential
-/// order.
-///
-/// The sequence of labels is simply the characters on a QWERTY keyboard going column by column.
-/// This keeps labels physically close to each other on the keyboard.
-pub(crate) const LABELS: &str = "qazwsxedcrfvtgbyhnujmik,ol.p;/[']";
-
-/// The label selection state of the tracker.
-#[derive(Copy, Clone, Debug, Default)]
-pub struct LabelSelection {
-	/// None if not in selection mode. <br>
-	/// Some if labels are being selected.
-	selection: [bool; 32],
-}
-
-impl LabelSelection {
-	pub const fn new() -> Self { Self { selection: [false; 32] } }
-	
-	pub const fn label_is_active(&self, index: usize) -> bool {
-		debug_assert!(index < 32);
-		self.selection[index]
-	}
-	
-	pub fn select(&mut self, label: char, label_count: usize) {
-		let Some(index) = Self::label_to_index(label, label_count) else { return };
-		debug_assert!(index < 32);
-		self.selection[index] = !self.selection[index];
-	}
-	
-	/// Converts a label to an index if the label is on screen.
-	///
-	/// label - The label character. <br>
-	/// label_count - The number of labels being displayed.
-	pub const fn label_to_index(label: char, label_count: usize) -> Option<usize> {
-		let index = match label {
-			'q' => 0,
-			'a' => 1,
-			'z' => 2,
-			'w' => 3,
-			's' => 4,
-			'x' => 5,
-			'e' => 6,
-			'd' => 7,
-			'c' => 8,
-			'r' => 9,
-			'f' => 10,
-			'v' => 11,
-			't' => 12,
-			'g' => 13,
-			'b' => 14,
-			'y' => 15,
-			'h' => 16,
-			'n' => 17,
-			'u' => 18,
-			'j' => 19,
-			'm' => 20,
-			'i' => 21,
-			'k' => 22,
-			',' => 23,
-			'l' => 24,
-			'.' => 25,
-			'p' => 26,
-			';' => 27,
-			'/' => 28,
-			'[' => 29,
-			'\'' => 30,
-			']' => 31,
-			_ => return None,
-		};
-		
-		// This ensures that only the labels shown on screen are selectable.
-		if index < label_count { Some(index) } else { None }
-	}
-	
-	/// Converts an index to a label if the label is on screen.
-	///
-	/// index - The label index. <br>
-	/// label_count - The number of labels being displayed.
-	pub const fn index_to_label(index: usize, label_count: usize) -> Option<char> {
-		let label = match index {
-			0 => 'q',
-			1 => 'a',
-			2 => 'z',
-			3 => 'w',
-			4 => 's',
-			5 => 'x',
-			6 => 'e',
-			7 => 'd',
-			8 => 'c',
-			9 => 'r',
-			10 => 'f',
-			11 => 'v',
-			12 => 't',
-			13 => 'g',
-			14 => 'b',
-			15 => 'y',
-			16 => 'h',
-			17 => 'n',
-			18 => 'u',
-			19 => 'j',
-			20 => 'm',
-			21 => 'i',
-			22 => 'k',
-			23 => ',',
-			24 => 'l',
-			25 => '.',
-			26 => 'p',
-			27 => ';',
-			28 => '/',
-			29 => '[',
-			30 => '\'',
-			31 => ']',
-			_ => return None,
-		};
-		
-		// This ensures that only the labels shown on screen are selectable.
-		if index < label_count { Some(label) } else { None }
-	}
-}
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 // -- Info Block -- //
 
@@ -151,306 +65,828 @@ impl InfoBlockMode {
     }
 }
 
-// -- Page Stuff -- //
+// -- Sort Order -- //
 
-#[derive(Clone, Debug, Default)]
-pub struct Page {
-	id: usize, // Page number
-	combatants: Vec<usize>, // Vec of combatant indexes in the tracker.
-	label_selection: Option<Box<LabelSelection>>, // Option<Box<_>> to save space.
-}
-
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-struct PageConfig {
-	page_size: usize,
-	current_page: usize,
+/// The order combatants are listed in the tracker.
+///
+/// This only changes how the tracker is displayed, the turn order is always the tracker's
+/// [`TurnOrder`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum SortOrder {
+    /// Turn order.
+	#[default]
+	Initiative,
+    /// Lowest hit points first.
+	HitPoints,
+    /// Alphabetical order.
+	Name,
+    /// Grouped by side, allies first.
+	Side,
+    /// Grouped by zone, in alphabetical order, then combatants in no zone.
+	Zone,
 }
 
-impl Page {
-	pub fn get_id(&self) -> usize { self.id }
-	
-	pub fn get_combatants(&self) -> &Vec<usize> { &self.combatants }
-	
-	pub fn get_selection(&self) -> Option<&Box<LabelSelection>>{
-		self.label_selection.as_ref()
-	}
-	
-	fn toggle_selection(&mut self, label: char) {
-		if let Some(ref mut select) = self.label_selection {
-			select.select(label, self.combatants.len());
-		} else {
-			let mut select = Box::new(LabelSelection::new());
-			select.select(label, self.combatants.len());
-			self.label_selection = Some(select);
-		}
-	}
-	
-	fn toggle_index(&mut self, index: usize) {
-		if let Some(ref mut select) = self.label_selection {
-			debug_assert!(index < select.selection.len());
-			select.selection[index] = !select.selection[index];
-		} else {
-			let mut select = Box::new(LabelSelection::new());
-			select.selection[index] = true;
-			self.label_selection = Some(select);
-		}
-	}
+impl SortOrder {
+    /// Cycle sort order.
+    pub fn cycle(&mut self) {
+        *self = match self {
+            SortOrder::Initiative => SortOrder::HitPoints,
+            SortOrder::HitPoints => SortOrder::Name,
+            SortOrder::Name => SortOrder::Side,
+            SortOrder::Side => SortOrder::Zone,
+            SortOrder::Zone => SortOrder::Initiative,
+        };
+    }
 	
-	/// Takes the page's label selection
-	fn take_selection(&mut self) -> Option<Box<LabelSelection>> {
-		self.label_selection.take()
-	}
+    /// Returns the name of the sort order shown in the tracker, or `None` for initiative order.
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            SortOrder::Initiative => None,
+            SortOrder::HitPoints => Some("HP"),
+            SortOrder::Name => Some("name"),
+            SortOrder::Side => Some("side"),
+            SortOrder::Zone => Some("zone"),
+        }
+    }
 	
-	fn from_combatants(combatants: &Vec<Combatant>, page_size: usize) -> Vec<Self> {
-		if page_size == 0 { return Vec::new() };
-		
-		let mut pages = Vec::new();
-		let mut count = combatants.len();
-		
-		'printer : loop {
-			let offset = pages.len() * page_size;
-			let space = count.min(page_size);
-			
-			let mut page = Self {
-				id: pages.len(),
-				combatants: Vec::with_capacity(space),
-				label_selection: None,
-			};
-			
-			(offset..(offset + space)).for_each(|i| page.combatants.push(i));
-			
-			pages.push(page);
-			if count > page_size { count -= page_size }
-			else { break 'printer }
+    /// Sorts combatant indexes, given in turn order, into this order. Ties are kept in turn order.
+    fn sort(self, indexes: &mut [usize], combatants: &[Combatant]) {
+        match self {
+            SortOrder::Initiative => (),
+            SortOrder::HitPoints => indexes.sort_by_key(|i| combatants[*i].hit_points),
+            SortOrder::Name => indexes.sort_by_key(|i| combatants[*i].name()),
+            SortOrder::Side => indexes.sort_by_key(|i| combatants[*i].side),
+            SortOrder::Zone => {
+                indexes.sort_by_key(|i| (combatants[*i].zone.is_none(), &combatants[*i].zone))
+            },
+        }
+    }
+}
+
+// -- Events -- //
+
+/// How often [`AppEvent::Tick`] is sent while no input arrives, which is how often timers such as
+/// notifications and animations are updated.
+const TICK_RATE: Duration = Duration::from_millis(100);
+
+/// The most keys macros can play before new input is read. Past it, the macro is assumed to play
+/// itself forever, and is stopped.
+const MAX_REPLAYED_KEYS: usize = 10_000;
+
+/// An event that the UI reacts to.
+#[derive(Debug)]
+pub(crate) enum AppEvent {
+	/// A key was pressed.
+	Key(KeyEvent),
+	/// The mouse was clicked or scrolled.
+	Mouse(MouseEvent),
+	/// Text was pasted.
+	Paste(String),
+	/// The terminal was resized.
+	Resize,
+	/// Work on a worker thread finished.
+	Task(TaskResult),
+	/// No input arrived for [`TICK_RATE`].
+	Tick,
+	/// The input source ran out of input, which stops [`Ui::run`] and cancels label mode.
+	InputEnded,
+}
+
+impl From<InputEvent> for AppEvent {
+	fn from(event: InputEvent) -> Self {
+		match event {
+			InputEvent::Key(key) => Self::Key(key),
+			InputEvent::Mouse(mouse) => Self::Mouse(mouse),
+			InputEvent::Paste(text) => Self::Paste(text),
+			InputEvent::Resize => Self::Resize,
+			InputEvent::Tick => Self::Tick,
+			InputEvent::Ended => Self::InputEnded,
 		}
-		
-		pages
 	}
-	
-	fn from_combatants_and_selection(
-		combatants: &Vec<Combatant>,
-		selections: Vec<usize>,
-		page_size: usize
-	) -> Vec<Self> {
-		if selections.len() == 0 { return Self::from_combatants(combatants, page_size) }
-		if page_size == 0 { return Vec::new() }
-		
-		let mut pages = Vec::new();
-		let mut count = combatants.len();
-		let mut selections = selections.into_iter().peekable();
-		
-		'printer : loop {
-			let offset = pages.len() * page_size;
-			let space = count.min(page_size);
-			
-			let mut page = Self {
-				id: pages.len(),
-				combatants: Vec::with_capacity(space),
-				label_selection: None,
+}
+
+// -- UI Struct -- //
+
+/// Serializes the encounter, to compare it with the last saved version.
+fn snapshot(tracker: &Tracker) -> String {
+	serde_json::to_string(tracker).unwrap_or_default()
+}
+
+/// Describes where the conditions of the combatant with the given index came from, if that was
+/// recorded, and how they end if an effect caused them, such as `Frightened (Frightful Presence
+/// from Dragon, ends on DC 16 WIS save)`.
+fn condition_sources(tracker: &Tracker, index: usize) -> impl Iterator<Item = String> + '_ {
+	let combatant = &tracker.combatants[index];
+	combatant.conditions
+		.iter()
+		.filter_map(move |condition| {
+			let from = condition.source.combatant.map(|source| tracker.combatants[source].name());
+			let mut parts = match (&condition.source.cause, from) {
+				(Some(cause), Some(from)) => vec![format!("{} from {}", cause, from)],
+				(Some(cause), None) => vec![cause.clone()],
+				(None, Some(from)) => vec![format!("from {}", from)],
+				(None, None) => Vec::new(),
 			};
-			
-			(offset..(offset + space)).for_each(|i| page.combatants.push(i));
-			
-			while let Some(index) = selections.peek() {
-				let idx = *index;
-				if idx < offset + page_size {
-					page.toggle_index(idx);
-					selections.next();
-				} else { break }
-			}
-			
-			pages.push(page);
-			
-			if count > page_size { count -= page_size } else { break 'printer }
-		}
-		
-		pages
-	}
+			let save = combatant.effects
+				.iter()
+				.filter(|effect| effect.condition == Some(condition.kind))
+				.find_map(|effect| match &effect.outcome {
+					outcome @ Outcome::Save { .. } => Some(format!("ends on {}", outcome)),
+					_ => None,
+				});
+			parts.extend(save);
+			(!parts.is_empty()).then(|| format!("{} ({})", condition.kind, parts.join(", ")))
+		})
 }
 
-impl PageConfig {
-	fn new<B: Backend>(terminal: &Terminal<B>) -> Self {
-		Self {
-			page_size: max_combatants_visible(terminal.size().unwrap_or_default()),
-			current_page: 0,
-		}
-	}
-	
-	/// Updates the page configuration.
-	///
-	/// Rewrites the pages if the configuration was modified.
-	fn update<B: Backend>(
-		&mut self,
-		pages: &mut Vec<Page>,
-		terminal: &Terminal<B>,
-		tracker: &Tracker,
-	) {
-		let updated_page_size = max_combatants_visible(terminal.size().unwrap_or_default());
-		if self.page_size != updated_page_size {
-			let selections = self.take_page_selections(pages);
-			
-			self.page_size = updated_page_size;
-			
-			*pages = Page::from_combatants_and_selection(
-				&tracker.combatants,
-				selections,
-				updated_page_size,
-			);
-			
-			if self.current_page >= pages.len() {
-				if pages.len() == 0 { self.current_page = 0 }
-				else { self.current_page = pages.len() - 1 }
-			}
-		}
-	}
-	
-	fn take_page_selections(&mut self, pages: &mut Vec<Page>) -> Vec<usize> {
-		let mut selections = Vec::new();
-		
-		let mut iter = 0;
-		
-		for page in pages {
-			if let Some(page_selection) = page.take_selection() {
-				for i in 0..self.page_size {
-					if page_selection.selection[i] {
-						selections.push(iter);
-					}
-					
-					iter += 1;
-				}
+/// Describes the grapples and summons the combatant with the given index is part of, on either
+/// side, and where its conditions came from.
+fn link_lines(tracker: &Tracker, index: usize) -> Vec<String> {
+	let grapples = tracker.grapples
+		.iter()
+		.filter(|grapple| grapple.involves(index))
+		.map(|grapple| {
+			let (holding, held) = match grapple.restrains {
+				true => ("Restraining", "Restrained by"),
+				false => ("Grappling", "Grappled by"),
+			};
+			let mut line = if grapple.grappler == index {
+				format!("{} {}", holding, tracker.combatants[grapple.target].name())
 			} else {
-				iter += self.page_size;
+				format!("{} {}", held, tracker.combatants[grapple.grappler].name())
+			};
+			if let Some(dc) = grapple.escape_dc {
+				line.push_str(&format!(" (escape DC {})", dc));
 			}
-		}
-		
-		
-		selections
-	}
+			line
+		});
+	let summoner = tracker.combatants[index].summoner
+		.map(|summoner| format!("Summoned by {}", tracker.combatants[summoner].name()));
+	let summons = tracker.summons(index)
+		.into_iter()
+		.map(|summon| tracker.combatants[summon].name())
+		.collect::<Vec<_>>();
+	let summons = (!summons.is_empty()).then(|| format!("Summons {}", summons.join(", ")));
+	grapples.chain(summoner).chain(summons).chain(condition_sources(tracker, index)).collect()
 }
 
-// -- UI Struct -- //
-
 /// A wrapper around a [`Tracker`] that handles UI-dependent logic such as label mode.
+///
+/// Input is read from `E`, the terminal unless a test scripts it.
 #[derive(Debug)]
-pub struct Ui<B: Backend> {
+pub struct Ui<B: Backend, E: EventSource = TerminalEvents> {
     /// The display terminal.
     pub terminal: Terminal<B>,
     /// The initiative tracker.
     pub tracker: Tracker,
+	/// User configuration
+	pub config: Config,
+	/// Settings changed at runtime
+	settings: Settings,
+	/// Monsters that can be added with the `add` command
+	compendium: Box<dyn MonsterSource>,
 
-	/// Page configuration style
-	page_config: PageConfig,
-	/// Combatant pages
-	pages: Vec<Page>,
+	/// Combatants shown in the tracker, split into pages, and the ones selected in label mode
+	pages: Pages,
 	/// Whether label selection mode is enabled
 	labels_enabled: bool,
     /// Current info block display mode
 	info_block_mode: InfoBlockMode,
-	/// (optional) Current action being applied
-	action_mode: Option<ActionState>,
+	/// Action states being applied, from the bottom layer to the top one, which takes input. Each
+	/// is drawn over the ones below it
+	states: Vec<ActionState>,
+	/// Confirmation dialogs, drawn over everything else, with the one taking input last
+	modals: Vec<Confirm>,
+	/// (optional) Last action applied to targets, without them, to repeat it on new targets
+	last_action: Option<ActionState>,
+	/// The encounter as it was last saved or loaded, to detect unsaved changes
+	saved: String,
+	/// Whether the encounter changed since it was last autosaved, set by a subscriber to the
+	/// tracker's changes
+	autosave_pending: Arc<AtomicBool>,
+	/// Cells of the combatants' rows, kept between frames
+	row_cache: RefCell<RowCache>,
+	/// Combatants changed since the last frame, whose rows are formatted again, recorded by a
+	/// subscriber to the tracker's changes
+	stale_rows: Arc<Mutex<Vec<Changed>>>,
+	/// When the encounter is next autosaved, if autosaving is on
+	next_autosave: Instant,
+	/// Whether the user confirmed quitting
+	quitting: bool,
+	/// (optional) Combatant under the cursor, shown in the info block instead of the current turn's
+	/// combatant
+	cursor: Option<usize>,
+	/// Count and keys typed on the main screen that don't form a command yet
+	sequence: InputSequence,
+	/// (optional) Macro being recorded from the keys typed
+	recording: Option<Recording>,
+	/// Keys of the macro being played, handled before new input
+	playback: VecDeque<KeyEvent>,
+	/// Number of macro keys handled since input was last read, to stop macros that play
+	/// themselves
+	replayed: usize,
+	/// (optional) Search that filters the combatants shown in the tracker
+	filter: Option<String>,
+	/// Order the combatants are shown in the tracker
+	sort_order: SortOrder,
+	/// Number of lines the combat log is scrolled up from the newest event
+	log_scroll: usize,
+	/// Commands run from the command line, from oldest to newest
+	command_history: Vec<String>,
+	/// Searches made, from oldest to newest
+	search_history: Vec<String>,
+	/// Notifications shown in the corner of the screen, from oldest to newest
+	toasts: Vec<Toast>,
+	/// Number of combat log entries already passed to plugins and checked for notifications
+	log_seen: usize,
+	/// Extensions that add their own modes, or react to combat log events
+	plugins: Plugins,
+	/// Number of ticks since the turn last advanced, while its row is flashing
+	turn_flash: Option<u32>,
+	/// Plain-text descriptions of changes to the encounter, in screen reader mode
+	announcer: Announcer,
+	/// Work running on worker threads, such as saving and loading encounters
+	tasks: Tasks,
+	/// The size of the terminal, as of the last redraw
+	screen: Rect,
+	/// An error that h5t can't carry on after, raised where it can't be returned directly
+	failure: Option<Error>,
+	/// The most recent log lines, shown in the debug panel
+	debug_log: DebugLog,
+	/// Whether the debug panel is shown
+	debug_panel: bool,
+	/// How long the recent frames took to draw, shown in the debug panel
+	frame_times: FrameTimes,
+	/// Whether anything on screen changed since it was last drawn. Events that change nothing,
+	/// such as ignored keys, don't redraw the screen
+	dirty: bool,
+	/// Where input is read from
+	events: E,
 	// (optional) Current label mode
     // label_state: Option<LabelModeState>,
 }
 
-impl<B: Backend> Ui<B> {
-    pub fn new(terminal: Terminal<B>, tracker: Tracker) -> Self {
-		let page_config = PageConfig::new(&terminal);
-		let pages = Page::from_combatants(&tracker.combatants, page_config.page_size);
-		
-        Self {
-            terminal, tracker,
-			page_config, pages,
+impl<B: Backend, E: EventSource> Ui<B, E> {
+    pub fn new(
+		terminal: Terminal<B>,
+		mut tracker: Tracker,
+		mut config: Config,
+		settings: Settings,
+		compendium: Box<dyn MonsterSource>,
+		debug_log: DebugLog,
+		plugins: Plugins,
+	) -> Result<Self>
+	where
+		E: Default,
+	{
+		let frame_area = Rect::from((Position::ORIGIN, terminal.size()?));
+		config.layout.compact = config.is_compact(frame_area);
+		let panels_area = config.panels_area(frame_area);
+		let tracker_area = config.layout.area_of(Panel::Tracker, panels_area).unwrap_or_default();
+		let pages = Pages::new(
+			(0..tracker.combatants.len()).collect(),
+			max_combatants_visible(tracker_area.as_size(), config.layout.compact),
+			config.view,
+		);
+		tracker.rules = config.rules;
+		let saved = snapshot(&tracker);
+		let next_autosave = Instant::now() + Duration::from_secs(config.autosave.interval);
+		
+        let mut ui = Self {
+            terminal, tracker, config, settings, compendium,
+			pages,
 			labels_enabled: false,
             info_block_mode: InfoBlockMode::CombatState,
-            action_mode: None,
+            states: Vec::new(),
+			modals: Vec::new(),
+			last_action: None,
+			autosave_pending: Arc::default(),
+			row_cache: RefCell::default(),
+			stale_rows: Arc::default(),
+			saved,
+			next_autosave,
+			quitting: false,
+			cursor: None,
+			sequence: InputSequence::default(),
+			recording: None,
+			playback: VecDeque::new(),
+			replayed: 0,
+			filter: None,
+			sort_order: SortOrder::default(),
+			log_scroll: 0,
+			command_history: Vec::new(),
+			search_history: Vec::new(),
+			toasts: Vec::new(),
+			log_seen: 0,
+			plugins,
+			turn_flash: None,
+			announcer: Announcer::default(),
+			tasks: Tasks::default(),
+			screen: frame_area,
+			failure: None,
+			debug_log,
+			debug_panel: false,
+			frame_times: FrameTimes::default(),
+			dirty: true,
+			events: E::default(),
             // label_state: None,
-        }
+        };
+		ui.watch_changes();
+		Ok(ui)
     }
 
-    pub fn run(&mut self) {
-		'run_loop : loop {
-			self.page_config.update(&mut self.pages, &self.terminal, &self.tracker);
-			
-            self.draw().unwrap();
+    /// Runs the tracker until the user quits, or until drawing to or reading from the terminal
+    /// fails.
+    pub fn run(&mut self) -> Result<()> {
+		while !self.quitting {
+			if let Some(error) = self.failure.take() {
+				return Err(error);
+			}
 			
-			let key_input = self.get_key_input();
-
-            // Handle any active tracker state.
-            if let Some(mut state) = self.action_mode.take() {
-                match state.handle_key(key_input) {
-                    AfterKey::Exit => state.apply(&mut self.tracker),
-                    AfterKey::Stay => self.action_mode = Some(state),
-                }
-				
-                continue 'run_loop;
-            }
-			
-			// Handle regular input.
-            match key_input.code {
-				KeyCode::Up => // Previous Page
-					if self.page_config.current_page > 0 {
-						self.page_config.current_page -= 1
-					},
-				
-				KeyCode::Down => // Next Page
-					if self.page_config.current_page + 1 < self.pages.len() {
-						self.page_config.current_page += 1
-					},
+			if self.dirty {
+				self.update_page_config()?;
+				if self.config.screen_reader { self.announcer.update(&self.tracker) }
+				self.handle_log_events();
+				self.ask_triggered_effects();
+				self.ask_lingering_conditions();
+				self.ask_orphaned_conditions();
+				self.ask_dismissals();
+				self.ask_morale_checks();
 				
-                KeyCode::Char('c') => {
-                    self.action_mode = Some(ActionState::Condition(ApplyCondition::default()));
-                },
-				
-                KeyCode::Char('d') => {
-                    let selected = self.enter_label_mode();
-                    self.action_mode = Some(ActionState::Damage(ApplyDamage::new(selected)));
-                },
-				
-                KeyCode::Char('a') => { self.tracker.use_action(); }
-                KeyCode::Char('b') => { self.tracker.use_bonus_action(); }
-                KeyCode::Char('r') => { self.tracker.use_reaction(); }
-				
-                KeyCode::Char('s') => self.info_block_mode.toggle(),
-                KeyCode::Char('n') => self.tracker.next_turn(),
-                KeyCode::Char('q') => break 'run_loop,
-				
-                _ => (),
-            }
+				self.draw()?;
+			}
+			
+			let changed = match self.next_event() {
+				// Dialogs and action states take keys before the main screen.
+				AppEvent::Key(key) => {
+					if self.open_help_layer(key)
+						|| self.dispatch(|handler, config| handler.handle_key(key, &config.keymap))
+					{
+						true
+					} else {
+						match self.handle_normal_key(key) {
+							Some(AfterKey::Exit) => break,
+							Some(_) => true,
+							// Counts and unfinished sequences aren't shown, so there's nothing to
+							// draw.
+							None => false,
+						}
+					}
+				},
+				AppEvent::Mouse(mouse) => self.handle_mouse(mouse),
+				// Pasting only does something in states with a text field.
+				AppEvent::Paste(text) => self.dispatch(|handler, _| {
+					handler.handle_paste(&text);
+					AfterKey::Stay
+				}),
+				AppEvent::Resize => true,
+				AppEvent::Task(result) => {
+					self.finish_task(result);
+					true
+				},
+				AppEvent::Tick => self.tick(),
+				AppEvent::InputEnded => break,
+			};
+			self.dirty |= changed;
         }
+		
+		Ok(())
     }
+	
+	/// Passes input to the topmost dialog, or to the topmost action state if no dialog is open,
+	/// and closes it once it's done, handing input back to the layer below.
+	///
+	/// Returns `false` if neither is open, so the input is left for the main screen.
+	fn dispatch(
+		&mut self,
+		handle: impl FnOnce(&mut dyn InputHandler, &Config) -> AfterKey,
+	) -> bool {
+		if let Some(mut modal) = self.modals.pop() {
+			match handle(&mut modal, &self.config) {
+				AfterKey::Exit => self.confirm(modal.into_action()),
+				AfterKey::Stay => self.modals.push(modal),
+				AfterKey::Cancel => (),
+			}
+		} else if let Some(mut state) = self.states.pop() {
+			match handle(&mut state, &self.config) {
+				AfterKey::Exit => self.exit_state(state),
+				AfterKey::Stay => self.states.push(state),
+				AfterKey::Cancel => (),
+			}
+		} else {
+			return false;
+		}
+		
+		true
+	}
+	
+	/// Opens the help overlay over the topmost action state if the key is bound to it, so keys can
+	/// be looked up without leaving the state. Returns whether it was opened.
+	fn open_help_layer(&mut self, key: KeyEvent) -> bool {
+		let open = self.modals.is_empty()
+			&& self.states.last().is_some_and(|state| !matches!(state, ActionState::Help(_)))
+			&& self.config.keymap.popup.get(key) == Some(PopupCommand::Help);
+		if open {
+			self.states.push(ActionState::Help(self.help()));
+		}
+		open
+	}
+	
+	/// Handle a key on the main screen, running the command its key sequence completes.
+	///
+	/// Returns [`AfterKey::Exit`] if the command quits h5t, or `None` if the key doesn't complete a
+	/// command, so nothing on screen changed.
+	fn handle_normal_key(&mut self, key_input: KeyEvent) -> Option<AfterKey> {
+		// Keys bound by plugins open their states, unless they continue a count or a sequence.
+		let target = self.cursor.unwrap_or(self.tracker.turn);
+		if self.sequence.is_empty()
+			&& let Some(state) = self.plugins.create_state(key_input.into(), &self.tracker, target)
+		{
+			self.states.push(ActionState::Plugin(state));
+			return Some(AfterKey::Stay);
+		}
+		
+		// So do the keys of macros.
+		if self.sequence.is_empty()
+			&& let Some(recorded) = self.macro_for(key_input)
+		{
+			self.play_macro(&recorded);
+			return Some(AfterKey::Stay);
+		}
+		
+		let Parsed::Command { command, count } =
+			self.sequence.push(key_input, &self.config.keymap.normal)
+		else { return None };
+		
+		tracing::debug!(?command, ?count, "command");
+		
+		// Commands that move repeat `count` times, others ignore it.
+		let repeat = count.unwrap_or(1);
+		
+        match command {
+			Command::PreviousPage => (0..repeat).for_each(|_| self.previous_page()),
+			
+			Command::NextPage => (0..repeat).for_each(|_| self.next_page()),
+			
+			// With a count, both go to that page instead (`3G` goes to page 3).
+			Command::FirstPage => self.go_to_page(count.map_or(0, |n| n - 1)),
+			Command::LastPage => self.go_to_page(count.map_or(usize::MAX, |n| n - 1)),
+			
+			Command::CursorUp => self.move_cursor(-(repeat as isize)),
+			Command::CursorDown => self.move_cursor(repeat as isize),
+			Command::ClearCursor => self.cursor = None,
+			
+            Command::ApplyCondition => {
+				let Some(mut targets) = self.select_targets() else { return Some(AfterKey::Stay) };
+				if targets.is_empty() { targets.push(self.tracker.turn) }
+				let state = ApplyCondition::new(targets, self.config.keymap.labels())
+					.with_source(self.tracker.turn);
+                self.states.push(ActionState::Condition(state));
+            },
+			
+            Command::ApplyDamage => {
+				let Some(targets) = self.select_targets() else { return Some(AfterKey::Stay) };
+                self.states.push(ActionState::Damage(ApplyDamage::new(targets)));
+            },
+			
+			Command::Heal => {
+				let Some(targets) = self.select_targets() else { return Some(AfterKey::Stay) };
+				self.states.push(ActionState::Heal(ApplyHealing::new(targets)));
+			},
+			
+			Command::GrantTemporaryHitPoints => {
+				let Some(targets) = self.select_targets() else { return Some(AfterKey::Stay) };
+				let state = GrantTemporaryHitPoints::new(targets);
+				self.states.push(ActionState::TemporaryHitPoints(state));
+			},
+			
+			Command::RepeatAction => self.repeat_action(),
+			
+			Command::EditHitPoints => {
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				let state = EditHitPoints::new(index, &self.tracker);
+				self.states.push(ActionState::HitPoints(state));
+			},
+			
+			Command::Stabilize => {
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				self.tracker.execute(TrackerCommand::Stabilize { index });
+			},
+			
+			Command::Kill => {
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				if self.tracker.combatants[index].dead { return Some(AfterKey::Stay) }
+				let message = format!("Mark {} as dead?", self.tracker.combatants[index].name());
+				self.modals.push(Confirm::new("Kill", message, ConfirmAction::Kill(index)));
+			},
+			
+			Command::EditCombatant => {
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				let state = EditCombatant::new(index, &self.tracker);
+				self.states.push(ActionState::EditCombatant(state));
+			},
+			
+			// The grid is shown to see where the token goes.
+			Command::MoveToken if self.config.layout.compact => {
+				self.toast("The screen is too small for the grid panel");
+			},
+			Command::MoveToken => {
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				if !self.is_shown(Panel::Grid) {
+					self.config.layout.toggle(Panel::Grid);
+				}
+				self.states.push(ActionState::Token(MoveToken::new(index, &self.tracker)));
+			},
+			
+            Command::UseAction => { self.tracker.execute(TrackerCommand::UseAction); }
+            Command::UseBonusAction => { self.tracker.execute(TrackerCommand::UseBonusAction); }
+            Command::UseReaction => { self.tracker.execute(TrackerCommand::UseReaction); }
+			
+            Command::ToggleInfoBlock => self.info_block_mode.toggle(),
+			Command::ToggleInfoPanel => self.config.layout.toggle(Panel::Info),
+			Command::ToggleLogPanel => self.config.layout.toggle(Panel::Log),
+			Command::ToggleNotesPanel => self.config.layout.toggle(Panel::Notes),
+			Command::ToggleGridPanel => self.config.layout.toggle(Panel::Grid),
+			Command::ToggleGraphPanel => self.config.layout.toggle(Panel::Graph),
+			Command::ToggleStacking => self.config.layout.toggle_stacking(),
+			Command::GrowTracker => self.resize_tracker(5 * repeat as i16),
+			Command::ShrinkTracker => self.resize_tracker(-5 * repeat as i16),
+			Command::CycleSortOrder => {
+				self.sort_order.cycle();
+				self.refresh_pages();
+			},
+            Command::NextTurn if self.tracker.turn_order == TurnOrder::Popcorn => {
+				if self.pass_turn() { self.turn_passed() }
+			},
+            Command::NextTurn => {
+				let mut passed = 0;
+				while passed < repeat {
+					let (_, new_round) = self.tracker.next_in_sequence(self.tracker.turn);
+					if new_round && self.tracker.turn_order == TurnOrder::SpeedFactor {
+						// everyone declares before initiative is rolled for the next round
+						let labels = self.config.keymap.labels();
+						let state = DeclareSpeedFactors::new(&self.tracker, labels);
+						self.states.push(ActionState::SpeedFactors(state));
+						break;
+					}
+					self.tracker.execute(TrackerCommand::NextTurn);
+					passed += 1;
+				}
+				if passed > 0 { self.turn_passed() }
+			},
+			Command::ToggleInspiration => {
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				let inspiration = !self.tracker.combatants[index].inspiration;
+				if !self.tracker.execute(TrackerCommand::SetInspiration { index, inspiration }) {
+					self.toast("Only player characters have Inspiration");
+				}
+			},
+			Command::EditNotes => {
+				let target = NotesTarget::Combatant(self.cursor.unwrap_or(self.tracker.turn));
+				let notes = EditNotes::new(target, &self.tracker);
+				self.states.push(ActionState::Notes(notes));
+			},
+			Command::EditEncounterNotes => {
+				let notes = EditNotes::new(NotesTarget::Encounter, &self.tracker);
+				self.states.push(ActionState::Notes(notes));
+			},
+			Command::Search => {
+				let query = self.filter.clone().unwrap_or_default();
+				let search = Search::new(query, self.search_history.clone());
+				self.states.push(ActionState::Search(search));
+			},
+			Command::OpenCommandLine => {
+				let line = CommandLine::new(self.monster_names())
+					.with_macros(self.macro_names())
+					.with_history(self.command_history.clone());
+				self.states.push(ActionState::Command(line));
+			},
+			Command::ShowHelp => self.states.push(ActionState::Help(self.help())),
+			Command::ShowConditionLegend => {
+				self.states.push(ActionState::Legend(ShowLegend(self.tracker.rules.edition)));
+			},
+			Command::RemoveCombatant => {
+				if self.tracker.combatants.len() <= 1 { return Some(AfterKey::Stay) }
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				let message = format!(
+					"Remove {} from the combat?",
+					self.tracker.combatants[index].name(),
+				);
+				let action = ConfirmAction::RemoveCombatant(index);
+				self.modals.push(Confirm::new("Remove Combatant", message, action));
+			},
+			Command::Quit if self.has_unsaved_changes() => {
+				let message = "The encounter has unsaved changes.\nQuit anyway?";
+				self.modals.push(Confirm::new("Quit", message, ConfirmAction::Quit));
+			},
+			Command::StopRecording => self.stop_recording(),
+			
+			Command::ToggleDebugPanel => self.debug_panel = !self.debug_panel,
+			
+            Command::Quit => return Some(AfterKey::Exit),
+        }
+		
+		Some(AfterKey::Stay)
+	}
 
-    pub fn draw(&'_ mut self) -> std::io::Result<ratatui::CompletedFrame<'_>> {
+    /// Draws the screen, and records how long it took for the debug panel.
+    pub fn draw(&mut self) -> Result<()> {
+		// counted when the pages are built, since filtering and sorting every frame is slow in big
+		// fights
+		let visible = self.pages.visible().len();
+		self.invalidate_rows();
+		
+		let scroll = match self.config.view {
+			ViewMode::Pages => None,
+			ViewMode::Scroll => Some((self.pages.first_row(), visible)),
+		};
+		let (mode, hints) = self.status();
+		let flash = self.turn_flash.is_some_and(|tick| tick % 2 == 0);
+		let debug_lines = match self.debug_panel {
+			true => self.debug_log.recent(DebugPanel::HEIGHT as usize),
+			false => Vec::new(),
+		};
+		
+		let start = Instant::now();
         self.terminal.draw(|frame| {
-            let layout = Layout::horizontal([
-                Constraint::Percentage(50),
-                Constraint::Percentage(50),
-            ]).split(frame.area());
-            let [tracker_area, info_area] = [layout[0], layout[1]];
-			
-			let tracker_widget = TrackerWidget::new(
-				&self.tracker,
-				self.pages.get(self.page_config.current_page),
-				self.labels_enabled,
-			);
+			for (panel, area) in self.config.layout.areas(self.config.panels_area(frame.area())) {
+				match panel {
+					Panel::Tracker => {
+						let tracker_widget = TrackerWidget::new(
+							&self.tracker,
+							self.pages.shown(),
+							self.labels_enabled,
+							&self.config.theme,
+						)
+							.with_filter(self.filter.as_deref())
+							.with_sort_order(self.sort_order.label())
+							.with_scroll(scroll)
+							.with_page_count(self.pages.count(), visible)
+							.with_flash(flash)
+							.with_cursor(self.cursor)
+							.with_compact(self.config.layout.compact)
+							.with_columns(&self.config.columns)
+							.with_labels(self.config.keymap.labels())
+							.with_row_cache(&self.row_cache);
+						
+						frame.render_widget(tracker_widget, area);
+					},
+					
+					Panel::Info => {
+						let index = self.cursor.unwrap_or(self.tracker.turn);
+						let combatant = &self.tracker.combatants[index];
+						let theme = &self.config.theme;
+						
+						match self.info_block_mode {
+							InfoBlockMode::CombatState => {
+								let block = CombatantBlock::new(combatant, theme)
+									.with_links(link_lines(&self.tracker, index))
+									.with_edition(self.tracker.rules.edition);
+								frame.render_widget(block, area);
+							},
+							
+							InfoBlockMode::Stats => match &combatant.kind {
+								CombatantKind::Monster(monster) =>
+									frame.render_widget(StatBlock::new(monster, theme), area),
+								CombatantKind::Character(character) =>
+									frame.render_widget(CharacterSheet::new(character, theme), area),
+							},
+						}
+					},
+					
+					Panel::Log => {
+						let log = LogPanel::new(&self.tracker.log, self.log_scroll, &self.config.theme);
+						frame.render_widget(log, area);
+					},
+					
+					Panel::Notes => {
+						let index = self.cursor.unwrap_or(self.tracker.turn);
+						let combatant = &self.tracker.combatants[index];
+						let theme = &self.config.theme;
+						let widget = NotesPanel::new(combatant, &self.tracker.notes, theme);
+						frame.render_widget(widget, area);
+					},
+					
+					// the token being moved is drawn where it is going
+					Panel::Grid => {
+						let (focus, moving) = match self.states.last() {
+							Some(ActionState::Token(state)) => (state.combatant(), Some(state.squares())),
+							_ => (self.cursor.unwrap_or(self.tracker.turn), None),
+						};
+						let widget = GridWidget::new(&self.tracker, focus, &self.config.theme)
+							.with_moving(moving);
+						frame.render_widget(widget, area);
+					},
+					
+					// the cursor's combatant, or the player characters without a cursor
+					Panel::Graph => {
+						let (name, indices) = match self.cursor {
+							Some(index) => (self.tracker.combatants[index].name(), vec![index]),
+							None => {
+								let party = self.tracker.combatants
+									.iter()
+									.enumerate()
+									.filter(|(_, c)| matches!(c.kind, CombatantKind::Character(_)))
+									.map(|(index, _)| index)
+									.collect();
+								("Party", party)
+							},
+						};
+						let max = indices
+							.iter()
+							.map(|&index| self.tracker.combatants[index].max_hit_points())
+							.sum();
+						let points = self.tracker.hit_points_by_round(indices);
+						let widget = HitPointsGraph::new(name, points, max, &self.config.theme);
+						frame.render_widget(widget, area);
+					},
+				}
+			}
 			
-			frame.render_widget(tracker_widget, tracker_area);
+			frame.render_widget(Toasts::new(&self.toasts, &self.config.theme), frame.area());
 			
-            let combatant = self.tracker.current_combatant();
+			if self.debug_panel {
+				let panel = DebugPanel::new(&debug_lines, &self.frame_times, &self.config.theme);
+				frame.render_widget(panel, self.config.panels_area(frame.area()));
+			}
 			
-			match self.info_block_mode {
-				InfoBlockMode::CombatState =>
-					frame.render_widget(CombatantBlock::new(combatant), info_area),
-				
-				InfoBlockMode::Stats => {
-					// TEMP Need to expand this for other combatant kinds
-					let CombatantKind::Monster(monster) = &combatant.kind;
-					frame.render_widget(StatBlock::new(monster), info_area);
+			if self.config.status_bar {
+				let area = frame.area();
+				let height = area.height.min(1);
+				let area = Rect { y: area.bottom() - height, height, ..area };
+				frame.render_widget(StatusBar::new(mode, &hints, &self.config.theme), area);
+			}
+			
+			if self.config.screen_reader {
+				let area = self.config.panels_area(frame.area());
+				let line = Rect { y: area.bottom(), height: 1, ..area }.intersection(frame.area());
+				let message = self.announcer.message();
+				frame.render_widget(Line::raw(message), line);
+				// screen readers follow the cursor, so leave it on the announcement unless a text
+				// field or dialog is open
+				if self.states.is_empty() && self.modals.is_empty() && !line.is_empty() {
+					let x = (line.x + Line::raw(message).width() as u16).min(line.right() - 1);
+					frame.set_cursor_position(Position::new(x, line.y));
 				}
 			}
 			
-            let Some(state) = self.action_mode.as_ref() else { return };
-            state.draw(frame);
-        })
+			// the action states from the bottom layer up, then the dialogs from oldest to newest
+			let overlays = self.states
+				.iter()
+				.map(|state| state as &dyn Drawable)
+				.chain(self.modals.iter().map(|modal| modal as &dyn Drawable));
+			for overlay in overlays {
+				overlay.draw(frame, &self.config.theme);
+			}
+			
+			self.config.theme.strip_colors(frame.buffer_mut());
+        })?;
+		self.frame_times.record(start.elapsed());
+		self.dirty = false;
+		
+		Ok(())
+    }
+
+    /// Returns the name of the current mode and its most useful keys, for the status bar.
+    fn status(&self) -> (&'static str, Vec<Hint>) {
+		let keymap = &self.config.keymap;
+		if let Some(modal) = self.modals.last() {
+			return ("CONFIRM", modal.hints(keymap));
+		}
+		if let Some(state) = self.states.last() {
+			return state.status(keymap);
+		}
+		
+		if self.labels_enabled {
+			let label = |command, label| Hint::bound(&keymap.label, command, label);
+			let hints = [
+				Some(Hint::new("a-z", "toggle")),
+				label(LabelCommand::Confirm, "confirm"),
+				label(LabelCommand::Cancel, "cancel"),
+				label(LabelCommand::SelectAll, "select all"),
+				label(LabelCommand::InvertSelection, "invert"),
+				label(LabelCommand::NextPage, "next page"),
+			];
+			return ("SELECT", hints.into_iter().flatten().collect());
+		}
+		
+		let normal = |command, label| Hint::bound(&keymap.normal, command, label);
+		if self.recording.is_some() {
+			let hints = [
+				normal(Command::StopRecording, "stop recording"),
+				normal(Command::ShowHelp, "help"),
+			];
+			return ("RECORD", hints.into_iter().flatten().collect());
+		}
+		let hints = [
+			normal(Command::NextTurn, "next turn"),
+			normal(Command::ApplyDamage, "damage"),
+			normal(Command::ApplyCondition, "condition"),
+			normal(Command::OpenCommandLine, "command"),
+			normal(Command::ShowHelp, "help"),
+			normal(Command::Quit, "quit"),
+		];
+		("NORMAL", hints.into_iter().flatten().collect())
     }
 
     /// Enters label mode.
@@ -460,90 +896,1299 @@ impl<B: Backend> Ui<B> {
     /// the user can press the corresponding key to toggle the label on or off.
     ///
     /// This function blocks until the user selects the combatants and presses the `Enter` key,
-    /// returning mutable references to the selected combatants.
-    pub fn enter_label_mode(&mut self) -> Vec<usize> {
+    /// returning the indexes of the selected combatants, or `None` if the selection was cancelled.
+    pub fn enter_label_mode(&mut self) -> Option<Vec<usize>> {
 		// If there aren't pages, no selections can be made.
-		if self.pages.len() == 0 { return Vec::new() }
+		if self.pages.count() == 0 { return Some(Vec::new()) }
 		
 		self.labels_enabled = true;
+		self.dirty = true;
+		let mut next_group = 0;
+		let mut next_zone = 0;
 		
-        'select_loop: loop {
-            self.draw().unwrap();
-			
-			let key_input = self.get_key_input();
-			
-			match key_input.code {
-				KeyCode::Enter => // Confirm Selections
-					break 'select_loop,
-				
-				KeyCode::Esc => // Cancel Selections
-					return Vec::new(),
-				
-				KeyCode::Up => // Previous Page
-					if self.page_config.current_page > 0 {
-						self.page_config.current_page -= 1
-					},
-				
-				KeyCode::Down => // Next Page
-					if self.page_config.current_page + 1 < self.pages.len() {
-						self.page_config.current_page += 1
-					},
-				
-				KeyCode::Char(label) =>
-					self.pages[self.page_config.current_page].toggle_selection(label),
-				
-				_ => (),
+        loop {
+			if self.dirty && let Err(error) = self.redraw() {
+				// give up on the selection, the main loop stops with the error
+				self.labels_enabled = false;
+				self.failure = Some(error);
+				return None;
 			}
+			
+			let changed = match self.next_event() {
+				AppEvent::Key(key) => {
+					match self.handle_label_key(key, &mut next_group, &mut next_zone) {
+						AfterKey::Stay => true,
+						AfterKey::Exit => break,
+						AfterKey::Cancel => return None,
+					}
+				},
+				// clicking a row toggles it, like its label
+				AppEvent::Mouse(mouse) => self.handle_scroll(mouse) || self.handle_label_click(mouse),
+				AppEvent::Paste(_) => false,
+				AppEvent::Resize => true,
+				AppEvent::Task(result) => {
+					self.finish_task(result);
+					true
+				},
+				AppEvent::Tick => self.tick(),
+				AppEvent::InputEnded => {
+					self.cancel_label_mode();
+					return None;
+				},
+			};
+			self.dirty |= changed;
         }
 		
 		self.labels_enabled = false;
 		
-		// Collect selections from pages.
-		let mut final_selection = Vec::new();
-		for page in &mut self.pages {
-			let Some(selections) = page.take_selection() else { continue };
+		Some(self.pages.take_selection())
+    }
+	
+	/// Leaves label mode, clearing the selection.
+	fn cancel_label_mode(&mut self) {
+		self.labels_enabled = false;
+		self.pages.clear_selection();
+	}
+	
+	/// Handle a key in label mode. `next_group` and `next_zone` are the indexes of the group and
+	/// the zone to select next.
+	///
+	/// Returns [`AfterKey::Exit`] once the selection is confirmed, or [`AfterKey::Cancel`] once
+	/// it is cancelled and cleared.
+	fn handle_label_key(
+		&mut self,
+		key_input: KeyEvent,
+		next_group: &mut usize,
+		next_zone: &mut usize,
+	) -> AfterKey {
+		match self.config.keymap.label.get(key_input) {
+			Some(LabelCommand::Confirm) => // Confirm Selections
+				return AfterKey::Exit,
+			
+			Some(LabelCommand::Cancel) => { // Cancel Selections
+				self.cancel_label_mode();
+				return AfterKey::Cancel
+			},
+			
+			Some(LabelCommand::PreviousPage) => self.previous_page(),
+			
+			Some(LabelCommand::NextPage) => self.next_page(),
+			
+			Some(LabelCommand::SelectPage) => self.pages.select_shown(),
+			
+			Some(LabelCommand::SelectAll) => self.pages.select_all(),
+			
+			Some(LabelCommand::CycleGroup) => {
+				let Some((name, members)) = self.tracker.groups
+					.iter()
+					.nth(*next_group % self.tracker.groups.len().max(1))
+					.map(|(name, members)| (name.clone(), members.clone()))
+				else {
+					self.toast("No groups saved, save one with :group <name>");
+					return AfterKey::Stay;
+				};
+				*next_group += 1;
+				self.pages.select(&members);
+				self.toast(format!("Selected group {}", name));
+			},
+			
+			Some(LabelCommand::CycleZone) => {
+				let zones = self.tracker.zones();
+				let Some(zone) = zones.get(*next_zone % zones.len().max(1)).map(|z| z.to_string())
+				else {
+					self.toast("No combatant is in a zone, move them with :zone <name>");
+					return AfterKey::Stay;
+				};
+				*next_zone += 1;
+				let members = self.tracker.in_zone(&zone);
+				self.pages.select(&members);
+				self.toast(format!("Selected zone {} ({})", zone, members.len()));
+			},
+			
+			Some(LabelCommand::InvertSelection) => self.pages.invert_selection(),
+			
+			Some(LabelCommand::SelectLast) => if !self.pages.select_last() {
+				self.toast("No targets picked yet");
+			},
+			
+			None => if let KeyCode::Char(label) = key_input.code {
+				let labels = self.config.keymap.labels();
+				self.pages.toggle_label(labels, label)
+			},
+		}
+		
+		AfterKey::Stay
+	}
+	
+	/// Selects the targets of an action through label mode. If no combatant is selected, the
+	/// combatant under the cursor is the target.
+	///
+	/// Returns `None` if the selection was cancelled.
+	fn select_targets(&mut self) -> Option<Vec<usize>> {
+		let targets = self.enter_label_mode()?;
+		match self.cursor {
+			Some(cursor) if targets.is_empty() => Some(vec![cursor]),
+			_ => Some(targets),
+		}
+	}
+	
+	/// Applies the last action applied to targets again, with the same amount, dice, or
+	/// conditions, to targets selected through label mode.
+	fn repeat_action(&mut self) {
+		if self.last_action.is_none() {
+			self.toast("No action to repeat yet");
+			return;
+		}
+		let Some(targets) = self.select_targets() else { return };
+		if targets.is_empty() { return }
+		
+		let action = self.last_action.as_ref().and_then(|action| action.retarget(targets));
+		if let Some(action) = action {
+			self.exit_state(action);
+		}
+	}
+	
+	/// Asks the current combatant who goes next in popcorn initiative, through label mode, and
+	/// passes the turn to them. Returns `false` if the turn wasn't passed.
+	fn pass_turn(&mut self) -> bool {
+		let Some(targets) = self.select_targets() else { return false };
+		let &[next] = targets.as_slice() else {
+			self.toast("Pick one combatant to go next");
+			return false;
+		};
+		if !self.tracker.execute(TrackerCommand::PassTurn { next }) {
+			let name = self.tracker.combatants[next].name();
+			self.toast(format!("{} already acted this round", name));
+			return false;
+		}
+		self.refresh_pages();
+		true
+	}
+	
+	/// Shows what damage did to each of its targets, if there were several of them.
+	fn show_damage_results(&mut self, results: &[DamageResult]) {
+		if results.len() < 2 { return }
+		let message = results
+			.iter()
+			.map(|result| result.describe(&self.tracker))
+			.collect::<Vec<_>>()
+			.join("\n");
+		self.modals.push(Confirm::alert("Damage Results", message));
+	}
+	
+	/// Applies a state that was exited. Commands typed into the command line are run here, since
+	/// they can do more than modify the tracker.
+	fn exit_state(&mut self, state: ActionState) {
+		if let Some(action) = state.retarget(Vec::new()) {
+			self.last_action = Some(action);
+		}
+		
+		let line = match state {
+			ActionState::Command(line) => line,
+			
+			// An empty search clears the filter.
+			ActionState::Search(search) => {
+				let query = search.query().trim();
+				push_history(&mut self.search_history, query);
+				self.filter = if query.is_empty() { None } else { Some(query.to_string()) };
+				self.pages.go_to(0);
+				self.refresh_pages();
+				return;
+			},
+			
+			// Damage to several targets is summed up, since each may take it differently.
+			ActionState::Damage(damage) => {
+				let results = damage.apply(&mut self.tracker);
+				self.show_damage_results(&results);
+				return;
+			},
+			
+			// The round starts once everyone declared.
+			ActionState::SpeedFactors(declare) => {
+				declare.apply(&mut self.tracker);
+				self.refresh_pages();
+				self.turn_passed();
+				return;
+			},
+			
+			state => {
+				state.apply(&mut self.tracker);
+				return;
+			},
+		};
+		
+		let input = line.input().to_string();
+		let Some(command) = line.into_command() else { return };
+		push_history(&mut self.command_history, &input);
+		
+		// Reopen the command line with the error so the command can be fixed.
+		if let Err(error) = self.run_command(command) {
+			let line = CommandLine::with_error(self.monster_names(), input, error)
+				.with_macros(self.macro_names())
+				.with_history(self.command_history.clone());
+			self.states.push(ActionState::Command(line));
+		}
+	}
+	
+	/// Runs a command typed into the command line.
+	fn run_command(&mut self, command: PaletteCommand) -> Result<(), String> {
+		match command {
+			PaletteCommand::Damage { amount, kind } => {
+				let targets = self.select_targets().unwrap_or_default();
+				let tracker = &mut self.tracker;
+				let results = apply_damage::damage_targets(tracker, &targets, amount, kind);
+				self.show_damage_results(&results);
+			},
+			
+			PaletteCommand::Add { monster, count } => {
+				self.add_monsters(&monster, count)?;
+			},
 			
-			for i in 0..page.combatants.len() {
-				if selections.selection[i] {
-					final_selection.push(i + page.id * self.page_config.page_size)
+			PaletteCommand::Summon { monster: Some(monster), count } => {
+				let summoner = self.cursor.unwrap_or(self.tracker.turn);
+				let side = self.tracker.combatants[summoner].side;
+				let zone = self.tracker.combatants[summoner].zone.clone();
+				for index in self.add_monsters(&monster, count)? {
+					self.tracker.execute(TrackerCommand::SetSide { index, side });
+					self.tracker.execute(TrackerCommand::SetZone { index, zone: zone.clone() });
+					let summoner = Some(summoner);
+					self.tracker.execute(TrackerCommand::SetSummoner { index, summoner });
 				}
-			}
+			},
+			
+			PaletteCommand::Summon { monster: None, .. } => {
+				let summoner = Some(self.cursor.unwrap_or(self.tracker.turn));
+				let Some(summons) = self.enter_label_mode() else { return Ok(()) };
+				for index in summons {
+					self.tracker.execute(TrackerCommand::SetSummoner { index, summoner });
+				}
+			},
+			
+			PaletteCommand::Exhaustion(level) => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::SetExhaustion { index, level });
+				}
+			},
+			
+			PaletteCommand::Surprise(surprised) => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::SetSurprised { index, surprised });
+				}
+			},
+			
+			PaletteCommand::Minion(minion) => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::SetMinion { index, minion });
+				}
+			},
+			
+			PaletteCommand::Leader(leader) => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::SetLeader { index, leader });
+				}
+			},
+			
+			PaletteCommand::Flee(fleeing) => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::SetFleeing { index, fleeing });
+				}
+			},
+			
+			PaletteCommand::Record { key, name } => {
+				if self.recording.is_some() {
+					return Err("already recording a macro, stop it first".to_string());
+				}
+				let stop = self.config.keymap.normal
+					.keys_for(Command::StopRecording)
+					.first()
+					.map_or("(unbound)".to_string(), |keys| keys.to_string());
+				self.toast(format!("Recording macro {}, press {} to stop", name, stop));
+				self.recording = Some(Recording::new(name, key));
+			},
+			
+			PaletteCommand::Macro(name) => {
+				let Some(recorded) = self.config.macros.iter().find(|m| m.name == name).cloned()
+				else { return Err(format!("no macro named `{}`", name)) };
+				self.play_macro(&recorded);
+			},
+			
+			PaletteCommand::Mob { attack, kind } => {
+				// the first target is asked about first, so its dialog goes on top
+				let targets = self.enter_label_mode().unwrap_or_default();
+				for index in targets.into_iter().rev() {
+					let target = &self.tracker.combatants[index];
+					let armor_class = target.armor_class();
+					let target = format!("{} (AC {})", target.name(), armor_class);
+					let Some(amount) = attack.damage(armor_class) else {
+						let attackers = attack.attackers;
+						self.toast(format!("None of the {} attackers hit {}", attackers, target));
+						continue;
+					};
+					let odds = match attack.attackers_per_hit(armor_class) {
+						1 => "every one hits".to_string(),
+						per_hit => format!("1 in {} hits", per_hit),
+					};
+					let message = format!(
+						"{} attackers need {} to hit {}: {}.\nRoll {} hits for {} damage?",
+						attack.attackers,
+						attack.roll_needed(armor_class),
+						target,
+						odds,
+						attack.hits(armor_class),
+						amount,
+					);
+					let action = ConfirmAction::RollDamage { index, amount, kind };
+					self.modals.push(Confirm::new("Mob Attack", message, action));
+				}
+			},
+			
+			PaletteCommand::Concentrate(concentrating) => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::SetConcentration { index, concentrating });
+				}
+			},
+			
+			PaletteCommand::Save(path) if path.exists() => {
+				let message = format!("{} already exists.\nOverwrite it?", path.display());
+				self.modals.push(Confirm::new("Save", message, ConfirmAction::Overwrite(path)));
+			},
+			
+			PaletteCommand::Save(path) => self.save(path)?,
+			
+			PaletteCommand::Keys(path) if path.exists() => {
+				let message = format!("{} already exists.\nOverwrite it?", path.display());
+				let action = ConfirmAction::OverwriteKeys(path);
+				self.modals.push(Confirm::new("Key Bindings", message, action));
+			},
+			
+			PaletteCommand::Keys(path) => self.write_cheat_sheet(&path)?,
+			
+			PaletteCommand::Side(side) => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::SetSide { index, side });
+				}
+				self.refresh_pages();
+			},
+			
+			PaletteCommand::TurnOrder(turn_order) => {
+				self.tracker.execute(TrackerCommand::SetTurnOrder(turn_order));
+				self.refresh_pages();
+			},
+			
+			PaletteCommand::CombineTurns(combined) => {
+				self.tracker.execute(TrackerCommand::SetCombinedTurns(combined));
+			},
+			
+			PaletteCommand::RollSideInitiative => {
+				// each side rolls a d20, rerolling ties, and neutral combatants go last
+				let mut rng = rand::rng();
+				let (allies, enemies) = loop {
+					let rolls = (rng.random_range(1..=20), rng.random_range(1..=20));
+					if rolls.0 != rolls.1 { break rolls }
+				};
+				let sides = if allies > enemies {
+					vec![Side::Ally, Side::Enemy]
+				} else {
+					vec![Side::Enemy, Side::Ally]
+				};
+				self.toast(format!(
+					"Allies rolled {}, enemies rolled {}: {}s go first",
+					allies,
+					enemies,
+					sides[0].to_string().to_lowercase(),
+				));
+				self.tracker.execute(TrackerCommand::SetTurnOrder(TurnOrder::Sides(sides)));
+				self.refresh_pages();
+			},
+			
+			PaletteCommand::Group(name) => {
+				let Some(members) = self.enter_label_mode() else { return Ok(()) };
+				let count = members.len();
+				self.tracker.execute(TrackerCommand::SaveGroup { name: name.clone(), members });
+				if count == 0 {
+					self.toast(format!("Deleted group {}", name));
+				} else {
+					self.toast(format!("Saved group {} ({})", name, count));
+				}
+			},
+			
+			PaletteCommand::Zone(zone) => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::SetZone { index, zone: zone.clone() });
+				}
+			},
+			
+			PaletteCommand::Xp => self.show_xp_award("XP Award"),
+			
+			PaletteCommand::Stats => {
+				self.states.push(ActionState::Stats(ShowStats::new(&self.tracker)));
+			},
+			
+			PaletteCommand::Loot { hoard } => self.roll_loot(hoard),
+			
+			PaletteCommand::PassTime(seconds) => {
+				self.tracker.execute(TrackerCommand::PassTime { seconds });
+			},
+			
+			PaletteCommand::Distance => {
+				let from = self.cursor.unwrap_or(self.tracker.turn);
+				if self.tracker.combatants[from].position.is_none() {
+					let name = self.tracker.combatants[from].name();
+					self.toast(format!("{} is not on the grid", name));
+					return Ok(());
+				}
+				let targets = self.select_targets().unwrap_or_default();
+				if targets.is_empty() { return Ok(()) }
+				let distances = targets
+					.into_iter()
+					.map(|index| {
+						let name = self.tracker.combatants[index].name();
+						match self.tracker.distance(from, index) {
+							Some(feet) => format!("{}: {} ft.", name, feet),
+							None => format!("{}: not on the grid", name),
+						}
+					})
+					.collect::<Vec<_>>()
+					.join(", ");
+				self.toast(distances);
+			},
+			
+			PaletteCommand::Effect(mut effect) => {
+				effect.source.combatant = Some(self.tracker.turn);
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::AddEffect { index, effect: effect.clone() });
+				}
+			},
+			
+			PaletteCommand::Regenerate(on) => {
+				for index in self.select_targets().unwrap_or_default() {
+					let suppressed = !on;
+					self.tracker.execute(TrackerCommand::SuppressRegeneration { index, suppressed });
+				}
+			},
+			
+			PaletteCommand::Grapple { restrains, escape_dc } => {
+				let grappler = self.cursor.unwrap_or(self.tracker.turn);
+				let Some(targets) = self.enter_label_mode() else { return Ok(()) };
+				for target in targets {
+					let grapple = Grapple { grappler, target, restrains, escape_dc };
+					self.tracker.execute(TrackerCommand::Grapple(grapple));
+				}
+			},
+			
+			PaletteCommand::Release => {
+				for index in self.select_targets().unwrap_or_default() {
+					self.tracker.execute(TrackerCommand::Release { index });
+				}
+			},
+			
+			PaletteCommand::ShortRest { hit_dice } => {
+				let mut rng = rand::rng();
+				for index in self.select_targets().unwrap_or_default() {
+					let combatant = &self.tracker.combatants[index];
+					let hit_dice = hit_dice.min(combatant.hit_dice_left());
+					let healing = match &combatant.kind {
+						CombatantKind::Character(character) if hit_dice > 0 => character
+							.hit_dice_roll(hit_dice)
+							.roll(|sides| rng.random_range(1..=sides)),
+						_ => 0,
+					};
+					self.tracker.execute(TrackerCommand::ShortRest { index, hit_dice, healing });
+				}
+			},
+			
+			PaletteCommand::LongRest => {
+				let targets = self.select_targets().unwrap_or_default();
+				if targets.is_empty() { return Ok(()) }
+				let names = targets
+					.iter()
+					.map(|&index| self.tracker.combatants[index].name())
+					.collect::<Vec<_>>()
+					.join(", ");
+				let message = format!(
+					"Have {} finish a long rest?\nThey regain their hit points and half their hit \
+					dice.",
+					names,
+				);
+				let action = ConfirmAction::LongRest(targets);
+				self.modals.push(Confirm::new("Long Rest", message, action));
+			},
+			
+			PaletteCommand::Initiative(value) => {
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				let value = Some(value);
+				self.tracker.execute(TrackerCommand::SetInitiative { index, value });
+			},
+			
+			PaletteCommand::RollInitiative => {
+				let mut rng = rand::rng();
+				let edition = self.tracker.rules.edition;
+				for index in self.select_targets().unwrap_or_default() {
+					let value = self.tracker.combatants[index]
+						.roll_initiative(edition, |sides| rng.random_range(1..=sides));
+					let value = Some(value);
+					self.tracker.execute(TrackerCommand::SetInitiative { index, value });
+				}
+			},
+			
+			PaletteCommand::InitiativeModifiers(modifiers) => {
+				let index = self.cursor.unwrap_or(self.tracker.turn);
+				self.tracker.execute(TrackerCommand::SetInitiativeModifiers { index, modifiers });
+			},
+			
+			PaletteCommand::Load(path) => {
+				tracing::info!(path = %path.display(), "loading encounter");
+				self.tasks.spawn(move || tasks::load(path));
+			},
 		}
 		
-		final_selection
-    }
+		Ok(())
+	}
 	
-	fn get_key_input(&mut self) -> KeyEvent {
-		'get_key_input: loop {
-			let Ok(event) = read() else { continue 'get_key_input };
-			match event {
-				Event::Key(key) => break 'get_key_input key,
-				
-				Event::Resize(_, _) => {
-					self.page_config.update(&mut self.pages, &self.terminal, &self.tracker);
-					self.draw().unwrap();
+	/// Adds copies of the monster with the given name from the compendium, returning their
+	/// indexes.
+	fn add_monsters(&mut self, name: &str, count: usize) -> Result<Vec<usize>, String> {
+		let monster = self.compendium
+			.find(name)
+			.ok_or_else(|| format!("no monster named `{}`", name))?;
+		// Combatants are added last, which is where they are shown unless the tracker is
+		// filtered or sorted differently.
+		let shown_last = self.filter.is_none() && self.sort_order == SortOrder::Initiative;
+		let first = self.tracker.combatants.len();
+		for _ in 0..count {
+			let combatant = Box::new(monster.clone().into());
+			self.tracker.execute(TrackerCommand::Add(combatant));
+			if shown_last { self.pages.push(self.tracker.combatants.len() - 1) }
+		}
+		if !shown_last { self.refresh_pages() }
+		Ok((first..self.tracker.combatants.len()).collect())
+	}
+	
+	/// Runs a destructive operation once its confirmation dialog is confirmed.
+	fn confirm(&mut self, action: ConfirmAction) {
+		match action {
+			ConfirmAction::RemoveCombatant(index) => {
+				self.remove(index);
+				self.cursor = None;
+			},
+			
+			ConfirmAction::Kill(index) => {
+				self.tracker.execute(TrackerCommand::Kill { index });
+			},
+			
+			ConfirmAction::EndEffect { index, effect } => {
+				self.tracker.execute(TrackerCommand::EndEffect { index, effect });
+			},
+			
+			ConfirmAction::RollDamage { index, amount, kind } => {
+				let mut rng = rand::rng();
+				let amount = amount.roll(|sides| rng.random_range(1..=sides));
+				self.tracker.execute(TrackerCommand::Damage { index, amount, kind });
+			},
+			
+			ConfirmAction::ClearConditions { index, conditions } => {
+				for kind in conditions {
+					self.tracker.execute(TrackerCommand::RemoveCondition { index, kind });
 				}
-				
-				_ => (),
-			}
+			},
+			
+			// a condition an effect causes ends with the effect, instead of leaving it to trigger
+			ConfirmAction::EndConditions(conditions) => {
+				for (index, kind) in conditions {
+					let Some(combatant) = self.tracker.combatants.get(index) else { continue };
+					let effect = combatant.effects
+						.iter()
+						.position(|effect| effect.condition == Some(kind));
+					let command = match effect {
+						Some(effect) => TrackerCommand::EndEffect { index, effect },
+						None => TrackerCommand::RemoveCondition { index, kind },
+					};
+					self.tracker.execute(command);
+				}
+			},
+			
+			ConfirmAction::RemoveSummons(summons) => {
+				// from last to first, so removing one doesn't move the others
+				for index in summons.into_iter().rev() {
+					self.remove(index);
+				}
+				self.cursor = None;
+			},
+			
+			ConfirmAction::MoraleSave(index) => {
+				let Some(combatant) = self.tracker.combatants.get(index) else { return };
+				let CombatantKind::Monster(monster) = &combatant.kind else { return };
+				let modifier = monster.saving_throw_modifiers().wisdom;
+				let total = rand::rng().random_range(1..=20) + modifier;
+				let name = combatant.name().to_string();
+				if total >= MoraleCheck::DC {
+					self.toast(format!("{} holds its ground (rolled {})", name, total));
+				} else {
+					self.toast(format!("{} flees (rolled {})", name, total));
+					self.tracker.execute(TrackerCommand::SetFleeing { index, fleeing: true });
+				}
+			},
+			
+			ConfirmAction::LongRest(targets) => {
+				for index in targets {
+					if !self.tracker.execute(TrackerCommand::LongRest { index })
+						&& let Some(combatant) = self.tracker.combatants.get(index)
+					{
+						let name = combatant.name();
+						self.toast(format!("{} needs at least 1 hit point to rest", name));
+					}
+				}
+			},
+			
+			ConfirmAction::AwardXp => { self.tracker.execute(TrackerCommand::AwardXp); }
+			
+			ConfirmAction::Quit => self.quitting = true,
+			
+			ConfirmAction::Overwrite(path) => if let Err(error) = self.save(path.clone()) {
+				self.reopen_command_line(format!("save {}", path.display()), error);
+			},
+			
+			ConfirmAction::OverwriteKeys(path) => {
+				if let Err(error) = self.write_cheat_sheet(&path) {
+					self.reopen_command_line(format!("keys {}", path.display()), error);
+				}
+			},
+			
+			ConfirmAction::Dismiss => (),
+		}
+	}
+	
+	/// Removes the combatant with the given index, keeping the pages and the dialogs still open in
+	/// step with the indexes that shift. Dialogs left with nothing to ask about are closed.
+	fn remove(&mut self, index: usize) {
+		if self.tracker.execute(TrackerCommand::Remove { index }) {
+			self.pages.remove(index);
+			// the source of the last conditions applied may have moved
+			self.last_action = None;
+			self.modals.retain_mut(|modal| modal.forget(index));
+		}
+	}
+	
+	/// Shows an error that h5t can carry on after in a popup, over everything else.
+	fn report(&mut self, error: Error) {
+		tracing::error!("{}", error);
+		self.modals.push(Confirm::alert("Error", error.to_string()));
+	}
+	
+	/// Saves the encounter to a file on a worker thread, replacing the file if it exists.
+	fn save(&mut self, path: std::path::PathBuf) -> Result<(), String> {
+		let contents = serde_json::to_string_pretty(&self.tracker)
+			.map_err(|err| format!("could not save {}: {}", path.display(), err))?;
+		let snapshot = snapshot(&self.tracker);
+		tracing::info!(path = %path.display(), "saving encounter");
+		self.tasks.spawn(move || tasks::save(path, contents, snapshot));
+		Ok(())
+	}
+	
+	/// Saves the encounter to the autosave file on a worker thread, if it changed since the last
+	/// autosave.
+	fn autosave(&mut self) {
+		self.next_autosave = Instant::now() + Duration::from_secs(self.config.autosave.interval);
+		if !self.autosave_pending.swap(false, Ordering::Relaxed) {
+			return;
+		}
+		
+		let Ok(contents) = serde_json::to_string_pretty(&self.tracker) else { return };
+		let path = self.config.autosave.path.clone();
+		tracing::debug!(path = %path.display(), "autosaving encounter");
+		self.tasks.spawn(move || tasks::autosave(path, contents));
+	}
+	
+	/// Subscribes to the tracker's changes, to know when an autosave is due and which rows to
+	/// format again. Called again whenever the tracker is replaced, since subscribers aren't
+	/// loaded with it.
+	fn watch_changes(&mut self) {
+		let pending = self.autosave_pending.clone();
+		self.tracker.subscribe(move |_| pending.store(true, Ordering::Relaxed));
+		
+		let stale = self.stale_rows.clone();
+		self.tracker.subscribe(move |event| {
+			let mut stale = stale.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+			stale.push(event.changed());
+		});
+	}
+	
+	/// Forgets the formatted rows of the combatants that changed since the last frame.
+	fn invalidate_rows(&mut self) {
+		let mut stale = self.stale_rows.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+		let row_cache = self.row_cache.get_mut();
+		for changed in stale.drain(..) {
+			row_cache.invalidate(changed, self.tracker.turn);
+		}
+	}
+	
+	/// Handles the result of work that finished on a worker thread.
+	fn finish_task(&mut self, result: TaskResult) {
+		tracing::debug!(?result, "task finished");
+		match result {
+			TaskResult::Saved { path, snapshot, result: Ok(()) } => {
+				self.saved = snapshot;
+				self.toast(format!("Saved {}", path.display()));
+			},
+			TaskResult::Saved { path, result: Err(error), .. } =>
+				self.reopen_command_line(format!("save {}", path.display()), error),
+			TaskResult::Loaded { path, result: Ok(tracker) } => {
+				self.tracker = *tracker;
+				self.tracker.rules = self.config.rules;
+				self.row_cache.get_mut().clear();
+				self.watch_changes();
+				self.log_seen = self.tracker.log.len();
+				self.announcer.reset(&self.tracker);
+				self.saved = snapshot(&self.tracker);
+				self.toast(format!("Loaded {}", path.display()));
+				self.cursor = None;
+				self.refresh_pages();
+			},
+			TaskResult::Loaded { path, result: Err(error) } =>
+				self.reopen_command_line(format!("load {}", path.display()), error),
+			TaskResult::Autosaved { path, result: Ok(()) } =>
+				tracing::debug!(path = %path.display(), "autosaved encounter"),
+			// Retried at the next autosave, so the current command line is left alone.
+			TaskResult::Autosaved { result: Err(error), .. } => {
+				self.autosave_pending.store(true, Ordering::Relaxed);
+				tracing::warn!("{}", error);
+				self.toast(error);
+			},
+		}
+	}
+	
+	/// Reopens the command line with a command that failed and its error, so the command can be
+	/// fixed. If something else is open by now, the error is shown in a popup instead.
+	fn reopen_command_line(&mut self, input: String, error: String) {
+		tracing::warn!("{}", error);
+		if !self.states.is_empty() || !self.modals.is_empty() {
+			self.modals.push(Confirm::alert("Error", error));
+			return;
+		}
+		
+		let line = CommandLine::with_error(self.monster_names(), input, error)
+			.with_macros(self.macro_names())
+			.with_history(self.command_history.clone());
+		self.states.push(ActionState::Command(line));
+	}
+	
+	/// Shows a notification in the corner of the screen for a few seconds.
+	fn toast(&mut self, message: impl Into<String>) {
+		self.toasts.push(Toast::new(message));
+	}
+	
+	/// Passes the combat log events since the last check to the plugins (including the events
+	/// they cause), then shows a notification for every combatant that dropped to 0 hit points,
+	/// and for every condition a combatant was immune to.
+	fn handle_log_events(&mut self) {
+		let start = self.log_seen.min(self.tracker.log.len());
+		for index in start.. {
+			let Some(entry) = self.tracker.log.get(index) else { break };
+			let event = entry.event.clone();
+			self.plugins.on_event(&event, &mut self.tracker);
+		}
+		
+		let notable = self.tracker.log[start..]
+			.iter()
+			.filter(|entry| {
+				matches!(entry.event, LogEvent::Downed { .. } | LogEvent::ConditionImmune { .. })
+			})
+			.map(|entry| entry.event.to_string())
+			.collect::<Vec<_>>();
+		
+		// the end screen comes up once the last enemy drops
+		let downed = self.tracker.log[start..]
+			.iter()
+			.any(|entry| matches!(entry.event, LogEvent::Downed { .. }));
+		if downed && self.tracker.is_over() {
+			self.show_xp_award("Combat Over");
+		}
+		
+		notable.into_iter().for_each(|message| self.toast(message));
+		self.log_seen = self.tracker.log.len();
+	}
+	
+	/// Shows the XP earned for the defeated enemies and each player character's share, offering
+	/// to record the award in the combat log.
+	fn show_xp_award(&mut self, title: &str) {
+		let award = self.tracker.xp_award();
+		if award.total() == 0 {
+			self.toast("No enemy was defeated yet");
+			return;
+		}
+		
+		let mut lines = award.defeated
+			.iter()
+			.map(|(name, count, xp)| match count {
+				1 => format!("{}: {} XP", name, xp),
+				_ => format!("{} x{}: {} XP", name, count, xp),
+			})
+			.collect::<Vec<_>>();
+		lines.push(format!("Total: {} XP", award.total()));
+		lines.push(String::new());
+		lines.extend(award.characters.iter().map(|name| format!("{}: {} XP", name, award.share())));
+		lines.push("(:loot rolls their treasure)".to_string());
+		lines.push("Record the award in the combat log?".to_string());
+		self.modals.push(Confirm::new(title, lines.join("\n"), ConfirmAction::AwardXp));
+	}
+	
+	/// Rolls the coins each defeated enemy carries, or a treasure hoard for the highest challenge
+	/// rating among them, and shows them in a dialog.
+	fn roll_loot(&mut self, hoard: bool) {
+		let defeated = self.tracker.defeated();
+		let Some(challenge_rating) = defeated
+			.iter()
+			.map(|(_, monster)| monster.challenge_rating)
+			.max_by(f32::total_cmp)
+		else {
+			self.toast("No enemy was defeated yet");
+			return;
+		};
+		
+		let mut rng = rand::rng();
+		let mut roll = |sides| rng.random_range(1..=sides);
+		let band = loot::challenge_band(challenge_rating);
+		let lines = if hoard {
+			vec![
+				format!("Hoard (CR {}): {}", band, loot::hoard_treasure(challenge_rating, &mut roll)),
+				format!("Gems, art objects, and magic items: see the CR {} hoard table", band),
+			]
+		} else {
+			let mut total = Coins::default();
+			let mut lines = defeated
+				.iter()
+				.map(|(index, monster)| {
+					let coins = loot::individual_treasure(monster.challenge_rating, &mut roll);
+					total.merge(coins);
+					format!("{}: {}", self.tracker.combatants[*index].name(), coins)
+				})
+				.collect::<Vec<_>>();
+			lines.push(format!("Total: {}", total));
+			lines
+		};
+		self.modals.push(Confirm::alert("Loot", lines.join("\n")));
+	}
+	
+	/// Asks about the effects that triggered and need the table to resolve them, with a dialog
+	/// for each: whether a saving throw succeeded, or whether to roll damage.
+	fn ask_triggered_effects(&mut self) {
+		// The first effect to trigger is asked about first, so its dialog goes on top. Effects of
+		// the same combatant trigger from the last to the first, so ending one doesn't move the
+		// ones asked about after it.
+		for triggered in std::mem::take(&mut self.tracker.triggered).into_iter().rev() {
+			let Triggered { index, effect, details } = triggered;
+			let message = format!("{}: {}", self.tracker.combatants[index].name(), details);
+			let modal = match details.outcome {
+				Outcome::Save { .. } => Confirm::new(
+					"Saving Throw",
+					format!("{}\nDid the save succeed?", message),
+					ConfirmAction::EndEffect { index, effect },
+				),
+				Outcome::Damage { amount, kind } => Confirm::new(
+					"Damage",
+					format!("{}\nRoll the damage?", message),
+					ConfirmAction::RollDamage { index, amount, kind },
+				),
+				// resolved by the tracker
+				Outcome::End => continue,
+			};
+			self.modals.push(modal);
+		}
+	}
+	
+	/// Offers to clear the conditions that came with a condition that ended, with a dialog for
+	/// each ended condition.
+	fn ask_lingering_conditions(&mut self) {
+		// the first condition to end is asked about first, so its dialog goes on top
+		for lingering in std::mem::take(&mut self.tracker.lingering).into_iter().rev() {
+			let Lingering { index, ended, conditions } = lingering;
+			let message = format!(
+				"{} is no longer {}.\nAlso clear {}?",
+				self.tracker.combatants[index].name(),
+				ended,
+				conditions.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+			);
+			let action = ConfirmAction::ClearConditions { index, conditions };
+			self.modals.push(Confirm::new("Implied Conditions", message, action));
 		}
 	}
+	
+	/// Offers to clear the conditions combatants that dropped applied to others, with a dialog for
+	/// each of them.
+	fn ask_orphaned_conditions(&mut self) {
+		// the first combatant to drop is asked about first, so its dialog goes on top
+		for orphaned in std::mem::take(&mut self.tracker.orphaned).into_iter().rev() {
+			let Orphaned { source, conditions } = orphaned;
+			let applied = conditions
+				.iter()
+				.map(|&(index, kind)| (self.tracker.combatants[index].name(), kind))
+				.map(|(name, kind)| format!("{} ({})", name, kind))
+				.collect::<Vec<_>>();
+			let message = format!(
+				"{} dropped.\nClear the conditions it applied? {}",
+				self.tracker.combatants[source].name(),
+				applied.join(", "),
+			);
+			let action = ConfirmAction::EndConditions(conditions);
+			self.modals.push(Confirm::new("Condition Sources", message, action));
+		}
+	}
+	
+	/// Offers to remove the summons of combatants that stopped concentrating or dropped, with a
+	/// dialog for each summoner.
+	fn ask_dismissals(&mut self) {
+		// the first summoner is asked about first, so its dialog goes on top
+		for dismissal in std::mem::take(&mut self.tracker.dismissals).into_iter().rev() {
+			let Dismissal { summoner, reason, summons } = dismissal;
+			let message = format!(
+				"{} {}.\nRemove {}?",
+				self.tracker.combatants[summoner].name(),
+				reason,
+				summons
+					.iter()
+					.map(|&index| self.tracker.combatants[index].name())
+					.collect::<Vec<_>>()
+					.join(", "),
+			);
+			let action = ConfirmAction::RemoveSummons(summons);
+			self.modals.push(Confirm::new("Summons", message, action));
+		}
+	}
+	
+	/// Offers to roll the morale saving throws of monsters whose leader dropped or whose group is
+	/// half down, with a dialog for each monster.
+	fn ask_morale_checks(&mut self) {
+		// the first monster is asked about first, so its dialog goes on top
+		for check in std::mem::take(&mut self.tracker.morale_checks).into_iter().rev() {
+			let MoraleCheck { index, reason } = check;
+			let message = format!(
+				"{}: {}.\nRoll a DC {} Wisdom saving throw, fleeing on a failure?",
+				self.tracker.combatants[index].name(),
+				reason,
+				MoraleCheck::DC,
+			);
+			let action = ConfirmAction::MoraleSave(index);
+			self.modals.push(Confirm::new("Morale", message, action));
+		}
+	}
+	
+	/// Returns whether the encounter changed since it was last saved or loaded.
+	fn has_unsaved_changes(&self) -> bool {
+		snapshot(&self.tracker) != self.saved
+	}
+	
+	/// Returns the indexes of the combatants shown in the tracker, in the order they are shown.
+	fn visible_combatants(&self) -> Vec<usize> {
+		let combatants = &self.tracker.combatants;
+		let mut indexes = self.tracker.turn_sequence();
+		if let Some(query) = &self.filter {
+			indexes.retain(|index| search::matches(&combatants[*index], query));
+		}
+		
+		self.sort_order.sort(&mut indexes, combatants);
+		indexes
+	}
+	
+	/// Resizes the pages if the terminal was resized, then draws the screen.
+	fn redraw(&mut self) -> Result<()> {
+		self.update_page_config()?;
+		self.draw()?;
+		Ok(())
+	}
+	
+	/// Resizes the pages to fit the tracker panel, if the terminal was resized.
+	fn update_page_config(&mut self) -> Result<()> {
+		let area = Rect::from((Position::ORIGIN, self.terminal.size()?));
+		self.screen = area;
+		self.config.layout.compact = self.config.is_compact(area);
+		let area = self.config.panels_area(area);
+		let tracker_area = self.config.layout.area_of(Panel::Tracker, area).unwrap_or_default();
+		let page_size = max_combatants_visible(tracker_area.as_size(), self.config.layout.compact);
+		if self.pages.resize(page_size) {
+			tracing::debug!(?tracker_area, page_size, "pages resized");
+		}
+		
+		Ok(())
+	}
+	
+	/// Rebuilds the pages after the visible combatants changed, keeping the label selection of
+	/// the combatants that are still visible.
+	fn refresh_pages(&mut self) {
+		self.pages.set_combatants(self.visible_combatants());
+	}
+	
+	/// Returns the help overlay, listing the keymap's bindings and the keys bound by plugins.
+	fn help(&self) -> ShowHelp {
+		ShowHelp::new(self.help_sections())
+	}
+	
+	/// Returns every key binding grouped by the context it is active in, plugins included.
+	fn help_sections(&self) -> Vec<HelpSection> {
+		let mut sections = self.config.keymap.help();
+		sections.extend(self.plugins.help());
+		sections
+	}
+	
+	/// Writes every key binding to a cheat sheet in the given file, in the format its extension
+	/// asks for.
+	fn write_cheat_sheet(&mut self, path: &Path) -> Result<(), String> {
+		let sheet = keymap::cheat_sheet(&self.help_sections(), SheetFormat::of(path));
+		std::fs::write(path, sheet)
+			.map_err(|error| format!("can't write {}: {}", path.display(), error))?;
+		self.toast(format!("Wrote the key bindings to {}", path.display()));
+		Ok(())
+	}
+	
+	/// Returns the names of the macros, for completing the `macro` command.
+	fn macro_names(&self) -> Vec<String> {
+		self.config.macros.iter().map(|m| m.name.clone()).collect()
+	}
+	
+	/// Returns the macro played by the given key, if any.
+	fn macro_for(&self, key: KeyEvent) -> Option<Macro> {
+		let key = key.into();
+		self.config.macros.iter().find(|m| m.key == key).cloned()
+	}
+	
+	/// Plays a macro, handling its keys before any new input, as if they were typed.
+	fn play_macro(&mut self, recorded: &Macro) {
+		let keys = recorded.keys.keys().iter().map(|key| KeyEvent::from(*key));
+		// a macro played by another one runs before the rest of it
+		for key in keys.rev() {
+			self.playback.push_front(key);
+		}
+	}
+	
+	/// Stops recording the macro, and saves it to the settings so it is kept between sessions.
+	fn stop_recording(&mut self) {
+		let Some(recording) = self.recording.take() else {
+			self.toast("Not recording a macro, start one with :record <key> <name>");
+			return;
+		};
+		let name = recording.name().to_string();
+		let stop = self.config.keymap.normal.keys_for(Command::StopRecording);
+		let Some(recorded) = recording.finish(stop) else {
+			self.toast(format!("Macro {} is empty, not saved", name));
+			return;
+		};
+		
+		self.toast(format!("Saved macro {} on {}", recorded.name, recorded.key));
+		macros::insert(&mut self.config.macros, recorded.clone());
+		macros::insert(&mut self.settings.macros, recorded);
+		// The macro can still be played this session if it can't be saved.
+		if let Err(error) = self.settings.save() {
+			self.report(Error::io(Settings::PATH)(error));
+		}
+	}
+	
+	/// Returns the names of the monsters in the compendium, for completing the `add` command.
+	fn monster_names(&self) -> Vec<String> {
+		self.compendium.names()
+	}
+	
+	/// Grows (or shrinks) the tracker panel by the given percentage of the screen, and saves the
+	/// new size to the settings.
+	fn resize_tracker(&mut self, percent: i16) {
+		self.config.layout.resize(Panel::Tracker, percent);
+		self.settings.update(&self.config.layout);
+		
+		// The size is still changed for this session if it can't be saved.
+		if let Err(error) = self.settings.save() {
+			self.report(Error::io(Settings::PATH)(error));
+		}
+	}
+	
+	/// Follows, flashes, and announces the turn after it passed to another combatant, as the
+	/// config asks.
+	fn turn_passed(&mut self) {
+		if self.config.follow_turn { self.follow_turn() }
+		if self.config.flash_turn { self.turn_flash = Some(0) }
+		if let Some(combatant) = self.tracker.combatants.get(self.tracker.turn) {
+			self.config.turn_alerts.raise(combatant.name(), self.tracker.round);
+		}
+	}
+	
+	/// Switches to the page of the combatant whose turn it is.
+	fn follow_turn(&mut self) {
+		self.show_combatant(self.tracker.turn);
+	}
+	
+	/// Moves the cursor by the given number of rows, in the order combatants are shown. The cursor
+	/// starts on the combatant whose turn it is.
+	fn move_cursor(&mut self, rows: isize) {
+		let visible = self.pages.visible();
+		let start = self.cursor.unwrap_or(self.tracker.turn);
+		let Some(position) = visible.iter().position(|i| *i == start).or_else(|| {
+			// the cursor's combatant is filtered out, so start from the top
+			(!visible.is_empty()).then_some(0)
+		}) else { return };
+		
+		let position = position.saturating_add_signed(rows).min(visible.len() - 1);
+		let combatant = visible[position];
+		self.cursor = Some(combatant);
+		self.show_combatant(combatant);
+	}
+	
+	/// Switches to the page of the given combatant, or scrolls it into view in scroll view mode.
+	/// Nothing happens if the combatant is filtered out.
+	fn show_combatant(&mut self, combatant: usize) {
+		self.pages.show(combatant);
+	}
+	
+	/// Switches to the previous page, if there is one.
+	///
+	/// In scroll view mode, scrolls up one row instead.
+	fn previous_page(&mut self) {
+		self.pages.previous();
+	}
+	
+	/// Switches to the next page, if there is one.
+	///
+	/// In scroll view mode, scrolls down one row instead.
+	fn next_page(&mut self) {
+		self.pages.next();
+	}
+	
+	/// Switches to the given page, or the last page if there aren't that many.
+	///
+	/// In scroll view mode, scrolls so the given page's first row is at the top instead.
+	fn go_to_page(&mut self, page: usize) {
+		self.pages.go_to(page);
+	}
+	
+	/// Handles the scroll wheel, which switches pages (or scrolls the combat log, over the combat
+	/// log panel) in every mode. Returns whether the event was a scroll.
+	fn handle_scroll(&mut self, mouse: MouseEvent) -> bool {
+		match mouse.kind {
+			MouseEventKind::ScrollUp if self.is_over_log(mouse) =>
+				self.log_scroll = (self.log_scroll + 1).min(self.tracker.log.len()),
+			MouseEventKind::ScrollDown if self.is_over_log(mouse) =>
+				self.log_scroll = self.log_scroll.saturating_sub(1),
+			MouseEventKind::ScrollUp => self.previous_page(),
+			MouseEventKind::ScrollDown => self.next_page(),
+			_ => return false,
+		}
+		
+		true
+	}
+	
+	/// Handles a mouse event outside of label mode. Returns whether anything on screen changed,
+	/// which isn't the case when the mouse just moves.
+	///
+	/// Scrolling is handled first. Otherwise, if an action state is active, the event is forwarded
+	/// to it, or clicking a combatant row shows that combatant in the info block (clicking it again
+	/// goes back to the current turn's combatant).
+	fn handle_mouse(&mut self, mouse: MouseEvent) -> bool {
+		let area = self.screen;
+		
+		if self.handle_scroll(mouse) {
+			return true;
+		}
+		if self.dispatch(|handler, config| handler.handle_mouse(mouse, area, &config.theme)) {
+			return true;
+		}
+		
+		let Some(index) = self.clicked_combatant(mouse) else { return false };
+		self.cursor = if self.cursor == Some(index) { None } else { Some(index) };
+		true
+	}
+	
+	/// Handles a mouse event in label mode, where clicking a combatant row toggles its selection.
+	/// Returns whether the selection changed.
+	fn handle_label_click(&mut self, mouse: MouseEvent) -> bool {
+		let Some(index) = self.clicked_combatant(mouse) else { return false };
+		self.pages.toggle(index);
+		true
+	}
+	
+	/// Returns the index of the combatant whose row was left clicked, if the mouse event is a left
+	/// click on a row of the shown page.
+	fn clicked_combatant(&self, mouse: MouseEvent) -> Option<usize> {
+		let MouseEventKind::Down(MouseButton::Left) = mouse.kind else { return None };
+		let panels = self.config.panels_area(self.screen);
+		let tracker_area = self.config.layout.area_of(Panel::Tracker, panels)?;
+		let position = Position::new(mouse.column, mouse.row);
+		
+		tracker_row_at(tracker_area, self.config.layout.compact, position)
+			.zip(self.pages.shown())
+			.and_then(|(row, page)| page.get_combatants().get(row).copied())
+	}
+	
+	/// Returns whether the given panel is on screen.
+	fn is_shown(&self, panel: Panel) -> bool {
+		self.config.layout.area_of(panel, self.config.panels_area(self.screen)).is_some()
+	}
+	
+	/// Returns whether the mouse is over the combat log panel.
+	fn is_over_log(&self, mouse: MouseEvent) -> bool {
+		self.config.layout
+			.area_of(Panel::Log, self.config.panels_area(self.screen))
+			.is_some_and(|log| log.contains(Position::new(mouse.column, mouse.row)))
+	}
+	
+	/// Advances timers by one tick: hides expired notifications, steps the flash on the new turn's
+	/// row, and autosaves when it's time. Returns whether anything on screen changed, which is
+	/// always the case while the debug panel is shown, to keep its log lines and frame timing
+	/// current.
+	fn tick(&mut self) -> bool {
+		if self.config.autosave.interval > 0 && Instant::now() >= self.next_autosave {
+			self.autosave();
+		}
+		
+		let toasts = self.toasts.len();
+		self.toasts.retain(Toast::is_visible);
+		
+		let flashing = self.turn_flash.is_some();
+		self.turn_flash = self.turn_flash
+			.map(|tick| tick + 1)
+			.filter(|tick| *tick < FLASH_TICKS);
+		
+		flashing || toasts != self.toasts.len() || self.debug_panel
+	}
+	
+	/// Returns the source input is read from, to send input to a scripted one.
+	pub fn events_mut(&mut self) -> &mut E {
+		&mut self.events
+	}
+	
+	/// Blocks until the next event, which is a [`AppEvent::Tick`] if no input arrives within
+	/// [`TICK_RATE`].
+	///
+	/// Results of background work are delivered before input.
+	fn next_event(&mut self) -> AppEvent {
+		if let Some(result) = self.tasks.try_recv() {
+			return AppEvent::Task(result);
+		}
+		
+		if self.replayed >= MAX_REPLAYED_KEYS {
+			self.playback.clear();
+			self.toast("Stopped a macro that keeps playing itself");
+		}
+		if let Some(key) = self.playback.pop_front() {
+			self.replayed += 1;
+			return AppEvent::Key(key);
+		}
+		
+		self.replayed = 0;
+		let event = self.events.next_event(TICK_RATE);
+		if let (Some(recording), InputEvent::Key(key)) = (&mut self.recording, &event) {
+			recording.push(*key);
+		}
+		event.into()
+	}
 }
 
-impl<B: Backend> Widget for Ui<B> {
+impl<B: Backend, E: EventSource> Widget for Ui<B, E> {
 	fn render(self, area: Rect, buf: &mut Buffer) {
 		TrackerWidget::new(
 			&self.tracker,
-			self.pages.get(self.page_config.current_page),
+			self.pages.shown(),
 			self.labels_enabled,
+			&self.config.theme,
 		).render(area, buf);
 	}
 }
 
-impl<B: Backend> Drop for Ui<B> {
-	fn drop(&mut self) { ratatui::restore() }
-}
-
 // NOTE `tracker` is already a public field, so these implementations aren't necessary.
 // impl<B: Backend> Deref for UI<B> {
 //     type Target = Tracker;
@@ -1,16 +1,18 @@
 use h5t_core::{
     ability::{Modifier as AbilityModifier, Score},
     Ability,
+    Character,
     Combatant,
     CombatantKind,
     Monster,
     score_to_modifier,
 };
 use ratatui::{prelude::*, widgets::*};
+use crate::theme::Theme;
 
 /// A widget to display a table of ability scores.
 #[derive(Debug)]
-pub struct AbilityScores {
+pub struct AbilityScores<'a> {
     /// The ability scores to display.
     scores: Ability<Score>,
 
@@ -19,27 +21,50 @@ pub struct AbilityScores {
     /// This is used to calculate saving throw modifiers. If this is `None`, the ability modifier
     /// is used instead.
     proficiencies: Ability<Option<AbilityModifier>>,
+
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
 }
 
-impl AbilityScores {
+impl<'a> AbilityScores<'a> {
     /// Create a new [`AbilityScores`] widget from a [`Combatant`].
-    pub fn new(combatant: &Combatant) -> Self {
+    pub fn new(combatant: &Combatant, theme: &'a Theme) -> Self {
         match &combatant.kind {
-            CombatantKind::Monster(monster) => Self::from(monster),
+            CombatantKind::Monster(monster) => Self::from_monster(monster, theme),
+            CombatantKind::Character(character) => Self::from_character(character, theme),
         }
     }
-}
 
-impl From<&Monster> for AbilityScores {
-    fn from(monster: &Monster) -> Self {
+    /// Create a new [`AbilityScores`] widget from a [`Character`].
+    pub fn from_character(character: &Character, theme: &'a Theme) -> Self {
+        let modifiers = character.saving_throw_modifiers();
+        let proficient = character.saving_throws;
+        let save = |proficient: bool, modifier| proficient.then_some(modifier);
+        Self {
+            scores: character.scores,
+            proficiencies: Ability {
+                strength: save(proficient.strength, modifiers.strength),
+                dexterity: save(proficient.dexterity, modifiers.dexterity),
+                constitution: save(proficient.constitution, modifiers.constitution),
+                intelligence: save(proficient.intelligence, modifiers.intelligence),
+                wisdom: save(proficient.wisdom, modifiers.wisdom),
+                charisma: save(proficient.charisma, modifiers.charisma),
+            },
+            theme,
+        }
+    }
+
+    /// Create a new [`AbilityScores`] widget from a [`Monster`].
+    pub fn from_monster(monster: &Monster, theme: &'a Theme) -> Self {
         Self {
             scores: monster.scores,
             proficiencies: monster.proficiencies.saving_throws,
+            theme,
         }
     }
 }
 
-impl Widget for AbilityScores {
+impl Widget for AbilityScores<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let (str, dex, con, int, wis, cha) = (
             self.scores.strength,
@@ -59,7 +84,7 @@ impl Widget for AbilityScores {
         );
 
         /// Helper to build a row for the ability scores table.
-        fn row(odd: bool, ability: &'_ str, score: i32, save: Option<i32>) -> Row<'_> {
+        fn row(stripe: Option<Color>, ability: &'_ str, score: i32, save: Option<i32>) -> Row<'_> {
             // more green for high scores, more red for low scores
             // 0: (255, 0, 0)
             // 10: (255, 255, 255)
@@ -84,17 +109,19 @@ impl Widget for AbilityScores {
                 Text::styled(format!("{:+}", modifier), main_color),
                 Text::styled(format!("{:+}", save.unwrap_or(modifier)), save_color),
             ])
-                .style(Style::default().bg(if odd { Color::DarkGray } else { Color::Black }))
+                .style(Style::default().bg(stripe.unwrap_or(Color::Reset)))
         }
 
+        let stripe = Some(self.theme.stripe);
+
         let widget = Table::new(
             vec![
-                row(false, "STR", str, str_save),
-                row(true, "DEX", dex, dex_save),
-                row(false, "CON", con, con_save),
-                row(true, "INT", int, int_save),
-                row(false, "WIS", wis, wis_save),
-                row(true, "CHA", cha, cha_save),
+                row(None, "STR", str, str_save),
+                row(stripe, "DEX", dex, dex_save),
+                row(None, "CON", con, con_save),
+                row(stripe, "INT", int, int_save),
+                row(None, "WIS", wis, wis_save),
+                row(stripe, "CHA", cha, cha_save),
             ],
             vec![
                 Constraint::Length(7), // ability abbreviation
@@ -0,0 +1,143 @@
+use h5t_core::{ability::Skill, Character};
+use ratatui::{prelude::*, widgets::*};
+use crate::theme::Theme;
+use super::AbilityScores;
+
+/// Creates a [`Paragraph`] widget for displaying the character's name, class, and player.
+fn name_and_class_paragraph(character: &'_ Character) -> Paragraph<'_> {
+    let mut class = format!("Level {}", character.level);
+    if !character.class.is_empty() {
+        class = format!("{} ({})", character.class, class);
+    }
+    if !character.player.is_empty() {
+        class.push_str(&format!(", played by {}", character.player));
+    }
+
+    Paragraph::new(vec![
+        Span::styled(&character.name, Modifier::BOLD).into(),
+        Line::styled(class, Modifier::ITALIC),
+    ])
+}
+
+/// Creates a [`Table`] widget for displaying a character's basic statistics and passive scores.
+fn basic_stats_table(character: &'_ Character) -> Table<'_> {
+    let (perception, insight, investigation) = character.passive_scores();
+
+    let row = |name: &'static str, value: String| {
+        Row::new(vec![Text::styled(name, Modifier::BOLD), Text::raw(value)])
+    };
+
+    Table::new(
+        vec![
+            row("Armor Class", character.armor_class.to_string()),
+            row("Hit Points", character.hit_points.to_string()),
+            row("Speed", character.speed.walk.clone().unwrap_or_else(|| "-".to_string())),
+            row("Proficiency Bonus", format!("{:+}", character.proficiency_bonus())),
+            row("Passive Perception", perception.to_string()),
+            row("Passive Insight", insight.to_string()),
+            row("Passive Investigation", investigation.to_string()),
+        ],
+        vec![
+            Constraint::Percentage(50), // stat name
+            Constraint::Percentage(50), // stat value
+        ],
+    )
+}
+
+/// Creates a [`Table`] widget for displaying a character's skill modifiers, two skills per row.
+///
+/// Skills the character is proficient in are shown in bold.
+fn skills_table(character: &'_ Character) -> Table<'_> {
+    /// Lists every skill with its name.
+    fn named<T: Copy>(skills: &Skill<T>) -> [(&'static str, T); 18] {
+        [
+            ("Acrobatics", skills.acrobatics),
+            ("Animal Handling", skills.animal_handling),
+            ("Arcana", skills.arcana),
+            ("Athletics", skills.athletics),
+            ("Deception", skills.deception),
+            ("History", skills.history),
+            ("Insight", skills.insight),
+            ("Intimidation", skills.intimidation),
+            ("Investigation", skills.investigation),
+            ("Medicine", skills.medicine),
+            ("Nature", skills.nature),
+            ("Perception", skills.perception),
+            ("Performance", skills.performance),
+            ("Persuasion", skills.persuasion),
+            ("Religion", skills.religion),
+            ("Sleight of Hand", skills.sleight_of_hand),
+            ("Stealth", skills.stealth),
+            ("Survival", skills.survival),
+        ]
+    }
+
+    let cells = named(&character.skill_modifiers())
+        .into_iter()
+        .zip(named(&character.skills))
+        .map(|((name, modifier), (_, proficient))| {
+            let style = if proficient { Style::new().bold() } else { Style::new() };
+            [Text::styled(name, style), Text::styled(format!("{:+}", modifier), style)]
+        })
+        .collect::<Vec<_>>();
+
+    let rows = cells
+        .chunks(2)
+        .map(|pair| Row::new(pair.iter().flatten().cloned().collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+
+    Table::new(
+        rows,
+        vec![
+            Constraint::Fill(3), // skill name
+            Constraint::Fill(1), // skill modifier
+            Constraint::Fill(3),
+            Constraint::Fill(1),
+        ],
+    )
+}
+
+/// A widget for displaying a player character's summary: basic stats, saving throws, skills, and
+/// passive scores.
+#[derive(Debug)]
+pub struct CharacterSheet<'a> {
+    /// The character to display.
+    pub character: &'a Character,
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
+}
+
+impl<'a> CharacterSheet<'a> {
+    /// Create a new [`CharacterSheet`] widget.
+    pub fn new(character: &'a Character, theme: &'a Theme) -> Self {
+        Self { character, theme }
+    }
+}
+
+impl<'a> Widget for CharacterSheet<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // draw bordered box
+        Block::bordered()
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
+            .title("Character Sheet")
+            .render(area, buf);
+
+        let [name, basic_stats, ability_scores, skills] = Layout::vertical([
+            Constraint::Min(2), // name and class
+            Constraint::Min(7), // basic stats
+            Constraint::Min(6), // ability scores
+            Constraint::Min(9), // skills
+        ])
+            .horizontal_margin(2)
+            .vertical_margin(1) // avoid the border
+            .spacing(1)
+            .areas(area);
+
+        name_and_class_paragraph(self.character).render(name, buf);
+        Widget::render(basic_stats_table(self.character), basic_stats, buf);
+        AbilityScores::from_character(self.character, self.theme).render(ability_scores, buf);
+        Widget::render(skills_table(self.character), skills, buf);
+    }
+}
@@ -1,20 +1,34 @@
-use h5t_core::{monster::Speed, Combatant};
+use h5t_core::{Combatant, CombatantKind, Edition, InitiativeModifier};
+use h5t_core::monster::{ArmorClassSource, Speed};
 use ratatui::{prelude::*, widgets::*};
+use crate::theme::Theme;
 use crate::widgets::conditions::FullConditions;
 
 use super::{AbilityScores, HitPoints};
 
-/// Creates a [`Text`] widget for displaying the combatant's name and whether they are dead.
+/// Creates a [`Text`] widget for displaying the combatant's name, whether they are a minion or a
+/// leader, whether they are fleeing, and whether they are stable or dead.
 fn basic_status_text(combatant: &'_ Combatant) -> Text<'_> {
-    if combatant.hit_points <= 0 {
-        Text::styled(format!("{} (Dead)", combatant.name()), Modifier::BOLD)
-    } else {
-        Text::styled(combatant.name(), Modifier::BOLD)
+    let mut name = combatant.name().to_string();
+    if combatant.minion {
+        name.push_str(" (Minion)");
     }
+    if combatant.leader {
+        name.push_str(" (Leader)");
+    }
+    if combatant.fleeing {
+        name.push_str(" (Fleeing)");
+    }
+    if combatant.stable {
+        name.push_str(" (Stable)");
+    } else if combatant.hit_points <= 0 {
+        name.push_str(" (Dead)");
+    }
+    Text::styled(name, Modifier::BOLD)
 }
 
-/// Creates a [`Table`] widget for displaying a monster's basic statistics.
-fn basic_stats_table(combatant: &'_ Combatant) -> Table<'_> {
+/// Creates the rows of a table displaying a combatant's basic statistics.
+fn basic_stats_rows(combatant: &'_ Combatant) -> Vec<Row<'_>> {
     /// Format's a speed value.
     fn fmt_speed(speed: &Speed) -> String {
         let mut parts = String::new();
@@ -47,30 +61,106 @@ fn basic_stats_table(combatant: &'_ Combatant) -> Table<'_> {
         parts
     }
 
-    Table::new(
-        vec![
-            Row::new(vec![
-                Text::styled("Armor Class", Modifier::BOLD),
-                Text::raw(combatant.armor_class().to_string()),
-            ]),
-            Row::new(vec![
-                Text::styled("Hit Points", Modifier::BOLD),
-                HitPoints::new(combatant).line().into(),
-            ]),
-            Row::new(vec![
-                Text::styled("Speed", Modifier::BOLD),
-                Text::raw(fmt_speed(combatant.speed())),
-            ]),
-            Row::new(vec![
-                Text::styled("Proficiency Bonus", Modifier::BOLD),
-                Text::raw(format!("{:+}", combatant.proficiency_bonus())),
-            ]),
-        ],
-        vec![
-            Constraint::Percentage(50), // stat name
-            Constraint::Percentage(50), // stat value
-        ],
-    )
+    /// Formats the combatant's initiative bonus, noting if it rolls with advantage.
+    fn fmt_initiative(combatant: &Combatant) -> String {
+        let bonus = format!("{:+}", combatant.initiative_bonus());
+        if combatant.initiative_modifiers.contains(&InitiativeModifier::Advantage) {
+            format!("{} (advantage)", bonus)
+        } else {
+            bonus
+        }
+    }
+
+    /// Formats the combatant's armor class, noting where a monster's comes from.
+    fn fmt_armor_class(combatant: &Combatant) -> String {
+        let armor_class = combatant.armor_class();
+        match &combatant.kind {
+            CombatantKind::Monster(monster) => match monster.armor_class.source {
+                ArmorClassSource::Dexterity => armor_class.to_string(),
+                ArmorClassSource::Natural => format!("{} (natural armor)", armor_class),
+                ArmorClassSource::Armor => format!("{} (armor)", armor_class),
+            },
+            CombatantKind::Character(_) => armor_class.to_string(),
+        }
+    }
+
+    let mut rows = vec![
+        Row::new(vec![
+            Text::styled("Armor Class", Modifier::BOLD),
+            Text::raw(fmt_armor_class(combatant)),
+        ]),
+        Row::new(vec![
+            Text::styled("Hit Points", Modifier::BOLD),
+            HitPoints::new(combatant).line().into(),
+        ]),
+        Row::new(vec![
+            Text::styled("Speed", Modifier::BOLD),
+            Text::raw(fmt_speed(combatant.speed())),
+        ]),
+        Row::new(vec![
+            Text::styled("Initiative", Modifier::BOLD),
+            Text::raw(fmt_initiative(combatant)),
+        ]),
+        Row::new(vec![
+            Text::styled("Proficiency Bonus", Modifier::BOLD),
+            Text::raw(format!("{:+}", combatant.proficiency_bonus())),
+        ]),
+        Row::new(vec![
+            Text::styled("Passive Perception", Modifier::BOLD),
+            Text::raw(combatant.passive_perception().to_string()),
+        ]),
+    ];
+    let senses = combatant.senses().to_string();
+    if !senses.is_empty() {
+        rows.push(Row::new(vec![Text::styled("Senses", Modifier::BOLD), Text::raw(senses)]));
+    }
+    if let CombatantKind::Character(character) = &combatant.kind {
+        rows.push(Row::new(vec![
+            Text::styled("Hit Dice", Modifier::BOLD),
+            Text::raw(format!(
+                "{}/{} (d{})",
+                combatant.hit_dice_left(),
+                character.level.max(1),
+                character.hit_die,
+            )),
+        ]));
+    }
+    rows
+}
+
+/// Creates a [`Text`] widget listing the effects on the combatant, its exhaustion (described by
+/// the given edition), surprise, concentration, regeneration, and links to other combatants,
+/// under a header.
+fn effects_text<'a>(combatant: &'a Combatant, edition: Edition, links: &'a [String]) -> Text<'a> {
+    let mut lines = vec![Line::styled("Effects", Modifier::BOLD)];
+    if combatant.exhaustion > 0 {
+        lines.push(Line::raw(format!(
+            "Exhaustion {}: {}",
+            combatant.exhaustion,
+            edition.exhaustion_effects(combatant.exhaustion),
+        )));
+    }
+    if combatant.surprised {
+        lines.push(Line::raw("Surprised"));
+    }
+    if combatant.concentrating {
+        lines.push(Line::raw("Concentrating"));
+    }
+    if !combatant.speed_factors.is_empty() {
+        let declared = combatant.speed_factors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        lines.push(Line::raw(format!("Declared {}", declared.join(", "))));
+    }
+    lines.extend(links.iter().map(|link| Line::raw(link.as_str())));
+    if let Some(regeneration) = &combatant.regeneration {
+        let suppressed = if regeneration.suppressed { " (suppressed)" } else { "" };
+        lines.push(Line::raw(format!(
+            "Regains {} HP at start of turn{}",
+            regeneration.amount,
+            suppressed,
+        )));
+    }
+    lines.extend(combatant.effects.iter().map(|effect| Line::raw(effect.to_string())));
+    Text::from(lines)
 }
 
 /// A widget similar to [`StatBlock`] that displays relevant combat information.
@@ -80,12 +170,34 @@ fn basic_stats_table(combatant: &'_ Combatant) -> Table<'_> {
 pub struct CombatantBlock<'a> {
     /// The combatant to display.
     combatant: &'a Combatant,
+
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
+
+    /// The grapples and summons the combatant is part of, described from its side.
+    links: Vec<String>,
+
+    /// The edition of the rules, which describes the combatant's exhaustion.
+    edition: Edition,
 }
 
 impl<'a> CombatantBlock<'a> {
     /// Create a new [`CombatantBlock`] widget.
-    pub fn new(combatant: &'a Combatant) -> Self {
-        Self { combatant }
+    pub fn new(combatant: &'a Combatant, theme: &'a Theme) -> Self {
+        Self { combatant, theme, links: Vec::new(), edition: Edition::default() }
+    }
+
+    /// List the grapples and summons the combatant is part of with its effects, such as
+    /// `Grappled by Ogre` or `Summoned by Cleric`.
+    pub fn with_links(mut self, links: Vec<String>) -> Self {
+        self.links = links;
+        self
+    }
+
+    /// Describe the combatant's exhaustion with the given edition of the rules.
+    pub fn with_edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self
     }
 }
 
@@ -93,14 +205,15 @@ impl<'a> Widget for CombatantBlock<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // draw bordered box
         Block::bordered()
-            .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::White))
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
             .title("Combatant Block")
             .render(area, buf);
 
+        let rows = basic_stats_rows(self.combatant);
         let layout = Layout::vertical([
             Constraint::Length(1), // name
-            Constraint::Min(5),    // basic stats
+            Constraint::Length(rows.len() as u16), // basic stats
             Constraint::Fill(1),   // conditions
             Constraint::Min(6),    // ability scores
         ])
@@ -116,8 +229,35 @@ impl<'a> Widget for CombatantBlock<'a> {
         ] = [layout[0], layout[1], layout[2], layout[3]];
 
         basic_status_text(self.combatant).render(name, buf);
-        Widget::render(basic_stats_table(self.combatant), basic_stats, buf);
-        FullConditions::new(self.combatant).render(conditions, buf);
-        AbilityScores::new(self.combatant).render(ability_scores, buf);
+        let basic_stats_table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(50), // stat name
+                Constraint::Percentage(50), // stat value
+            ],
+        );
+        Widget::render(basic_stats_table, basic_stats, buf);
+        // effects are listed under the conditions, if there are any
+        let [conditions, effects] = Layout::vertical([
+            Constraint::Length(self.combatant.conditions.len() as u16 + 1),
+            Constraint::Fill(1),
+        ])
+            .spacing(1)
+            .areas(conditions);
+        FullConditions::new(self.combatant)
+            .with_ascii(self.theme.ascii)
+            .render(conditions, buf);
+        if !self.combatant.effects.is_empty()
+            || self.combatant.regeneration.is_some()
+            || self.combatant.concentrating
+            || self.combatant.exhaustion > 0
+            || self.combatant.surprised
+            || !self.links.is_empty()
+        {
+            Paragraph::new(effects_text(self.combatant, self.edition, &self.links))
+                .wrap(Wrap { trim: true })
+                .render(effects, buf);
+        }
+        AbilityScores::new(self.combatant, self.theme).render(ability_scores, buf);
     }
 }
@@ -3,7 +3,7 @@ use itertools::Itertools;
 use ratatui::{prelude::*, widgets::*};
 
 /// Returns a unique color for the condition.
-fn condition_color(kind: ConditionKind) -> Color {
+pub(crate) fn condition_color(kind: ConditionKind) -> Color {
     match kind {
         ConditionKind::Blinded => Color::White,
         ConditionKind::Charmed => Color::Magenta,
@@ -13,21 +13,72 @@ fn condition_color(kind: ConditionKind) -> Color {
         ConditionKind::Grappled => Color::Rgb(255, 80, 0), // dark orange
         ConditionKind::Incapacitated => Color::LightBlue,
         ConditionKind::Invisible => Color::Rgb(200, 200, 200), // light gray
-        ConditionKind::Paralyzed => Color::Rgb(0, 0, 85), // dark blue
+        ConditionKind::Paralyzed => Color::Rgb(110, 40, 160), // purple
         ConditionKind::Petrified => Color::DarkGray,
-        ConditionKind::Poisoned => Color::Rgb(0, 110, 0), // dark green
+        ConditionKind::Poisoned => Color::Rgb(140, 200, 0), // sickly green
         ConditionKind::Prone => Color::Rgb(255, 165, 140), // light orange
-        ConditionKind::Restrained => Color::Rgb(80, 165, 0), // dark brown
-        ConditionKind::Stunned => Color::LightBlue,
-        ConditionKind::Unconscious => Color::Rgb(0, 0, 80), // dark blue
+        ConditionKind::Restrained => Color::Rgb(140, 80, 20), // brown
+        ConditionKind::Stunned => Color::Cyan,
+        ConditionKind::Unconscious => Color::Rgb(0, 0, 110), // dark blue
     }
 }
 
-/// A widget to display a combatant's active conditions in a compact form.
+/// Returns the style of a condition badge: the condition's color as the background, with black or
+/// white text, whichever is easier to read.
+pub(crate) fn badge_style(kind: ConditionKind) -> Style {
+    let background = condition_color(kind);
+    let light = match background {
+        Color::Rgb(r, g, b) => 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32 > 140.0,
+        Color::White | Color::Yellow | Color::LightBlue | Color::Cyan => true,
+        _ => false,
+    };
+    let foreground = if light { Color::Black } else { Color::White };
+    Style::new().fg(foreground).bg(background).bold()
+}
+
+/// Creates a badge [`Span`] for a condition kind, with optional text after the abbreviation.
+pub(crate) fn badge(kind: ConditionKind, suffix: &str) -> Span<'static> {
+    Span::styled(format!(" {}{} ", kind.abbreviation(), suffix), badge_style(kind))
+}
+
+/// Creates the badge shown for bloodied combatants, which isn't a condition of its own, but is
+/// shown like one since many abilities key off it.
+pub(crate) fn bloodied_badge() -> Span<'static> {
+    Span::styled(" BLD ", Style::new().fg(Color::White).bg(Color::Rgb(150, 0, 0)).bold())
+}
+
+/// Creates the badge shown for combatants fleeing the fight, before their conditions.
+pub(crate) fn fleeing_badge() -> Span<'static> {
+    Span::styled(" FLEE ", Style::new().fg(Color::Black).bg(Color::Rgb(0, 180, 180)).bold())
+}
+
+/// Creates the badge shown for combatants stable at 0 hit points.
+pub(crate) fn stable_badge() -> Span<'static> {
+    Span::styled(" STBL ", Style::new().fg(Color::Black).bg(Color::Rgb(120, 200, 120)).bold())
+}
+
+/// Creates the badge shown for combatants marked dead.
+pub(crate) fn dead_badge() -> Span<'static> {
+    Span::styled(" DEAD ", Style::new().fg(Color::White).bg(Color::Rgb(60, 60, 60)).bold())
+}
+
+/// A widget to display a combatant's active conditions in a compact form, as colored badges.
 #[derive(Debug)]
 pub struct CompactConditions<'a> {
     /// The conditions to display.
     pub current: &'a [Condition],
+
+    /// Whether to show the bloodied badge before the conditions.
+    pub bloodied: bool,
+
+    /// Whether to show the fleeing badge before the conditions.
+    pub fleeing: bool,
+
+    /// Whether to show the stable badge before the conditions.
+    pub stable: bool,
+
+    /// Whether to show the dead badge before the conditions.
+    pub dead: bool,
 }
 
 impl<'a> CompactConditions<'a> {
@@ -35,30 +86,32 @@ impl<'a> CompactConditions<'a> {
     pub fn new(combatant: &'a Combatant) -> Self {
         Self {
             current: &combatant.conditions,
+            bloodied: combatant.is_bloodied(),
+            fleeing: combatant.fleeing && combatant.hit_points > 0,
+            stable: combatant.stable,
+            dead: combatant.dead,
         }
     }
 
-    /// Creates a [`Line`] widget containing the hit points display.
+    /// Creates a [`Line`] widget containing a badge for each condition, after the dead, stable,
+    /// fleeing, and bloodied badges if the combatant is any of those.
     pub fn line(&self) -> Line<'static> {
-        /// Create a [`Span`] for each condition.
+        /// Create a badge for each condition.
         fn make_span(condition: &Condition) -> Span<'static> {
-            Span::styled(
-                if let Some(rounds_left) = condition.duration.rounds_left() {
-                    format!("{}:{}", condition.kind.abbreviation(), rounds_left)
-                } else {
-                    // infinite duration
-                    format!("{}", condition.kind.abbreviation())
-                },
-                condition_color(condition.kind),
-            )
+            match condition.duration.rounds_left() {
+                Some(rounds_left) => badge(condition.kind, &format!(":{}", rounds_left)),
+                None => badge(condition.kind, ""), // infinite duration
+            }
         }
 
-        let conditions = self
-            .current
-            .iter()
-            .map(make_span)
-            .intersperse(Span::raw(","))
-            .collect::<Vec<_>>();
+        let badges = self.dead
+            .then(dead_badge)
+            .into_iter()
+            .chain(self.stable.then(stable_badge))
+            .chain(self.fleeing.then(fleeing_badge))
+            .chain(self.bloodied.then(bloodied_badge))
+            .chain(self.current.iter().map(make_span));
+        let conditions = Itertools::intersperse(badges, Span::raw(" ")).collect::<Vec<_>>();
         Line::from(conditions)
     }
 }
@@ -74,6 +127,9 @@ impl Widget for CompactConditions<'_> {
 pub struct FullConditions<'a> {
     /// The conditions to display.
     pub current: &'a [Condition],
+
+    /// Whether to avoid non-ASCII symbols.
+    pub ascii: bool,
 }
 
 impl<'a> FullConditions<'a> {
@@ -81,9 +137,16 @@ impl<'a> FullConditions<'a> {
     pub fn new(combatant: &'a Combatant) -> Self {
         Self {
             current: &combatant.conditions,
+            ascii: false,
         }
     }
 
+    /// Show conditions without a duration as `-` instead of `∞`.
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
     /// Creates a [`Table`] widget containing the conditions.
     pub fn table(&self) -> Table<'static> {
         let rows = self
@@ -94,6 +157,8 @@ impl<'a> FullConditions<'a> {
                     Text::styled(condition.kind.to_string(), condition_color(condition.kind)),
                     Text::raw(if let Some(rounds_left) = condition.duration.rounds_left() {
                         format!("{}", rounds_left)
+                    } else if self.ascii {
+                        "-".to_string()
                     } else {
                         "∞".to_string()
                     }),
@@ -0,0 +1,54 @@
+use crate::debug::FrameTimes;
+use crate::theme::Theme;
+use ratatui::{prelude::*, widgets::*};
+
+/// A widget drawn over the bottom of the screen with the frame timing and the most recent log
+/// lines, for debugging the UI.
+#[derive(Debug)]
+pub struct DebugPanel<'a> {
+    /// The log lines to show, from oldest to newest.
+    pub lines: &'a [String],
+
+    /// How long the recent frames took to draw.
+    pub frame_times: &'a FrameTimes,
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
+}
+
+impl<'a> DebugPanel<'a> {
+    /// The height of the panel, including its borders.
+    pub const HEIGHT: u16 = 12;
+
+    /// Create a new [`DebugPanel`] widget.
+    pub fn new(lines: &'a [String], frame_times: &'a FrameTimes, theme: &'a Theme) -> Self {
+        Self { lines, frame_times, theme }
+    }
+}
+
+impl Widget for DebugPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let height = Self::HEIGHT.min(area.height);
+        let area = Rect { y: area.bottom() - height, height, ..area };
+
+        let timing = format!(
+            " frame {:.1?} (avg {:.1?}) ",
+            self.frame_times.last(),
+            self.frame_times.average(),
+        );
+        let block = Block::bordered()
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
+            .title("Debug")
+            .title(Line::raw(timing).right_aligned())
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        // the newest lines that fit
+        let start = self.lines.len().saturating_sub(inner.height as usize);
+        let lines = self.lines[start..].iter().map(|line| Line::raw(line.as_str()));
+        Paragraph::new(lines.collect::<Vec<_>>()).render(inner, buf);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::theme::Theme;
+use h5t_core::{Position as Square, Tracker};
+use ratatui::{prelude::*, widgets::*};
+
+/// Returns the letter of the token of the combatant with the given index: `A` to `Z`, then `a`
+/// to `z`, then `#` for every combatant after that.
+pub fn token(index: usize) -> char {
+    match index {
+        0..26 => (b'A' + index as u8) as char,
+        26..52 => (b'a' + (index - 26) as u8) as char,
+        _ => '#',
+    }
+}
+
+/// A widget to display the combatants placed on the battle grid as letters, with a legend of the
+/// tokens and their distance from the focused token.
+///
+/// The grid is centered on the focused token, so it follows a token being moved. Each square is
+/// two cells wide, to look roughly square in most fonts.
+#[derive(Debug)]
+pub struct GridWidget<'a> {
+    /// The tracker with the combatants to display.
+    pub tracker: &'a Tracker,
+
+    /// The index of the focused combatant, whose token is highlighted and distances are measured
+    /// from.
+    pub focus: usize,
+
+    /// (optional) The square the focused token started on, and the square it is being moved to.
+    pub moving: Option<(Square, Square)>,
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
+}
+
+impl<'a> GridWidget<'a> {
+    /// Create a new [`GridWidget`] focused on the given combatant.
+    pub fn new(tracker: &'a Tracker, focus: usize, theme: &'a Theme) -> Self {
+        Self { tracker, focus, moving: None, theme }
+    }
+
+    /// Shows the focused token being moved from one square to another.
+    pub fn with_moving(mut self, moving: Option<(Square, Square)>) -> Self {
+        self.moving = moving;
+        self
+    }
+
+    /// Returns the square of the combatant with the given index, following the token being moved.
+    fn square_of(&self, index: usize) -> Option<Square> {
+        match self.moving {
+            Some((_, square)) if index == self.focus => Some(square),
+            _ => self.tracker.combatants[index].position,
+        }
+    }
+
+    /// Returns the style of the token of the combatant with the given index.
+    fn token_style(&self, index: usize) -> Style {
+        if index == self.tracker.turn {
+            self.theme.highlight(self.theme.current_turn)
+        } else if index == self.focus {
+            self.theme.highlight(self.theme.cursor_row)
+        } else {
+            Style::new().add_modifier(Modifier::BOLD)
+        }
+    }
+
+    /// Creates the legend, with a line for each token on the grid and one counting the combatants
+    /// that aren't on it.
+    fn legend(&self) -> Vec<Line<'a>> {
+        let focus = self.square_of(self.focus);
+        let mut lines = Vec::new();
+        let mut off_grid = 0;
+        for (index, combatant) in self.tracker.combatants.iter().enumerate() {
+            let Some(square) = self.square_of(index) else {
+                off_grid += 1;
+                continue;
+            };
+            let mut line = Line::from(vec![
+                Span::styled(token(index).to_string(), self.token_style(index)),
+                Span::raw(format!(" {}", combatant.name())),
+            ]);
+            if let Some(focus) = focus.filter(|_| index != self.focus) {
+                line.push_span(Span::raw(format!(" {} ft.", focus.distance(square))));
+            }
+            lines.push(line);
+        }
+        if off_grid > 0 {
+            lines.push(Line::styled(format!("{} off the grid", off_grid), Modifier::ITALIC));
+        }
+        lines
+    }
+}
+
+impl Widget for GridWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match self.moving {
+            Some((start, square)) => format!("Grid (moved {} ft.)", start.distance(square)),
+            None => "Grid".to_string(),
+        };
+        let block = Block::bordered()
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
+            .title(title)
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let legend = self.legend();
+        let legend_height = (legend.len() as u16).min(inner.height / 2);
+        let [grid, legend_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(legend_height),
+        ])
+            .spacing(1)
+            .areas(inner);
+
+        // center the view on the focused token, or on the first token if it isn't on the grid
+        let center = self.square_of(self.focus)
+            .or_else(|| (0..self.tracker.combatants.len()).find_map(|i| self.square_of(i)))
+            .unwrap_or_default();
+        let columns = grid.width / 2;
+        let origin = center.offset(-(columns as i32 / 2), -(grid.height as i32 / 2));
+
+        let empty = if self.theme.ascii { "." } else { "·" };
+        for row in 0..grid.height {
+            for column in 0..columns {
+                buf.set_string(grid.x + column * 2, grid.y + row, empty, self.theme.border);
+            }
+        }
+
+        // draw in reverse, so the first combatant on a square is drawn on top
+        for index in (0..self.tracker.combatants.len()).rev() {
+            let Some(square) = self.square_of(index) else { continue };
+            let (column, row) = (square.x - origin.x, square.y - origin.y);
+            if !(0..columns as i32).contains(&column) || !(0..grid.height as i32).contains(&row) {
+                continue;
+            }
+            let (x, y) = (grid.x + column as u16 * 2, grid.y + row as u16);
+            buf.set_string(x, y, token(index).to_string(), self.token_style(index));
+        }
+
+        Paragraph::new(legend).render(legend_area, buf);
+    }
+}
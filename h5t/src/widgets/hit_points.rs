@@ -1,6 +1,9 @@
 use h5t_core::Combatant;
 use ratatui::prelude::*;
 
+/// Characters for a partially filled gauge cell, in eighths of a cell.
+const PARTIAL_BLOCKS: [&str; 8] = [" ", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
 /// A widget to display a creature's hit points, changing color based on the current hit points.
 #[derive(Debug)]
 pub struct HitPoints {
@@ -9,6 +12,9 @@ pub struct HitPoints {
 
     /// The maximum hit points.
     pub max: i32,
+
+    /// The temporary hit points, shown after the hit points when there are any.
+    pub temporary: i32,
 }
 
 impl HitPoints {
@@ -17,21 +23,58 @@ impl HitPoints {
         Self {
             current: combatant.hit_points,
             max: combatant.max_hit_points(),
+            temporary: combatant.temporary_hit_points,
+        }
+    }
+
+    /// Returns the fraction of hit points left, between 0 and 1.
+    fn fraction(&self) -> f32 {
+        if self.max <= 0 { return 0.0 }
+        (self.current as f32 / self.max as f32).clamp(0.0, 1.0)
+    }
+
+    /// Returns the color of the hit points, going from green at full hit points, to yellow at half
+    /// hit points, to red at 0 hit points.
+    pub fn color(&self) -> Color {
+        let fraction = self.fraction();
+        if fraction >= 0.5 {
+            Color::Rgb(((1.0 - fraction) * 2.0 * 255.0) as u8, 255, 0)
+        } else {
+            Color::Rgb(255, (fraction * 2.0 * 255.0) as u8, 0)
         }
     }
 
-    /// Creates a [`Line`] widget containing the hit points display.
+    /// Creates a [`Line`] widget containing the hit points display, followed by the temporary
+    /// hit points, such as `12/20 +5`.
     pub fn line(&self) -> Line<'static> {
-        let hp_color = Color::Rgb(
-            (255.0 - self.current as f32 / self.max as f32 * 255.0) as u8,
-            (self.current as f32 / self.max as f32 * 255.0) as u8,
-            0,
-        );
-
-        Line::from(vec![
-            Span::styled(format!("{}", self.current), hp_color),
+        let mut line = Line::from(vec![
+            Span::styled(format!("{}", self.current), self.color()),
             Span::raw(format!("/{}", self.max)),
-        ])
+        ]);
+        if self.temporary > 0 {
+            line.push_span(Span::styled(format!(" +{}", self.temporary), Color::Cyan));
+        }
+        line
+    }
+
+    /// Creates a [`Line`] widget containing a bar `width` cells wide, filled in proportion to the
+    /// hit points left (down to an eighth of a cell, or a whole cell of `#` if `ascii` is set).
+    pub fn gauge(&self, width: usize, ascii: bool) -> Line<'static> {
+        if ascii {
+            let full = (self.fraction() * width as f32).round() as usize;
+            return Line::styled(format!("{:<width$}", "#".repeat(full)), self.color());
+        }
+
+        let eighths = (self.fraction() * width as f32 * 8.0).round() as usize;
+        let (full, partial) = (eighths / 8, eighths % 8);
+
+        let mut bar = "█".repeat(full);
+        if full < width {
+            bar.push_str(PARTIAL_BLOCKS[partial]);
+            bar.push_str(&" ".repeat(width - full - 1));
+        }
+
+        Line::styled(bar, self.color())
     }
 }
 
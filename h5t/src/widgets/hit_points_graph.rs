@@ -0,0 +1,106 @@
+use crate::theme::Theme;
+use crate::widgets::HitPoints;
+use ratatui::{prelude::*, symbols::bar, widgets::*};
+
+/// Bars drawn with `#` and `=`, for themes limited to ASCII.
+const ASCII_BARS: bar::Set = bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "#",
+    five_eighths: "=",
+    half: "=",
+    three_eighths: "=",
+    one_quarter: "_",
+    one_eighth: "_",
+    empty: " ",
+};
+
+/// A widget to display a sparkline of hit points over the rounds of the encounter, with the
+/// lowest point reached, to show how close the fight was.
+#[derive(Debug)]
+pub struct HitPointsGraph<'a> {
+    /// Whose hit points are shown: a combatant's name, or the party.
+    pub name: &'a str,
+
+    /// The hit points before the first round and at the end of every round since, the last being
+    /// the hit points now.
+    pub points: Vec<i32>,
+
+    /// The maximum hit points, the height of a full bar.
+    pub max: i32,
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
+}
+
+impl<'a> HitPointsGraph<'a> {
+    /// Create a new [`HitPointsGraph`] widget.
+    pub fn new(name: &'a str, points: Vec<i32>, max: i32, theme: &'a Theme) -> Self {
+        Self { name, points, max, theme }
+    }
+
+    /// Creates the line summarizing the graph, such as `Now 30/45, lowest 12 after round 2`.
+    fn summary(&self) -> Line<'static> {
+        let hit_points = |current| HitPoints { current, max: self.max, temporary: 0 }.line();
+        let now = self.points.last().copied().unwrap_or_default();
+        let (lowest, value) = self.points
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|&(_, value)| value)
+            .unwrap_or_default();
+
+        let mut line = Line::raw("Now ");
+        line.extend(hit_points(now));
+        line.push_span(", lowest ");
+        line.extend(hit_points(value));
+        match lowest {
+            0 => line.push_span(" at the start"),
+            round => line.push_span(format!(" after round {}", round)),
+        }
+        line
+    }
+
+    /// Returns the points to draw in the given number of columns: each point repeated to fill the
+    /// width, or the most recent points if they don't all fit.
+    fn bars(&self, width: usize) -> Vec<u64> {
+        let start = self.points.len().saturating_sub(width);
+        let repeat = (width / self.points.len().max(1)).max(1);
+        self.points[start..]
+            .iter()
+            .flat_map(|&value| std::iter::repeat_n(value.max(0) as u64, repeat))
+            .collect()
+    }
+}
+
+impl Widget for HitPointsGraph<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
+            .title(format!("{} HP", self.name))
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.max <= 0 {
+            Line::styled("(no hit points to show)", Modifier::ITALIC).render(inner, buf);
+            return;
+        }
+
+        let [summary, graph] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)])
+            .spacing(1)
+            .areas(inner);
+        self.summary().render(summary, buf);
+
+        let now = self.points.last().copied().unwrap_or_default();
+        let color = HitPoints { current: now, max: self.max, temporary: 0 }.color();
+        let highest = self.points.iter().copied().max().unwrap_or_default().max(self.max);
+        Sparkline::default()
+            .data(self.bars(graph.width as usize))
+            .max(highest as u64)
+            .bar_set(if self.theme.ascii { ASCII_BARS } else { bar::NINE_LEVELS })
+            .style(Style::default().fg(color))
+            .render(graph, buf);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::theme::Theme;
+use h5t_core::LogEntry;
+use ratatui::{prelude::*, widgets::*};
+
+/// A widget to display the most recent combat log events, with a separator at the start of each
+/// round.
+#[derive(Debug)]
+pub struct LogPanel<'a> {
+    /// The combat log, from oldest to newest event.
+    pub log: &'a [LogEntry],
+
+    /// The number of lines scrolled up from the newest event.
+    pub scroll: usize,
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
+}
+
+impl<'a> LogPanel<'a> {
+    /// Create a new [`LogPanel`] widget.
+    pub fn new(log: &'a [LogEntry], scroll: usize, theme: &'a Theme) -> Self {
+        Self { log, scroll, theme }
+    }
+
+    /// Creates the lines of the log, from oldest to newest.
+    fn lines(&self) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        let mut round = None;
+        for entry in self.log {
+            if round != Some(entry.round) {
+                round = Some(entry.round);
+                let rule = if self.theme.ascii { "--" } else { "──" };
+                let separator = format!("{} Round {} {}", rule, entry.round + 1, rule);
+                lines.push(Line::styled(separator, Modifier::BOLD).centered());
+            }
+            lines.push(Line::raw(entry.event.to_string()));
+        }
+        lines
+    }
+}
+
+impl Widget for LogPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
+            .title("Combat Log")
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // show the newest lines that fit, moved up by the scroll offset
+        let lines = self.lines();
+        let height = inner.height as usize;
+        let end = lines.len().saturating_sub(self.scroll.min(lines.len().saturating_sub(height)));
+        let start = end.saturating_sub(height);
+
+        Paragraph::new(lines[start..end].to_vec()).render(inner, buf);
+    }
+}
@@ -1,16 +1,34 @@
 pub mod ability_scores;
+pub mod character_sheet;
 pub mod combatant_block;
 pub mod conditions;
+pub mod debug_panel;
+pub mod grid;
 pub mod hit_points;
+pub mod hit_points_graph;
+pub mod log_panel;
+pub mod notes_panel;
 pub mod popup;
 pub mod stat_block;
+pub mod status_bar;
+pub mod text_input;
+pub mod toast;
 pub mod tracker;
 
 pub use ability_scores::AbilityScores;
+pub use character_sheet::CharacterSheet;
 pub use combatant_block::CombatantBlock;
 pub use conditions::CompactConditions;
+pub use debug_panel::DebugPanel;
+pub use grid::GridWidget;
 pub use hit_points::HitPoints;
+pub use hit_points_graph::HitPointsGraph;
+pub use log_panel::LogPanel;
+pub use notes_panel::NotesPanel;
 pub use stat_block::StatBlock;
+pub use status_bar::{Hint, StatusBar};
+pub use text_input::TextInput;
+pub use toast::{Toast, Toasts};
 pub use tracker::TrackerWidget;
 
-pub(crate) use tracker::max_combatants_visible;
+pub(crate) use tracker::{max_combatants_visible, tracker_row_at};
@@ -0,0 +1,54 @@
+use crate::theme::Theme;
+use h5t_core::Combatant;
+use ratatui::{prelude::*, widgets::*};
+
+/// A widget to display a combatant's notes and the encounter's notes.
+#[derive(Debug)]
+pub struct NotesPanel<'a> {
+    /// The combatant whose notes are shown.
+    pub combatant: &'a Combatant,
+
+    /// The notes of the whole encounter.
+    pub encounter: &'a str,
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
+}
+
+impl<'a> NotesPanel<'a> {
+    /// Create a new [`NotesPanel`] widget.
+    pub fn new(combatant: &'a Combatant, encounter: &'a str, theme: &'a Theme) -> Self {
+        Self { combatant, encounter, theme }
+    }
+}
+
+impl Widget for NotesPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // draw bordered box
+        Block::bordered()
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
+            .title("Notes")
+            .render(area, buf);
+
+        let [combatant, encounter] = Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)])
+            .horizontal_margin(2)
+            .vertical_margin(1) // avoid the border
+            .spacing(1)
+            .areas(area);
+
+        /// Creates a [`Paragraph`] with a bold title and the notes, or a placeholder if empty.
+        fn section<'a>(title: &'a str, notes: &'a str) -> Paragraph<'a> {
+            let mut text = Text::from(Line::styled(title, Modifier::BOLD));
+            if notes.is_empty() {
+                text.push_line(Line::styled("(no notes)", Modifier::ITALIC));
+            } else {
+                text.extend(Text::raw(notes));
+            }
+            Paragraph::new(text).wrap(Wrap { trim: false })
+        }
+
+        section(self.combatant.name(), &self.combatant.notes).render(combatant, buf);
+        section("Encounter", self.encounter).render(encounter, buf);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::theme::Theme;
+use itertools::Itertools;
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::popup_area;
+
+/// Computes the area of a [`Dialog`] with the given message and buttons, centered in `area`.
+fn dialog_area(area: Rect, title: &str, message: &str, buttons: &[&str]) -> Rect {
+    let buttons_width = buttons.iter().map(|b| b.len() + 4).sum::<usize>() + buttons.len();
+    let content_width = message
+        .lines()
+        .map(str::len)
+        .chain([buttons_width, title.len()])
+        .max()
+        .unwrap_or(0);
+    let size = (
+        // 4 for borders and text padding
+        content_width.max(30).min(area.width.saturating_sub(4) as usize) as u16 + 4,
+        // 2 for borders, 1 for spacing, 1 for the buttons
+        message.lines().count() as u16 + 4,
+    );
+    popup_area(area, Flex::Center, Flex::Center, size, 0)
+}
+
+/// Returns the areas of a dialog's buttons, right-aligned on the last line inside the border.
+fn button_areas(dialog: Rect, buttons: &[&str]) -> Vec<Rect> {
+    let inner = dialog.inner(Margin::new(2, 1));
+    let line = Rect { y: inner.bottom().saturating_sub(1), height: 1, ..inner };
+    Layout::horizontal(buttons.iter().map(|b| Constraint::Length(b.len() as u16 + 4)))
+        .flex(Flex::End)
+        .spacing(1)
+        .split(line)
+        .to_vec()
+}
+
+/// Returns the index of the button of a [`Dialog`] that is drawn at the given position, if any.
+///
+/// `area`, `title`, `message`, and `buttons` must be the same values the dialog is rendered with.
+pub(crate) fn button_at(
+    area: Rect,
+    title: &str,
+    message: &str,
+    buttons: &[&str],
+    position: Position,
+) -> Option<usize> {
+    let dialog = dialog_area(area, title, message, buttons);
+    button_areas(dialog, buttons).iter().position(|button| button.contains(position))
+}
+
+/// A modal popup showing a message and a row of buttons, one of which has keyboard focus.
+///
+/// This widget doesn't handle input, the state that owns it moves the focus.
+pub struct Dialog<'a> {
+    /// The title of the dialog.
+    title: &'a str,
+
+    /// The message to show, which can span several lines.
+    message: &'a str,
+
+    /// The labels of the buttons, from left to right.
+    buttons: &'a [&'a str],
+
+    /// The index of the focused button.
+    focus: usize,
+
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
+}
+
+impl<'a> Dialog<'a> {
+    /// Create a new [`Dialog`] popup.
+    pub fn new(
+        title: &'a str,
+        message: &'a str,
+        buttons: &'a [&'a str],
+        focus: usize,
+        theme: &'a Theme,
+    ) -> Self {
+        Self { title, message, buttons, focus, theme }
+    }
+}
+
+impl Widget for Dialog<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = dialog_area(area, self.title, self.message, self.buttons);
+
+        // clear the area
+        Clear.render(area, buf);
+
+        Block::bordered()
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
+            .title(self.title)
+            .render(area, buf);
+
+        Paragraph::new(self.message).render(area.inner(Margin::new(2, 1)), buf);
+
+        for (i, (label, button)) in self.buttons
+            .iter()
+            .zip_eq(button_areas(area, self.buttons))
+            .enumerate()
+        {
+            let style = if i == self.focus {
+                Style::new().reversed().bold()
+            } else {
+                Style::new()
+            };
+            Line::styled(format!("[ {} ]", label), style).render(button, buf);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::keymap::HelpSection;
+use crate::theme::Theme;
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::popup_area;
+
+/// A popup that lists key bindings, grouped by the context they are active in.
+pub struct Help<'a> {
+    /// The groups of key bindings to display.
+    sections: &'a [HelpSection],
+
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
+}
+
+impl<'a> Help<'a> {
+    /// Create a new [`Help`] popup.
+    pub fn new(sections: &'a [HelpSection], theme: &'a Theme) -> Self {
+        Self { sections, theme }
+    }
+}
+
+impl Widget for Help<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut rows = Vec::new();
+        for section in self.sections {
+            if !rows.is_empty() {
+                rows.push(Row::default());
+            }
+            rows.push(Row::new([
+                Text::styled(section.title, Modifier::BOLD | Modifier::UNDERLINED),
+            ]));
+            rows.extend(section.entries.iter().map(|(keys, description)| Row::new([
+                Text::styled(keys.as_str(), Modifier::BOLD),
+                Text::raw(*description),
+            ])));
+        }
+
+        // 2 for top and bottom border
+        let size = (60, rows.len() as u16 + 2);
+        let area = popup_area(area, Flex::Center, Flex::Center, size, 0);
+
+        // clear the area
+        Clear.render(area, buf);
+
+        let widget = Table::new(rows, [Constraint::Length(16), Constraint::Fill(1)])
+            .column_spacing(2)
+            .block(Block::bordered()
+                .border_set(self.theme.border_set())
+                .border_style(Style::default().fg(self.theme.border))
+                .title("Help")
+                .title_bottom("Press any key to close")
+                .padding(Padding::symmetric(1, 0)));
+
+        Widget::render(widget, area, buf);
+    }
+}
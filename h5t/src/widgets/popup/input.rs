@@ -1,4 +1,5 @@
 use ratatui::{layout::Flex, prelude::*, widgets::*};
+use crate::theme::Theme;
 use super::popup_area;
 
 /// A popup to get a line of input from the user.
@@ -16,6 +17,12 @@ pub struct Input<'a> {
 
     /// Maximum length of the input field.
     max_length: usize,
+
+    /// (optional) Why the value is invalid, shown under the input field.
+    error: Option<&'a str>,
+
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
 }
 
 impl<'a> Input<'a> {
@@ -25,14 +32,23 @@ impl<'a> Input<'a> {
         prompt: &'a str,
         value: &'a str,
         max_length: usize,
+        theme: &'a Theme,
     ) -> Self {
         Self {
             color,
             prompt,
             value,
             max_length,
+            error: None,
+            theme,
         }
     }
+
+    /// Shows why the value is invalid under the input field.
+    pub fn with_error(mut self, error: Option<&'a str>) -> Self {
+        self.error = error;
+        self
+    }
 }
 
 impl Widget for Input<'_> {
@@ -40,7 +56,7 @@ impl Widget for Input<'_> {
         // center the input
         let size = (
             // 4 includes borders and text padding
-            self.prompt.len().max(self.max_length) as u16 + 4,
+            self.prompt.len().max(self.max_length).max(self.error.map_or(0, str::len)) as u16 + 4,
             3, // 2 for borders, 1 for text
         );
         let area = popup_area(area, Flex::Center, Flex::End, size, 1);
@@ -49,11 +65,14 @@ impl Widget for Input<'_> {
         Clear.render(area, buf);
 
         // draw bordered box for the input field
-        Block::bordered()
-            .border_type(BorderType::Rounded)
+        let mut block = Block::bordered()
+            .border_set(self.theme.border_set())
             .border_style(Style::default().fg(self.color))
-            .title(self.prompt)
-            .render(area, buf);
+            .title(self.prompt);
+        if let Some(error) = self.error {
+            block = block.title_bottom(error);
+        }
+        block.render(area, buf);
 
         let text_area = area.inner(Margin::new(2, 1));
 
@@ -68,6 +87,6 @@ impl Widget for Input<'_> {
         // buf.set_.set_symbol(cursor_x, cursor_y, "▌");
         buf.cell_mut((cursor_x, cursor_y))
             .expect("cursor out of bounds")
-            .set_bg(Color::White); // TODO: cursor color
+            .set_style(self.theme.highlight(self.theme.cursor));
     }
 }
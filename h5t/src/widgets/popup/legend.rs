@@ -0,0 +1,54 @@
+use crate::selectable::Selectable;
+use crate::theme::Theme;
+use crate::widgets::conditions::badge;
+use h5t_core::{ConditionKind, Edition};
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::popup_area;
+
+/// A popup that lists the condition badges, the condition each one stands for, and what it does
+/// in an edition of the rules.
+pub struct ConditionLegend<'a> {
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
+
+    /// The edition the conditions are summarized from.
+    edition: Edition,
+}
+
+impl<'a> ConditionLegend<'a> {
+    /// Create a new [`ConditionLegend`] popup, summarizing the conditions from the given edition.
+    pub fn new(theme: &'a Theme, edition: Edition) -> Self {
+        Self { theme, edition }
+    }
+}
+
+impl Widget for ConditionLegend<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let rows = ConditionKind::variants()
+            .map(|kind| Row::new([
+                Text::from(badge(kind, "")),
+                Text::raw(kind.to_string()),
+                Text::raw(kind.summary(self.edition)),
+            ]))
+            .collect::<Vec<_>>();
+
+        // 2 for top and bottom border, shrunk to fit smaller screens
+        let size = (110.min(area.width), ConditionKind::N as u16 + 2);
+        let area = popup_area(area, Flex::Center, Flex::Center, size, 0);
+
+        // clear the area
+        Clear.render(area, buf);
+
+        let widths = [Constraint::Length(5), Constraint::Length(13), Constraint::Fill(1)];
+        let widget = Table::new(rows, widths)
+            .column_spacing(2)
+            .block(Block::bordered()
+                .border_set(self.theme.border_set())
+                .border_style(Style::default().fg(self.theme.border))
+                .title(format!("Conditions ({})", self.edition))
+                .title_bottom("Press any key to close")
+                .padding(Padding::symmetric(1, 0)));
+
+        Widget::render(widget, area, buf);
+    }
+}
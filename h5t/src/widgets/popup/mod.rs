@@ -1,12 +1,23 @@
 //! Widgets that pop up and cover the screen.
 
+pub mod dialog;
+pub mod help;
 pub mod input;
+pub mod legend;
 pub mod multiselect;
+pub mod numeric_input;
 pub mod select;
+pub mod stats;
 
+pub use dialog::Dialog;
+pub use help::Help;
 pub use input::Input;
+pub use legend::ConditionLegend;
 pub use multiselect::Multiselect;
+pub use numeric_input::NumericInput;
 pub use select::Select;
+pub use stats::CombatStatsTable;
+use crate::selectable::Selectable;
 use ratatui::{layout::Flex, prelude::*};
 
 /// Computes the area to render a popup in, given horizontal and vertical alignment requirements
@@ -29,3 +40,32 @@ pub(crate) fn popup_area(
 
     area
 }
+
+/// Computes the area occupied by a [`Select`] or [`Multiselect`] popup over the options of `T`,
+/// given the area it is centered in and the title it is rendered with.
+pub(crate) fn select_area<T: Selectable>(area: Rect, prompt: &str) -> Rect {
+    // 4 for borders and text padding, 2 for space for labels
+    let content_width = 4 + 2 + T::variants()
+        .map(|v| v.to_string().len())
+        .max()
+        .unwrap_or(0) as u16;
+    let size = (
+        content_width.max(prompt.len() as u16 + 2),
+        // 2 for top and bottom border
+        2 + T::N as u16,
+    );
+    popup_area(area, Flex::Center, Flex::Center, size, 0)
+}
+
+/// Returns the option of a [`Select`] or [`Multiselect`] popup that is drawn at the given
+/// position, if any.
+///
+/// `area` and `prompt` must be the same values the popup is rendered with.
+pub(crate) fn option_at<T: Selectable>(area: Rect, prompt: &str, position: Position) -> Option<T> {
+    // skip the borders and horizontal padding
+    let options = select_area::<T>(area, prompt).inner(Margin::new(2, 1));
+    if !options.contains(position) {
+        return None;
+    }
+    T::variants().nth((position.y - options.y) as usize)
+}
@@ -1,7 +1,7 @@
-use crate::{selectable::Selectable, ui::LABELS};
-use ratatui::{layout::Flex, prelude::*, widgets::*};
+use crate::{keymap::KeyboardLayout, selectable::Selectable, theme::Theme};
+use ratatui::{prelude::*, widgets::*};
 use std::collections::HashSet;
-use super::popup_area;
+use super::{option_at, select_area};
 
 /// A popup that displays a multi-select prompt for an enum. Like [`Select`], but for multiple
 /// options.
@@ -18,58 +18,67 @@ pub struct Multiselect<'a, T> {
 
     /// Whether to render the widget in an active state.
     active: bool,
+
+    /// The labels of the options, in order.
+    labels: &'a str,
+
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
 }
 
 impl<'a, T> Multiselect<'a, T> {
     /// Create a new [`Multiselect`] popup with all the required fields.
-    pub fn new(prompt: &'a str, selected: &'a HashSet<T>, active: bool) -> Self {
-        Self { prompt, selected, active }
+    pub fn new(prompt: &'a str, selected: &'a HashSet<T>, active: bool, theme: &'a Theme) -> Self {
+        Self { prompt, selected, active, labels: KeyboardLayout::default().labels(), theme }
+    }
+
+    /// Label the options with the labels of a keyboard layout.
+    pub fn with_labels(mut self, labels: &'a str) -> Self {
+        self.labels = labels;
+        self
+    }
+}
+
+impl<T: Selectable> Multiselect<'_, T> {
+    /// Returns the title of the popup, including the number of selected options.
+    fn title(&self) -> String {
+        format!("{} ({}/{})", self.prompt, self.selected.len(), T::N)
+    }
+
+    /// Returns the option drawn at the given position if the popup is rendered in `area`.
+    pub fn option_at(&self, area: Rect, position: Position) -> Option<T> {
+        option_at(area, &self.title(), position)
     }
 }
 
 impl<T: Selectable> Widget for Multiselect<'_, T> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let prompt = format!("{} ({}/{})", self.prompt, self.selected.len(), T::N);
+        let prompt = self.title();
 
         // center widget
-        // 4 for borders and text padding, 2 for space for labels
-        let content_width = 4 + 2 + T::variants()
-            .map(|v| v.to_string().len())
-            .max()
-            .unwrap_or(0) as u16;
-        let size = (
-            content_width.max(prompt.len() as u16 + 2),
-            // 2 for top and bottom border
-            2 + T::N as u16,
-        );
-        let area = popup_area(area, Flex::Center, Flex::Center, size, 0);
+        let area = select_area::<T>(area, &prompt);
 
         // clear the area
         Clear.render(area, buf);
 
+        let theme = self.theme;
         let widget = Table::new(
-            LABELS.chars()
+            self.labels.chars()
                 .zip(T::variants())
                 .map(|(label, option)| {
                     let is_label_selected = self.selected.contains(&option);
                     let mut style = Style::default();
                     if is_label_selected {
-                        style = style.bold();
+                        style = theme.highlight(theme.selected).bold();
                     }
 
-                    let bg_color = if is_label_selected {
-                        Color::Rgb(128, 85, 0)
-                    } else {
-                        Color::Reset
-                    };
-
                     let fg_color = if self.active {
-                        Color::White
+                        theme.text
                     } else {
-                        Color::Rgb(128, 128, 128)
+                        theme.inactive
                     };
 
-                    style = style.bg(bg_color).fg(fg_color);
+                    style = style.fg(fg_color);
 
                     Row::new(vec![
                         Text::styled(label.to_string(), Modifier::BOLD),
@@ -82,8 +91,8 @@ impl<T: Selectable> Widget for Multiselect<'_, T> {
             ],
         )
             .block(Block::bordered()
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(if self.active { Color::White } else { Color::Rgb(128, 128, 128) }))
+                .border_set(theme.border_set())
+                .border_style(Style::default().fg(if self.active { theme.border } else { theme.inactive }))
                 .title(prompt)
                 .padding(Padding::symmetric(1, 0)));
 
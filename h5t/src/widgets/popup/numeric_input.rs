@@ -0,0 +1,126 @@
+use crate::keymap::{Keymap, PopupCommand};
+use crate::state::AfterKey;
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent};
+use h5t_core::DiceExpression;
+use rand::Rng;
+use ratatui::prelude::*;
+use super::Input;
+
+/// A popup input field for a number, or a dice expression such as `2d6+3` if allowed.
+///
+/// Unlike [`Input`], this handles its own key events and validates the value as it is typed,
+/// highlighting the field and showing the reason if the value is invalid.
+#[derive(Clone, Debug)]
+pub struct NumericInput {
+    /// The prompt to display as the title of the input box.
+    prompt: &'static str,
+
+    /// The text that the user has entered.
+    value: String,
+
+    /// Maximum length of the input field.
+    max_length: usize,
+
+    /// The smallest value allowed.
+    min: i32,
+
+    /// The largest value allowed.
+    max: i32,
+
+    /// Whether dice expressions are allowed.
+    dice: bool,
+
+    /// (optional) Why the value is invalid. Empty values aren't reported until confirmed.
+    error: Option<String>,
+}
+
+impl NumericInput {
+    /// Create a new [`NumericInput`] accepting any whole number.
+    pub fn new(prompt: &'static str, max_length: usize) -> Self {
+        Self {
+            prompt,
+            value: String::new(),
+            max_length,
+            min: i32::MIN,
+            max: i32::MAX,
+            dice: false,
+            error: None,
+        }
+    }
+
+    /// Only accept values between `min` and `max` (inclusive). Dice expressions must not be able
+    /// to roll outside the range.
+    pub fn with_range(mut self, min: i32, max: i32) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Accept dice expressions, such as `2d6+3`, as well as plain numbers.
+    pub fn with_dice(mut self) -> Self {
+        self.dice = true;
+        self
+    }
+
+    /// Start with the given value, so an existing number can be edited.
+    pub fn with_value(mut self, value: impl ToString) -> Self {
+        self.value = value.to_string();
+        self
+    }
+
+    /// Parses and validates the value.
+    pub fn expression(&self) -> Result<DiceExpression, String> {
+        let expression = self.value.parse::<DiceExpression>()?;
+        if !self.dice && !expression.is_constant() {
+            return Err("dice aren't allowed here".to_string());
+        }
+
+        let (low, high) = expression.range();
+        if low < self.min {
+            return Err(format!("must be at least {}", self.min));
+        }
+        if high > self.max {
+            return Err(format!("must be at most {}", self.max));
+        }
+        Ok(expression)
+    }
+
+    /// Returns the value, rolling any dice, or [`None`] if the value is invalid.
+    pub fn value(&self) -> Option<i32> {
+        let mut rng = rand::rng();
+        let expression = self.expression().ok()?;
+        Some(expression.roll(|sides| rng.random_range(1..=sides)))
+    }
+
+    /// Handle a key event. Confirming only exits if the value is valid.
+    pub fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> AfterKey {
+        match (keymap.popup.get(key), key.code) {
+            (Some(PopupCommand::Confirm), _) => {
+                self.error = self.expression().err();
+                if self.error.is_none() { return AfterKey::Exit }
+                return AfterKey::Stay;
+            },
+            (Some(PopupCommand::Back), _) => return AfterKey::Cancel,
+            (Some(PopupCommand::Erase), _) => { self.value.pop(); },
+            (None, KeyCode::Char(c)) if self.value.len() >= self.max_length => {
+                self.error = Some(format!("can't type `{}`, the value is too long", c));
+                return AfterKey::Stay;
+            },
+            (None, KeyCode::Char(c @ ('0'..='9' | '+' | '-' | ' '))) => self.value.push(c),
+            (None, KeyCode::Char(c @ ('d' | 'D'))) if self.dice => self.value.push(c),
+            _ => (),
+        }
+
+        self.error = if self.value.trim().is_empty() { None } else { self.expression().err() };
+        AfterKey::Stay
+    }
+
+    /// Draw the input field to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        let color = if self.error.is_some() { Color::Red } else { Color::Reset };
+        let input = Input::new(color, self.prompt, &self.value, self.max_length, theme)
+            .with_error(self.error.as_deref());
+        frame.render_widget(input, frame.area());
+    }
+}
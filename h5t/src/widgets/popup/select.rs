@@ -1,6 +1,6 @@
-use crate::{selectable::Selectable, ui::LABELS};
-use ratatui::{layout::Flex, prelude::*, widgets::*};
-use super::popup_area;
+use crate::{keymap::KeyboardLayout, selectable::Selectable, theme::Theme};
+use ratatui::{prelude::*, widgets::*};
+use super::{option_at, select_area};
 
 /// A popup that displays a selection prompt for an enum. Like [`Multiselect`], but for a single
 /// option.
@@ -17,42 +17,55 @@ pub struct Select<'a, T> {
 
     /// Whether to render the widget in an active state.
     active: bool,
+
+    /// The labels of the options, in order.
+    labels: &'a str,
+
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
 }
 
 impl<'a, T> Select<'a, T> {
     /// Create a new [`Select`] popup with all the required fields.
-    pub fn new(prompt: &'a str, selected: &'a T, active: bool) -> Self {
-        Self { prompt, selected, active }
+    pub fn new(prompt: &'a str, selected: &'a T, active: bool, theme: &'a Theme) -> Self {
+        Self { prompt, selected, active, labels: KeyboardLayout::default().labels(), theme }
+    }
+
+    /// Label the options with the labels of a keyboard layout.
+    pub fn with_labels(mut self, labels: &'a str) -> Self {
+        self.labels = labels;
+        self
+    }
+}
+
+impl<T: Selectable> Select<'_, T> {
+    /// Returns the option drawn at the given position if the popup is rendered in `area`.
+    pub fn option_at(&self, area: Rect, position: Position) -> Option<T> {
+        option_at(area, self.prompt, position)
     }
 }
 
 impl<T: Selectable> Widget for Select<'_, T> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // center widget
-        // 4 for borders and text padding, 2 for space for labels
-        let content_width = 4 + 2 + T::variants()
-            .map(|v| v.to_string().len())
-            .max()
-            .unwrap_or(0) as u16;
-        let size = (
-            content_width.max(self.prompt.len() as u16 + 2),
-            // 2 for top and bottom border
-            2 + T::N as u16,
-        );
-        let area = popup_area(area, Flex::Center, Flex::Center, size, 0);
+        let area = select_area::<T>(area, self.prompt);
 
         // clear the area
         Clear.render(area, buf);
 
+        let theme = self.theme;
         let widget = Table::new(
-            LABELS.chars()
+            self.labels.chars()
                 .zip(T::variants())
                 .map(|(label, option)| {
                     let style = match (*self.selected == option, self.active) {
-                        (true, true) => Style::default().bold().fg(Color::White).bg(Color::Rgb(128, 85, 0)),
-                        (true, false) => Style::default().bold().fg(Color::Rgb(128, 128, 128)).bg(Color::Rgb(64, 42, 0)),
-                        (false, true) => Color::White.into(),
-                        (false, false) => Color::Rgb(128, 128, 128).into(),
+                        (true, true) => theme.highlight(theme.selected).bold().fg(theme.text),
+                        (true, false) => theme
+                            .highlight(theme.selected_inactive)
+                            .bold()
+                            .fg(theme.inactive),
+                        (false, true) => theme.text.into(),
+                        (false, false) => theme.inactive.into(),
                     };
                     Row::new(vec![
                         Text::styled(label.to_string(), Modifier::BOLD),
@@ -65,8 +78,8 @@ impl<T: Selectable> Widget for Select<'_, T> {
             ],
         )
             .block(Block::bordered()
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(if self.active { Color::White } else { Color::Rgb(128, 128, 128) }))
+                .border_set(theme.border_set())
+                .border_style(Style::default().fg(if self.active { theme.border } else { theme.inactive }))
                 .title(self.prompt)
                 .padding(Padding::symmetric(1, 0)));
 
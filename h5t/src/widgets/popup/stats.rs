@@ -0,0 +1,64 @@
+use crate::theme::Theme;
+use h5t_core::CombatStats;
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::popup_area;
+
+/// A popup that lists the damage dealt and taken, healing done, and kills of every combatant in
+/// the encounter, most damage dealt first.
+pub struct CombatStatsTable<'a> {
+    /// The theme to draw the widget with.
+    theme: &'a Theme,
+
+    /// The stats to list, in order.
+    stats: &'a [CombatStats],
+}
+
+impl<'a> CombatStatsTable<'a> {
+    /// Create a new [`CombatStatsTable`] popup, listing the given stats in order.
+    pub fn new(theme: &'a Theme, stats: &'a [CombatStats]) -> Self {
+        Self { theme, stats }
+    }
+}
+
+impl Widget for CombatStatsTable<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let header = Row::new(["Combatant", "Dealt", "Taken", "Healed", "Kills"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows = self.stats
+            .iter()
+            .map(|stats| Row::new([
+                Text::raw(stats.name.as_str()),
+                Text::raw(stats.damage_dealt.to_string()).right_aligned(),
+                Text::raw(stats.damage_taken.to_string()).right_aligned(),
+                Text::raw(stats.healing_done.to_string()).right_aligned(),
+                Text::raw(stats.kills.to_string()).right_aligned(),
+            ]))
+            .collect::<Vec<_>>();
+
+        // 2 for top and bottom border, 1 for the header, shrunk to fit smaller screens
+        let size = (60.min(area.width), self.stats.len() as u16 + 3);
+        let area = popup_area(area, Flex::Center, Flex::Center, size, 0);
+
+        // clear the area
+        Clear.render(area, buf);
+
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(5),
+        ];
+        let widget = Table::new(rows, widths)
+            .header(header)
+            .column_spacing(2)
+            .block(Block::bordered()
+                .border_set(self.theme.border_set())
+                .border_style(Style::default().fg(self.theme.border))
+                .title("Combat Stats")
+                .title_bottom("Press any key to close")
+                .padding(Padding::symmetric(1, 0)));
+
+        Widget::render(widget, area, buf);
+    }
+}
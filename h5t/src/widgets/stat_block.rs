@@ -1,5 +1,6 @@
 use h5t_core::{monster::{Size, Speed, Type, Usage}, Monster};
 use ratatui::{prelude::*, widgets::*};
+use crate::theme::Theme;
 use super::AbilityScores;
 
 /// Creates a [`Paragraph`] widget for displaying the monster's name and type.
@@ -48,6 +49,19 @@ fn name_and_type_paragraph(monster: &'_ Monster) -> Paragraph<'_> {
     ])
 }
 
+/// Formats a challenge rating and the experience it is worth, such as `1/4 (50 XP)`.
+pub fn fmt_cr(cr: f32, xp: i32) -> String {
+    let cr_value = if cr == 0.0 {
+        "0".to_string()
+    } else if cr < 1.0 {
+        format!("1/{}", 1.0 / cr)
+    } else {
+        cr.to_string()
+    };
+
+    format!("{} ({} XP)", cr_value, xp)
+}
+
 /// Creates a [`Table`] widget for displaying a monster's basic statistics.
 fn basic_stats_table(monster: &'_ Monster) -> Table<'_> {
     /// Format's a speed value.
@@ -82,17 +96,13 @@ fn basic_stats_table(monster: &'_ Monster) -> Table<'_> {
         parts
     }
 
-    /// Formats a challenge rating.
-    fn fmt_cr(cr: f32, xp: i32) -> String {
-        let cr_value = if cr == 0.0 {
-            "0".to_string()
-        } else if cr < 1.0 {
-            format!("1/{}", 1.0 / cr)
-        } else {
-            cr.to_string()
-        };
-
-        format!("{} ({} XP)", cr_value, xp)
+    /// Formats a monster's senses, ending with its passive Perception as in the books.
+    fn fmt_senses(monster: &Monster) -> String {
+        let passive_perception = monster.passive_perception();
+        match monster.senses.to_string() {
+            listed if listed.is_empty() => format!("passive Perception {}", passive_perception),
+            listed => format!("{}, passive Perception {}", listed, passive_perception),
+        }
     }
 
     Table::new(
@@ -109,6 +119,10 @@ fn basic_stats_table(monster: &'_ Monster) -> Table<'_> {
                 Text::styled("Speed", Modifier::BOLD),
                 Text::raw(fmt_speed(&monster.speed)),
             ]),
+            Row::new(vec![
+                Text::styled("Senses", Modifier::BOLD),
+                Text::raw(fmt_senses(monster)),
+            ]),
             Row::new(vec![
                 Text::styled("Challenge", Modifier::BOLD),
                 Text::raw(fmt_cr(monster.challenge_rating, monster.xp)),
@@ -129,7 +143,7 @@ fn basic_stats_table(monster: &'_ Monster) -> Table<'_> {
 fn traits_paragraph(monster: &'_ Monster) -> Paragraph<'_> {
     use itertools::Itertools;
 
-    let text = monster
+    let lines = monster
         .traits
         .iter()
         .map(|ability| {
@@ -144,8 +158,8 @@ fn traits_paragraph(monster: &'_ Monster) -> Paragraph<'_> {
                 Span::styled(constraint, Modifier::BOLD | Modifier::ITALIC),
                 Span::raw(&ability.desc),
             ])
-        })
-        .intersperse(Line::raw(""))
+        });
+    let text = Itertools::intersperse(lines, Line::raw(""))
         .collect::<Vec<_>>();
     Paragraph::new(text)
         .wrap(Wrap { trim: true })
@@ -156,12 +170,15 @@ fn traits_paragraph(monster: &'_ Monster) -> Paragraph<'_> {
 pub struct StatBlock<'a> {
     /// The monster to display.
     pub monster: &'a Monster,
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
 }
 
 impl<'a> StatBlock<'a> {
     /// Create a new [`StatBlock`] widget.
-    pub fn new(monster: &'a Monster) -> Self {
-        Self { monster }
+    pub fn new(monster: &'a Monster, theme: &'a Theme) -> Self {
+        Self { monster, theme }
     }
 }
 
@@ -169,14 +186,14 @@ impl<'a> Widget for StatBlock<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // draw bordered box
         Block::bordered()
-            .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::White))
+            .border_set(self.theme.border_set())
+            .border_style(Style::default().fg(self.theme.border))
             .title("Monster Stat Block")
             .render(area, buf);
 
         let layout = Layout::vertical([
             Constraint::Min(2), // name and type
-            Constraint::Min(4), // basic stats
+            Constraint::Min(6), // basic stats
             Constraint::Min(6), // ability scores
             Constraint::Min(1), // traits
         ])
@@ -193,7 +210,7 @@ impl<'a> Widget for StatBlock<'a> {
 
         name_and_type_paragraph(self.monster).render(name, buf);
         Widget::render(basic_stats_table(self.monster), basic_stats, buf);
-        AbilityScores::from(self.monster).render(ability_scores, buf);
+        AbilityScores::from_monster(self.monster, self.theme).render(ability_scores, buf);
         traits_paragraph(self.monster).render(traits, buf);
     }
 }
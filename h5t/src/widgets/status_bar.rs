@@ -0,0 +1,72 @@
+use crate::keymap::{Bindable, Bindings};
+use crate::theme::Theme;
+use ratatui::{prelude::*, widgets::*};
+
+/// A key and what pressing it does, shown in the [`StatusBar`].
+#[derive(Clone, Debug)]
+pub struct Hint {
+    /// The key to press.
+    pub key: String,
+
+    /// A short description of what the key does.
+    pub label: &'static str,
+}
+
+impl Hint {
+    /// Create a [`Hint`] for a key that isn't configurable, such as the arrow keys.
+    pub fn new(key: impl Into<String>, label: &'static str) -> Self {
+        Self { key: key.into(), label }
+    }
+
+    /// Create a [`Hint`] for the first key sequence bound to a command, or [`None`] if the
+    /// command is unbound.
+    pub fn bound<C: Bindable>(
+        bindings: &Bindings<C>,
+        command: C,
+        label: &'static str,
+    ) -> Option<Self> {
+        bindings.keys_for(command).first().map(|keys| Self::new(keys.to_string(), label))
+    }
+}
+
+/// A single line at the bottom of the screen showing the current mode and its most useful keys.
+#[derive(Debug)]
+pub struct StatusBar<'a> {
+    /// The name of the current mode.
+    pub mode: &'a str,
+
+    /// The keys to show, from most to least useful. Hints that don't fit are left out.
+    pub hints: &'a [Hint],
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
+}
+
+impl<'a> StatusBar<'a> {
+    /// Create a new [`StatusBar`] widget.
+    pub fn new(mode: &'a str, hints: &'a [Hint], theme: &'a Theme) -> Self {
+        Self { mode, hints, theme }
+    }
+}
+
+impl Widget for StatusBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut spans = vec![
+            Span::styled(format!(" {} ", self.mode), Style::new().reversed().bold()),
+        ];
+        let mut width = spans[0].width();
+        for hint in self.hints {
+            let key_style = Style::new().fg(self.theme.text).bold();
+            let key = Span::styled(format!("  {}", hint.key), key_style);
+            let label = Span::styled(format!(" {}", hint.label), Style::new().fg(self.theme.inactive));
+            width += key.width() + label.width();
+            if width > area.width as usize {
+                break;
+            }
+            spans.extend([key, label]);
+        }
+
+        Clear.render(area, buf);
+        Line::from(spans).render(area, buf);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::keymap::{Keymap, PopupCommand};
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+/// A single-line text field with a movable cursor and a history of previous values.
+///
+/// The field only handles editing keys (typing, erasing, moving the cursor, and recalling
+/// history). The state that owns it decides what confirming, cancelling, or completing does.
+#[derive(Clone, Debug, Default)]
+pub struct TextInput {
+    /// The text typed so far.
+    value: String,
+
+    /// The byte index of the cursor in the value, always on a character boundary.
+    cursor: usize,
+
+    /// Previous values, from oldest to newest.
+    history: Vec<String>,
+
+    /// The index of the history entry being shown, if one was recalled.
+    recalled: Option<usize>,
+
+    /// The value typed before recalling history, restored when moving past the newest entry.
+    draft: String,
+}
+
+impl TextInput {
+    /// Create a [`TextInput`] starting with the given text, with the cursor at the end.
+    pub fn new(value: impl Into<String>) -> Self {
+        let value = value.into();
+        Self { cursor: value.len(), value, ..Default::default() }
+    }
+
+    /// Allow recalling the given previous values, from oldest to newest, with the up and down
+    /// arrow keys.
+    pub fn with_history(mut self, history: Vec<String>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Returns the text typed so far.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Replaces the text, moving the cursor to the end.
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.len();
+    }
+
+    /// Returns the text before the cursor.
+    pub fn before_cursor(&self) -> &str {
+        &self.value[..self.cursor]
+    }
+
+    /// Inserts text at the cursor, such as pasted text. Line breaks become spaces.
+    pub fn insert_str(&mut self, text: &str) {
+        let text = text.replace(['\r', '\n'], " ");
+        self.value.insert_str(self.cursor, &text);
+        self.cursor += text.len();
+    }
+
+    /// Returns the byte index of the character boundary before the cursor.
+    fn previous_boundary(&self) -> usize {
+        self.value[..self.cursor].char_indices().next_back().map_or(0, |(i, _)| i)
+    }
+
+    /// Returns the byte index of the character boundary after the cursor.
+    fn next_boundary(&self) -> usize {
+        self.value[self.cursor..]
+            .chars()
+            .next()
+            .map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+
+    /// Shows the history entry with the given index, or the draft if [`None`].
+    fn recall(&mut self, index: Option<usize>) {
+        if self.recalled.is_none() {
+            self.draft = self.value.clone();
+        }
+        self.recalled = index;
+        let value = index.map_or_else(|| self.draft.clone(), |i| self.history[i].clone());
+        self.set_value(value);
+    }
+
+    /// Handle a key event. Returns `true` if the key edited the field or moved its cursor.
+    pub fn handle_key(&mut self, key: KeyEvent, keymap: &Keymap) -> bool {
+        match (keymap.popup.get(key), key.code) {
+            (Some(PopupCommand::Erase), _) => {
+                let start = self.previous_boundary();
+                self.value.replace_range(start..self.cursor, "");
+                self.cursor = start;
+            },
+            (Some(_), _) => return false,
+            (None, KeyCode::Delete) => {
+                let end = self.next_boundary();
+                self.value.replace_range(self.cursor..end, "");
+            },
+            (None, KeyCode::Left) => self.cursor = self.previous_boundary(),
+            (None, KeyCode::Right) => self.cursor = self.next_boundary(),
+            (None, KeyCode::Home) => self.cursor = 0,
+            (None, KeyCode::End) => self.cursor = self.value.len(),
+            (None, KeyCode::Up) if !self.history.is_empty() => {
+                let index = self.recalled.map_or(self.history.len() - 1, |i| i.saturating_sub(1));
+                self.recall(Some(index));
+            },
+            (None, KeyCode::Down) if self.recalled.is_some() => {
+                let index = self.recalled.map(|i| i + 1).filter(|i| *i < self.history.len());
+                self.recall(index);
+            },
+            (None, KeyCode::Char(c)) => {
+                self.value.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            },
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Draws the text after `prefix` on a single line, with a fake cursor.
+    pub fn render(&self, frame: &mut Frame, area: Rect, prefix: &str, theme: &Theme) {
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(format!("{}{}", prefix, self.value)), area);
+
+        let offset = Line::raw(prefix).width() + Line::raw(self.before_cursor()).width();
+        let cursor = Position::new(area.x + offset as u16, area.y);
+        match frame.buffer_mut().cell_mut(cursor) {
+            // keep the character under the cursor readable
+            Some(cell) if cell.symbol() != " " => { cell.set_style(Modifier::REVERSED); },
+            Some(cell) => { cell.set_style(theme.highlight(theme.cursor)); },
+            None => (),
+        }
+    }
+}
+
+/// Adds a value to a history list, unless it is empty or repeats the newest entry.
+pub fn push_history(history: &mut Vec<String>, value: &str) {
+    let value = value.trim();
+    if !value.is_empty() && history.last().is_none_or(|last| last != value) {
+        history.push(value.to_string());
+    }
+}
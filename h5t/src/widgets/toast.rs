@@ -0,0 +1,73 @@
+use crate::theme::Theme;
+use ratatui::{prelude::*, widgets::*};
+use std::time::{Duration, Instant};
+
+/// A short message shown in a corner of the screen for a few seconds.
+#[derive(Clone, Debug)]
+pub struct Toast {
+    /// The message to show.
+    pub message: String,
+
+    /// When the toast disappears.
+    pub expires: Instant,
+}
+
+impl Toast {
+    /// How long toasts are shown for.
+    pub const DURATION: Duration = Duration::from_secs(3);
+
+    /// Create a [`Toast`] that disappears after [`Toast::DURATION`].
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into(), expires: Instant::now() + Self::DURATION }
+    }
+
+    /// Returns whether the toast should still be shown.
+    pub fn is_visible(&self) -> bool {
+        Instant::now() < self.expires
+    }
+}
+
+/// A widget that stacks toasts in the top right corner, newest at the bottom.
+#[derive(Debug)]
+pub struct Toasts<'a> {
+    /// The toasts to show, from oldest to newest.
+    pub toasts: &'a [Toast],
+
+    /// The theme to draw the widget with.
+    pub theme: &'a Theme,
+}
+
+impl<'a> Toasts<'a> {
+    /// Create a new [`Toasts`] widget.
+    pub fn new(toasts: &'a [Toast], theme: &'a Theme) -> Self {
+        Self { toasts, theme }
+    }
+}
+
+impl Widget for Toasts<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut y = area.y + 1;
+        for toast in self.toasts {
+            // 4 for borders and text padding
+            let width = (Line::raw(toast.message.as_str()).width() as u16 + 4).min(area.width);
+            if y + 3 > area.bottom() {
+                break;
+            }
+            let toast_area = Rect {
+                x: area.right().saturating_sub(width + 1),
+                y,
+                width,
+                height: 3,
+            };
+            y += 3;
+
+            Clear.render(toast_area, buf);
+            Paragraph::new(toast.message.as_str())
+                .block(Block::bordered()
+                    .border_set(self.theme.border_set())
+                    .border_style(Style::default().fg(self.theme.border))
+                    .padding(Padding::horizontal(1)))
+                .render(toast_area, buf);
+        }
+    }
+}
@@ -1,39 +1,285 @@
 // -- Imports -- //
 
-use crate::ui::{Page, LabelSelection};
+use crate::keymap::KeyboardLayout;
+use crate::layout::Column;
+use crate::theme::Theme;
+use crate::pages::Page;
 
-use h5t_core::Action;
+use h5t_core::{Action, Changed, Combatant};
 use h5t_core::Tracker as CoreTracker;
 
 use ratatui::prelude::*;
+use ratatui::symbols::scrollbar;
 use ratatui::widgets::*;
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 // -- Constants -- //
 
+/// Number of ticks the flash on the new turn's row lasts. The row is inverted on even ticks.
+pub const FLASH_TICKS: u32 = 6;
+
 const DIVIDER_CHARACTER: &str = " | ";
-const ACTION_COLOR: Color = Color::Green;
-const BONUS_ACTION_COLOR: Color = Color::Rgb(255, 165, 0);
+
+/// Width of the hit point gauges, in cells.
+const HP_GAUGE_WIDTH: usize = 8;
+
+/// Width of the table's standard columns, in cells, before any optional column is added. Tables
+/// narrower than this leave out the hit point gauges, so the conditions have room.
+const BASE_TABLE_WIDTH: u16 = 70;
+
+/// Number of upcoming combatants shown in the "on deck" strip.
+const ON_DECK_COUNT: usize = 5;
+
+/// Scrollbar drawn with ASCII characters only.
+const ASCII_SCROLLBAR: scrollbar::Set = scrollbar::Set {
+	track: "|",
+	thumb: "#",
+	begin: "^",
+	end: "v",
+};
 
 // -- Exports -- //
 
 /// Returns the maximum number of combatants that can be displayed in the tracker widget.
-pub(crate) fn max_combatants_visible(widget_size: Size) -> usize {
-	// 2 Lines for upper and lower borders
-	// 4 Lines for header, spacing, etc...
+pub(crate) fn max_combatants_visible(widget_size: Size, compact: bool) -> usize {
+	// the rows of the table, below its header
 	// maximum of 32 combatants per page
-	(widget_size.height as usize).saturating_sub(6).min(32)
+	let [_, combatants] = tracker_layout(Rect::from((Position::ORIGIN, widget_size)), compact);
+	(combatants.height as usize).saturating_sub(1).min(32)
+}
+
+/// Returns the index of the page row drawn at the given position, if the tracker widget is
+/// rendered in `area`.
+///
+/// The index is not checked against the number of combatants on the page.
+pub(crate) fn tracker_row_at(area: Rect, compact: bool, position: Position) -> Option<usize> {
+	let [_, combatants] = tracker_layout(area, compact);
+	
+	// the first line of the table is the header
+	let rows = Rect { y: combatants.y + 1, height: combatants.height.saturating_sub(1), ..combatants };
+	
+	if rows.contains(position) { Some((position.y - rows.y) as usize) } else { None }
+}
+
+/// Cells of the combatants' rows, formatted once and kept until the combatant changes, so a page
+/// of a big fight isn't formatted cell by cell on every frame.
+///
+/// The label and name cells change with label mode and the markers drawn before the name, so only
+/// the cells after them are kept.
+#[derive(Debug, Default)]
+pub struct RowCache {
+	/// Compact mode, the hit point gauge, the optional columns, and the theme the cells were
+	/// formatted with. The cells are formatted again once any of them changes
+	format: Option<(bool, bool, Vec<Column>, Theme)>,
+	/// Cells after the name of each combatant's row, by combatant index
+	cells: HashMap<usize, Vec<Text<'static>>>,
+}
+
+impl RowCache {
+	/// Forgets the cells of the combatants a change can affect, given the index of the combatant
+	/// whose turn it is.
+	pub fn invalidate(&mut self, changed: Changed, turn: usize) {
+		match changed {
+			Changed::Encounter => (),
+			Changed::Combatant(index) => { self.cells.remove(&index); },
+			Changed::Turn => { self.cells.remove(&turn); },
+			Changed::All => self.cells.clear(),
+		}
+	}
+	
+	/// Forgets every cell, such as when the encounter is replaced.
+	pub fn clear(&mut self) {
+		self.cells.clear();
+	}
+	
+	/// Forgets every cell if they were formatted for another layout or theme.
+	fn prepare(&mut self, compact: bool, gauge: bool, columns: &[Column], theme: &Theme) {
+		let unchanged = self.format.as_ref().is_some_and(|format| {
+			format.0 == compact && format.1 == gauge && format.2 == columns && &format.3 == theme
+		});
+		if !unchanged {
+			self.format = Some((compact, gauge, columns.to_vec(), theme.clone()));
+			self.cells.clear();
+		}
+	}
+	
+	/// Returns the cells after the name of a combatant's row, formatting them unless they are
+	/// kept already.
+	fn cells(
+		&mut self,
+		index: usize,
+		combatant: &Combatant,
+		compact: bool,
+		gauge: bool,
+		columns: &[Column],
+		theme: &Theme,
+	) -> Vec<Text<'static>> {
+		use utility_functions::combatant_cells;
+		
+		self.cells
+			.entry(index)
+			.or_insert_with(|| combatant_cells(combatant, compact, gauge, columns, theme))
+			.clone()
+	}
 }
 
 #[derive(Copy, Clone, Debug)]
 pub struct TrackerWidget<'a> {
 	tracker: &'a CoreTracker,
-	page: Option<&'a Page>,
+	page: Option<Page<'a>>,
 	draw_labels: bool,
+	theme: &'a Theme,
+	filter: Option<&'a str>,
+	sort_order: Option<&'a str>,
+	scroll: Option<(usize, usize)>,
+	page_count: Option<(usize, usize)>,
+	cursor: Option<usize>,
+	flash: bool,
+	compact: bool,
+	columns: &'a [Column],
+	labels: &'a str,
+	row_cache: Option<&'a RefCell<RowCache>>,
 }
 
 impl<'a> TrackerWidget<'a> {
-	pub fn new(tracker: &'a CoreTracker, page: Option<&'a Page>, draw_labels: bool) -> Self {
-		Self { tracker, page, draw_labels }
+	pub fn new(
+		tracker: &'a CoreTracker,
+		page: Option<Page<'a>>,
+		draw_labels: bool,
+		theme: &'a Theme,
+	) -> Self {
+		Self {
+			tracker, page, draw_labels, theme,
+			filter: None,
+			sort_order: None,
+			scroll: None,
+			page_count: None,
+			cursor: None,
+			flash: false,
+			compact: false,
+			columns: &[],
+			labels: KeyboardLayout::default().labels(),
+			row_cache: None,
+		}
+	}
+	
+	/// Reuses the cells of rows formatted on earlier frames, and keeps the ones formatted now.
+	pub fn with_row_cache(mut self, row_cache: &'a RefCell<RowCache>) -> Self {
+		self.row_cache = Some(row_cache);
+		self
+	}
+	
+	/// Draws the labels of the given keyboard layout in label mode.
+	pub fn with_labels(mut self, labels: &'a str) -> Self {
+		self.labels = labels;
+		self
+	}
+	
+	/// Adds optional columns to the table, in order, for as long as they fit.
+	pub fn with_columns(mut self, columns: &'a [Column]) -> Self {
+		self.columns = columns;
+		self
+	}
+	
+	/// Draws a one-line header and merged columns, for small screens.
+	pub fn with_compact(mut self, compact: bool) -> Self {
+		self.compact = compact;
+		self
+	}
+	
+	/// Shows the search that the combatants on the page were filtered by.
+	pub fn with_filter(mut self, filter: Option<&'a str>) -> Self {
+		self.filter = filter;
+		self
+	}
+	
+	/// Shows the order the combatants are sorted in, if it isn't initiative order.
+	pub fn with_sort_order(mut self, sort_order: Option<&'a str>) -> Self {
+		self.sort_order = sort_order;
+		self
+	}
+	
+	/// Highlights the row of the combatant with the given index.
+	pub fn with_cursor(mut self, cursor: Option<usize>) -> Self {
+		self.cursor = cursor;
+		self
+	}
+	
+	/// Inverts the colors of the row of the combatant whose turn it is, for one step of the flash
+	/// when the turn changes.
+	pub fn with_flash(mut self, flash: bool) -> Self {
+		self.flash = flash;
+		self
+	}
+	
+	/// Shows which page is drawn out of how many, and which combatants are on it, given the
+	/// number of pages and the total number of combatants on them.
+	pub fn with_page_count(mut self, page_count: usize, total: usize) -> Self {
+		self.page_count = Some((page_count, total)).filter(|(count, _)| *count > 0);
+		self
+	}
+	
+	/// Returns the index of the first row shown among all rows, and the total number of rows, if
+	/// known.
+	fn position(&self) -> Option<(usize, usize)> {
+		if self.scroll.is_some() {
+			return self.scroll;
+		}
+		
+		let (_, total) = self.page_count?;
+		Some((self.page?.first_row(), total))
+	}
+	
+	/// Draws a scrollbar instead of the page number, given the index of the first row shown and
+	/// the total number of rows.
+	pub fn with_scroll(mut self, scroll: Option<(usize, usize)>) -> Self {
+		self.scroll = scroll;
+		self
+	}
+	
+	/// Returns the groups of combatants whose turns come next, in turn order, wrapping around to
+	/// the start of the next round, and whether each one is in the next round. Without combined
+	/// turns, each group is one combatant. Groups whose turns are skipped are left out.
+	fn on_deck(&self, groups: &[Vec<usize>]) -> Vec<(bool, Vec<usize>)> {
+		let len = groups.len();
+		let position = groups
+			.iter()
+			.position(|group| group.contains(&self.tracker.turn))
+			.unwrap_or(0);
+		(1..len)
+			.map(|offset| (position + offset >= len, &groups[(position + offset) % len]))
+			.filter(|(_, group)| !group.iter().all(|index| self.tracker.skips_turn(*index)))
+			.take(ON_DECK_COUNT)
+			.map(|(next_round, group)| (next_round, group.clone()))
+			.collect()
+	}
+	
+	/// Creates a [`Line`] listing the combatants on deck, with a separator where the next round
+	/// starts. Combatants taking their turns together are listed once, with their number.
+	fn on_deck_line(&self) -> Line<'a> {
+		let groups = self.tracker.turn_groups();
+		let mut spans = vec![Span::styled("On deck: ", Modifier::BOLD)];
+		let mut round_shown = false;
+		for (i, (next_round, group)) in self.on_deck(&groups).into_iter().enumerate() {
+			if next_round && !round_shown {
+				round_shown = true;
+				let separator = if i > 0 { " | " } else { "" };
+				spans.push(Span::styled(
+					format!("{}round {}: ", separator, self.tracker.round + 2),
+					Modifier::ITALIC,
+				));
+			} else if i > 0 {
+				spans.push(Span::raw(", "));
+			}
+			let name = self.tracker.combatants[group[0]].name();
+			match group.len() {
+				1 => spans.push(Span::raw(name)),
+				count => spans.push(Span::raw(format!("{} x{}", name, count))),
+			}
+		}
+		Line::from(spans)
 	}
 }
 
@@ -43,61 +289,134 @@ impl<'a> Widget for TrackerWidget<'a> {
 		Self: Sized
 	{
 		Block::bordered()
-			.border_type(BorderType::Rounded)
-			.border_style(Style::default().fg(Color::White))
+			.border_set(self.theme.border_set())
+			.border_style(Style::default().fg(self.theme.border))
 			.title("Initiative Tracker")
 			.render(area, buf);
 		
-		let layout = Layout::vertical([
-			Constraint::Length(3), // round and turn
-			Constraint::Fill(1),
-		])
-			.horizontal_margin(2)
-			.vertical_margin(1) // avoid the border
-			.spacing(1)
-			.split(area);
-		
-		let [round_and_turn, combatants] = [layout[0], layout[1]];
+		let [round_and_turn, combatants] = tracker_layout(area, self.compact);
 		
 		let page_number = self.page.map(|p| p.get_id()).unwrap_or(0);
 		
-		let text = vec![
-			Line::styled(format!("Page: {}", page_number + 1), Modifier::BOLD),
-			Line::styled(format!("Round: {}", self.tracker.round + 1), Modifier::BOLD),
-			Line::styled(
-				format!("Turn: {}/{}", self.tracker.turn + 1, self.tracker.combatants.len()),
-				Modifier::BOLD
-			),
-		];
+		let page_length = self.page.map(|p| p.get_combatants().len()).unwrap_or(0);
+		let rows = self.position().map(|(offset, total)| {
+			format!("{}-{}/{}", (offset + 1).min(total), offset + page_length, total)
+		});
+		let pages = self.page_count.map_or(String::new(), |(count, _)| format!("/{}", count));
+		let mut page_text = match (self.scroll, self.compact, rows) {
+			(Some(_), _, Some(rows)) => format!("Rows: {}", rows),
+			(_, false, Some(rows)) =>
+				format!("Page: {}{} (combatants {})", page_number + 1, pages, rows),
+			(_, false, None) => format!("Page: {}{}", page_number + 1, pages),
+			(_, true, _) => format!("Pg {}{}", page_number + 1, pages),
+		};
+		if let Some(filter) = self.filter {
+			page_text.push_str(&format!(" (/{})", filter));
+		}
+		if let Some(sort_order) = self.sort_order {
+			page_text.push_str(&format!(" (by {})", sort_order));
+		}
+		
+		let round = self.tracker.round + 1;
+		let clock = fmt_clock(self.tracker.elapsed);
+		let groups = self.tracker.turn_groups();
+		let position = groups.iter().position(|group| group.contains(&self.tracker.turn));
+		let mut turn = format!("{}/{}", position.unwrap_or(0) + 1, groups.len());
+		if let Some(threat) = self.tracker.threat() {
+			// how hard the rest of the fight is, for the party
+			match self.compact {
+				true => turn.push_str(&format!(" | {}", threat.difficulty())),
+				false => turn.push_str(&format!(
+					" | Threat: {} ({} XP)",
+					threat.difficulty(),
+					threat.adjusted_xp,
+				)),
+			}
+		}
+		let text = if self.compact {
+			let header = format!("{} | Rd {} ({}) | Turn {}", page_text, round, clock, turn);
+			vec![Line::styled(header, Modifier::BOLD)]
+		} else {
+			vec![
+				Line::styled(page_text, Modifier::BOLD),
+				Line::styled(format!("Round: {} | Time: {}", round, clock), Modifier::BOLD),
+				Line::styled(format!("Turn: {}", turn), Modifier::BOLD),
+				self.on_deck_line(),
+			]
+		};
 		
 		Paragraph::new(text)
 			.wrap(Wrap { trim: true })
 			.render(round_and_turn, buf);
 		
-		Widget::render(make_combat_table(self), combatants, buf);
+		Widget::render(make_combat_table(self, combatants.width), combatants, buf);
+		
+		if let Some((offset, total)) = self.position()
+			&& total > page_length
+		{
+			let mut state = ScrollbarState::new(total.saturating_sub(page_length) + 1)
+				.position(offset)
+				.viewport_content_length(page_length);
+			
+			// drawn over the right border, next to the rows
+			let rows = Rect { y: combatants.y + 1, height: combatants.height.saturating_sub(1), ..area };
+			let symbols = if self.theme.ascii {
+				ASCII_SCROLLBAR
+			} else {
+				scrollbar::DOUBLE_VERTICAL
+			};
+			Scrollbar::new(ScrollbarOrientation::VerticalRight)
+				.symbols(symbols)
+				.begin_symbol(None)
+				.end_symbol(None)
+				.style(Style::default().fg(self.theme.border))
+				.render(rows, buf, &mut state);
+		}
 	}
 }
 
 // -- Private Functions -- //
 
+/// Formats in-game seconds as a clock, `m:ss` under an hour and `h:mm:ss` after.
+fn fmt_clock(seconds: u64) -> String {
+	let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+	if hours > 0 {
+		format!("{}:{:02}:{:02}", hours, minutes, seconds)
+	} else {
+		format!("{}:{:02}", minutes, seconds)
+	}
+}
+
+/// Splits the tracker widget's area into the round and turn header and the combatant table.
+fn tracker_layout(area: Rect, compact: bool) -> [Rect; 2] {
+	Layout::vertical([
+		Constraint::Length(if compact { 1 } else { 4 }), // round and turn, on deck
+		Constraint::Fill(1),
+	])
+		.horizontal_margin(2)
+		.vertical_margin(1) // avoid the border
+		.spacing(1)
+		.areas(area)
+}
+
 /// Creates a [`Line`] widget for displaying a list of actions.
-fn action_line(actions: Action) -> Line<'static> {
+fn action_line(actions: Action, theme: &Theme) -> Line<'static> {
 	use utility_functions::fmt_action;
 	
 	let mut spans = Vec::new();
 	
 	if actions.actions > 0 {
-		spans.push(Span::styled(fmt_action("A", actions.actions), ACTION_COLOR));
+		spans.push(Span::styled(fmt_action("A", actions.actions), theme.action));
 		spans.push(Span::raw(DIVIDER_CHARACTER));
 	}
 	
 	if actions.bonus_actions > 0 {
-		spans.push(Span::styled(fmt_action("B", actions.bonus_actions), BONUS_ACTION_COLOR));
+		spans.push(Span::styled(fmt_action("B", actions.bonus_actions), theme.bonus_action));
 		spans.push(Span::raw(DIVIDER_CHARACTER));
 	}
 	
 	if actions.reactions > 0 {
-		spans.push(Span::styled(fmt_action("R", actions.reactions), Color::Magenta));
+		spans.push(Span::styled(fmt_action("R", actions.reactions), theme.reaction));
 		spans.push(Span::raw(DIVIDER_CHARACTER));
 	}
 	
@@ -106,95 +425,179 @@ fn action_line(actions: Action) -> Line<'static> {
 	Line::from(spans)
 }
 
+/// Returns the optional columns that fit in a table `width` cells wide, in order.
+fn visible_columns(columns: &[Column], width: u16) -> Vec<Column> {
+	let mut remaining = width.saturating_sub(BASE_TABLE_WIDTH);
+	columns
+		.iter()
+		.copied()
+		.take_while(|column| {
+			// 1 for the spacing between columns
+			let fits = remaining > column.width();
+			remaining = remaining.saturating_sub(column.width() + 1);
+			fits
+		})
+		.collect()
+}
+
+/// Creates the table of the combatants on the widget's page, for a table `width` cells wide.
 // 'b: 'a => b outlives a.
-fn make_combat_table<'a, 'b: 'a>(tracker_widget: TrackerWidget<'b>) -> Table<'a> {
-	use utility_functions::{combatant_row, mix_colors};
-	
-	let TrackerWidget { tracker, page, draw_labels } = tracker_widget;
-	let page = if let Some(page) = page { page } else { &Page::default() };
+pub fn make_combat_table<'a, 'b: 'a>(tracker_widget: TrackerWidget<'b>, width: u16) -> Table<'a> {
+	use utility_functions::{combatant_cells, combatant_row, mix_colors};
 	
-	let page_length = page.get_combatants().len();
+	let TrackerWidget {
+		tracker, page, draw_labels, theme, cursor, flash, compact, labels, row_cache, ..
+	} = tracker_widget;
+	let columns = if compact { Vec::new() } else { visible_columns(tracker_widget.columns, width) };
+	let gauge = width >= BASE_TABLE_WIDTH;
+	let mut row_cache = row_cache.map(RefCell::borrow_mut);
+	if let Some(cache) = &mut row_cache {
+		cache.prepare(compact, gauge, &columns, theme);
+	}
+	let page = page.unwrap_or_default();
+	let turn_members = tracker.turn_members();
 	
-	let combatants = page
+	let iter = page
 		.get_combatants()
 		.iter()
-		.map(|i| &tracker.combatants[*i])
-		.collect::<Vec<_>>();
-	
-	let selection = if draw_labels
-		&& let Some(select) = page.get_selection()
-	{
-		**select
-	} else {
-		LabelSelection::default()
-	};
-	
-	let iter = combatants
-		.into_iter()
 		.enumerate()
 		.map(
-			|(index, combatant)| {
-				let is_owner_of_turn = index + page.get_id() * page_length == tracker.turn;
-				let is_label_selected = draw_labels && selection.label_is_active(index);
+			|(index, &combatant_index)| {
+				let combatant = &tracker.combatants[combatant_index];
+				let is_owner_of_turn = turn_members.contains(&combatant_index);
+				let is_under_cursor = cursor == Some(combatant_index);
+				let is_label_selected = draw_labels && page.is_selected(combatant_index);
+				
+				let label = if draw_labels { page.label_of_row(labels, index) } else { None };
 				
-				let label = if draw_labels {
-					LabelSelection::index_to_label(index, page_length)
-				} else { None };
+				let is_down = combatant.hit_points <= 0;
+				let is_bloodied = combatant.is_bloodied();
+				let is_fleeing = combatant.fleeing && !is_down;
 				
-				let row = combatant_row(label, combatant);
+				// without colors, the row's state is shown by markers before the name instead
+				let marker = if theme.monochrome {
+					[(is_owner_of_turn, '>'), (is_label_selected, '*'), (is_down, 'x')]
+						.into_iter()
+						.filter_map(|(active, marker)| active.then_some(marker))
+						.collect::<String>()
+				} else {
+					String::new()
+				};
+				let cells = match &mut row_cache {
+					Some(cache) => {
+						cache.cells(combatant_index, combatant, compact, gauge, &columns, theme)
+					},
+					None => combatant_cells(combatant, compact, gauge, &columns, theme),
+				};
+				let row = combatant_row(label, &marker, combatant, cells, theme.ascii);
 				
 				let mut style = Style::default();
 				let mut bg_color = None;
 				
 				if is_label_selected { style = style.bold() }
 				
-				if combatant.hit_points <= 0 {
+				if theme.monochrome {
+					// the turn's row is already inverted, so the flash inverts it back
+					if is_owner_of_turn && !flash { style = style.reversed() }
+					if is_under_cursor { style = style.underlined() }
+					if is_down { style = style.crossed_out() }
+					if is_bloodied { style = style.italic() }
+					if is_fleeing { style = style.dim() }
+					return row.style(style);
+				}
+				
+				if is_under_cursor || is_owner_of_turn || is_label_selected {
+					// the highlight keeps its color, and the row's state is shown as in monochrome
+					if is_down { style = style.crossed_out() }
+					if is_bloodied { style = style.italic() }
+					if is_fleeing { style = style.dim() }
+				} else if is_down {
+					bg_color = Some(theme.dead);
+				} else if is_fleeing {
+					bg_color = Some(theme.fleeing);
+				} else if is_bloodied {
+					bg_color = Some(theme.bloodied);
+				}
+				if is_under_cursor {
 					bg_color = bg_color
-						.map(|current| mix_colors((255, 0, 0), current))
-						.or(Some((100, 0, 0)))
+						.map(|current| mix_colors(theme.cursor_row, current))
+						.or(Some(theme.cursor_row));
 				}
 				if is_owner_of_turn {
 					bg_color = bg_color
-						.map(|current| mix_colors((0, 48, 130), current))
-						.or(Some((0, 48, 130)));
+						.map(|current| mix_colors(theme.current_turn, current))
+						.or(Some(theme.current_turn));
 				}
 				if is_label_selected {
 					bg_color = bg_color
-						.map(|current| mix_colors((128, 85, 0), current))
-						.or(Some((128, 85, 0)));
+						.map(|current| mix_colors(theme.selected, current))
+						.or(Some(theme.selected));
 				}
 				
-				let bg_color = bg_color.map(|bg| Color::Rgb(bg.0, bg.1, bg.2)).unwrap_or(Color::Reset);
-				style = style.bg(bg_color);
+				style = style.bg(bg_color.unwrap_or(Color::Reset));
+				if flash && is_owner_of_turn { style = style.reversed() }
 				
 				row.style(style)
 			}
 		);
 	
+	if compact {
+		return Table::new(
+			iter,
+			[
+				Constraint::Length(2), // label
+				Constraint::Fill(2),   // name
+				Constraint::Length(3), // armor class
+				Constraint::Fill(1),   // hp / max hp
+				Constraint::Fill(2),   // actions and conditions
+			]
+		)
+			.header(Row::new([
+				Text::raw(""),
+				Text::from("Name").centered(),
+				Text::from("AC"),
+				Text::from("HP").centered(),
+				Text::from("Status"),
+			]).bold());
+	}
+	
+	let mut widths = vec![
+		Constraint::Length(2), // label
+		Constraint::Fill(2),   // name
+		Constraint::Fill(1),   // actions
+		Constraint::Fill(1),   // hp / max hp
+		Constraint::Length(HP_GAUGE_WIDTH as u16), // hp gauge
+		Constraint::Fill(1),   // conditions
+	];
+	let mut header = vec![
+		Text::raw(""),
+		Text::from("Name").centered(),
+		Text::from("Actions").centered(),
+		Text::from("HP").centered(),
+		Text::raw(""),
+		Text::from("Conditions").centered(),
+	];
+	if !gauge {
+		widths.remove(4);
+		header.remove(4);
+	}
+	
 	Table::new(
 		iter,
-		[
-			Constraint::Length(2), // label
-			Constraint::Fill(2),   // name
-			Constraint::Fill(1),   // actions
-			Constraint::Fill(1),   // hp / max hp
-			Constraint::Fill(1),   // conditions
-		]
+		widths.into_iter().chain(columns.iter().map(|column| Constraint::Length(column.width()))),
 	)
-		.header(Row::new([
-			Text::raw(""),
-			Text::from("Name").centered(),
-			Text::from("Actions").centered(),
-			Text::from("HP").centered(),
-			Text::from("Conditions").centered(),
-		]).bold())
+		.header(Row::new(
+			header.into_iter().chain(columns.iter().map(|column| Text::from(column.header())))
+		).bold())
 }
 
 mod utility_functions {
 	// Imports //
 	
+	use crate::layout::Column;
+	use crate::theme::Theme;
 	use crate::widgets::{CompactConditions, HitPoints};
-	use crate::widgets::tracker::action_line;
+	use crate::widgets::tracker::{action_line, HP_GAUGE_WIDTH};
 	use h5t_core::Combatant;
 	use ratatui::prelude::*;
 	use ratatui::widgets::*;
@@ -202,18 +605,20 @@ mod utility_functions {
 	// Functions //
 	
 	/// Mix two RGB colors together.
-	pub(super) fn mix_colors(color1: (u8, u8, u8), color2: (u8, u8, u8)) -> (u8, u8, u8) {
+	///
+	/// Colors that aren't RGB can't be mixed, so the first color is returned as-is.
+	pub(super) fn mix_colors(color1: Color, color2: Color) -> Color {
+		let (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) = (color1, color2) else {
+			return color1
+		};
+		
 		let f = |n1: u8, n2: u8| -> u8 {
 			let n1 = (255 - n1) as f32;
 			let n2 = (255 - n2) as f32;
 			((n1.powi(2) + n2.powi(2)).sqrt() / 2.0) as u8
 		};
 		
-		(
-			f(color1.0, color2.0),
-			f(color1.1, color2.1),
-			f(color1.2, color2.2),
-		)
+		Color::Rgb(f(r1, r2), f(g1, g2), f(b1, b2))
 	}
 	
 	/// Format multiple actions in a compact way (e.g. `ACT:4 | R`).
@@ -222,19 +627,85 @@ mod utility_functions {
 		else { format!("{}:{}", label, count) }
 	}
 	
-	/// Builds a table [`Row`] for a combatant.
-	pub(super) fn combatant_row(label: Option<char>, combatant: &'_ Combatant) -> Row<'_> {
+	/// Builds a table [`Row`] for a combatant, with `marker` shown before its name, followed by
+	/// the cells made by [`combatant_cells`]. Combatants with Inspiration get a star after their
+	/// name (or `(I)`, if `ascii` is set).
+	pub(super) fn combatant_row<'a>(
+		label: Option<char>,
+		marker: &str,
+		combatant: &'a Combatant,
+		cells: Vec<Text<'static>>,
+		ascii: bool,
+	) -> Row<'a> {
 		let label_text = label
-			.map(|l| Text::from(format!("{}", l)).bold())
+			.map(|l| Text::from(l.to_string()).bold())
 			.unwrap_or_default();
+		let inspiration = match (combatant.inspiration, ascii) {
+			(false, _) => "",
+			(true, false) => " ★",
+			(true, true) => " (I)",
+		};
+		let name = if marker.is_empty() && inspiration.is_empty() {
+			Text::from(combatant.name())
+		} else if marker.is_empty() {
+			Text::from(format!("{}{}", combatant.name(), inspiration))
+		} else {
+			Text::from(format!("{} {}{}", marker, combatant.name(), inspiration))
+		};
 		
-		Row::new([
-			label_text,
-			Text::from(combatant.name()),
-			action_line(combatant.actions).centered().into(),
+		Row::new([label_text, name].into_iter().chain(cells))
+	}
+	
+	/// Builds the cells of a combatant's row that come after its name.
+	///
+	/// In compact mode, the hit point gauge is left out, and the actions and conditions share a
+	/// column. The armor class is kept, since there is no room for optional columns. Otherwise,
+	/// the gauge is only left out if `gauge` isn't set.
+	pub(super) fn combatant_cells(
+		combatant: &Combatant,
+		compact: bool,
+		gauge: bool,
+		columns: &[Column],
+		theme: &Theme,
+	) -> Vec<Text<'static>> {
+		if compact {
+			let mut status = action_line(combatant.actions, theme);
+			let conditions = CompactConditions::new(combatant).line();
+			if !conditions.spans.is_empty() {
+				status.push_span(" ");
+				status.spans.extend(conditions.spans);
+			}
+			return vec![
+				Text::from(combatant.armor_class().to_string()),
+				HitPoints::new(combatant).line().centered().into(),
+				status.into(),
+			];
+		}
+		
+		let mut cells = vec![
+			action_line(combatant.actions, theme).centered().into(),
 			HitPoints::new(combatant).line().centered().into(),
-			CompactConditions::new(combatant).line().into(),
-		])
+		];
+		if gauge {
+			cells.push(HitPoints::new(combatant).gauge(HP_GAUGE_WIDTH, theme.ascii).into());
+		}
+		cells.push(CompactConditions::new(combatant).line().into());
+		cells.extend(columns.iter().map(|column| column_cell(*column, combatant)));
+		cells
+	}
+	
+	/// Builds the cell of an optional column for a combatant.
+	fn column_cell(column: Column, combatant: &Combatant) -> Text<'static> {
+		match column {
+			Column::ArmorClass => Text::from(combatant.armor_class().to_string()),
+			Column::Initiative => match combatant.initiative {
+				Some(initiative) => Text::from(initiative.to_string()),
+				None => Text::from("-"),
+			},
+			Column::PassivePerception => Text::from(combatant.passive_perception().to_string()),
+			Column::Zone => Text::from(combatant.zone.clone().unwrap_or_default()),
+			Column::Notes => Text::from(combatant.notes.lines().next().unwrap_or_default().to_string()),
+		}
 	}
 }
 
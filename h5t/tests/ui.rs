@@ -0,0 +1,789 @@
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use h5t::config::Config;
+use h5t::input::InputEvent;
+use h5t::plugin::{Plugins, Script};
+use h5t::testing::TestUi;
+use h5t::theme::Theme;
+use h5t_core::{
+    Character, Combatant, Compendium, ConditionDuration, ConditionKind, ConditionSource, Position,
+    Rules, SpeedFactor, Tracker,
+};
+use ratatui::style::Modifier;
+use std::num::NonZeroU32;
+
+/// Returns the path of a snapshot in `tests/snapshots`.
+fn snapshot(name: &str) -> String {
+    format!("{}/tests/snapshots/{}.txt", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// Creates an encounter with the named monsters from the bundled compendium.
+fn encounter(names: &[&str]) -> Tracker {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/monsters.json");
+    let file = std::fs::File::open(path).unwrap();
+    let compendium = Compendium::from_reader(std::io::BufReader::new(file)).unwrap();
+    Tracker::new(names
+        .iter()
+        .map(|name| Combatant::from(compendium.get(name).unwrap().clone()))
+        .collect::<Vec<_>>())
+}
+
+/// The tracker starts on the first combatant's turn.
+#[test]
+fn start() {
+    let ui = TestUi::new(encounter(&["Goblin", "Goblin", "Ogre"]));
+    ui.assert_snapshot(snapshot("start"));
+}
+
+/// Advancing the turn moves the highlight and wraps around to the next round.
+#[test]
+fn next_turn() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("n n n");
+    assert_eq!(ui.tracker().turn, 1);
+    assert_eq!(ui.tracker().round, 1);
+    ui.assert_snapshot(snapshot("next_turn"));
+}
+
+/// Damage is dealt to the combatants selected in label mode, by the amount typed into a popup.
+#[test]
+fn apply_damage() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    let goblin = ui.tracker().combatants[0].hit_points;
+    // label mode only lasts while keys are being sent, so the selection is sent at once
+    ui.press("d q enter");
+    ui.type_text("5");
+    ui.assert_snapshot(snapshot("apply_damage_popup"));
+
+    ui.press("enter");
+    assert_eq!(ui.tracker().combatants[0].hit_points, goblin - 5);
+    ui.assert_snapshot(snapshot("apply_damage"));
+}
+
+/// Damage dealt to several targets sums up what it did to each of them once applied.
+#[test]
+fn damage_results() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("t a enter");
+    ui.type_text("3");
+    ui.press("enter d q a enter");
+    ui.type_text("5");
+    ui.press("enter");
+    let screen = ui.screen();
+    assert!(screen.contains("Damage Results"));
+    assert!(screen.contains("Goblin: 5 damage, 2/7 HP, bloodied"));
+    assert!(screen.contains("Ogre: 5 damage (3 to temp HP), 57/59 HP"));
+
+    ui.press("enter");
+    assert!(!ui.screen().contains("Damage Results"));
+}
+
+/// Several conditions are toggled in one go, and applied to every selected target.
+#[test]
+fn apply_several_conditions() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("c q a enter");
+    // charmed and incapacitated, until removed
+    ui.press("a e enter w enter");
+    for combatant in &ui.tracker().combatants {
+        let kinds = combatant.conditions.iter().map(|c| c.kind.to_string()).collect::<Vec<_>>();
+        assert_eq!(kinds, ["Charmed", "Incapacitated"]);
+    }
+}
+
+/// A number typed while picking the duration sets how many rounds or minutes the conditions last.
+#[test]
+fn condition_duration_count() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("c q enter a enter 1 2 backspace 3");
+    assert!(ui.screen().contains("For how long? 13"));
+    ui.press("a enter");
+    let condition = &ui.tracker().combatants[0].conditions[0];
+    assert_eq!(condition.duration, ConditionDuration::Rounds(NonZeroU32::new(13).unwrap()));
+}
+
+/// Clicking rows in label mode toggles them like their labels, and clicking one again deselects it.
+#[test]
+fn click_to_select() {
+    /// Returns a left click on the given row of the screen.
+    fn click(row: u16) -> InputEvent {
+        InputEvent::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 10,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+    let key = |code| InputEvent::Key(KeyEvent::from(code));
+
+    let mut ui = TestUi::new(encounter(&["Goblin", "Goblin", "Ogre"]));
+    // the combatant rows start below the header, on the 8th row of the screen
+    ui.send([key(KeyCode::Char('d')), click(7), click(8), click(9), click(8), key(KeyCode::Enter)]);
+    ui.type_text("5");
+    ui.press("enter");
+    let hit_points = ui.tracker().combatants.iter().map(|c| c.hit_points).collect::<Vec<_>>();
+    assert_eq!(hit_points, [2, 7, 54]);
+}
+
+/// The last targets confirmed in label mode are selected again with `R`, but not cancelled ones.
+#[test]
+fn repeat_targets() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Goblin", "Ogre"]));
+    ui.press("d q z enter");
+    ui.type_text("1");
+    ui.press("enter enter d a esc");
+    ui.press("d R enter");
+    ui.type_text("2");
+    ui.press("enter enter");
+    let hit_points = ui.tracker().combatants.iter().map(|c| c.hit_points).collect::<Vec<_>>();
+    assert_eq!(hit_points, [4, 7, 56]);
+}
+
+/// `.` applies the last action again, with the same amount, to newly selected targets.
+#[test]
+fn repeat_action() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Goblin", "Ogre"]));
+    ui.press(".");
+    assert!(ui.screen().contains("No action to repeat yet"));
+
+    ui.press("d q enter");
+    ui.type_text("3");
+    ui.press("enter . a z enter enter");
+    let hit_points = ui.tracker().combatants.iter().map(|c| c.hit_points).collect::<Vec<_>>();
+    assert_eq!(hit_points, [4, 4, 56]);
+
+    ui.press("c q enter a enter w enter . z enter");
+    let kinds = ui.tracker().combatants[2].conditions.iter().map(|c| c.kind).collect::<Vec<_>>();
+    assert_eq!(kinds, [ConditionKind::Charmed]);
+}
+
+/// Keys typed after `:record` are saved as a macro once `Q` stops recording, and replayed by the
+/// macro's key or by `:macro`.
+#[test]
+fn record_macro() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press(":");
+    ui.type_text("record f5 goblin hit");
+    ui.press("enter");
+    assert!(ui.screen().contains(" RECORD "));
+
+    ui.press("d q enter");
+    ui.type_text("2");
+    ui.press("enter Q");
+    assert!(ui.screen().contains("Saved macro goblin hit on f5"));
+    assert_eq!(ui.tracker().combatants[0].hit_points, 5);
+
+    ui.press("f5");
+    assert_eq!(ui.tracker().combatants[0].hit_points, 3);
+    ui.press(":");
+    ui.type_text("macro goblin hit");
+    ui.press("enter");
+    assert_eq!(ui.tracker().combatants[0].hit_points, 1);
+    assert_eq!(ui.tracker().combatants[1].hit_points, 59);
+}
+
+/// `:keys` writes every key binding to a cheat sheet, as a Markdown table for `.md` files and as
+/// aligned columns otherwise.
+#[test]
+fn key_cheat_sheet() {
+    let mut ui = TestUi::new(encounter(&["Goblin"]));
+    let dir = std::env::temp_dir();
+    let markdown = dir.join(format!("h5t-keys-{}.md", std::process::id()));
+    let text = dir.join(format!("h5t-keys-{}.txt", std::process::id()));
+    for path in [&markdown, &text] {
+        ui.press(":");
+        ui.type_text(&format!("keys {}", path.display()));
+        ui.press("enter");
+    }
+
+    let sheet = std::fs::read_to_string(&markdown).unwrap();
+    assert!(sheet.starts_with("# h5t key bindings\n"));
+    assert!(sheet.contains("## Label mode\n\n| Keys | Command |\n| --- | --- |\n"));
+    assert!(sheet.contains("| `n` | Advance turn |"));
+    assert!(sheet.contains("| `home, g g` | First page (or page N) |"));
+    let sheet = std::fs::read_to_string(&text).unwrap();
+    assert!(sheet.contains("\nMain screen\n-----------\n"));
+    assert!(sheet.contains("\nn          Advance turn\n"));
+    std::fs::remove_file(markdown).unwrap();
+    std::fs::remove_file(text).unwrap();
+}
+
+/// `:stats` lists the damage dealt and taken, healing done, and kills of every combatant, credited
+/// to whoever's turn it was.
+#[test]
+fn combat_stats() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("n d q enter");
+    ui.type_text("10");
+    ui.press("enter :");
+    ui.type_text("stats");
+    ui.press("enter");
+    let screen = ui.screen();
+    assert!(screen.contains("Combatant                  Dealt   Taken   Healed  Kills"));
+    assert!(screen.contains("Ogre                           10       0       0      1"));
+    assert!(screen.contains("Goblin                          0      10       0      0"));
+
+    ui.press("enter");
+    assert!(!ui.screen().contains("Combat Stats"));
+}
+
+/// Small screens switch to the compact layout.
+#[test]
+fn compact() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.resize(80, 20);
+    ui.assert_snapshot(snapshot("compact"));
+}
+
+/// The help overlay opens over a popup, which takes input again once the help is closed.
+#[test]
+fn help_over_popup() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("d q enter");
+    ui.type_text("3");
+    ui.press("f1");
+    ui.assert_snapshot(snapshot("help_over_popup"));
+
+    ui.press("esc");
+    assert!(ui.screen().contains(" DAMAGE "));
+    ui.press("enter");
+    assert_eq!(ui.tracker().combatants[0].hit_points, 4);
+}
+
+/// Label mode is cancelled once the input runs out, without applying the action.
+#[test]
+fn label_mode_ends_with_input() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    let before = ui.screen();
+    ui.press("d q");
+    assert_eq!(ui.screen(), before);
+
+    ui.press("d q enter");
+    assert!(ui.screen().contains(" DAMAGE "));
+}
+
+/// Keys that don't do anything leave the screen alone instead of drawing it again.
+#[test]
+fn ignored_keys_dont_redraw() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.ui_mut().terminal.clear().unwrap();
+    ui.press("z 3");
+    assert_eq!(ui.screen().trim(), "");
+
+    ui.press("n");
+    assert!(ui.screen().contains("Goblin"));
+}
+
+/// Rows are drawn again once their combatant changes, even though they are kept between frames.
+#[test]
+fn changed_rows_redrawn() {
+    let goblin_row = |ui: &TestUi| {
+        ui.screen().lines().find(|line| line.contains("   Goblin ")).unwrap().to_string()
+    };
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    assert!(goblin_row(&ui).contains("A | B | R"));
+    ui.press("a");
+    assert!(goblin_row(&ui).contains("B | R"));
+    assert!(!goblin_row(&ui).contains("A | B | R"));
+}
+
+/// Damage carried over from a minion to the rest of its group redraws their rows too.
+#[test]
+fn minion_rows_redrawn() {
+    let mut tracker = encounter(&["Goblin", "Goblin", "Goblin"]);
+    tracker.combatants.iter_mut().for_each(|goblin| goblin.minion = true);
+    tracker.save_group("goblins", vec![0, 1, 2]);
+    let config = Config {
+        rules: Rules { minions: true, ..Default::default() },
+        ..Default::default()
+    };
+    let mut ui = TestUi::with_parts(tracker, config, Compendium::default(), Plugins::default());
+    assert!(ui.screen().contains("7/7"));
+
+    ui.press("d q enter");
+    ui.type_text("20");
+    ui.press("enter");
+    let hit_points = ui.tracker().combatants.iter().map(|goblin| goblin.hit_points);
+    assert_eq!(hit_points.collect::<Vec<_>>(), [-13, 0, 7]);
+    let screen = ui.screen();
+    let rows = screen.lines().filter(|line| line.contains("   Goblin ")).collect::<Vec<_>>();
+    assert!(rows[1].contains(" 0/7"), "{}", rows[1]);
+    assert!(rows[2].contains(" 7/7"), "{}", rows[2]);
+}
+
+/// Combatants selected in label mode stay selected while other pages are shown.
+#[test]
+fn selection_across_pages() {
+    let mut ui = TestUi::new(encounter(&["Goblin"; 30]));
+    ui.resize(100, 20);
+    let page_size = ui.screen().lines().filter(|line| line.contains("   Goblin ")).count();
+    assert!(page_size < 30);
+
+    ui.press("d q down q up enter");
+    ui.type_text("5");
+    ui.press("enter");
+    let damaged = ui.tracker().combatants
+        .iter()
+        .enumerate()
+        .filter(|(_, combatant)| combatant.hit_points < 7)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    assert_eq!(damaged, [0, page_size]);
+}
+
+/// Effects ask for their saving throw at the end of their combatant's turn, and end once it
+/// succeeds, offering to clear the conditions that came with theirs.
+#[test]
+fn end_of_turn_save() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press(":");
+    ui.type_text("effect save wis 13 paralyzed");
+    ui.press("enter q enter");
+    assert_eq!(ui.tracker().combatants[0].effects.len(), 1);
+    assert_eq!(ui.tracker().combatants[0].conditions.len(), 2);
+
+    ui.press("n");
+    assert!(ui.screen().contains("Goblin: DC 13 Wis save at end of turn, ends Paralyzed"));
+    ui.press("y");
+    assert!(ui.tracker().combatants[0].effects.is_empty());
+    assert_eq!(ui.tracker().combatants[0].conditions.len(), 1);
+
+    assert!(ui.screen().contains("Also clear Incapacitated?"));
+    ui.press("y");
+    assert!(ui.tracker().combatants[0].conditions.is_empty());
+}
+
+/// Conditions show where they came from, and are offered to be cleared once their source drops.
+#[test]
+fn condition_sources() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press(":");
+    ui.type_text("effect save wis 16 frightened from Frightful Presence");
+    ui.press("enter a enter j");
+    assert!(ui.screen().contains("Frightened (Frightful Presence from Goblin, ends on DC"));
+
+    ui.press(":");
+    ui.type_text("damage 7");
+    ui.press("enter q enter");
+    assert!(ui.screen().contains("Goblin dropped."));
+    ui.press("y");
+    let ogre = &ui.tracker().combatants[1];
+    assert!(ogre.conditions.is_empty() && ogre.effects.is_empty());
+}
+
+/// Summons linked to their summoner are offered for removal once it stops concentrating.
+#[test]
+fn summons_dismissed() {
+    let mut ui = TestUi::new(encounter(&["Ogre", "Goblin", "Goblin"]));
+    ui.press(":");
+    ui.type_text("concentrate on");
+    ui.press("enter q enter");
+    ui.press(":");
+    ui.type_text("summon");
+    ui.press("enter a z enter");
+    assert_eq!(ui.tracker().summons(0), [1, 2]);
+    assert!(ui.screen().contains("Summons Goblin, Goblin"));
+
+    ui.press(":");
+    ui.type_text("concentrate off");
+    ui.press("enter q enter");
+    assert!(ui.screen().contains("Remove Goblin, Goblin?"));
+    ui.press("y");
+    assert_eq!(ui.tracker().combatants.len(), 1);
+}
+
+/// Mob attacks work out how many attackers hit the target's AC, and roll their damage together.
+#[test]
+fn mob_attack() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press(":");
+    ui.type_text("mob 20 4 1d6+2 piercing");
+    ui.press("enter a enter");
+    assert!(ui.screen().contains("20 attackers need 7 to hit Ogre (AC 11): 1 in 2 hits."));
+    assert!(ui.screen().contains("Roll 10 hits for 10d6 + 20 damage?"));
+
+    ui.press("y");
+    assert!(ui.tracker().combatants[1].hit_points <= 59 - 30);
+}
+
+/// In popcorn initiative, advancing the turn asks who goes next, and skips nobody.
+#[test]
+fn popcorn_initiative() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre", "Wolf"]));
+    ui.press(":");
+    ui.type_text("order popcorn");
+    ui.press("enter n z enter");
+    assert_eq!(ui.tracker().turn, 2);
+
+    // the goblin is listed first now, having acted
+    ui.press("n q enter");
+    assert!(ui.screen().contains("Goblin already acted this round"));
+    ui.press("n z enter");
+    assert_eq!(ui.tracker().turn, 1);
+    assert_eq!(ui.tracker().round, 0);
+}
+
+/// In speed factor initiative, every combatant declares what it will do at the end of the round,
+/// then the next round starts with initiative rolled again.
+#[test]
+fn speed_factor_initiative() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press(":");
+    ui.type_text("order speed");
+    ui.press("enter n");
+    assert_eq!(ui.tracker().turn, 1);
+
+    ui.press("n");
+    assert!(ui.screen().contains("Goblin declares, 1 of 2"));
+    ui.press("a enter");
+    assert!(ui.screen().contains("Ogre declares, 2 of 2"));
+    ui.press("q enter");
+    assert_eq!(ui.tracker().round, 1);
+    assert_eq!(ui.tracker().combatants[0].speed_factors, [SpeedFactor::LightWeapon]);
+    assert_eq!(ui.tracker().combatants[1].speed_factors, [SpeedFactor::HeavyWeapon]);
+    let first = ui.tracker().turn_sequence()[0];
+    assert_eq!(ui.tracker().turn, first);
+    assert!(ui.tracker().combatants.iter().all(|c| c.initiative.is_some()));
+}
+
+/// With combined turns, copies of the same monster take one turn, and are on deck together.
+#[test]
+fn combined_turns() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Goblin", "Ogre"]));
+    ui.press(":");
+    ui.type_text("combine on");
+    ui.press("enter");
+    assert!(ui.screen().contains("Turn: 1/2"));
+
+    ui.press("n");
+    assert_eq!(ui.tracker().turn, 2);
+    assert!(ui.screen().contains("On deck: round 2: Goblin x2"));
+}
+
+/// Combatants moved to a zone can be selected together by their zone in label mode.
+#[test]
+fn zones() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre", "Goblin"]));
+    ui.press(":");
+    ui.type_text("zone Bridge");
+    ui.press("enter q z enter");
+    assert_eq!(ui.tracker().in_zone("Bridge"), [0, 2]);
+
+    ui.press(":");
+    ui.type_text("damage 3");
+    ui.press("enter Z enter");
+    let hit_points = ui.tracker().combatants.iter().map(|c| c.hit_points).collect::<Vec<_>>();
+    assert_eq!(hit_points, [4, 59, 4]);
+}
+
+/// Tokens are moved square by square on the grid, which shows the distance between them.
+#[test]
+fn battle_grid() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre", "Goblin"]));
+    ui.press("m l l j enter");
+    assert_eq!(ui.tracker().combatants[0].position, Some(Position::new(2, 1)));
+    assert!(ui.screen().contains("Grid"));
+
+    ui.press("j m n esc");
+    assert_eq!(ui.tracker().combatants[1].position, None);
+    ui.press("m enter");
+    assert_eq!(ui.tracker().combatants[1].position, Some(Position::new(0, 0)));
+
+    ui.press(":");
+    ui.type_text("distance");
+    ui.press("enter q z enter");
+    assert!(ui.screen().contains("Goblin: 10 ft., Goblin: not on the grid"));
+}
+
+/// Each round takes six seconds of in-game time, and time can be added between encounters.
+#[test]
+fn in_game_time() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("n n");
+    assert!(ui.screen().contains("Round: 2 | Time: 0:06"));
+
+    ui.press(":");
+    ui.type_text("time 1h30m");
+    ui.press("enter");
+    assert_eq!(ui.tracker().elapsed, 5406);
+    assert!(ui.screen().contains("Time: 1:30:06"));
+}
+
+/// Once every enemy drops, the end screen totals their XP, and confirming records the award.
+#[test]
+fn xp_award() {
+    let mut tracker = encounter(&["Goblin", "Goblin"]);
+    tracker.combatants.push(Character { name: "Tav".to_string(), ..Default::default() }.into());
+    let mut ui = TestUi::new(tracker);
+    ui.press(":");
+    ui.type_text("damage 20");
+    ui.press("enter q a enter");
+    let screen = ui.screen();
+    assert!(screen.contains("Combat Over"));
+    assert!(screen.contains("Goblin x2: 100 XP"));
+    assert!(screen.contains("Tav: 100 XP"));
+
+    ui.press("y");
+    let award = ui.tracker().log.last().map(|entry| entry.event.to_string());
+    assert_eq!(award.as_deref(), Some("Awarded 100 XP to 1 character"));
+}
+
+/// The loot of the defeated enemies is rolled on the treasure tables for their challenge rating.
+#[test]
+fn loot() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Goblin"]));
+    ui.press(":");
+    ui.type_text("loot");
+    ui.press("enter");
+    assert!(ui.screen().contains("No enemy was defeated yet"));
+
+    ui.press(":");
+    ui.type_text("damage 20");
+    ui.press("enter q enter");
+    ui.press(":");
+    ui.type_text("loot hoard");
+    ui.press("enter");
+    assert!(ui.screen().contains("Hoard (CR 0-4):"));
+}
+
+/// The header rates the threat of the enemies still standing for the party, as they drop.
+#[test]
+fn threat_meter() {
+    let mut tracker = encounter(&["Goblin", "Goblin"]);
+    let tav = Character { name: "Tav".to_string(), level: 1, ..Default::default() };
+    tracker.combatants.push(tav.into());
+    let mut ui = TestUi::new(tracker);
+    assert!(ui.screen().contains("Threat: Deadly (200 XP)"));
+
+    ui.press(":");
+    ui.type_text("damage 20");
+    ui.press("enter q enter");
+    assert!(ui.screen().contains("Threat: Hard (75 XP)"));
+}
+
+/// The HP graph panel sparks the party's hit points over the rounds, with the lowest point, or
+/// the hit points of the combatant under the cursor.
+#[test]
+fn hit_points_graph() {
+    let mut tracker = encounter(&["Goblin"]);
+    let tav = Character { name: "Tav".to_string(), hit_points: 20, ..Default::default() };
+    tracker.combatants.push(tav.into());
+    let mut ui = TestUi::new(tracker);
+    ui.press("P n d a enter");
+    ui.type_text("15");
+    ui.press("enter n n h a enter");
+    ui.type_text("5");
+    ui.press("enter");
+    let screen = ui.screen();
+    assert!(screen.contains("Party HP"));
+    assert!(screen.contains("Now 10/20, lowest 5/20 after round 1"));
+
+    ui.press("k");
+    let screen = ui.screen();
+    assert!(screen.contains("Goblin HP"));
+    assert!(screen.contains("Now 7/7, lowest 7/7 at the start"));
+}
+
+/// A script's changes to combatants that don't exist are skipped, and the rest are still made.
+#[test]
+fn script_forged_index() {
+    let path = std::env::temp_dir().join(format!("h5t-forged-{}.rhai", std::process::id()));
+    std::fs::write(&path, "fn on_turn_start(combatant) {
+        let forged = combatant;
+        forged.index = 99;
+        damage(forged, 5);
+        damage(combatant, 2);
+    }").unwrap();
+    let mut plugins = Plugins::default();
+    plugins.add(Box::new(Script::load(&path).unwrap()));
+    std::fs::remove_file(path).unwrap();
+
+    let tracker = encounter(&["Goblin", "Ogre"]);
+    let mut ui = TestUi::with_parts(tracker, Config::default(), Compendium::default(), plugins);
+    ui.press("n");
+    assert_eq!(ui.tracker().combatants[0].hit_points, 7);
+    assert_eq!(ui.tracker().combatants[1].hit_points, 57);
+}
+
+/// A script reacting to damage is given the combatant that took it, even if others share its name.
+#[test]
+fn script_duplicate_names() {
+    let path = std::env::temp_dir().join(format!("h5t-duplicate-{}.rhai", std::process::id()));
+    std::fs::write(&path, "fn on_damage(combatant, amount, kind) {
+        damage(combatant, 1);
+    }").unwrap();
+    let mut plugins = Plugins::default();
+    plugins.add(Box::new(Script::load(&path).unwrap()));
+    std::fs::remove_file(path).unwrap();
+
+    let tracker = encounter(&["Goblin", "Goblin"]);
+    let mut ui = TestUi::with_parts(tracker, Config::default(), Compendium::default(), plugins);
+    ui.press("d a enter");
+    ui.type_text("5");
+    ui.press("enter");
+    assert_eq!(ui.tracker().combatants[0].hit_points, 7);
+    assert_eq!(ui.tracker().combatants[1].hit_points, 1);
+}
+
+/// When two summoners drop at once, confirming both dialogs removes each one's own summons.
+#[test]
+fn dismiss_two_summoners() {
+    let mut tracker = encounter(&["Mage", "Priest", "Wolf", "Skeleton", "Goblin"]);
+    tracker.combatants[2].summoner = Some(0);
+    tracker.combatants[3].summoner = Some(1);
+    let mut ui = TestUi::new(tracker);
+    ui.press(":");
+    ui.type_text("damage 200");
+    ui.press("enter q a enter");
+    assert!(ui.screen().contains("Remove Wolf?") || ui.screen().contains("Remove Skeleton?"));
+
+    ui.press("y y");
+    let names = ui.tracker().combatants.iter().map(|c| c.name().to_string()).collect::<Vec<_>>();
+    assert_eq!(names, ["Mage", "Priest", "Goblin"]);
+}
+
+/// Conditions asked about under a dialog removing summons still end on the right combatants once
+/// the summons are gone.
+#[test]
+fn orphaned_conditions_after_dismissal() {
+    let mut tracker = encounter(&["Mage", "Wolf", "Goblin"]);
+    tracker.combatants[1].summoner = Some(0);
+    let source = ConditionSource { combatant: Some(0), cause: None };
+    let (kind, duration) = (ConditionKind::Frightened, ConditionDuration::Forever);
+    tracker.apply_condition_from(2, kind, duration, &source);
+    let mut ui = TestUi::new(tracker);
+    ui.press(":");
+    ui.type_text("damage 200");
+    ui.press("enter q enter");
+
+    ui.press("y y");
+    let names = ui.tracker().combatants.iter().map(|c| c.name().to_string()).collect::<Vec<_>>();
+    assert_eq!(names, ["Mage", "Goblin"]);
+    assert!(ui.tracker().combatants[1].conditions.is_empty());
+}
+
+/// A down combatant's row keeps the current turn's color, with its name struck through.
+#[test]
+fn down_on_turn() {
+    let mut tracker = encounter(&["Goblin", "Ogre"]);
+    tracker.combatants[0].hit_points = 0;
+    let ui = TestUi::new(tracker);
+    let screen = ui.screen();
+    let (y, line) = screen.lines().enumerate().find(|(_, line)| line.contains("Goblin  ")).unwrap();
+    let x = line[..line.find("Goblin").unwrap()].chars().count();
+    let cell = &ui.buffer()[(x as u16, y as u16)];
+    assert_eq!(cell.bg, Theme::default().current_turn);
+    assert!(cell.modifier.contains(Modifier::CROSSED_OUT));
+}
+
+/// With the morale rule, a leader dropping asks for its followers' morale saves, and fleeing
+/// combatants get a badge.
+#[test]
+fn morale_checks() {
+    let config = Config {
+        rules: Rules { morale: true, ..Default::default() },
+        ..Default::default()
+    };
+    let tracker = encounter(&["Ogre", "Goblin"]);
+    let mut ui = TestUi::with_parts(tracker, config, Compendium::default(), Plugins::default());
+    ui.press(":");
+    ui.type_text("leader on");
+    ui.press("enter q enter");
+    ui.press(":");
+    ui.type_text("damage 59");
+    ui.press("enter q enter");
+    assert!(ui.screen().contains("Goblin: its leader dropped."));
+    ui.press("y");
+    let screen = ui.screen();
+    assert!(screen.contains("Goblin flees") || screen.contains("Goblin holds its ground"));
+
+    ui.press(":");
+    ui.type_text("flee on");
+    ui.press("enter a enter");
+    assert!(ui.tracker().combatants[1].fleeing);
+    assert!(ui.screen().contains("FLEE"));
+}
+
+/// The edit form changes a combatant's stats in place, and won't save an invalid field.
+#[test]
+fn edit_combatant() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("C");
+    ui.press("backspace backspace backspace backspace backspace backspace");
+    ui.type_text("Gobbo");
+    ui.press("tab tab backspace");
+    ui.type_text("10");
+    ui.press("tab tab");
+    ui.type_text("x");
+    ui.press("enter");
+    assert!(ui.screen().contains("invalid initiative `x`"));
+    ui.assert_snapshot(snapshot("edit_combatant_popup"));
+
+    ui.press("backspace");
+    ui.type_text("15");
+    ui.press("enter");
+    let goblin = &ui.tracker().combatants[0];
+    let stats = (goblin.name(), goblin.max_hit_points(), goblin.initiative);
+    assert_eq!(stats, ("Gobbo", 10, Some(15)));
+    let renamed = ui.tracker().log.iter().any(|entry| {
+        entry.event.to_string() == "Goblin was renamed Gobbo"
+    });
+    assert!(renamed);
+}
+
+/// `h` heals the selected targets, like `d` damages them, up to their maximum hit points.
+#[test]
+fn quick_heal() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press(":");
+    ui.type_text("damage 5");
+    // close the damage results
+    ui.press("enter q a enter enter");
+    ui.press("h q a enter");
+    ui.type_text("3");
+    assert!(ui.screen().contains("Healing amount"));
+    ui.press("enter");
+    let hit_points = ui.tracker().combatants.iter().map(|c| c.hit_points).collect::<Vec<_>>();
+    assert_eq!(hit_points, [5, 57]);
+
+    ui.press("h a enter");
+    ui.type_text("20");
+    ui.press("enter");
+    assert_eq!(ui.tracker().combatants[1].hit_points, 59);
+}
+
+/// `t` grants temporary hit points to the selected targets, shown after their hit points, and
+/// damage takes them first.
+#[test]
+fn temporary_hit_points() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("t q a enter");
+    ui.type_text("5");
+    assert!(ui.screen().contains("Temporary hit points"));
+    ui.press("enter");
+    assert!(ui.screen().contains("7/7 +5"));
+    assert!(ui.screen().contains("59/59 +5"));
+
+    ui.press(":");
+    ui.type_text("damage 8");
+    ui.press("enter q enter");
+    let goblin = &ui.tracker().combatants[0];
+    assert_eq!((goblin.hit_points, goblin.temporary_hit_points), (4, 0));
+    assert!(!ui.screen().contains("4/7 +"));
+}
+
+/// `S` stabilizes the cursor's combatant at 0 hit points, and `K` marks it dead once confirmed.
+#[test]
+fn stabilize_and_kill() {
+    let mut ui = TestUi::new(encounter(&["Goblin", "Ogre"]));
+    ui.press("S");
+    assert_eq!(ui.tracker().combatants[0].hit_points, 0);
+    assert!(ui.screen().contains("STBL"));
+
+    ui.press("j K");
+    assert!(ui.screen().contains("Mark Ogre as dead?"));
+    ui.press("y");
+    assert!(ui.tracker().combatants[1].dead);
+    assert!(ui.screen().contains("DEAD"));
+}